@@ -0,0 +1,10 @@
+use serde::Serialize;
+
+#[derive(Clone, Serialize)]
+pub struct AdminConfig {
+    /// host:port, or unix:/path/to/socket for local-only access
+    pub bind: String,
+    /// required as "Authorization: Bearer <token>" by all endpoints if not empty,
+    /// binding to a non-loopback address requires it
+    pub token: String,
+}
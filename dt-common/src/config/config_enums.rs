@@ -1,8 +1,18 @@
 use serde::{Deserialize, Serialize};
-use strum::{Display, EnumString, IntoStaticStr};
+use strum::{Display, EnumIter, EnumString, IntoEnumIterator, IntoStaticStr};
 
 #[derive(
-    Clone, Display, EnumString, IntoStaticStr, Debug, PartialEq, Eq, Default, Serialize, Deserialize,
+    Clone,
+    Display,
+    EnumString,
+    EnumIter,
+    IntoStaticStr,
+    Debug,
+    PartialEq,
+    Eq,
+    Default,
+    Serialize,
+    Deserialize,
 )]
 pub enum DbType {
     #[default]
@@ -24,9 +34,32 @@ pub enum DbType {
     Foxlake,
     #[strum(serialize = "tidb")]
     Tidb,
+    #[strum(to_string = "elasticsearch", serialize = "opensearch")]
+    Elasticsearch,
+    #[strum(serialize = "webhook")]
+    Webhook,
+    #[strum(serialize = "file")]
+    File,
+    #[strum(serialize = "duckdb")]
+    DuckDb,
+    #[strum(serialize = "doris")]
+    Doris,
+    /// any other db_type, created by the factory registered with this name in PluginRegistry
+    #[strum(default)]
+    Plugin(String),
 }
 
-#[derive(Display, EnumString, IntoStaticStr, Debug, Clone)]
+impl DbType {
+    /// Names of the built-in db_types, plugins excluded
+    pub fn built_in_names() -> Vec<String> {
+        Self::iter()
+            .filter(|i| !matches!(i, Self::Plugin(_)))
+            .map(|i| i.to_string())
+            .collect()
+    }
+}
+
+#[derive(Display, EnumString, IntoStaticStr, Debug, Clone, Serialize)]
 pub enum ExtractType {
     #[strum(serialize = "snapshot")]
     Snapshot,
@@ -38,12 +71,16 @@ pub enum ExtractType {
     Struct,
     #[strum(serialize = "snapshot_file")]
     SnapshotFile,
+    #[strum(serialize = "aof_file")]
+    AofFile,
     #[strum(serialize = "scan")]
     Scan,
     #[strum(serialize = "reshard")]
     Reshard,
     #[strum(serialize = "foxlake_s3")]
     FoxlakeS3,
+    #[strum(serialize = "snapshot_and_cdc")]
+    SnapshotAndCdc,
 }
 
 #[derive(Display, EnumString, IntoStaticStr)]
@@ -66,7 +103,7 @@ pub enum SinkType {
     Merge,
 }
 
-#[derive(EnumString, IntoStaticStr, Clone, Display)]
+#[derive(EnumString, EnumIter, IntoStaticStr, Clone, Display, Serialize)]
 pub enum ParallelType {
     #[strum(serialize = "serial")]
     Serial,
@@ -78,6 +115,8 @@ pub enum ParallelType {
     RdbMerge,
     #[strum(serialize = "rdb_check")]
     RdbCheck,
+    #[strum(serialize = "rdb_transaction")]
+    RdbTransaction,
     #[strum(serialize = "table")]
     Table,
     #[strum(serialize = "mongo")]
@@ -86,9 +125,21 @@ pub enum ParallelType {
     Redis,
     #[strum(serialize = "foxlake")]
     Foxlake,
+    #[strum(default)]
+    Plugin(String),
+}
+
+impl ParallelType {
+    /// Names of the built-in parallel_types, plugins excluded
+    pub fn built_in_names() -> Vec<String> {
+        Self::iter()
+            .filter(|i| !matches!(i, Self::Plugin(_)))
+            .map(|i| i.to_string())
+            .collect()
+    }
 }
 
-#[derive(EnumString, IntoStaticStr, Clone, Display)]
+#[derive(EnumString, IntoStaticStr, Clone, Display, Serialize)]
 pub enum PipelineType {
     #[strum(serialize = "basic")]
     Basic,
@@ -96,7 +147,7 @@ pub enum PipelineType {
     HttpServer,
 }
 
-#[derive(Clone, Debug, EnumString, IntoStaticStr, PartialEq, Default)]
+#[derive(Clone, Debug, EnumString, IntoStaticStr, PartialEq, Default, Serialize)]
 pub enum ConflictPolicyEnum {
     #[strum(serialize = "ignore")]
     Ignore,
@@ -105,6 +156,49 @@ pub enum ConflictPolicyEnum {
     Interrupt,
 }
 
+#[derive(Clone, Debug, EnumString, IntoStaticStr, PartialEq, Default, Serialize)]
+pub enum PgCopyFormatEnum {
+    #[default]
+    #[strum(serialize = "text")]
+    Text,
+    #[strum(serialize = "binary")]
+    Binary,
+}
+
+#[derive(Clone, Debug, EnumString, IntoStaticStr, PartialEq, Default, Serialize)]
+pub enum WebhookFormatEnum {
+    #[default]
+    #[strum(serialize = "json")]
+    Json,
+    #[strum(serialize = "avro")]
+    Avro,
+}
+
+#[derive(Clone, Debug, EnumString, IntoStaticStr, PartialEq, Default, Serialize)]
+pub enum FileFormatEnum {
+    #[default]
+    #[strum(serialize = "csv")]
+    Csv,
+    #[strum(serialize = "jsonl")]
+    Jsonl,
+    #[strum(serialize = "parquet")]
+    Parquet,
+}
+
+#[derive(Clone, Debug, EnumString, IntoStaticStr, PartialEq, Default, Serialize)]
+pub enum FileCompressionEnum {
+    #[default]
+    #[strum(serialize = "none")]
+    None,
+    #[strum(serialize = "gzip")]
+    Gzip,
+    #[strum(serialize = "zstd")]
+    Zstd,
+    // parquet only
+    #[strum(serialize = "snappy")]
+    Snappy,
+}
+
 #[derive(Display, EnumString, IntoStaticStr, PartialEq)]
 pub enum MetaCenterType {
     #[strum(serialize = "basic")]
@@ -112,3 +206,32 @@ pub enum MetaCenterType {
     #[strum(serialize = "dbengine")]
     DbEngine,
 }
+
+#[derive(Clone, Debug, Display, EnumString, IntoStaticStr, PartialEq, Eq, Default, Serialize)]
+pub enum TlsMode {
+    #[default]
+    #[strum(serialize = "disable")]
+    Disable,
+    // encrypt the connection without verifying the server certificate
+    #[strum(serialize = "require")]
+    Require,
+    // verify the server certificate against tls_ca_file, skip hostname verification
+    #[strum(serialize = "verify_ca")]
+    VerifyCa,
+    // verify both the server certificate and the hostname
+    #[strum(serialize = "verify_full")]
+    VerifyFull,
+}
+
+#[derive(Clone, Debug, Display, EnumString, IntoStaticStr, PartialEq, Eq, Default, Serialize)]
+pub enum SaslMechanism {
+    #[default]
+    #[strum(serialize = "none")]
+    None,
+    #[strum(serialize = "PLAIN")]
+    Plain,
+    #[strum(serialize = "SCRAM-SHA-256")]
+    ScramSha256,
+    #[strum(serialize = "SCRAM-SHA-512")]
+    ScramSha512,
+}
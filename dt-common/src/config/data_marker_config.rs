@@ -1,4 +1,6 @@
-#[derive(Clone, Default)]
+use serde::Serialize;
+
+#[derive(Clone, Default, Serialize)]
 pub struct DataMarkerConfig {
     pub topo_name: String,
     pub topo_nodes: String,
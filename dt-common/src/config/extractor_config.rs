@@ -1,9 +1,14 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
 use super::{
     config_enums::{DbType, ExtractType},
     s3_config::S3Config,
+    tls_config::{SaslConfig, TlsConfig},
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum ExtractorConfig {
     MysqlStruct {
         url: String,
@@ -22,6 +27,8 @@ pub enum ExtractorConfig {
         sample_interval: usize,
         parallel_size: usize,
         batch_size: usize,
+        consistent_snapshot: bool,
+        flush_tables_with_read_lock: bool,
     },
 
     MysqlCdc {
@@ -35,6 +42,8 @@ pub enum ExtractorConfig {
         heartbeat_tb: String,
         start_time_utc: String,
         end_time_utc: String,
+        signal_tb: String,
+        batch_size: usize,
     },
 
     MysqlCheck {
@@ -49,6 +58,9 @@ pub enum ExtractorConfig {
         tb: String,
         sample_interval: usize,
         batch_size: usize,
+        consistent_snapshot: bool,
+        // extract by COPY (SELECT ...) TO STDOUT (FORMAT binary) instead of SELECT
+        extract_by_copy: bool,
     },
 
     PgCdc {
@@ -63,6 +75,8 @@ pub enum ExtractorConfig {
         ddl_meta_tb: String,
         start_time_utc: String,
         end_time_utc: String,
+        signal_tb: String,
+        batch_size: usize,
     },
 
     PgCheck {
@@ -116,6 +130,10 @@ pub enum ExtractorConfig {
         file_path: String,
     },
 
+    RedisAofFile {
+        file_path: String,
+    },
+
     RedisScan {
         url: String,
         scan_count: u64,
@@ -133,6 +151,7 @@ pub enum ExtractorConfig {
         partition: i32,
         offset: i64,
         ack_interval_secs: u64,
+        sasl: SaslConfig,
     },
 
     FoxlakeS3 {
@@ -142,11 +161,34 @@ pub enum ExtractorConfig {
         s3_config: S3Config,
         batch_size: usize,
     },
+
+    /// csv / jsonl / parquet files, url is a file or a directory
+    File {
+        url: String,
+        // schema / tb of the rows, override the names parsed from file paths if not empty
+        db: String,
+        tb: String,
+        batch_size: usize,
+    },
+
+    /// extract_type=snapshot_and_cdc, MysqlSnapshot / PgSnapshot followed by MysqlCdc / PgCdc,
+    /// the cdc position is recorded before the snapshot starts
+    SnapshotAndCdc {
+        snapshot: Box<ExtractorConfig>,
+        cdc: Box<ExtractorConfig>,
+    },
+
+    /// all keys / values in [extractor], passed to the plugin registered as name
+    Plugin {
+        name: String,
+        configs: HashMap<String, String>,
+    },
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct BasicExtractorConfig {
     pub db_type: DbType,
     pub extract_type: ExtractType,
     pub url: String,
+    pub tls: TlsConfig,
 }
@@ -1,4 +1,6 @@
-#[derive(Clone, Default)]
+use serde::Serialize;
+
+#[derive(Clone, Default, Serialize)]
 pub struct FilterConfig {
     pub do_schemas: String,
     pub ignore_schemas: String,
@@ -9,4 +11,9 @@ pub struct FilterConfig {
     pub do_structures: String,
     pub do_ddls: String,
     pub ignore_cmds: String,
+    pub do_keys: String,
+    pub ignore_keys: String,
+    pub do_key_types: String,
+    pub ignore_key_types: String,
+    pub max_key_size: usize,
 }
@@ -1,6 +1,8 @@
+use serde::Serialize;
+
 use super::config_enums::ConflictPolicyEnum;
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize)]
 pub enum MetaCenterConfig {
     #[default]
     Basic,
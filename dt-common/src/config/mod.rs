@@ -1,3 +1,4 @@
+pub mod admin_config;
 pub mod config_enums;
 pub mod config_token_parser;
 pub mod data_marker_config;
@@ -15,3 +16,5 @@ pub mod runtime_config;
 pub mod s3_config;
 pub mod sinker_config;
 pub mod task_config;
+pub mod task_config_builder;
+pub mod tls_config;
@@ -1,7 +1,13 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
 use super::config_enums::ParallelType;
 
-#[derive(Clone)]
+#[derive(Clone, Serialize)]
 pub struct ParallelizerConfig {
     pub parallel_type: ParallelType,
     pub parallel_size: usize,
+    /// all keys / values in [parallelizer], passed to the plugin if parallel_type is a plugin
+    pub configs: HashMap<String, String>,
 }
@@ -1,6 +1,8 @@
+use serde::Serialize;
+
 use super::config_enums::PipelineType;
 
-#[derive(Clone)]
+#[derive(Clone, Serialize)]
 pub struct PipelineConfig {
     pub pipeline_type: PipelineType,
     pub buffer_size: usize,
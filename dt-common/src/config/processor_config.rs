@@ -1,5 +1,9 @@
-#[derive(Clone)]
+use serde::Serialize;
+
+#[derive(Clone, Serialize)]
 pub struct ProcessorConfig {
     pub lua_code_file: String,
+    // the content of lua_code_file, not printed with the task config
+    #[serde(skip_serializing)]
     pub lua_code: String,
 }
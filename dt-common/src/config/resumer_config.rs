@@ -1,4 +1,6 @@
-#[derive(Clone, Default)]
+use serde::Serialize;
+
+#[derive(Clone, Default, Serialize)]
 pub struct ResumerConfig {
     pub resume_config_file: String,
     pub resume_from_log: bool,
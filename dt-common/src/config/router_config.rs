@@ -1,9 +1,13 @@
-#[derive(Clone)]
+use serde::Serialize;
+
+#[derive(Clone, Serialize)]
 pub enum RouterConfig {
     Rdb {
         schema_map: String,
         tb_map: String,
         col_map: String,
         topic_map: String,
+        key_prefix_map: String,
+        endpoint_map: String,
     },
 }
@@ -1,4 +1,6 @@
-#[derive(Clone)]
+use serde::Serialize;
+
+#[derive(Clone, Serialize)]
 pub struct RuntimeConfig {
     pub log_level: String,
     pub log_dir: String,
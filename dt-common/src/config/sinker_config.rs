@@ -1,20 +1,33 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
 use super::{
-    config_enums::{ConflictPolicyEnum, DbType},
+    config_enums::{
+        ConflictPolicyEnum, DbType, FileCompressionEnum, FileFormatEnum, PgCopyFormatEnum,
+        WebhookFormatEnum,
+    },
     s3_config::S3Config,
+    tls_config::{SaslConfig, TlsConfig},
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum SinkerConfig {
     Dummy,
 
     Mysql {
         url: String,
         batch_size: usize,
+        // snapshot only, load batches by LOAD DATA LOCAL INFILE instead of INSERT
+        bulk_load: bool,
     },
 
     Pg {
         url: String,
         batch_size: usize,
+        // snapshot only, load batches by COPY ... FROM STDIN instead of INSERT
+        bulk_load: bool,
+        copy_format: PgCopyFormatEnum,
     },
 
     Mongo {
@@ -58,6 +71,7 @@ pub enum SinkerConfig {
         ack_timeout_secs: u64,
         required_acks: String,
         with_field_defs: bool,
+        sasl: SaslConfig,
     },
 
     Redis {
@@ -65,6 +79,10 @@ pub enum SinkerConfig {
         batch_size: usize,
         method: String,
         is_cluster: bool,
+        row_mappings: String,
+        // stream / pubsub, publish rdb / mongo changes as events
+        event_mode: String,
+        stream_max_len: usize,
     },
 
     RedisStatistic {
@@ -72,6 +90,9 @@ pub enum SinkerConfig {
         data_size_threshold: usize,
         freq_threshold: i64,
         statistic_log_dir: String,
+        top_n: usize,
+        key_prefix_delimiter: String,
+        key_prefix_depth: usize,
     },
 
     StarRocks {
@@ -86,9 +107,23 @@ pub enum SinkerConfig {
         conflict_policy: ConflictPolicyEnum,
     },
 
+    Doris {
+        url: String,
+        batch_size: usize,
+        stream_load_url: String,
+        hard_delete: bool,
+        group_commit: String,
+    },
+
+    DorisStruct {
+        url: String,
+        conflict_policy: ConflictPolicyEnum,
+    },
+
     ClickHouse {
         url: String,
         batch_size: usize,
+        async_insert: bool,
     },
 
     ClickhouseStruct {
@@ -97,6 +132,52 @@ pub enum SinkerConfig {
         engine: String,
     },
 
+    // url is the path of the .duckdb file
+    DuckDb {
+        url: String,
+        batch_size: usize,
+    },
+
+    DuckDbStruct {
+        url: String,
+        conflict_policy: ConflictPolicyEnum,
+    },
+
+    Elasticsearch {
+        url: String,
+        batch_size: usize,
+        // send updates as partial updates of existing documents instead of full replacements
+        partial_update: bool,
+    },
+
+    ElasticsearchStruct {
+        url: String,
+        conflict_policy: ConflictPolicyEnum,
+    },
+
+    Webhook {
+        url: String,
+        batch_size: usize,
+        format: WebhookFormatEnum,
+        with_field_defs: bool,
+        // sign requests by HMAC-SHA256 if not empty
+        hmac_secret: String,
+        max_retries: u32,
+        retry_interval_ms: u64,
+        timeout_secs: u64,
+    },
+
+    File {
+        // root directory of files
+        url: String,
+        format: FileFormatEnum,
+        compression: FileCompressionEnum,
+        // rotate a file when its size reaches max_file_bytes
+        max_file_bytes: u64,
+        // rotate a file when it has been open for rotate_interval_secs, 0 to disable
+        rotate_interval_secs: u64,
+    },
+
     Foxlake {
         url: String,
         batch_size: usize,
@@ -127,11 +208,18 @@ pub enum SinkerConfig {
     Sql {
         reverse: bool,
     },
+
+    /// all keys / values in [sinker], passed to the plugin registered as name
+    Plugin {
+        name: String,
+        configs: HashMap<String, String>,
+    },
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize)]
 pub struct BasicSinkerConfig {
     pub db_type: DbType,
     pub url: String,
     pub batch_size: usize,
+    pub tls: TlsConfig,
 }
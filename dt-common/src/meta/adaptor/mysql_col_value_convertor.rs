@@ -414,7 +414,7 @@ impl MysqlColValueConvertor {
                 }
             },
             MysqlColType::Date => match db_type {
-                DbType::StarRocks | DbType::Foxlake => {
+                DbType::StarRocks | DbType::Doris | DbType::Foxlake => {
                     let value: Vec<u8> = row.get_unchecked(col);
                     let str: String = String::from_utf8_lossy(&value).to_string();
                     Ok(ColValue::Date(str))
@@ -425,7 +425,7 @@ impl MysqlColValueConvertor {
                 }
             },
             MysqlColType::DateTime => match db_type {
-                DbType::StarRocks | DbType::Foxlake => {
+                DbType::StarRocks | DbType::Doris | DbType::Foxlake => {
                     let value: Vec<u8> = row.get_unchecked(col);
                     let str: String = String::from_utf8_lossy(&value).to_string();
                     Ok(ColValue::DateTime(str))
@@ -1,6 +1,11 @@
+use std::io::Cursor;
+
+use anyhow::bail;
+use byteorder::{BigEndian, ReadBytesExt};
 use bytes::Bytes;
 use sqlx::{postgres::PgRow, Row};
 
+use crate::error::Error;
 use crate::meta::{
     col_value::ColValue,
     pg::{pg_col_type::PgColType, pg_meta_manager::PgMetaManager, pg_value_type::PgValueType},
@@ -118,6 +123,45 @@ impl PgColValueConvertor {
         Self::from_str(col_type, value_str, meta_manager)
     }
 
+    /// Decode a field of COPY ... TO STDOUT (FORMAT binary), the column should be selected as
+    /// get_extract_type, so the field is in the binary format of the extract type,
+    /// which is the same as from_query.
+    pub fn from_copy_binary(col_type: &PgColType, value: &[u8]) -> anyhow::Result<ColValue> {
+        if col_type.is_array() {
+            return Ok(ColValue::String(String::from_utf8(value.to_vec())?));
+        }
+
+        let mut cursor = Cursor::new(value);
+        let col_value = match Self::get_extract_type(col_type).as_str() {
+            "text" => {
+                let value = String::from_utf8(value.to_vec())?;
+                match col_type.value_type {
+                    PgValueType::Numeric => ColValue::Decimal(value),
+                    PgValueType::TimestampTZ => ColValue::Timestamp(value),
+                    PgValueType::Timestamp => ColValue::DateTime(value),
+                    PgValueType::Time => ColValue::Time(value),
+                    PgValueType::JSON => ColValue::Json2(value),
+                    _ => ColValue::String(value),
+                }
+            }
+            // oid is extracted as int8
+            "int8" => ColValue::LongLong(cursor.read_i64::<BigEndian>()?),
+            _ => match col_type.value_type {
+                PgValueType::Boolean => ColValue::Bool(cursor.read_u8()? != 0),
+                PgValueType::Int16 => ColValue::Short(cursor.read_i16::<BigEndian>()?),
+                PgValueType::Int32 => ColValue::Long(cursor.read_i32::<BigEndian>()?),
+                PgValueType::Float32 => ColValue::Float(cursor.read_f32::<BigEndian>()?),
+                PgValueType::Float64 => ColValue::Double(cursor.read_f64::<BigEndian>()?),
+                PgValueType::Bytes => ColValue::Blob(value.to_vec()),
+                _ => bail! {Error::ExtractorError(format!(
+                    "unexpected col type in copy binary: {}",
+                    col_type.alias
+                ))},
+            },
+        };
+        Ok(col_value)
+    }
+
     pub fn from_query(row: &PgRow, col: &str, col_type: &PgColType) -> anyhow::Result<ColValue> {
         let value: Option<Vec<u8>> = row.get_unchecked(col);
         if value.is_none() {
@@ -208,3 +252,124 @@ impl PgColValueConvertor {
         Ok(col_value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_col_type(alias: &str, category: &str) -> PgColType {
+        PgColType {
+            value_type: PgValueType::from_alias(alias),
+            schema: "pg_catalog".into(),
+            name: alias.into(),
+            alias: alias.into(),
+            oid: 0,
+            parent_oid: 0,
+            element_oid: 0,
+            category: category.into(),
+            enum_values: None,
+        }
+    }
+
+    #[test]
+    fn test_from_copy_binary() {
+        let cases: Vec<(&str, &str, Vec<u8>, ColValue)> = vec![
+            ("bool", "B", vec![1], ColValue::Bool(true)),
+            (
+                "int2",
+                "N",
+                (-2i16).to_be_bytes().to_vec(),
+                ColValue::Short(-2),
+            ),
+            (
+                "int4",
+                "N",
+                10i32.to_be_bytes().to_vec(),
+                ColValue::Long(10),
+            ),
+            (
+                "int8",
+                "N",
+                i64::MAX.to_be_bytes().to_vec(),
+                ColValue::LongLong(i64::MAX),
+            ),
+            // oid is extracted as int8
+            (
+                "oid",
+                "N",
+                7i64.to_be_bytes().to_vec(),
+                ColValue::LongLong(7),
+            ),
+            (
+                "float4",
+                "N",
+                1.5f32.to_be_bytes().to_vec(),
+                ColValue::Float(1.5),
+            ),
+            (
+                "float8",
+                "N",
+                (-0.25f64).to_be_bytes().to_vec(),
+                ColValue::Double(-0.25),
+            ),
+            ("bytea", "U", vec![0, 255], ColValue::Blob(vec![0, 255])),
+            (
+                "numeric",
+                "N",
+                b"123.45600000".to_vec(),
+                ColValue::Decimal("123.45600000".into()),
+            ),
+            (
+                "timestamptz",
+                "D",
+                b"2024-01-01 00:00:01+00".to_vec(),
+                ColValue::Timestamp("2024-01-01 00:00:01+00".into()),
+            ),
+            (
+                "timestamp",
+                "D",
+                b"2024-01-01 00:00:01".to_vec(),
+                ColValue::DateTime("2024-01-01 00:00:01".into()),
+            ),
+            (
+                "time",
+                "D",
+                b"12:00:00".to_vec(),
+                ColValue::Time("12:00:00".into()),
+            ),
+            (
+                "jsonb",
+                "U",
+                br#"{"a": 1}"#.to_vec(),
+                ColValue::Json2(r#"{"a": 1}"#.into()),
+            ),
+            (
+                "varchar",
+                "S",
+                b"a'b".to_vec(),
+                ColValue::String("a'b".into()),
+            ),
+            // arrays are extracted as text
+            (
+                "_int4",
+                "A",
+                b"{1,2}".to_vec(),
+                ColValue::String("{1,2}".into()),
+            ),
+        ];
+
+        for (alias, category, value, expected) in cases {
+            let col_type = mock_col_type(alias, category);
+            assert_eq!(
+                PgColValueConvertor::from_copy_binary(&col_type, &value).unwrap(),
+                expected,
+                "alias: {}",
+                alias
+            );
+        }
+
+        // a truncated int4
+        let col_type = mock_col_type("int4", "N");
+        assert!(PgColValueConvertor::from_copy_binary(&col_type, &[0, 1]).is_err());
+    }
+}
@@ -38,6 +38,11 @@ impl DtQueue {
         self.queue.len()
     }
 
+    /// Bytes of data in queue, only tracked if max_bytes is set, otherwise 0
+    pub fn bytes(&self) -> i64 {
+        self.cur_bytes.load(Ordering::Acquire)
+    }
+
     #[inline(always)]
     pub async fn push(&self, item: DtItem) -> anyhow::Result<()> {
         while self.queue.is_full() {
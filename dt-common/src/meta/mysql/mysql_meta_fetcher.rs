@@ -29,6 +29,14 @@ const CHARACTER_SET_NAME: &str = "CHARACTER_SET_NAME";
 const NUMERIC_PRECISION: &str = "NUMERIC_PRECISION";
 const NUMERIC_SCALE: &str = "NUMERIC_SCALE";
 
+// a row of DESC tb ALL in doris
+struct DorisDescRow {
+    index_name: String,
+    index_keys_type: String,
+    field: String,
+    key: String,
+}
+
 impl MysqlMetaFetcher {
     pub async fn new(conn_pool: Pool<MySql>) -> anyhow::Result<Self> {
         Self::new_mysql_compatible(conn_pool, DbType::Mysql).await
@@ -84,7 +92,11 @@ impl MysqlMetaFetcher {
         if !self.cache.contains_key(&full_name) {
             let (cols, col_origin_type_map, col_type_map) =
                 Self::parse_cols(&self.conn_pool, &self.db_type, schema, tb).await?;
-            let key_map = Self::parse_keys(&self.conn_pool, schema, tb).await?;
+            let key_map = if self.db_type == DbType::Doris {
+                Self::parse_doris_keys(&self.conn_pool, schema, tb).await?
+            } else {
+                Self::parse_keys(&self.conn_pool, schema, tb).await?
+            };
             let (order_col, partition_col, id_cols) =
                 RdbMetaManager::parse_rdb_cols(&key_map, &cols)?;
             let (foreign_keys, ref_by_foreign_keys) =
@@ -325,6 +337,63 @@ impl MysqlMetaFetcher {
         Ok(key_map)
     }
 
+    /// Doris tables have no primary keys or unique indexes, the keys of unique key tables are used,
+    /// which are the key columns of the base index in DESC ALL:
+    /// +-----------+---------------+-------+-----+-----+-----+
+    /// | IndexName | IndexKeysType | Field | ... | Key | ... |
+    /// +-----------+---------------+-------+-----+-----+-----+
+    /// | tb_1      | UNIQUE_KEYS   | id    | ... | true| ... |
+    /// |           |               | value | ... |false| ... |
+    /// | rollup_1  | UNIQUE_KEYS   | id    | ... | true| ... |
+    /// +-----------+---------------+-------+-----+-----+-----+
+    async fn parse_doris_keys(
+        conn_pool: &Pool<MySql>,
+        schema: &str,
+        tb: &str,
+    ) -> anyhow::Result<HashMap<String, Vec<String>>> {
+        let mut key_map: HashMap<String, Vec<String>> = HashMap::new();
+        let sql = format!("DESC `{}`.`{}` ALL", schema, tb);
+        let mut rows = sqlx::query(&sql).disable_arguments().fetch(conn_pool);
+        let mut desc_rows = Vec::new();
+        while let Some(row) = rows.try_next().await? {
+            desc_rows.push(DorisDescRow {
+                index_name: row.try_get("IndexName")?,
+                index_keys_type: row.try_get("IndexKeysType")?,
+                field: row.try_get("Field")?,
+                key: row.try_get("Key")?,
+            });
+        }
+        if let Some(key_cols) = Self::parse_doris_unique_key(tb, &desc_rows) {
+            key_map.insert("primary".into(), key_cols);
+        }
+        Ok(key_map)
+    }
+
+    /// IndexName and IndexKeysType are only in the first row of each index.
+    fn parse_doris_unique_key(tb: &str, desc_rows: &[DorisDescRow]) -> Option<Vec<String>> {
+        let mut index_name = "";
+        let mut index_keys_type = "";
+        let mut key_cols = Vec::new();
+        for row in desc_rows {
+            if !row.index_name.is_empty() {
+                index_name = &row.index_name;
+                index_keys_type = &row.index_keys_type;
+            }
+            if index_name.eq_ignore_ascii_case(tb)
+                && index_keys_type == "UNIQUE_KEYS"
+                && row.key.eq_ignore_ascii_case("true")
+            {
+                key_cols.push(row.field.to_lowercase());
+            }
+        }
+
+        if key_cols.is_empty() {
+            None
+        } else {
+            Some(key_cols)
+        }
+    }
+
     async fn get_foreign_keys(
         conn_pool: &Pool<MySql>,
         db_type: &DbType,
@@ -333,7 +402,7 @@ impl MysqlMetaFetcher {
     ) -> anyhow::Result<(Vec<ForeignKey>, Vec<ForeignKey>)> {
         let mut foreign_keys = Vec::new();
         let mut ref_by_foreign_keys = Vec::new();
-        if db_type == &DbType::StarRocks {
+        if matches!(db_type, DbType::StarRocks | DbType::Doris) {
             return Ok((foreign_keys, ref_by_foreign_keys));
         }
 
@@ -398,3 +467,43 @@ impl MysqlMetaFetcher {
         bail! {Error::MetadataError("failed to init mysql version".into())}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_desc_rows(rows: &[(&str, &str, &str, &str)]) -> Vec<DorisDescRow> {
+        rows.iter()
+            .map(|(index_name, index_keys_type, field, key)| DorisDescRow {
+                index_name: index_name.to_string(),
+                index_keys_type: index_keys_type.to_string(),
+                field: field.to_string(),
+                key: key.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_doris_unique_key() {
+        // rollup keys are not taken, names with ")" or "UNIQUE KEY(" in comments do not matter
+        let desc_rows = mock_desc_rows(&[
+            ("tb_1", "UNIQUE_KEYS", "ID", "true"),
+            ("", "", "k)2", "true"),
+            ("", "", "value", "false"),
+            ("rollup_1", "UNIQUE_KEYS", "value", "true"),
+        ]);
+        assert_eq!(
+            MysqlMetaFetcher::parse_doris_unique_key("tb_1", &desc_rows),
+            Some(vec!["id".to_string(), "k)2".to_string()])
+        );
+
+        let desc_rows = mock_desc_rows(&[
+            ("tb_1", "DUP_KEYS", "id", "true"),
+            ("", "", "value", "false"),
+        ]);
+        assert_eq!(
+            MysqlMetaFetcher::parse_doris_unique_key("tb_1", &desc_rows),
+            None
+        );
+    }
+}
@@ -5,6 +5,8 @@ use serde_json::json;
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct PgColType {
     pub value_type: PgValueType,
+    // schema of the type, pg_catalog for built-in types
+    pub schema: String,
     pub name: String,
     pub alias: String,
     pub oid: i32,
@@ -1,8 +1,10 @@
+use anyhow::bail;
 use futures::TryStreamExt;
 use sqlx::{postgres::PgRow, Pool, Postgres, Row};
 use std::collections::HashMap;
 
 use super::{pg_col_type::PgColType, pg_value_type::PgValueType};
+use crate::error::Error;
 
 #[derive(Clone)]
 pub struct TypeRegistry {
@@ -21,6 +23,7 @@ impl TypeRegistry {
     pub async fn init(mut self) -> anyhow::Result<Self> {
         // TODO check duplicate typename in pg_catalog.pg_type
         let sql = "SELECT t.oid AS oid,
+                    n.nspname AS schema,
                     t.typname AS name,
                     t.typelem AS element,
                     t.typbasetype AS parentoid,
@@ -44,9 +47,32 @@ impl TypeRegistry {
         Ok(self)
     }
 
+    /// Type to cast literals to, e.g. int4, "my schema"."my_enum". Types out of pg_catalog
+    /// are qualified by schema and quoted, since they may be out of the search_path.
+    pub fn get_cast_type(&self, oid: i32) -> anyhow::Result<String> {
+        let col_type = match self.oid_to_type.get(&oid) {
+            Some(col_type) => col_type,
+            None => bail! {Error::MetadataError(format!("no type found for oid: {}", oid))},
+        };
+
+        if col_type.schema == "pg_catalog" || col_type.schema.is_empty() {
+            // bit without length is bit(1), which truncates longer literals
+            if col_type.alias == "bit" {
+                return Ok("varbit".into());
+            }
+            return Ok(col_type.alias.clone());
+        }
+        Ok(format!(
+            r#""{}"."{}""#,
+            col_type.schema.replace('"', r#""""#),
+            col_type.name.replace('"', r#""""#)
+        ))
+    }
+
     fn parse_col_meta(&mut self, row: &PgRow) -> anyhow::Result<PgColType> {
         let oid: i32 = row.get_unchecked("oid");
         let value_type = PgValueType::from_oid(oid);
+        let schema: String = row.try_get("schema")?;
         let name: String = row.try_get("name")?;
         let alias = Self::name_to_alias(&name);
         let element_oid: i32 = row.get_unchecked("element");
@@ -63,6 +89,7 @@ impl TypeRegistry {
         Ok(PgColType {
             oid,
             value_type,
+            schema,
             name,
             alias,
             element_oid,
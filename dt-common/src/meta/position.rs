@@ -57,6 +57,12 @@ pub enum Position {
         tb: String,
         s3_meta_file: String,
     },
+    File {
+        // relative to the extractor url
+        file: String,
+        // csv / jsonl: the uncompressed byte offset of the next row, parquet: the next row index
+        offset: u64,
+    },
 }
 
 impl Position {
@@ -128,6 +134,7 @@ mod test {
         let strs = [
             r#"{"type":"None"}"#,
             r#"{"type":"RdbSnapshot","db_type":"mysql","schema":"test_db_1","tb":"numeric_table","order_col":"f_0","value":"127"}"#,
+            r#"{"type":"File","file":"test_db_1/tb_1/part-0.csv.gz","offset":1024}"#,
         ];
 
         for str in strs {
@@ -83,11 +83,22 @@ impl ListObject {
 }
 
 #[derive(Debug, Clone)]
-pub struct ModuleObject {}
+pub struct ModuleObject {
+    pub key: RedisString,
+    pub module_name: String,
+    pub enc_ver: u64,
+    // native cmds decoded from the module value, empty if the module is not supported
+    pub cmds: Vec<RedisCmd>,
+}
 
 impl ModuleObject {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            key: RedisString::new(),
+            module_name: String::new(),
+            enc_ver: 0,
+            cmds: vec![],
+        }
     }
 }
 
@@ -272,6 +283,19 @@ impl RedisCmd {
         size
     }
 
+    /// the value type of the keys operated by this cmd, parse_keys should be called before this
+    pub fn get_key_type(&self) -> Option<&'static str> {
+        match self.group.as_str() {
+            "STRING" | "BITMAP" | "HYPERLOGLOG" => Some(TYPE_STRING),
+            "LIST" => Some(TYPE_LIST),
+            "HASH" => Some(TYPE_HASH),
+            "SET" => Some(TYPE_SET),
+            "SORTED_SET" | "GEO" => Some(TYPE_ZSET),
+            "STREAM" => Some(TYPE_STREAM),
+            _ => None,
+        }
+    }
+
     pub fn parse_keys(&mut self, key_parser: &KeyParser) -> anyhow::Result<()> {
         let args = self.args_to_string();
         let (cmd_name, group, keys, keys_indexes) = key_parser.parse_key_from_argv(&args)?;
@@ -330,7 +354,13 @@ impl RedisObject {
                 }
                 size + v.key.bytes.len()
             }
-            RedisObject::Module(_) => 0,
+            RedisObject::Module(v) => {
+                let mut size = 0;
+                for cmd in v.cmds.iter() {
+                    size += cmd.get_malloc_size();
+                }
+                size + v.key.bytes.len()
+            }
             RedisObject::Unknown => 0,
         }
     }
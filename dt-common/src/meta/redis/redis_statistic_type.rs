@@ -6,4 +6,6 @@ pub enum RedisStatisticType {
     BigKey,
     #[strum(serialize = "hot_key")]
     HotKey,
+    #[strum(serialize = "memory_report")]
+    MemoryReport,
 }
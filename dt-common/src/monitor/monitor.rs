@@ -1,11 +1,11 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use crate::log_monitor;
 use crate::monitor::counter_type::AggregateType;
 
 use super::counter::Counter;
 use super::counter_type::{CounterType, WindowType};
-use super::time_window_counter::TimeWindowCounter;
+use super::time_window_counter::{TimeWindowCounter, WindowCounterStatistic};
 
 #[derive(Clone, Default)]
 pub struct Monitor {
@@ -39,16 +39,10 @@ impl Monitor {
             let statistics = counter.statistics();
             let mut log = format!("{} | {}", self.name, counter_type);
             for aggregate_type in counter_type.get_aggregate_types() {
-                let aggregate_value = match aggregate_type {
-                    AggregateType::AvgByCount => statistics.avg_by_count,
-                    AggregateType::AvgBySec => statistics.avg_by_sec,
-                    AggregateType::Sum => statistics.sum,
-                    AggregateType::MaxBySec => statistics.max_by_sec,
-                    AggregateType::MaxByCount => statistics.max,
-                    AggregateType::Count => statistics.count,
-                    _ => continue,
-                };
-                log = format!("{} | {}={}", log, aggregate_type, aggregate_value);
+                if let Some(aggregate_value) = Self::aggregate_window(&statistics, &aggregate_type)
+                {
+                    log = format!("{} | {}={}", log, aggregate_type, aggregate_value);
+                }
             }
             log_monitor!("{}", log);
         }
@@ -56,17 +50,64 @@ impl Monitor {
         for (counter_type, counter) in self.no_window_counters.iter() {
             let mut log = format!("{} | {}", self.name, counter_type);
             for aggregate_type in counter_type.get_aggregate_types() {
-                let aggregate_value = match aggregate_type {
-                    AggregateType::Latest => counter.value,
-                    AggregateType::AvgByCount => counter.avg_by_count(),
-                    _ => continue,
-                };
-                log = format!("{} | {}={}", log, aggregate_type, aggregate_value);
+                if let Some(aggregate_value) = Self::aggregate_no_window(counter, &aggregate_type) {
+                    log = format!("{} | {}={}", log, aggregate_type, aggregate_value);
+                }
             }
             log_monitor!("{}", log);
         }
     }
 
+    /// The same aggregated values as flush() logs, e.g. {"sinked_count": {"latest": 100}}
+    pub fn snapshot(&mut self) -> BTreeMap<String, BTreeMap<String, usize>> {
+        let mut snapshot = BTreeMap::new();
+        for (counter_type, counter) in self.time_window_counters.iter_mut() {
+            let statistics = counter.statistics();
+            let mut values = BTreeMap::new();
+            for aggregate_type in counter_type.get_aggregate_types() {
+                if let Some(aggregate_value) = Self::aggregate_window(&statistics, &aggregate_type)
+                {
+                    values.insert(aggregate_type.to_string(), aggregate_value);
+                }
+            }
+            snapshot.insert(counter_type.to_string(), values);
+        }
+
+        for (counter_type, counter) in self.no_window_counters.iter() {
+            let mut values = BTreeMap::new();
+            for aggregate_type in counter_type.get_aggregate_types() {
+                if let Some(aggregate_value) = Self::aggregate_no_window(counter, &aggregate_type) {
+                    values.insert(aggregate_type.to_string(), aggregate_value);
+                }
+            }
+            snapshot.insert(counter_type.to_string(), values);
+        }
+        snapshot
+    }
+
+    fn aggregate_window(
+        statistics: &WindowCounterStatistic,
+        aggregate_type: &AggregateType,
+    ) -> Option<usize> {
+        match aggregate_type {
+            AggregateType::AvgByCount => Some(statistics.avg_by_count),
+            AggregateType::AvgBySec => Some(statistics.avg_by_sec),
+            AggregateType::Sum => Some(statistics.sum),
+            AggregateType::MaxBySec => Some(statistics.max_by_sec),
+            AggregateType::MaxByCount => Some(statistics.max),
+            AggregateType::Count => Some(statistics.count),
+            _ => None,
+        }
+    }
+
+    fn aggregate_no_window(counter: &Counter, aggregate_type: &AggregateType) -> Option<usize> {
+        match aggregate_type {
+            AggregateType::Latest => Some(counter.value),
+            AggregateType::AvgByCount => Some(counter.avg_by_count()),
+            _ => None,
+        }
+    }
+
     pub fn add_batch_counter(
         &mut self,
         counter_type: CounterType,
@@ -18,6 +18,7 @@ use serde::{Deserialize, Serialize};
 type IgnoreCols = HashMap<(String, String), HashSet<String>>;
 
 const JSON_PREFIX: &str = "json:";
+const REGEX_PREFIX: &str = "regex:";
 
 #[derive(Debug, Clone)]
 pub struct RdbFilter {
@@ -31,6 +32,11 @@ pub struct RdbFilter {
     pub do_structures: HashSet<String>,
     pub do_ddls: HashSet<String>,
     pub ignore_cmds: HashSet<String>,
+    pub do_keys: Vec<Regex>,
+    pub ignore_keys: Vec<Regex>,
+    pub do_key_types: HashSet<String>,
+    pub ignore_key_types: HashSet<String>,
+    pub max_key_size: usize,
     pub cache: HashMap<(String, String), bool>,
 }
 
@@ -47,6 +53,11 @@ impl RdbFilter {
             do_structures: Self::parse_single_tokens(&config.do_structures, db_type)?,
             do_ddls: Self::parse_single_tokens(&config.do_ddls, db_type)?,
             ignore_cmds: Self::parse_single_tokens(&config.ignore_cmds, db_type)?,
+            do_keys: Self::parse_key_patterns(&config.do_keys)?,
+            ignore_keys: Self::parse_key_patterns(&config.ignore_keys)?,
+            do_key_types: Self::parse_key_types(&config.do_key_types),
+            ignore_key_types: Self::parse_key_types(&config.ignore_key_types),
+            max_key_size: config.max_key_size,
             cache: HashMap::new(),
         })
     }
@@ -117,16 +128,50 @@ impl RdbFilter {
         self.ignore_cmds.contains(cmd)
     }
 
+    pub fn has_redis_key_filter(&self) -> bool {
+        !self.do_keys.is_empty() || !self.ignore_keys.is_empty()
+    }
+
+    pub fn has_redis_key_type_filter(&self) -> bool {
+        !self.do_key_types.is_empty() || !self.ignore_key_types.is_empty()
+    }
+
+    pub fn filter_redis_key(&self, key: &str) -> bool {
+        if self.ignore_keys.iter().any(|i| i.is_match(key)) {
+            return true;
+        }
+        !self.do_keys.is_empty() && !self.do_keys.iter().any(|i| i.is_match(key))
+    }
+
+    pub fn filter_redis_key_type(&self, key_type: &str) -> bool {
+        if self.ignore_key_types.contains(key_type) {
+            return true;
+        }
+        !self.do_key_types.is_empty() && !self.do_key_types.contains(key_type)
+    }
+
+    pub fn filter_redis_key_size(&self, size: usize) -> bool {
+        self.max_key_size > 0 && size > self.max_key_size
+    }
+
     pub fn get_ignore_cols(&self, schema: &str, tb: &str) -> Option<&HashSet<String>> {
         self.ignore_cols.get(&(schema.to_string(), tb.to_string()))
     }
 
     pub fn add_ignore_tb(&mut self, schema: &str, tb: &str) {
         self.ignore_tbs.insert((schema.into(), tb.into()));
+        // tbs may be added while running, e.g. cdc backfill, cached results are stale
+        self.cache.clear();
     }
 
     pub fn add_do_tb(&mut self, schema: &str, tb: &str) {
         self.do_tbs.insert((schema.into(), tb.into()));
+        self.cache.clear();
+    }
+
+    pub fn remove_do_tb(&mut self, schema: &str, tb: &str) {
+        self.do_tbs.remove(&(schema.to_string(), tb.to_string()));
+        self.cache.clear();
     }
 
     fn match_all(set: &HashSet<String>) -> bool {
@@ -206,6 +251,97 @@ impl RdbFilter {
         ConfigTokenParser::parse_config(config_str, db_type, &delimiters)
     }
 
+    fn parse_key_patterns(config_str: &str) -> anyhow::Result<Vec<Regex>> {
+        let config_str = config_str.trim();
+        if config_str.is_empty() {
+            return Ok(Vec::new());
+        }
+        // do_keys=session:*,user:?:profile,regex:^order:[0-9]+$
+        // use json if the pattern contains ',', e.g. do_keys=json:["a,b*","c*"]
+        let patterns: Vec<String> = if let Some(json_str) = config_str.strip_prefix(JSON_PREFIX) {
+            serde_json::from_str(json_str)?
+        } else {
+            config_str
+                .split(',')
+                .map(|i| i.trim().to_string())
+                .collect()
+        };
+
+        let mut results = Vec::new();
+        for pattern in patterns.iter().filter(|i| !i.is_empty()) {
+            let regex_str = if let Some(regex_str) = pattern.strip_prefix(REGEX_PREFIX) {
+                regex_str.to_string()
+            } else {
+                Self::redis_glob_to_regex(pattern)
+            };
+            let regex = Regex::new(&regex_str)
+                .with_context(|| format!("invalid key pattern: [{}]", pattern))?;
+            results.push(regex);
+        }
+        Ok(results)
+    }
+
+    fn redis_glob_to_regex(pattern: &str) -> String {
+        // same syntax as redis KEYS/SCAN MATCH:
+        // * : matching any chars
+        // ? : matching exactly 1 char
+        // [abc], [^a], [a-z] : matching 1 char in/out of the set
+        // \ : escape the next char
+        let mut regex_str = String::from("(?s)^");
+        let mut chars = pattern.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '*' => regex_str.push_str(".*"),
+                '?' => regex_str.push('.'),
+                '\\' => {
+                    if let Some(next) = chars.next() {
+                        regex_str.push_str(&regex::escape(&next.to_string()));
+                    }
+                }
+                '[' => {
+                    let mut class = String::new();
+                    let mut closed = false;
+                    if chars.peek() == Some(&'^') {
+                        chars.next();
+                        class.push('^');
+                    }
+                    while let Some(c) = chars.next() {
+                        match c {
+                            ']' => {
+                                closed = true;
+                                break;
+                            }
+                            '\\' => {
+                                if let Some(next) = chars.next() {
+                                    class.push_str(&regex::escape(&next.to_string()));
+                                }
+                            }
+                            '-' => class.push('-'),
+                            _ => class.push_str(&regex::escape(&c.to_string())),
+                        }
+                    }
+                    if closed && !class.is_empty() && class != "^" {
+                        regex_str.push_str(&format!("[{}]", class));
+                    } else {
+                        // unclosed or empty brackets are matched literally
+                        regex_str.push_str(&regex::escape(&format!("[{}", class)));
+                    }
+                }
+                _ => regex_str.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+        regex_str.push('$');
+        regex_str
+    }
+
+    fn parse_key_types(config_str: &str) -> HashSet<String> {
+        config_str
+            .split(',')
+            .map(|i| i.trim().to_lowercase())
+            .filter(|i| !i.is_empty())
+            .collect()
+    }
+
     fn parse_ignore_cols(config_str: &str) -> anyhow::Result<IgnoreCols> {
         let mut results = IgnoreCols::new();
         if config_str.trim().is_empty() {
@@ -466,6 +602,25 @@ mod tests {
         assert!(!rdb_fitler.filter_event("db_test_position.aaa", "c", &RowType::Insert));
     }
 
+    #[test]
+    fn test_rdb_filter_add_tbs_while_running() {
+        let db_type = DbType::Mysql;
+        let config = FilterConfig {
+            do_tbs: "a.b".to_string(),
+            do_events: "insert".to_string(),
+            ..Default::default()
+        };
+        let mut rdb_fitler = RdbFilter::from_config(&config, &db_type).unwrap();
+        assert!(!rdb_fitler.filter_event("a", "b", &RowType::Insert));
+        assert!(rdb_fitler.filter_event("a", "c", &RowType::Insert));
+
+        rdb_fitler.add_do_tb("a", "c");
+        assert!(!rdb_fitler.filter_event("a", "c", &RowType::Insert));
+
+        rdb_fitler.add_ignore_tb("a", "b");
+        assert!(rdb_fitler.filter_event("a", "b", &RowType::Insert));
+    }
+
     #[test]
     fn test_rdb_filter_ignore_dbs_without_escapes() {
         let db_type = DbType::Mysql;
@@ -824,4 +979,69 @@ mod tests {
         assert!(rdb_fitler.filter_event("test_db_1", "aaaa", &RowType::Update));
         assert!(rdb_fitler.filter_event("test_db_1", "aaaa", &RowType::Delete));
     }
+
+    #[test]
+    fn test_rdb_filter_redis_keys() {
+        let db_type = DbType::Redis;
+
+        let config = FilterConfig {
+            do_keys: "session:*,user:?:profile,regex:^order:[0-9]+$".to_string(),
+            ignore_keys: "session:tmp_*".to_string(),
+            ..Default::default()
+        };
+        let rdb_filter = RdbFilter::from_config(&config, &db_type).unwrap();
+        assert!(rdb_filter.has_redis_key_filter());
+        assert!(!rdb_filter.filter_redis_key("session:1"));
+        assert!(!rdb_filter.filter_redis_key("session:"));
+        assert!(rdb_filter.filter_redis_key("session:tmp_1"));
+        assert!(!rdb_filter.filter_redis_key("user:1:profile"));
+        assert!(rdb_filter.filter_redis_key("user::profile"));
+        assert!(rdb_filter.filter_redis_key("user:12:profile"));
+        assert!(!rdb_filter.filter_redis_key("order:123"));
+        assert!(rdb_filter.filter_redis_key("order:12a"));
+        assert!(rdb_filter.filter_redis_key("cache:1"));
+
+        // no do_keys, keep all keys except ignored
+        let config = FilterConfig {
+            ignore_keys: r#"json:["a,b*","h[ae]llo","h[^e]y","x\\*"]"#.to_string(),
+            ..Default::default()
+        };
+        let rdb_filter = RdbFilter::from_config(&config, &db_type).unwrap();
+        assert!(rdb_filter.filter_redis_key("a,b_1"));
+        assert!(rdb_filter.filter_redis_key("hello"));
+        assert!(rdb_filter.filter_redis_key("hallo"));
+        assert!(!rdb_filter.filter_redis_key("hillo"));
+        assert!(rdb_filter.filter_redis_key("hay"));
+        assert!(!rdb_filter.filter_redis_key("hey"));
+        assert!(rdb_filter.filter_redis_key("x*"));
+        assert!(!rdb_filter.filter_redis_key("xy"));
+        assert!(!rdb_filter.filter_redis_key("a.b"));
+    }
+
+    #[test]
+    fn test_rdb_filter_redis_key_types_and_size() {
+        let db_type = DbType::Redis;
+
+        let config = FilterConfig {
+            do_key_types: "string, Hash".to_string(),
+            max_key_size: 100,
+            ..Default::default()
+        };
+        let rdb_filter = RdbFilter::from_config(&config, &db_type).unwrap();
+        assert!(!rdb_filter.filter_redis_key_type("string"));
+        assert!(!rdb_filter.filter_redis_key_type("hash"));
+        assert!(rdb_filter.filter_redis_key_type("zset"));
+        assert!(!rdb_filter.filter_redis_key_size(100));
+        assert!(rdb_filter.filter_redis_key_size(101));
+
+        let config = FilterConfig {
+            ignore_key_types: "stream,module".to_string(),
+            ..Default::default()
+        };
+        let rdb_filter = RdbFilter::from_config(&config, &db_type).unwrap();
+        assert!(!rdb_filter.filter_redis_key_type("string"));
+        assert!(rdb_filter.filter_redis_key_type("stream"));
+        assert!(rdb_filter.filter_redis_key_type("module"));
+        assert!(!rdb_filter.filter_redis_key_size(usize::MAX));
+    }
 }
@@ -1,27 +1,68 @@
+use crate::config::{config_enums::TlsMode, tls_config::TlsConfig};
 use crate::error::Error;
 use crate::log_info;
 use crate::meta::redis::cluster_node::ClusterNode;
 use crate::meta::redis::command::cmd_encoder::CmdEncoder;
 use crate::meta::redis::redis_object::RedisCmd;
 use anyhow::{bail, Context};
-use redis::{Connection, ConnectionLike, Value};
+use redis::{ClientTlsConfig, Connection, ConnectionLike, TlsCertificates, Value};
 use regex::Regex;
 use std::collections::HashMap;
 use std::str::FromStr;
+use url::Url;
 
 pub struct RedisUtil {}
 
 const SLOTS_COUNT: usize = 16384;
 
 impl RedisUtil {
-    pub async fn create_redis_conn(url: &str) -> anyhow::Result<redis::Connection> {
-        let conn = redis::Client::open(url)
-            .with_context(|| format!("invalid redis url: [{}]", url))?
+    pub async fn create_redis_conn(
+        url: &str,
+        tls: &TlsConfig,
+    ) -> anyhow::Result<redis::Connection> {
+        let client = if tls.is_enabled() {
+            Self::create_tls_client(url, tls)?
+        } else {
+            redis::Client::open(url).with_context(|| format!("invalid redis url: [{}]", url))?
+        };
+        let conn = client
             .get_connection()
             .with_context(|| format!("can not connect redis: [{}]", url))?;
         Ok(conn)
     }
 
+    fn create_tls_client(url: &str, tls: &TlsConfig) -> anyhow::Result<redis::Client> {
+        let mut url_info = Url::parse(url)?;
+        if url_info.set_scheme("rediss").is_err() {
+            bail! {Error::ConfigError(format!("invalid redis url: [{}]", url))}
+        }
+        // redis uses rustls which always checks the hostname once the certificate is verified,
+        // the hostname verification can not be skipped alone
+        if tls.mode == TlsMode::VerifyCa {
+            bail! {Error::ConfigError(
+                "tls_mode verify_ca is not supported by redis, use require or verify_full".into()
+            )}
+        }
+        if !tls.verify_cert() {
+            url_info.set_fragment(Some("insecure"));
+        }
+
+        let client_tls = match (tls.read_cert()?, tls.read_key()?) {
+            (Some(client_cert), Some(client_key)) => Some(ClientTlsConfig {
+                client_cert,
+                client_key,
+            }),
+            _ => None,
+        };
+        let certs = TlsCertificates {
+            client_tls,
+            root_cert: tls.read_ca()?,
+        };
+        let client = redis::Client::build_with_tls(url_info.as_str(), certs)
+            .with_context(|| format!("invalid redis url: [{}]", url))?;
+        Ok(client)
+    }
+
     pub fn send_cmd(conn: &mut Connection, cmd: &[&str]) -> anyhow::Result<Value> {
         let cmd = RedisCmd::from_str_args(cmd);
         let packed_cmd = CmdEncoder::encode(&cmd);
@@ -228,9 +269,201 @@ impl RedisUtil {
 
 #[cfg(test)]
 mod tests {
+    use std::{
+        fs,
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread::{self, JoinHandle},
+    };
+
+    use openssl::{
+        asn1::Asn1Time,
+        bn::BigNum,
+        ec::{EcGroup, EcKey},
+        hash::MessageDigest,
+        nid::Nid,
+        pkey::{PKey, Private},
+        ssl::{SslAcceptor, SslMethod},
+        x509::{
+            extension::{BasicConstraints, ExtendedKeyUsage, KeyUsage, SubjectAlternativeName},
+            X509Name, X509,
+        },
+    };
 
     use super::*;
 
+    fn new_key() -> PKey<Private> {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap()
+    }
+
+    fn new_cert(cn: &str, key: &PKey<Private>, issuer: Option<(&X509, &PKey<Private>)>) -> X509 {
+        let mut name = X509Name::builder().unwrap();
+        name.append_entry_by_text("CN", cn).unwrap();
+        let name = name.build();
+
+        let mut builder = X509::builder().unwrap();
+        builder.set_version(2).unwrap();
+        let serial = BigNum::from_u32(rand_serial())
+            .unwrap()
+            .to_asn1_integer()
+            .unwrap();
+        builder.set_serial_number(&serial).unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_pubkey(key).unwrap();
+        builder
+            .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        builder
+            .set_not_after(&Asn1Time::days_from_now(1).unwrap())
+            .unwrap();
+
+        match issuer {
+            // server certificate with 127.0.0.1 as the subject alternative name
+            Some((ca_cert, ca_key)) => {
+                builder.set_issuer_name(ca_cert.subject_name()).unwrap();
+                let san = SubjectAlternativeName::new()
+                    .ip("127.0.0.1")
+                    .build(&builder.x509v3_context(Some(ca_cert), None))
+                    .unwrap();
+                builder.append_extension(san).unwrap();
+                builder
+                    .append_extension(ExtendedKeyUsage::new().server_auth().build().unwrap())
+                    .unwrap();
+                builder.sign(ca_key, MessageDigest::sha256()).unwrap();
+            }
+            // self-signed ca
+            None => {
+                builder.set_issuer_name(&name).unwrap();
+                builder
+                    .append_extension(BasicConstraints::new().critical().ca().build().unwrap())
+                    .unwrap();
+                builder
+                    .append_extension(
+                        KeyUsage::new()
+                            .critical()
+                            .key_cert_sign()
+                            .crl_sign()
+                            .build()
+                            .unwrap(),
+                    )
+                    .unwrap();
+                builder.sign(key, MessageDigest::sha256()).unwrap();
+            }
+        }
+        builder.build()
+    }
+
+    fn rand_serial() -> u32 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .subsec_nanos()
+    }
+
+    /// A redis server with a self-signed certificate, accepts one connection and replies +OK to every cmd.
+    fn start_tls_server(cert: &X509, key: &PKey<Private>) -> (u16, JoinHandle<()>) {
+        let mut acceptor = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+        acceptor.set_certificate(cert).unwrap();
+        acceptor.set_private_key(key).unwrap();
+        let acceptor = acceptor.build();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            // handshake fails if the client rejects the certificate
+            let stream = match acceptor.accept(stream) {
+                Ok(stream) => stream,
+                Err(_) => return,
+            };
+
+            let mut reader = BufReader::new(stream);
+            loop {
+                // *<arg count>\r\n followed by $<len>\r\n<arg>\r\n for each arg
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                    return;
+                }
+                let arg_count: usize = line.trim()[1..].parse().unwrap();
+                for _ in 0..arg_count {
+                    line.clear();
+                    reader.read_line(&mut line).unwrap();
+                    let arg_len: usize = line.trim()[1..].parse().unwrap();
+                    let mut arg = vec![0; arg_len + 2];
+                    reader.read_exact(&mut arg).unwrap();
+                }
+                reader.get_mut().write_all(b"+OK\r\n").unwrap();
+            }
+        });
+        (port, handle)
+    }
+
+    #[tokio::test]
+    async fn test_create_tls_conn() {
+        let ca_key = new_key();
+        let ca_cert = new_cert("ape_dts_test_ca", &ca_key, None);
+        let server_key = new_key();
+        let server_cert = new_cert("127.0.0.1", &server_key, Some((&ca_cert, &ca_key)));
+
+        let ca_file =
+            std::env::temp_dir().join(format!("ape_dts_redis_tls_ca_{}.pem", std::process::id()));
+        fs::write(&ca_file, ca_cert.to_pem().unwrap()).unwrap();
+        let tls = |mode: TlsMode, ca_file: &str| TlsConfig {
+            mode,
+            ca_file: ca_file.into(),
+            ..Default::default()
+        };
+        let ca_file = ca_file.to_str().unwrap().to_string();
+
+        // certificate and hostname verified against the ca
+        let (port, handle) = start_tls_server(&server_cert, &server_key);
+        let url = format!("redis://127.0.0.1:{}", port);
+        let mut conn = RedisUtil::create_redis_conn(&url, &tls(TlsMode::VerifyFull, &ca_file))
+            .await
+            .unwrap();
+        assert_eq!(
+            RedisUtil::send_cmd(&mut conn, &["PING"]).unwrap(),
+            Value::Okay
+        );
+        drop(conn);
+        handle.join().unwrap();
+
+        // the self-signed certificate is rejected without the ca
+        let (port, handle) = start_tls_server(&server_cert, &server_key);
+        let url = format!("redis://127.0.0.1:{}", port);
+        assert!(
+            RedisUtil::create_redis_conn(&url, &tls(TlsMode::VerifyFull, ""))
+                .await
+                .is_err()
+        );
+        handle.join().unwrap();
+
+        // encrypted only, the certificate is not verified
+        let (port, handle) = start_tls_server(&server_cert, &server_key);
+        let url = format!("redis://127.0.0.1:{}", port);
+        let mut conn = RedisUtil::create_redis_conn(&url, &tls(TlsMode::Require, ""))
+            .await
+            .unwrap();
+        assert_eq!(
+            RedisUtil::send_cmd(&mut conn, &["PING"]).unwrap(),
+            Value::Okay
+        );
+        drop(conn);
+        handle.join().unwrap();
+
+        // the hostname can not be skipped alone by rustls
+        let err = RedisUtil::create_redis_conn(
+            "redis://127.0.0.1:6379",
+            &tls(TlsMode::VerifyCa, &ca_file),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("verify_ca is not supported"));
+
+        fs::remove_file(ca_file).unwrap();
+    }
+
     #[test]
     fn test_parse_cluster_nodes() {
         let cluster_nodes = r#"09596be5c2150ad93c51fdca1ff9116d1077e042 172.28.0.17:6379@16379 master - 0 1711678515085 7 connected 0-1671 2268 5461-7127 8620 10923-12588 15759
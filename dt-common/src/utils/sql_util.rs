@@ -56,10 +56,14 @@ impl SqlUtil {
 
     pub fn get_escape_pairs(db_type: &DbType) -> Vec<(char, char)> {
         match db_type {
-            DbType::Mysql | DbType::ClickHouse | DbType::Foxlake | DbType::StarRocks => {
+            DbType::Mysql
+            | DbType::ClickHouse
+            | DbType::Foxlake
+            | DbType::StarRocks
+            | DbType::Doris => {
                 vec![(MYSQL_ESCAPE, MYSQL_ESCAPE)]
             }
-            DbType::Pg => vec![(PG_ESCAPE, PG_ESCAPE)],
+            DbType::Pg | DbType::DuckDb => vec![(PG_ESCAPE, PG_ESCAPE)],
             DbType::Redis => vec![(REDIS_ESCAPE, REDIS_ESCAPE)],
             _ => vec![],
         }
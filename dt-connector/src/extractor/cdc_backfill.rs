@@ -0,0 +1,794 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use anyhow::bail;
+use dt_common::{
+    config::{config_enums::DbType, config_token_parser::ConfigTokenParser},
+    error::Error,
+    log_error, log_finished, log_info, log_warn,
+    meta::{
+        col_value::ColValue, position::Position, row_data::RowData, row_type::RowType,
+        syncer::Syncer,
+    },
+    rdb_filter::RdbFilter,
+    utils::sql_util::SqlUtil,
+};
+use postgres_types::PgLsn;
+
+use super::resumer::BACKFILL_POSITION_LOG_FLAG;
+
+pub const SIGNAL_BACKFILL: &str = "backfill";
+pub const SIGNAL_LOW_WATERMARK: &str = "low_watermark";
+pub const SIGNAL_HIGH_WATERMARK: &str = "high_watermark";
+
+/// Backfill requests received by the admin server, they are written into the signal table
+/// by the cdc extractor, so they are handled in the same order as the binlog / wal.
+#[derive(Clone, Default)]
+pub struct BackfillRequests {
+    enabled: Arc<AtomicBool>,
+    tbs: Arc<Mutex<Vec<String>>>,
+}
+
+impl BackfillRequests {
+    pub fn enable(&self) {
+        self.enabled.store(true, Ordering::Release);
+    }
+
+    /// tbs: db_1.tb_1,db_2.tb_2, the same format as do_tbs
+    pub fn push(&self, tbs: &str) -> anyhow::Result<()> {
+        if !self.enabled.load(Ordering::Acquire) {
+            bail! {Error::ConfigError(
+                "backfill is only supported by mysql / pg cdc extractor with signal_tb".into()
+            )}
+        }
+        self.tbs.lock().unwrap().push(tbs.into());
+        Ok(())
+    }
+
+    pub fn take(&self) -> Vec<String> {
+        std::mem::take(&mut *self.tbs.lock().unwrap())
+    }
+}
+
+/// A row of the signal table:
+///   CREATE TABLE test_db_1.ape_dts_signal(
+///     id VARCHAR(64) PRIMARY KEY,
+///     type VARCHAR(32),
+///     data VARCHAR(4096)
+///   );
+pub struct BackfillSignal {
+    pub id: String,
+    pub signal_type: String,
+    pub data: String,
+}
+
+/// The next chunk to select, between the low and high watermarks.
+pub struct BackfillTask {
+    pub schema: String,
+    pub tb: String,
+    /// order_col value of the last row in the previous chunk, ColValue::None for the first chunk
+    pub start_value: ColValue,
+    /// order_col value committed before restart, only used by the first chunk after restart
+    pub resume_value: Option<String>,
+}
+
+struct BackfillTb {
+    schema: String,
+    tb: String,
+    order_col: String,
+    start_value: ColValue,
+    resume_value: Option<String>,
+    finished: bool,
+    // the tb was filtered before backfill, remove it from filter again if skipped
+    added_to_filter: bool,
+}
+
+struct BackfillChunk {
+    id: String,
+    low_watermark_seen: bool,
+    id_cols: Vec<String>,
+    rows: Vec<(Vec<String>, RowData)>,
+    // keys of rows changed between the low and high watermarks
+    changed_keys: HashSet<Vec<String>>,
+}
+
+/// Incremental snapshot of tables added to a running cdc task, refer to DBLog:
+///   1, a table is added to filter and queued when a backfill signal is received
+///   2, for each chunk: write the low watermark, select the chunk, write the high watermark
+///   3, when the low watermark is received from binlog / wal, start tracking changed rows
+///   4, when the high watermark is received, emit rows of the chunk which were not changed
+///      in the window as inserts, changed rows have been emitted by cdc with newer values
+/// Tables are chunked by order_col, tables without a single-column primary / unique key are skipped.
+/// The progress is logged in finished.log once the cdc position of its signal is committed,
+/// and is loaded by CdcResumer after restart.
+pub struct CdcBackfill {
+    pub db_type: DbType,
+    pub signal_schema: String,
+    pub signal_tb: String,
+    pub batch_size: usize,
+    pending_tbs: VecDeque<(String, String)>,
+    // order_col values committed before restart
+    resume_values: HashMap<(String, String), String>,
+    current_tb: Option<BackfillTb>,
+    chunk: Option<BackfillChunk>,
+    // cdc position of the last signal
+    last_position: Position,
+    // (cdc position, progress) waiting for the cdc position to be committed
+    uncommitted: VecDeque<(Position, Position)>,
+}
+
+impl CdcBackfill {
+    pub fn new(
+        signal_tb: &str,
+        batch_size: usize,
+        db_type: &DbType,
+    ) -> anyhow::Result<Option<Self>> {
+        let tokens = ConfigTokenParser::parse_config(signal_tb, db_type, &['.'])?;
+        if tokens.is_empty() {
+            return Ok(None);
+        }
+        if tokens.len() != 2 {
+            bail! {Error::ConfigError(format!("invalid signal_tb: {}", signal_tb))}
+        }
+
+        Ok(Some(Self {
+            db_type: db_type.clone(),
+            signal_schema: SqlUtil::unescape_by_db_type(&tokens[0], db_type),
+            signal_tb: SqlUtil::unescape_by_db_type(&tokens[1], db_type),
+            batch_size: batch_size.max(1),
+            pending_tbs: VecDeque::new(),
+            resume_values: HashMap::new(),
+            current_tb: None,
+            chunk: None,
+            last_position: Position::None,
+            uncommitted: VecDeque::new(),
+        }))
+    }
+
+    pub fn is_signal_tb(&self, schema: &str, tb: &str) -> bool {
+        self.signal_schema == schema && self.signal_tb == tb
+    }
+
+    pub fn new_chunk_id() -> String {
+        uuid::Uuid::new_v4().to_string()
+    }
+
+    /// Only inserts into the signal table are considered as signals.
+    pub fn parse_signal(row_data: &RowData) -> Option<BackfillSignal> {
+        if row_data.row_type != RowType::Insert {
+            return None;
+        }
+
+        let after = row_data.after.as_ref()?;
+        let get_string = |col: &str| -> String {
+            after
+                .get(col)
+                .and_then(|v| v.to_option_string())
+                .unwrap_or_default()
+        };
+        Some(BackfillSignal {
+            id: get_string("id"),
+            signal_type: get_string("type"),
+            data: get_string("data"),
+        })
+    }
+
+    /// Restores the queue from progress committed before restart, refer to CdcResumer,
+    /// tables whose backfill finished are added to filter again.
+    pub fn resume(&mut self, positions: &[Position], filter: &mut RdbFilter) {
+        let mut finished_tbs = Vec::new();
+        for position in positions {
+            match position {
+                Position::RdbSnapshot {
+                    schema, tb, value, ..
+                } => {
+                    let key = (schema.clone(), tb.clone());
+                    finished_tbs.retain(|i| *i != key);
+                    if !self.pending_tbs.contains(&key) {
+                        self.pending_tbs.push_back(key.clone());
+                    }
+                    if value.is_empty() {
+                        self.resume_values.remove(&key);
+                    } else {
+                        self.resume_values.insert(key, value.clone());
+                    }
+                }
+
+                Position::RdbSnapshotFinished { schema, tb, .. } => {
+                    let key = (schema.clone(), tb.clone());
+                    self.pending_tbs.retain(|i| *i != key);
+                    self.resume_values.remove(&key);
+                    finished_tbs.push(key);
+                }
+
+                _ => {}
+            }
+        }
+
+        for (schema, tb) in finished_tbs.iter() {
+            filter.add_do_tb(schema, tb);
+        }
+        for (schema, tb) in self.pending_tbs.iter() {
+            log_info!("backfill resumed, schema: {}, tb: {}", schema, tb);
+        }
+    }
+
+    /// Returns the rows of the chunk to be emitted if the high watermark is received.
+    /// position: the cdc position of the signal.
+    pub fn on_signal(
+        &mut self,
+        signal: &BackfillSignal,
+        position: &Position,
+    ) -> Option<Vec<RowData>> {
+        self.last_position = position.clone();
+        match signal.signal_type.as_str() {
+            SIGNAL_BACKFILL => {
+                self.add_tbs(&signal.data);
+                None
+            }
+
+            SIGNAL_LOW_WATERMARK => {
+                if let Some(chunk) = self.chunk.as_mut() {
+                    if chunk.id == signal.data {
+                        chunk.low_watermark_seen = true;
+                    }
+                }
+                None
+            }
+
+            SIGNAL_HIGH_WATERMARK => {
+                let chunk = match self.chunk.take() {
+                    Some(chunk) if chunk.id == signal.data => chunk,
+                    other => {
+                        // watermarks of an outdated chunk
+                        self.chunk = other;
+                        return None;
+                    }
+                };
+                let rows: Vec<RowData> = chunk
+                    .rows
+                    .into_iter()
+                    .filter(|(key, _)| !chunk.changed_keys.contains(key))
+                    .map(|(_, row_data)| row_data)
+                    .collect();
+
+                if let Some(current_tb) = &self.current_tb {
+                    let progress = if current_tb.finished {
+                        log_info!(
+                            "backfill finished, schema: {}, tb: {}",
+                            current_tb.schema,
+                            current_tb.tb
+                        );
+                        self.finished_position(&current_tb.schema, &current_tb.tb)
+                    } else {
+                        Position::RdbSnapshot {
+                            db_type: self.db_type.to_string(),
+                            schema: current_tb.schema.clone(),
+                            tb: current_tb.tb.clone(),
+                            order_col: current_tb.order_col.clone(),
+                            value: current_tb
+                                .start_value
+                                .to_option_string()
+                                .unwrap_or_default(),
+                        }
+                    };
+                    if current_tb.finished {
+                        self.current_tb = None;
+                    }
+                    self.push_progress(progress);
+                }
+                Some(rows)
+            }
+
+            _ => {
+                log_warn!("unknown signal type: {}", signal.signal_type);
+                None
+            }
+        }
+    }
+
+    /// Track changes of the backfilling table between the low and high watermarks.
+    pub fn on_row(&mut self, row_data: &RowData) {
+        let chunk = match self.chunk.as_mut() {
+            Some(chunk) if chunk.low_watermark_seen => chunk,
+            _ => return,
+        };
+        if !self
+            .current_tb
+            .as_ref()
+            .is_some_and(|i| i.schema == row_data.schema && i.tb == row_data.tb)
+        {
+            return;
+        }
+
+        for col_values in [&row_data.before, &row_data.after].into_iter().flatten() {
+            chunk
+                .changed_keys
+                .insert(Self::get_key(col_values, &chunk.id_cols));
+        }
+    }
+
+    /// Returns the next chunk to select if no chunk is in progress,
+    /// a table is added to filter when its first chunk starts.
+    pub fn next_task(&mut self, filter: &mut RdbFilter) -> Option<BackfillTask> {
+        if self.chunk.is_some() {
+            return None;
+        }
+
+        while self.current_tb.is_none() {
+            let (schema, tb) = self.pending_tbs.pop_front()?;
+            let added_to_filter = filter.filter_tb(&schema, &tb);
+            filter.add_do_tb(&schema, &tb);
+            // tbs in ignore_tbs / ignore_dbs can not be added
+            if filter.filter_tb(&schema, &tb) {
+                log_warn!(
+                    "backfill skipped since tb is filtered, schema: {}, tb: {}",
+                    schema,
+                    tb
+                );
+                continue;
+            }
+
+            log_info!("backfill started, schema: {}, tb: {}", schema, tb);
+            let resume_value = self.resume_values.remove(&(schema.clone(), tb.clone()));
+            self.current_tb = Some(BackfillTb {
+                schema,
+                tb,
+                order_col: String::new(),
+                start_value: ColValue::None,
+                resume_value,
+                finished: false,
+                added_to_filter,
+            });
+        }
+
+        let current_tb = self.current_tb.as_ref().unwrap();
+        Some(BackfillTask {
+            schema: current_tb.schema.clone(),
+            tb: current_tb.tb.clone(),
+            start_value: current_tb.start_value.clone(),
+            resume_value: current_tb.resume_value.clone(),
+        })
+    }
+
+    /// Gives up the current table, e.g. it has no order_col to be chunked by.
+    /// No progress is logged, so the table will be checked and skipped again after restart.
+    pub fn skip_current_tb(&mut self, filter: &mut RdbFilter) {
+        if let Some(current_tb) = self.current_tb.take() {
+            if current_tb.added_to_filter {
+                filter.remove_do_tb(&current_tb.schema, &current_tb.tb);
+            }
+        }
+    }
+
+    /// Called after the chunk is selected and before the high watermark is written.
+    /// finished: no more chunks for the current table.
+    pub fn open_chunk(
+        &mut self,
+        id: &str,
+        id_cols: &[String],
+        order_col: &str,
+        rows: Vec<RowData>,
+        last_value: ColValue,
+        finished: bool,
+    ) {
+        let rows = rows
+            .into_iter()
+            .map(|row_data| {
+                let key = Self::get_key(row_data.after.as_ref().unwrap(), id_cols);
+                (key, row_data)
+            })
+            .collect();
+
+        if let Some(current_tb) = self.current_tb.as_mut() {
+            current_tb.order_col = order_col.into();
+            current_tb.start_value = last_value;
+            current_tb.resume_value = None;
+            current_tb.finished = finished;
+        }
+        self.chunk = Some(BackfillChunk {
+            id: id.into(),
+            low_watermark_seen: false,
+            id_cols: id_cols.to_vec(),
+            rows,
+            changed_keys: HashSet::new(),
+        });
+    }
+
+    fn add_tbs(&mut self, data: &str) {
+        let tokens = match ConfigTokenParser::parse_config(data, &self.db_type, &['.', ',']) {
+            Ok(tokens) if tokens.len() % 2 == 0 => tokens,
+            _ => {
+                log_error!("invalid backfill signal data: {}", data);
+                return;
+            }
+        };
+
+        for i in (0..tokens.len()).step_by(2) {
+            let schema = SqlUtil::unescape_by_db_type(&tokens[i], &self.db_type);
+            let tb = SqlUtil::unescape_by_db_type(&tokens[i + 1], &self.db_type);
+            let key = (schema, tb);
+            if self.pending_tbs.contains(&key)
+                || self
+                    .current_tb
+                    .as_ref()
+                    .is_some_and(|i| i.schema == key.0 && i.tb == key.1)
+            {
+                log_warn!("backfill already queued, schema: {}, tb: {}", key.0, key.1);
+                continue;
+            }
+
+            log_info!("backfill queued, schema: {}, tb: {}", key.0, key.1);
+            self.push_progress(Position::RdbSnapshot {
+                db_type: self.db_type.to_string(),
+                schema: key.0.clone(),
+                tb: key.1.clone(),
+                order_col: String::new(),
+                value: String::new(),
+            });
+            self.pending_tbs.push_back(key);
+        }
+    }
+
+    /// Logs the progress whose cdc position has been committed by the sinkers,
+    /// so rows of finished chunks will not be lost after restart.
+    pub fn flush_progress(&mut self, syncer: &Arc<Mutex<Syncer>>) {
+        if self.uncommitted.is_empty() {
+            return;
+        }
+
+        let committed_position = syncer.lock().unwrap().committed_position.clone();
+        while let Some((position, _)) = self.uncommitted.front() {
+            if !Self::is_committed(position, &committed_position) {
+                break;
+            }
+            let (_, progress) = self.uncommitted.pop_front().unwrap();
+            log_finished!("{} {}", BACKFILL_POSITION_LOG_FLAG, progress);
+        }
+    }
+
+    fn push_progress(&mut self, progress: Position) {
+        if self.last_position == Position::None {
+            // no signal received since start, nothing to wait for
+            log_finished!("{} {}", BACKFILL_POSITION_LOG_FLAG, progress);
+        } else {
+            self.uncommitted
+                .push_back((self.last_position.clone(), progress));
+        }
+    }
+
+    fn finished_position(&self, schema: &str, tb: &str) -> Position {
+        Position::RdbSnapshotFinished {
+            db_type: self.db_type.to_string(),
+            schema: schema.into(),
+            tb: tb.into(),
+        }
+    }
+
+    // events after the signal have been committed, the signal itself may not be the
+    // last event of its transaction
+    fn is_committed(position: &Position, committed_position: &Position) -> bool {
+        match (position, committed_position) {
+            (
+                Position::MysqlCdc {
+                    binlog_filename,
+                    next_event_position,
+                    ..
+                },
+                Position::MysqlCdc {
+                    binlog_filename: committed_binlog_filename,
+                    next_event_position: committed_next_event_position,
+                    ..
+                },
+            ) => {
+                // binlog files: mysql-bin.000001, mysql-bin.000002...
+                let get_index = |filename: &str| -> u64 {
+                    filename
+                        .rsplit('.')
+                        .next()
+                        .and_then(|i| i.parse().ok())
+                        .unwrap_or_default()
+                };
+                (get_index(binlog_filename), *next_event_position)
+                    < (
+                        get_index(committed_binlog_filename),
+                        *committed_next_event_position,
+                    )
+            }
+
+            (
+                Position::PgCdc { lsn, .. },
+                Position::PgCdc {
+                    lsn: committed_lsn, ..
+                },
+            ) => match (lsn.parse::<PgLsn>(), committed_lsn.parse::<PgLsn>()) {
+                (Ok(lsn), Ok(committed_lsn)) => u64::from(lsn) < u64::from(committed_lsn),
+                _ => false,
+            },
+
+            _ => false,
+        }
+    }
+
+    fn get_key(col_values: &HashMap<String, ColValue>, id_cols: &[String]) -> Vec<String> {
+        id_cols
+            .iter()
+            .map(|col| col_values.get(col).map_or(String::new(), |v| v.to_string()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dt_common::config::filter_config::FilterConfig;
+
+    use super::*;
+
+    fn signal(signal_type: &str, data: &str) -> BackfillSignal {
+        BackfillSignal {
+            id: CdcBackfill::new_chunk_id(),
+            signal_type: signal_type.into(),
+            data: data.into(),
+        }
+    }
+
+    fn row(row_type: RowType, id: i32, value: &str) -> RowData {
+        let col_values = HashMap::from([
+            ("id".to_string(), ColValue::Long(id)),
+            ("value".to_string(), ColValue::String(value.into())),
+        ]);
+        let (before, after) = match row_type {
+            RowType::Insert => (None, Some(col_values)),
+            RowType::Delete => (Some(col_values), None),
+            RowType::Update => (Some(col_values.clone()), Some(col_values)),
+        };
+        RowData::new("db_1".into(), "tb_1".into(), row_type, before, after)
+    }
+
+    #[test]
+    fn test_backfill_chunk_dedup() {
+        let db_type = DbType::Mysql;
+        let mut backfill = CdcBackfill::new("db_1.ape_dts_signal", 3, &db_type)
+            .unwrap()
+            .unwrap();
+        let config = FilterConfig {
+            do_tbs: "db_2.*".into(),
+            do_events: "*".into(),
+            ..Default::default()
+        };
+        let mut filter = RdbFilter::from_config(&config, &db_type).unwrap();
+        assert!(filter.filter_tb("db_1", "tb_1"));
+        assert!(backfill.next_task(&mut filter).is_none());
+
+        backfill.on_signal(&signal(SIGNAL_BACKFILL, "db_1.tb_1"), &Position::None);
+        let task = backfill.next_task(&mut filter).unwrap();
+        assert_eq!((task.schema.as_str(), task.tb.as_str()), ("db_1", "tb_1"));
+        assert_eq!(task.start_value, ColValue::None);
+        assert!(!filter.filter_tb("db_1", "tb_1"));
+
+        // first chunk
+        let chunk_id = CdcBackfill::new_chunk_id();
+        let rows = vec![
+            row(RowType::Insert, 1, "a"),
+            row(RowType::Insert, 2, "b"),
+            row(RowType::Insert, 3, "c"),
+        ];
+        let id_cols = vec!["id".to_string()];
+        backfill.open_chunk(&chunk_id, &id_cols, "id", rows, ColValue::Long(3), false);
+        assert!(backfill.next_task(&mut filter).is_none());
+
+        // changes before the low watermark are not tracked
+        backfill.on_row(&row(RowType::Update, 1, "a"));
+        backfill.on_signal(&signal(SIGNAL_LOW_WATERMARK, &chunk_id), &Position::None);
+        backfill.on_row(&row(RowType::Update, 2, "b"));
+        backfill.on_row(&row(RowType::Delete, 3, "c"));
+        assert!(backfill
+            .on_signal(&signal(SIGNAL_HIGH_WATERMARK, "other"), &Position::None)
+            .is_none());
+        let rows = backfill
+            .on_signal(&signal(SIGNAL_HIGH_WATERMARK, &chunk_id), &Position::None)
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(
+            rows[0].after.as_ref().unwrap().get("id"),
+            Some(&ColValue::Long(1))
+        );
+
+        // last chunk
+        let task = backfill.next_task(&mut filter).unwrap();
+        assert_eq!(task.start_value, ColValue::Long(3));
+        let chunk_id = CdcBackfill::new_chunk_id();
+        let rows = vec![row(RowType::Insert, 4, "d")];
+        backfill.open_chunk(&chunk_id, &id_cols, "id", rows, ColValue::Long(4), true);
+        backfill.on_signal(&signal(SIGNAL_LOW_WATERMARK, &chunk_id), &Position::None);
+        let rows = backfill
+            .on_signal(&signal(SIGNAL_HIGH_WATERMARK, &chunk_id), &Position::None)
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert!(backfill.next_task(&mut filter).is_none());
+    }
+
+    #[test]
+    fn test_backfill_skip_ignored_tbs() {
+        let db_type = DbType::Mysql;
+        let mut backfill = CdcBackfill::new("db_1.ape_dts_signal", 3, &db_type)
+            .unwrap()
+            .unwrap();
+        let config = FilterConfig {
+            ignore_tbs: "db_1.tb_1".into(),
+            do_events: "*".into(),
+            ..Default::default()
+        };
+        let mut filter = RdbFilter::from_config(&config, &db_type).unwrap();
+        backfill.on_signal(
+            &signal(SIGNAL_BACKFILL, "db_1.tb_1,`db_1`.`tb_2`"),
+            &Position::None,
+        );
+        let task = backfill.next_task(&mut filter).unwrap();
+        assert_eq!((task.schema.as_str(), task.tb.as_str()), ("db_1", "tb_2"));
+
+        assert!(CdcBackfill::new("", 3, &db_type).unwrap().is_none());
+        assert!(CdcBackfill::new("db_1", 3, &db_type).is_err());
+    }
+
+    fn mysql_position(binlog_filename: &str, next_event_position: u32) -> Position {
+        Position::MysqlCdc {
+            server_id: String::new(),
+            binlog_filename: binlog_filename.into(),
+            next_event_position,
+            gtid_set: String::new(),
+            timestamp: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_backfill_skip_current_tb() {
+        let db_type = DbType::Mysql;
+        let mut backfill = CdcBackfill::new("db_1.ape_dts_signal", 3, &db_type)
+            .unwrap()
+            .unwrap();
+        let config = FilterConfig {
+            do_tbs: "db_1.tb_2".into(),
+            do_events: "*".into(),
+            ..Default::default()
+        };
+        let mut filter = RdbFilter::from_config(&config, &db_type).unwrap();
+        backfill.on_signal(
+            &signal(SIGNAL_BACKFILL, "db_1.tb_1,db_1.tb_2"),
+            &Position::None,
+        );
+
+        // tb_1 was filtered before backfill, filter it again
+        let task = backfill.next_task(&mut filter).unwrap();
+        assert_eq!(task.tb, "tb_1");
+        assert!(!filter.filter_tb("db_1", "tb_1"));
+        backfill.skip_current_tb(&mut filter);
+        assert!(filter.filter_tb("db_1", "tb_1"));
+
+        // tb_2 was synced before backfill, keep syncing it
+        let task = backfill.next_task(&mut filter).unwrap();
+        assert_eq!(task.tb, "tb_2");
+        backfill.skip_current_tb(&mut filter);
+        assert!(!filter.filter_tb("db_1", "tb_2"));
+        assert!(backfill.next_task(&mut filter).is_none());
+    }
+
+    #[test]
+    fn test_backfill_flush_progress() {
+        let db_type = DbType::Mysql;
+        let mut backfill = CdcBackfill::new("db_1.ape_dts_signal", 3, &db_type)
+            .unwrap()
+            .unwrap();
+        let mut filter = RdbFilter::from_config(&FilterConfig::default(), &db_type).unwrap();
+        let syncer = Arc::new(Mutex::new(Syncer {
+            received_position: Position::None,
+            committed_position: mysql_position("mysql-bin.000009", 100),
+        }));
+
+        backfill.on_signal(
+            &signal(SIGNAL_BACKFILL, "db_1.tb_1"),
+            &mysql_position("mysql-bin.000009", 200),
+        );
+        backfill.next_task(&mut filter).unwrap();
+        let chunk_id = CdcBackfill::new_chunk_id();
+        let rows = vec![row(RowType::Insert, 1, "a")];
+        let id_cols = vec!["id".to_string()];
+        backfill.open_chunk(&chunk_id, &id_cols, "id", rows, ColValue::Long(1), false);
+        backfill.on_signal(
+            &signal(SIGNAL_HIGH_WATERMARK, &chunk_id),
+            &mysql_position("mysql-bin.000010", 4),
+        );
+        assert_eq!(backfill.uncommitted.len(), 2);
+
+        // the backfill signal is committed when a later event is committed
+        backfill.flush_progress(&syncer);
+        assert_eq!(backfill.uncommitted.len(), 2);
+        syncer.lock().unwrap().committed_position = mysql_position("mysql-bin.000009", 300);
+        backfill.flush_progress(&syncer);
+        assert_eq!(backfill.uncommitted.len(), 1);
+        assert_eq!(
+            backfill.uncommitted[0].1,
+            Position::RdbSnapshot {
+                db_type: "mysql".into(),
+                schema: "db_1".into(),
+                tb: "tb_1".into(),
+                order_col: "id".into(),
+                value: "1".into(),
+            }
+        );
+        syncer.lock().unwrap().committed_position = mysql_position("mysql-bin.000010", 100);
+        backfill.flush_progress(&syncer);
+        assert!(backfill.uncommitted.is_empty());
+
+        assert!(CdcBackfill::is_committed(
+            &Position::PgCdc {
+                lsn: "0/FFFFFFFF".into(),
+                timestamp: String::new(),
+            },
+            &Position::PgCdc {
+                lsn: "1/0".into(),
+                timestamp: String::new(),
+            },
+        ));
+    }
+
+    #[test]
+    fn test_backfill_resume() {
+        let db_type = DbType::Mysql;
+        let mut backfill = CdcBackfill::new("db_1.ape_dts_signal", 3, &db_type)
+            .unwrap()
+            .unwrap();
+        let config = FilterConfig {
+            do_tbs: "db_2.*".into(),
+            ..Default::default()
+        };
+        let mut filter = RdbFilter::from_config(&config, &db_type).unwrap();
+        let queued = |tb: &str, value: &str| Position::RdbSnapshot {
+            db_type: "mysql".into(),
+            schema: "db_1".into(),
+            tb: tb.into(),
+            order_col: if value.is_empty() { "" } else { "id" }.into(),
+            value: value.into(),
+        };
+        let finished = |tb: &str| Position::RdbSnapshotFinished {
+            db_type: "mysql".into(),
+            schema: "db_1".into(),
+            tb: tb.into(),
+        };
+        backfill.resume(
+            &[
+                queued("tb_1", ""),
+                queued("tb_2", ""),
+                queued("tb_3", ""),
+                queued("tb_1", "10"),
+                queued("tb_1", "20"),
+                finished("tb_1"),
+                queued("tb_2", "5"),
+                finished("tb_3"),
+            ],
+            &mut filter,
+        );
+        assert!(!filter.filter_tb("db_1", "tb_1"));
+        assert!(!filter.filter_tb("db_1", "tb_3"));
+
+        let task = backfill.next_task(&mut filter).unwrap();
+        assert_eq!(task.tb, "tb_2");
+        assert_eq!(task.start_value, ColValue::None);
+        assert_eq!(task.resume_value, Some("5".to_string()));
+
+        // the resume value is only used by the first chunk
+        let chunk_id = CdcBackfill::new_chunk_id();
+        let rows = vec![row(RowType::Insert, 8, "a")];
+        let id_cols = vec!["id".to_string()];
+        backfill.open_chunk(&chunk_id, &id_cols, "id", rows, ColValue::Long(8), false);
+        backfill.on_signal(&signal(SIGNAL_HIGH_WATERMARK, &chunk_id), &Position::None);
+        let task = backfill.next_task(&mut filter).unwrap();
+        assert_eq!(task.start_value, ColValue::Long(8));
+        assert_eq!(task.resume_value, None);
+    }
+}
@@ -0,0 +1,626 @@
+use std::{
+    cmp,
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::{bail, Context};
+use async_trait::async_trait;
+use dt_common::{
+    config::config_enums::DbType,
+    error::Error,
+    log_info, log_warn,
+    meta::{
+        adaptor::{
+            mysql_col_value_convertor::MysqlColValueConvertor,
+            pg_col_value_convertor::PgColValueConvertor,
+        },
+        col_value::ColValue,
+        mongo::mongo_constant::MongoConstants,
+        mysql::mysql_col_type::MysqlColType,
+        pg::{pg_col_type::PgColType, pg_meta_manager::PgMetaManager, pg_value_type::PgValueType},
+        position::Position,
+        rdb_meta_manager::RdbMetaManager,
+        row_data::RowData,
+        row_type::RowType,
+    },
+    rdb_filter::RdbFilter,
+};
+use mongodb::bson::Bson;
+use serde_json::Value;
+use tokio::sync::mpsc::{self, Sender};
+
+use super::file_reader::{FileReader, FileRecord};
+use crate::{
+    extractor::{base_extractor::BaseExtractor, resumer::cdc_resumer::CdcResumer},
+    sinker::file::{file_manifest::FileManifest, file_writer::OP_COL},
+    Extractor,
+};
+
+/// Loads csv / jsonl / parquet files, values are converted by the column types of target tables.
+pub struct FileExtractor {
+    pub url: String,
+    pub db: String,
+    pub tb: String,
+    pub batch_size: usize,
+    pub base_extractor: BaseExtractor,
+    pub filter: RdbFilter,
+    pub resumer: CdcResumer,
+    // meta of target tables, None if the sinker is not mysql / pg
+    pub meta_manager: Option<RdbMetaManager>,
+    pub sinker_db_type: DbType,
+}
+
+#[async_trait]
+impl Extractor for FileExtractor {
+    async fn extract(&mut self) -> anyhow::Result<()> {
+        log_info!(
+            "FileExtractor starts, url: {}, batch_size: {}",
+            self.url,
+            self.batch_size
+        );
+
+        let (resume_file, resume_offset) = match &self.resumer.checkpoint_position {
+            Position::File { file, offset } => {
+                log_info!("resume from: {}", self.resumer.checkpoint_position);
+                (file.clone(), *offset)
+            }
+            _ => (String::new(), 0),
+        };
+
+        let (root_dir, files) = Self::list_files(&self.url)?;
+        // files are extracted in order, skip the finished ones
+        let start = if resume_file.is_empty() {
+            0
+        } else {
+            match files.iter().position(|file| *file == resume_file) {
+                Some(i) => i,
+                None => bail! {Error::ExtractorError(format!(
+                    "checkpoint file: {} not found in: {}",
+                    resume_file, self.url
+                ))},
+            }
+        };
+        for file in files.into_iter().skip(start) {
+            let offset = if file == resume_file {
+                resume_offset
+            } else {
+                0
+            };
+            self.extract_file(&root_dir, &file, offset).await?;
+        }
+        self.base_extractor.wait_task_finish().await
+    }
+
+    async fn close(&mut self) -> anyhow::Result<()> {
+        if let Some(meta_manager) = &self.meta_manager {
+            meta_manager.close().await?;
+        }
+        Ok(())
+    }
+}
+
+impl FileExtractor {
+    async fn extract_file(
+        &mut self,
+        root_dir: &Path,
+        file: &str,
+        offset: u64,
+    ) -> anyhow::Result<()> {
+        let (schema, tb) = Self::parse_schema_tb(file, &self.db, &self.tb);
+        if schema.is_empty() {
+            bail! {Error::ConfigError(format!(
+                "can not get schema from file: {}, please set db in [extractor]",
+                file
+            ))}
+        }
+
+        if self.filter.filter_tb(&schema, &tb) {
+            log_info!("file filtered: {}, schema: {}, tb: {}", file, schema, tb);
+            return Ok(());
+        }
+        log_info!(
+            "extract file: {}, schema: {}, tb: {}, offset: {}",
+            file,
+            schema,
+            tb,
+            offset
+        );
+
+        // files are read and decompressed in a blocking thread
+        let (sender, mut receiver) = mpsc::channel(1);
+        let path = root_dir.join(file);
+        let batch_size = cmp::max(1, self.batch_size);
+        let read_task =
+            tokio::task::spawn_blocking(move || Self::read_file(path, offset, batch_size, sender));
+
+        while let Some(records) = receiver.recv().await {
+            for (record, offset) in records {
+                let position = Position::File {
+                    file: file.to_string(),
+                    offset,
+                };
+                self.push_record(&schema, &tb, record, position).await?;
+            }
+        }
+        read_task.await?
+    }
+
+    fn read_file(
+        path: PathBuf,
+        offset: u64,
+        batch_size: usize,
+        sender: Sender<Vec<(FileRecord, u64)>>,
+    ) -> anyhow::Result<()> {
+        let mut reader = FileReader::open(&path, offset)?;
+        let mut records = Vec::with_capacity(batch_size);
+        while let Some(record) = reader.read()? {
+            records.push((record, reader.offset));
+            if records.len() >= batch_size
+                && sender.blocking_send(std::mem::take(&mut records)).is_err()
+            {
+                // the extractor has exited
+                return Ok(());
+            }
+        }
+
+        if !records.is_empty() {
+            let _ = sender.blocking_send(records);
+        }
+        Ok(())
+    }
+
+    async fn push_record(
+        &mut self,
+        schema: &str,
+        tb: &str,
+        mut record: FileRecord,
+        position: Position,
+    ) -> anyhow::Result<()> {
+        // files written by the file sinker have the _op column, others are inserts
+        let row_type = match record.remove(OP_COL) {
+            Some(Value::String(op)) => RowType::from_str(&op)?,
+            _ => RowType::Insert,
+        };
+        if self.filter.filter_event(schema, tb, &row_type) {
+            return Ok(());
+        }
+
+        if let Some(ignore_cols) = self.filter.get_ignore_cols(schema, tb) {
+            record.retain(|col, _| !ignore_cols.contains(col));
+        }
+
+        let col_values = self.get_col_values(schema, tb, record).await?;
+        let (before, after) = match row_type {
+            RowType::Insert => (None, Some(col_values)),
+            RowType::Delete => (Some(col_values), None),
+            // the file sinker writes updates changing keys as delete + insert, so the keys
+            // of an update row are unchanged and the row is located by them
+            RowType::Update => {
+                self.check_update(schema, tb).await?;
+                (Some(col_values.clone()), Some(col_values))
+            }
+        };
+        let row_data = RowData::new(schema.into(), tb.into(), row_type, before, after);
+        self.base_extractor.push_row(row_data, position).await
+    }
+
+    async fn get_col_values(
+        &mut self,
+        schema: &str,
+        tb: &str,
+        record: FileRecord,
+    ) -> anyhow::Result<HashMap<String, ColValue>> {
+        if self.sinker_db_type == DbType::Mongo {
+            return Self::get_mongo_col_values(record);
+        }
+
+        let meta_manager = match self.meta_manager.as_mut() {
+            Some(meta_manager) => meta_manager,
+            None => {
+                return Ok(record
+                    .into_iter()
+                    .map(|(col, value)| (col, Self::json_to_col_value(value)))
+                    .collect())
+            }
+        };
+
+        // rows are routed after extracted, get target meta by the routed names
+        let router = &self.base_extractor.router;
+        let (dst_schema, dst_tb) = router.get_tb_map(schema, tb);
+        let col_map = router.get_col_map(schema, tb);
+        let get_dst_col = |col: &str| -> String {
+            col_map
+                .and_then(|m| m.get(col))
+                .map(|dst_col| dst_col.to_owned())
+                .unwrap_or_else(|| col.to_string())
+        };
+
+        let mut col_values = HashMap::new();
+        if let Some(mysql_meta_manager) = meta_manager.mysql_meta_manager.as_mut() {
+            let tb_meta = mysql_meta_manager.get_tb_meta(dst_schema, dst_tb).await?;
+            for (col, value) in record {
+                let dst_col = get_dst_col(&col);
+                let col_type = match tb_meta.col_type_map.get(&dst_col) {
+                    Some(col_type) => col_type,
+                    None => return Self::col_not_found(&dst_col, dst_schema, dst_tb),
+                };
+                let col_value = Self::to_mysql_col_value(col_type, value)
+                    .with_context(|| format!("invalid value of column: {}", col))?;
+                col_values.insert(col, col_value);
+            }
+        } else if let Some(pg_meta_manager) = meta_manager.pg_meta_manager.as_mut() {
+            let tb_meta = pg_meta_manager
+                .get_tb_meta(dst_schema, dst_tb)
+                .await?
+                .clone();
+            for (col, value) in record {
+                let dst_col = get_dst_col(&col);
+                let col_type = match tb_meta.col_type_map.get(&dst_col) {
+                    Some(col_type) => col_type,
+                    None => return Self::col_not_found(&dst_col, dst_schema, dst_tb),
+                };
+                let col_value = Self::to_pg_col_value(col_type, value, pg_meta_manager)
+                    .with_context(|| format!("invalid value of column: {}", col))?;
+                col_values.insert(col, col_value);
+            }
+        }
+        Ok(col_values)
+    }
+
+    /// Update rows keep only the after image, which locates the target row by all cols
+    /// if the target table has no primary / unique key and matches nothing.
+    async fn check_update(&mut self, schema: &str, tb: &str) -> anyhow::Result<()> {
+        let meta_manager = match self.meta_manager.as_mut() {
+            Some(meta_manager) => meta_manager,
+            None => return Ok(()),
+        };
+
+        let (dst_schema, dst_tb) = self.base_extractor.router.get_tb_map(schema, tb);
+        let has_key = if let Some(mysql_meta_manager) = meta_manager.mysql_meta_manager.as_mut() {
+            let tb_meta = mysql_meta_manager.get_tb_meta(dst_schema, dst_tb).await?;
+            !tb_meta.basic.key_map.is_empty()
+        } else if let Some(pg_meta_manager) = meta_manager.pg_meta_manager.as_mut() {
+            let tb_meta = pg_meta_manager.get_tb_meta(dst_schema, dst_tb).await?;
+            !tb_meta.basic.key_map.is_empty()
+        } else {
+            true
+        };
+
+        if !has_key {
+            bail! {Error::ExtractorError(format!(
+                "update rows can not be loaded into table: {}.{} without primary / unique key",
+                dst_schema, dst_tb
+            ))}
+        }
+        Ok(())
+    }
+
+    fn to_mysql_col_value(col_type: &MysqlColType, value: Value) -> anyhow::Result<ColValue> {
+        let text = match Self::json_to_text(value) {
+            Some(text) => text,
+            None => return Ok(ColValue::None),
+        };
+
+        match col_type {
+            MysqlColType::Binary { .. }
+            | MysqlColType::VarBinary { .. }
+            | MysqlColType::TinyBlob
+            | MysqlColType::MediumBlob
+            | MysqlColType::Blob
+            | MysqlColType::LongBlob => Ok(ColValue::Blob(Self::text_to_bytes(text)?)),
+
+            // bool values from jsonl / parquet files
+            MysqlColType::TinyInt { .. }
+            | MysqlColType::SmallInt { .. }
+            | MysqlColType::MediumInt { .. }
+            | MysqlColType::Int { .. }
+            | MysqlColType::BigInt { .. }
+            | MysqlColType::Bit => {
+                let text = match text.as_str() {
+                    "true" => "1",
+                    "false" => "0",
+                    _ => &text,
+                };
+                MysqlColValueConvertor::from_str(col_type, text)
+            }
+
+            _ => MysqlColValueConvertor::from_str(col_type, &text),
+        }
+    }
+
+    fn to_pg_col_value(
+        col_type: &PgColType,
+        value: Value,
+        meta_manager: &mut PgMetaManager,
+    ) -> anyhow::Result<ColValue> {
+        let text = match Self::json_to_text(value) {
+            Some(text) => text,
+            None => return Ok(ColValue::None),
+        };
+
+        // PgColValueConvertor only takes "t" as true, which is the text format of wal
+        if matches!(col_type.value_type, PgValueType::Boolean) && !col_type.is_array() {
+            let value = matches!(
+                text.to_lowercase().as_str(),
+                "t" | "true" | "1" | "y" | "yes" | "on"
+            );
+            return Ok(ColValue::Bool(value));
+        }
+        PgColValueConvertor::from_str(col_type, &text, meta_manager)
+    }
+
+    /// Mongo rows are built from the doc column written by the file sinker, or the whole row.
+    fn get_mongo_col_values(mut record: FileRecord) -> anyhow::Result<HashMap<String, ColValue>> {
+        let value = match record.remove(MongoConstants::DOC) {
+            // doc in csv / parquet files is the relaxed extended JSON text
+            Some(Value::String(v)) => serde_json::from_str(&v)?,
+            Some(v) => v,
+            None => Value::Object(record),
+        };
+
+        match Bson::try_from(value)? {
+            Bson::Document(doc) => Ok(HashMap::from([(
+                MongoConstants::DOC.to_string(),
+                ColValue::MongoDoc(doc),
+            )])),
+            _ => bail! {Error::ExtractorError(
+                "mongo document should be a json object".into()
+            )},
+        }
+    }
+
+    /// Values of sinkers without meta, like kafka, redis and file.
+    fn json_to_col_value(value: Value) -> ColValue {
+        match value {
+            Value::Null => ColValue::None,
+            Value::Bool(v) => ColValue::Bool(v),
+            Value::String(v) => ColValue::String(v),
+            Value::Number(v) => {
+                if let Some(v) = v.as_i64() {
+                    ColValue::LongLong(v)
+                } else if let Some(v) = v.as_u64() {
+                    ColValue::UnsignedLongLong(v)
+                } else {
+                    ColValue::Double(v.as_f64().unwrap_or_default())
+                }
+            }
+            v => ColValue::Json3(v),
+        }
+    }
+
+    fn json_to_text(value: Value) -> Option<String> {
+        match value {
+            Value::Null => None,
+            Value::String(v) => Some(v),
+            v => Some(v.to_string()),
+        }
+    }
+
+    /// Binary values are in the hex format of postgres bytea, like: \x0102, or raw strings.
+    fn text_to_bytes(text: String) -> anyhow::Result<Vec<u8>> {
+        if let Some(hex_str) = text.strip_prefix("\\x") {
+            return hex::decode(hex_str).with_context(|| format!("invalid hex value: {}", text));
+        }
+        Ok(text.into_bytes())
+    }
+
+    fn col_not_found<T>(col: &str, schema: &str, tb: &str) -> anyhow::Result<T> {
+        bail! {Error::ExtractorError(format!(
+            "column: {} not found in target table: {}.{}",
+            col, schema, tb
+        ))}
+    }
+
+    /// Lists files to extract, returns the root directory and the sorted paths relative to it.
+    fn list_files(url: &str) -> anyhow::Result<(PathBuf, Vec<String>)> {
+        let path = Path::new(url);
+        if path.is_file() {
+            let root_dir = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+            return Ok((root_dir, vec![file_name.to_string()]));
+        }
+
+        if !path.is_dir() {
+            bail! {Error::ConfigError(format!("file or directory not found: {}", url))}
+        }
+
+        let mut files = Vec::new();
+        let mut dirs = vec![path.to_path_buf()];
+        while let Some(dir) = dirs.pop() {
+            for entry in fs::read_dir(&dir)? {
+                let entry_path = entry?.path();
+                if entry_path.is_dir() {
+                    dirs.push(entry_path);
+                    continue;
+                }
+
+                let file = entry_path
+                    .strip_prefix(path)?
+                    .components()
+                    .map(|i| i.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                if file == FileManifest::FILE_NAME {
+                    continue;
+                }
+                if FileReader::get_format(&file).is_none() {
+                    log_warn!("unsupported file ignored: {}", file);
+                    continue;
+                }
+                files.push(file);
+            }
+        }
+        Self::sort_files(url, &mut files)?;
+        Ok((path.to_path_buf(), files))
+    }
+
+    /// Files in manifest.jsonl are ordered as they were closed, which keeps the order of
+    /// changes across tables. Others follow by directories and part ids, e.g. part-2 before
+    /// part-10, then by names.
+    fn sort_files(root_dir: &str, files: &mut [String]) -> anyhow::Result<()> {
+        let manifest_order: HashMap<String, usize> = FileManifest::load(root_dir)?
+            .into_iter()
+            .enumerate()
+            .map(|(i, entry)| (entry.file, i))
+            .collect();
+
+        files.sort_by_cached_key(|file| {
+            let (dir, file_name) = file.rsplit_once('/').unwrap_or(("", file));
+            (
+                manifest_order.get(file).copied().unwrap_or(usize::MAX),
+                dir.to_string(),
+                FileManifest::parse_part_id(file_name),
+                file.clone(),
+            )
+        });
+        Ok(())
+    }
+
+    /// db_1/tb_1/part-0.csv -> (db_1, tb_1), db_1/tb_1.csv -> (db_1, tb_1), tb_1.csv -> (db, tb_1),
+    /// db / tb override the names from the path if not empty.
+    fn parse_schema_tb(file: &str, db: &str, tb: &str) -> (String, String) {
+        let parts: Vec<&str> = file.split('/').collect();
+        let dirs = &parts[..parts.len() - 1];
+        let file_stem = parts[parts.len() - 1].split('.').next().unwrap_or_default();
+        let (mut schema, mut table) = match dirs.len() {
+            0 => ("", file_stem),
+            1 => (dirs[0], file_stem),
+            n => (dirs[n - 2], dirs[n - 1]),
+        };
+
+        if !db.is_empty() {
+            schema = db;
+        }
+        if !tb.is_empty() {
+            table = tb;
+        }
+        (schema.to_string(), table.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::sinker::file::file_manifest::FileManifestEntry;
+
+    #[test]
+    fn test_parse_schema_tb() {
+        let parse = |file: &str, db: &str, tb: &str| FileExtractor::parse_schema_tb(file, db, tb);
+        assert_eq!(
+            parse("export/db_1/tb_1/part-0.csv.gz", "", ""),
+            ("db_1".into(), "tb_1".into())
+        );
+        assert_eq!(
+            parse("db_1/tb_1.parquet", "", ""),
+            ("db_1".into(), "tb_1".into())
+        );
+        assert_eq!(parse("tb_1.jsonl", "", ""), ("".into(), "tb_1".into()));
+        assert_eq!(
+            parse("tb_1.jsonl", "db_2", "tb_2"),
+            ("db_2".into(), "tb_2".into())
+        );
+    }
+
+    #[test]
+    fn test_list_files() {
+        let root_dir =
+            std::env::temp_dir().join(format!("ape_dts_list_files_{}", std::process::id()));
+        let root_dir = root_dir.to_str().unwrap();
+        for tb in ["tb_1", "tb_2"] {
+            fs::create_dir_all(format!("{}/db_1/{}", root_dir, tb)).unwrap();
+        }
+        for part_id in 0..12 {
+            fs::write(format!("{}/db_1/tb_1/part-{}.csv", root_dir, part_id), "").unwrap();
+        }
+        fs::write(format!("{}/db_1/tb_2/part-0.csv", root_dir), "").unwrap();
+        fs::write(format!("{}/db_1/tb_2/other.txt", root_dir), "").unwrap();
+
+        // without manifest, by part ids
+        let (_, files) = FileExtractor::list_files(root_dir).unwrap();
+        let mut expected: Vec<String> = (0..12)
+            .map(|part_id| format!("db_1/tb_1/part-{}.csv", part_id))
+            .collect();
+        expected.push("db_1/tb_2/part-0.csv".into());
+        assert_eq!(files, expected);
+
+        // by manifest order, files not in manifest follow
+        let mut manifest = FileManifest::new(root_dir);
+        for file in [
+            "db_1/tb_1/part-9.csv",
+            "db_1/tb_2/part-0.csv",
+            "db_1/tb_1/part-10.csv",
+        ] {
+            let entry = FileManifestEntry {
+                file: file.into(),
+                schema: "db_1".into(),
+                tb: String::new(),
+                row_count: 0,
+                file_bytes: 0,
+                start_position: Position::None,
+                end_position: Position::None,
+            };
+            manifest.append(&entry).unwrap();
+        }
+        let (_, files) = FileExtractor::list_files(root_dir).unwrap();
+        assert_eq!(
+            files[..4],
+            [
+                "db_1/tb_1/part-9.csv",
+                "db_1/tb_2/part-0.csv",
+                "db_1/tb_1/part-10.csv",
+                "db_1/tb_1/part-0.csv",
+            ]
+        );
+        assert_eq!(files.last().unwrap(), "db_1/tb_1/part-11.csv");
+        assert_eq!(files.len(), 13);
+        fs::remove_dir_all(root_dir).unwrap();
+    }
+
+    #[test]
+    fn test_to_mysql_col_value() {
+        let to_value = FileExtractor::to_mysql_col_value;
+        assert_eq!(
+            to_value(&MysqlColType::Blob, json!("\\x0102")).unwrap(),
+            ColValue::Blob(vec![1, 2])
+        );
+        assert_eq!(
+            to_value(&MysqlColType::TinyInt { unsigned: false }, json!(true)).unwrap(),
+            ColValue::Tiny(1)
+        );
+        assert_eq!(
+            to_value(&MysqlColType::BigInt { unsigned: true }, json!(u64::MAX)).unwrap(),
+            ColValue::UnsignedLongLong(u64::MAX)
+        );
+        assert_eq!(
+            to_value(&MysqlColType::Json, json!({"a": 1})).unwrap(),
+            ColValue::Json2(r#"{"a":1}"#.into())
+        );
+        assert_eq!(
+            to_value(&MysqlColType::Int { unsigned: false }, Value::Null).unwrap(),
+            ColValue::None
+        );
+    }
+
+    #[test]
+    fn test_get_mongo_col_values() {
+        let record = json!({"_id": {"$oid": "65e5b5d8f1d2c3a4b5c6d7e8"}, "name": "a"});
+        let col_values =
+            FileExtractor::get_mongo_col_values(record.as_object().unwrap().clone()).unwrap();
+        let doc = match col_values.get(MongoConstants::DOC) {
+            Some(ColValue::MongoDoc(doc)) => doc,
+            _ => panic!("doc not found"),
+        };
+        assert!(doc.get_object_id(MongoConstants::ID).is_ok());
+        assert_eq!(doc.get_str("name").unwrap(), "a");
+
+        let record = json!({"doc": r#"{"_id": 1}"#, "diff_doc": null});
+        let col_values =
+            FileExtractor::get_mongo_col_values(record.as_object().unwrap().clone()).unwrap();
+        assert_eq!(col_values.len(), 1);
+    }
+}
@@ -0,0 +1,350 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Read},
+    path::Path,
+};
+
+use anyhow::{bail, Context};
+use dt_common::{
+    config::config_enums::{FileCompressionEnum, FileFormatEnum},
+    error::Error,
+};
+use flate2::read::MultiGzDecoder;
+use parquet::{
+    file::reader::SerializedFileReader,
+    record::{reader::RowIter, Field},
+};
+use serde_json::{Map, Value};
+
+/// A row read from a file, csv values are strings or null.
+pub type FileRecord = Map<String, Value>;
+
+/// Reads rows from a csv / jsonl / parquet file, in the formats written by the file sinker.
+pub struct FileReader {
+    // csv / jsonl: uncompressed bytes read, parquet: rows read
+    pub offset: u64,
+    decoder: FileDecoder,
+}
+
+enum FileDecoder {
+    Csv {
+        reader: Box<dyn BufRead>,
+        header: Vec<String>,
+    },
+    Jsonl {
+        reader: Box<dyn BufRead>,
+    },
+    Parquet {
+        rows: RowIter<'static>,
+    },
+}
+
+impl FileReader {
+    /// Opens the file and skips to offset, which is a position of a row read before.
+    pub fn open(path: &Path, offset: u64) -> anyhow::Result<Self> {
+        let (format, compression) = match Self::get_format(&path.to_string_lossy()) {
+            Some(v) => v,
+            None => bail! {Error::ExtractorError(format!(
+                "unsupported file: {}",
+                path.display()
+            ))},
+        };
+
+        let file =
+            File::open(path).with_context(|| format!("failed to open file: {}", path.display()))?;
+        let decoder = match format {
+            FileFormatEnum::Parquet => {
+                let reader = SerializedFileReader::new(file)
+                    .with_context(|| format!("invalid parquet file: {}", path.display()))?;
+                FileDecoder::Parquet {
+                    rows: RowIter::from_file_into(Box::new(reader)),
+                }
+            }
+
+            _ => {
+                let reader: Box<dyn BufRead> = match compression {
+                    FileCompressionEnum::Gzip => {
+                        Box::new(BufReader::new(MultiGzDecoder::new(file)))
+                    }
+                    FileCompressionEnum::Zstd => {
+                        Box::new(BufReader::new(zstd::stream::read::Decoder::new(file)?))
+                    }
+                    _ => Box::new(BufReader::new(file)),
+                };
+                if format == FileFormatEnum::Csv {
+                    FileDecoder::Csv {
+                        reader,
+                        header: Vec::new(),
+                    }
+                } else {
+                    FileDecoder::Jsonl { reader }
+                }
+            }
+        };
+
+        let mut me = Self { offset: 0, decoder };
+        me.seek(offset)?;
+        Ok(me)
+    }
+
+    /// part-0.csv.gz -> (csv, gzip), returns None for unsupported files.
+    pub fn get_format(file_name: &str) -> Option<(FileFormatEnum, FileCompressionEnum)> {
+        let (name, compression) = if let Some(name) = file_name.strip_suffix(".gz") {
+            (name, FileCompressionEnum::Gzip)
+        } else if let Some(name) = file_name.strip_suffix(".zst") {
+            (name, FileCompressionEnum::Zstd)
+        } else {
+            (file_name, FileCompressionEnum::None)
+        };
+
+        if name.ends_with(".csv") {
+            Some((FileFormatEnum::Csv, compression))
+        } else if name.ends_with(".jsonl") {
+            Some((FileFormatEnum::Jsonl, compression))
+        } else if name.ends_with(".parquet") && compression == FileCompressionEnum::None {
+            Some((FileFormatEnum::Parquet, compression))
+        } else {
+            None
+        }
+    }
+
+    pub fn read(&mut self) -> anyhow::Result<Option<FileRecord>> {
+        match &mut self.decoder {
+            FileDecoder::Csv { reader, header } => {
+                let line = match Self::read_csv_line(reader.as_mut(), &mut self.offset)? {
+                    Some(line) => line,
+                    None => return Ok(None),
+                };
+
+                let fields = Self::parse_csv_line(&line);
+                if fields.len() != header.len() {
+                    bail! {Error::ExtractorError(format!(
+                        "csv row has {} fields while the header has {}, offset: {}",
+                        fields.len(),
+                        header.len(),
+                        self.offset
+                    ))}
+                }
+
+                let mut record = FileRecord::new();
+                for (name, field) in header.iter().zip(fields) {
+                    let value = match field {
+                        Some(v) => Value::String(v),
+                        None => Value::Null,
+                    };
+                    record.insert(name.clone(), value);
+                }
+                Ok(Some(record))
+            }
+
+            FileDecoder::Jsonl { reader } => loop {
+                let mut line = String::new();
+                let n = reader.read_line(&mut line)?;
+                if n == 0 {
+                    return Ok(None);
+                }
+                self.offset += n as u64;
+
+                if !line.trim().is_empty() {
+                    let record = serde_json::from_str(line.trim_end())
+                        .with_context(|| format!("invalid json line, offset: {}", self.offset))?;
+                    return Ok(Some(record));
+                }
+            },
+
+            FileDecoder::Parquet { rows } => {
+                let row = match rows.next() {
+                    Some(row) => row?,
+                    None => return Ok(None),
+                };
+                self.offset += 1;
+
+                let mut record = FileRecord::new();
+                for (name, field) in row.get_column_iter() {
+                    record.insert(name.clone(), Self::field_to_json(field));
+                }
+                Ok(Some(record))
+            }
+        }
+    }
+
+    fn seek(&mut self, offset: u64) -> anyhow::Result<()> {
+        match &mut self.decoder {
+            FileDecoder::Csv { reader, header } => {
+                if let Some(line) = Self::read_csv_line(reader.as_mut(), &mut self.offset)? {
+                    *header = Self::parse_csv_line(&line)
+                        .into_iter()
+                        .map(|i| i.unwrap_or_default())
+                        .collect();
+                }
+                Self::skip_bytes(reader.as_mut(), &mut self.offset, offset)
+            }
+
+            FileDecoder::Jsonl { reader } => {
+                Self::skip_bytes(reader.as_mut(), &mut self.offset, offset)
+            }
+
+            FileDecoder::Parquet { rows } => {
+                while self.offset < offset {
+                    if rows.next().is_none() {
+                        break;
+                    }
+                    self.offset += 1;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn skip_bytes(
+        reader: &mut dyn BufRead,
+        offset: &mut u64,
+        to_offset: u64,
+    ) -> anyhow::Result<()> {
+        if to_offset > *offset {
+            *offset += io::copy(&mut reader.take(to_offset - *offset), &mut io::sink())?;
+        }
+        Ok(())
+    }
+
+    /// Reads a csv record, which may have line breaks in quoted fields. Empty lines are skipped.
+    fn read_csv_line(reader: &mut dyn BufRead, offset: &mut u64) -> anyhow::Result<Option<String>> {
+        let mut line = String::new();
+        loop {
+            let n = reader.read_line(&mut line)?;
+            *offset += n as u64;
+            if n == 0 {
+                break;
+            }
+            // an odd count of quotes means the record continues in the next line
+            if line.matches('"').count() % 2 == 0 {
+                if line.trim().is_empty() {
+                    line.clear();
+                    continue;
+                }
+                break;
+            }
+        }
+
+        if line.is_empty() {
+            return Ok(None);
+        }
+        let len = line.trim_end_matches(['\r', '\n']).len();
+        line.truncate(len);
+        Ok(Some(line))
+    }
+
+    /// NULL is an empty field, an empty string is "".
+    fn parse_csv_line(line: &str) -> Vec<Option<String>> {
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let (mut quoted, mut in_quotes) = (false, false);
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            if in_quotes {
+                if c != '"' {
+                    field.push(c);
+                } else if chars.peek() == Some(&'"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            } else if c == '"' {
+                in_quotes = true;
+                quoted = true;
+            } else if c == ',' {
+                fields.push(Self::to_csv_field(field, quoted));
+                field = String::new();
+                quoted = false;
+            } else {
+                field.push(c);
+            }
+        }
+        fields.push(Self::to_csv_field(field, quoted));
+        fields
+    }
+
+    fn to_csv_field(field: String, quoted: bool) -> Option<String> {
+        if field.is_empty() && !quoted {
+            None
+        } else {
+            Some(field)
+        }
+    }
+
+    fn field_to_json(field: &Field) -> Value {
+        match field {
+            // same as the file sinker, binary values are in the hex format of postgres bytea
+            Field::Bytes(v) => Value::String(format!("\\x{}", hex::encode(v.data()))),
+            _ => field.to_json_value(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io::Write};
+
+    use flate2::{write::GzEncoder, Compression};
+    use serde_json::json;
+
+    use super::*;
+
+    fn read_all(path: &Path, offset: u64) -> Vec<(Value, u64)> {
+        let mut reader = FileReader::open(path, offset).unwrap();
+        let mut records = Vec::new();
+        while let Some(record) = reader.read().unwrap() {
+            records.push((Value::Object(record), reader.offset));
+        }
+        records
+    }
+
+    #[test]
+    fn test_get_format() {
+        assert_eq!(
+            FileReader::get_format("db_1/tb_1/part-0.csv.gz"),
+            Some((FileFormatEnum::Csv, FileCompressionEnum::Gzip))
+        );
+        assert_eq!(
+            FileReader::get_format("part-0.jsonl.zst"),
+            Some((FileFormatEnum::Jsonl, FileCompressionEnum::Zstd))
+        );
+        assert_eq!(
+            FileReader::get_format("part-0.parquet"),
+            Some((FileFormatEnum::Parquet, FileCompressionEnum::None))
+        );
+        assert_eq!(FileReader::get_format("part-0.parquet.gz"), None);
+        assert_eq!(FileReader::get_format("manifest.json"), None);
+    }
+
+    #[test]
+    fn test_read_csv() {
+        let dir = std::env::temp_dir().join(format!("ape_dts_file_reader_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("tb_1.csv.gz");
+        let mut encoder = GzEncoder::new(File::create(&path).unwrap(), Compression::default());
+        encoder
+            .write_all(b"_op,id,name\ninsert,1,\"a,\"\"b\"\"\nc\"\n\ndelete,2,\"\"\ninsert,3,\n")
+            .unwrap();
+        encoder.finish().unwrap();
+
+        let records = read_all(&path, 0);
+        assert_eq!(
+            records,
+            vec![
+                (
+                    json!({"_op": "insert", "id": "1", "name": "a,\"b\"\nc"}),
+                    33
+                ),
+                (json!({"_op": "delete", "id": "2", "name": ""}), 46),
+                (json!({"_op": "insert", "id": "3", "name": null}), 56),
+            ]
+        );
+
+        // resume from the offset of the first row
+        assert_eq!(read_all(&path, 33), records[1..].to_vec());
+        assert_eq!(read_all(&path, 56), Vec::new());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
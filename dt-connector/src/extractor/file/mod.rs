@@ -0,0 +1,2 @@
+pub mod file_extractor;
+pub mod file_reader;
@@ -1,9 +1,11 @@
 use crate::extractor::resumer::cdc_resumer::CdcResumer;
+use crate::tls_util::TlsUtil;
 use crate::{extractor::base_extractor::BaseExtractor, Extractor};
 use anyhow::Context;
 use async_trait::async_trait;
 use std::sync::{Arc, Mutex};
 
+use dt_common::config::tls_config::{SaslConfig, TlsConfig};
 use dt_common::log_info;
 use dt_common::meta::{avro::avro_converter::AvroConverter, position::Position, syncer::Syncer};
 use rdkafka::{
@@ -19,6 +21,8 @@ pub struct KafkaExtractor {
     pub partition: i32,
     pub offset: i64,
     pub ack_interval_secs: u64,
+    pub tls: TlsConfig,
+    pub sasl: SaslConfig,
     pub avro_converter: AvroConverter,
     pub syncer: Arc<Mutex<Syncer>>,
     pub resumer: CdcResumer,
@@ -69,6 +73,7 @@ impl KafkaExtractor {
         config.set("group.id", &self.group);
         config.set("auto.offset.reset", "latest");
         config.set("session.timeout.ms", "10000");
+        TlsUtil::set_rdkafka_security(&mut config, &self.tls, &self.sasl);
 
         let consumer: StreamConsumer = config.create().unwrap();
         // only support extract data from one topic, one partition
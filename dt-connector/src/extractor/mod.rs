@@ -1,6 +1,8 @@
 pub mod base_check_extractor;
 pub mod base_extractor;
+pub mod cdc_backfill;
 pub mod extractor_monitor;
+pub mod file;
 pub mod foxlake;
 pub mod kafka;
 pub mod mongo;
@@ -8,3 +10,4 @@ pub mod mysql;
 pub mod pg;
 pub mod redis;
 pub mod resumer;
+pub mod snapshot_conn_pool;
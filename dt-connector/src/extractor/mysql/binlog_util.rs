@@ -1,7 +1,8 @@
-use dt_common::{log_info, utils::time_util::TimeUtil};
+use anyhow::bail;
+use dt_common::{error::Error, log_info, utils::time_util::TimeUtil};
 use futures::TryStreamExt;
 use mysql_binlog_connector_rust::{binlog_client::BinlogClient, event::event_data::EventData};
-use sqlx::{MySql, Pool, Row};
+use sqlx::{MySql, MySqlConnection, Pool, Row};
 
 pub struct BinlogUtil {}
 
@@ -74,6 +75,39 @@ impl BinlogUtil {
         }
     }
 
+    /// Get the current (binlog_filename, binlog_position, executed_gtid_set) of the server.
+    pub async fn get_binlog_status(
+        conn: &mut MySqlConnection,
+    ) -> anyhow::Result<(String, u32, String)> {
+        // SHOW MASTER STATUS was removed in mysql 8.4
+        let row = match sqlx::query("SHOW MASTER STATUS")
+            .fetch_optional(&mut *conn)
+            .await
+        {
+            Ok(row) => row,
+            Err(_) => {
+                sqlx::query("SHOW BINARY LOG STATUS")
+                    .fetch_optional(&mut *conn)
+                    .await?
+            }
+        };
+
+        if let Some(row) = row {
+            let binlog_filename: String = row.try_get("File")?;
+            let binlog_position: u64 = row.try_get("Position")?;
+            let gtid_set: String = row.try_get("Executed_Gtid_Set")?;
+            Ok((
+                binlog_filename,
+                binlog_position as u32,
+                gtid_set.replace('\n', ""),
+            ))
+        } else {
+            bail! {Error::ExtractorError(
+                "failed to get binlog status, binlog may be disabled".into()
+            )}
+        }
+    }
+
     async fn get_binary_logs(conn_pool: &Pool<MySql>) -> anyhow::Result<Vec<String>> {
         let mut binlogs = Vec::new();
         let sql = "SHOW BINARY LOGS";
@@ -3,3 +3,4 @@ pub mod mysql_cdc_extractor;
 pub mod mysql_check_extractor;
 pub mod mysql_snapshot_extractor;
 pub mod mysql_struct_extractor;
+pub mod mysql_tls_tunnel;
@@ -12,9 +12,14 @@ use std::{
 };
 
 use dt_common::meta::{
-    adaptor::mysql_col_value_convertor::MysqlColValueConvertor, col_value::ColValue,
-    dt_data::DtData, mysql::mysql_meta_manager::MysqlMetaManager, position::Position,
-    row_data::RowData, row_type::RowType, syncer::Syncer,
+    adaptor::{mysql_col_value_convertor::MysqlColValueConvertor, sqlx_ext::SqlxMysqlExt},
+    col_value::ColValue,
+    dt_data::DtData,
+    mysql::mysql_meta_manager::MysqlMetaManager,
+    position::Position,
+    row_data::RowData,
+    row_type::RowType,
+    syncer::Syncer,
 };
 use mysql_binlog_connector_rust::{
     binlog_client::BinlogClient,
@@ -26,16 +31,25 @@ use mysql_binlog_connector_rust::{
 };
 
 use dt_common::{
-    config::config_enums::DbType, error::Error, log_error, log_info, rdb_filter::RdbFilter,
+    config::{config_enums::DbType, tls_config::TlsConfig},
+    error::Error,
+    log_error, log_info,
+    rdb_filter::RdbFilter,
     utils::time_util::TimeUtil,
 };
 
 use crate::{
     close_conn_pool,
     extractor::{
-        base_extractor::BaseExtractor, mysql::binlog_util::BinlogUtil,
+        base_extractor::BaseExtractor,
+        cdc_backfill::{
+            BackfillRequests, CdcBackfill, SIGNAL_BACKFILL, SIGNAL_HIGH_WATERMARK,
+            SIGNAL_LOW_WATERMARK,
+        },
+        mysql::{binlog_util::BinlogUtil, mysql_tls_tunnel::MysqlTlsTunnel},
         resumer::cdc_resumer::CdcResumer,
     },
+    rdb_query_builder::RdbQueryBuilder,
     Extractor,
 };
 
@@ -45,6 +59,7 @@ pub struct MysqlCdcExtractor {
     pub conn_pool: Pool<MySql>,
     pub filter: RdbFilter,
     pub url: String,
+    pub tls: TlsConfig,
     pub binlog_filename: String,
     pub binlog_position: u32,
     pub server_id: u64,
@@ -54,6 +69,8 @@ pub struct MysqlCdcExtractor {
     pub heartbeat_tb: String,
     pub syncer: Arc<Mutex<Syncer>>,
     pub resumer: CdcResumer,
+    pub backfill: Option<CdcBackfill>,
+    pub backfill_requests: BackfillRequests,
 }
 
 struct Context {
@@ -67,10 +84,18 @@ const QUERY_BEGIN: &str = "BEGIN";
 #[async_trait]
 impl Extractor for MysqlCdcExtractor {
     async fn extract(&mut self) -> anyhow::Result<()> {
+        // the binlog client can not connect with tls, it connects through a local tls tunnel
+        let tunnel = if self.tls.is_enabled() {
+            Some(MysqlTlsTunnel::start(&self.url, &self.tls).await?)
+        } else {
+            None
+        };
+        let binlog_url = tunnel.as_ref().map_or(self.url.clone(), |t| t.url.clone());
+
         if self.base_extractor.time_filter.start_timestamp > 0 {
             self.binlog_filename = BinlogUtil::find_last_binlog_before_timestamp(
                 self.base_extractor.time_filter.start_timestamp,
-                &self.url,
+                &binlog_url,
                 self.server_id,
                 &self.conn_pool,
             )
@@ -99,7 +124,7 @@ impl Extractor for MysqlCdcExtractor {
             self.heartbeat_interval_secs,
             self.heartbeat_tb
         );
-        self.extract_internal().await?;
+        self.extract_internal(&binlog_url).await?;
         self.base_extractor.wait_task_finish().await
     }
 
@@ -110,9 +135,9 @@ impl Extractor for MysqlCdcExtractor {
 }
 
 impl MysqlCdcExtractor {
-    async fn extract_internal(&mut self) -> anyhow::Result<()> {
+    async fn extract_internal(&mut self, binlog_url: &str) -> anyhow::Result<()> {
         let mut client = BinlogClient {
-            url: self.url.clone(),
+            url: binlog_url.into(),
             binlog_filename: self.binlog_filename.clone(),
             binlog_position: self.binlog_position,
             server_id: self.server_id,
@@ -132,6 +157,9 @@ impl MysqlCdcExtractor {
 
         // start heartbeat
         self.start_heartbeat(self.base_extractor.shut_down.clone())?;
+        self.start_backfill(self.base_extractor.shut_down.clone())?;
+        // continue the backfill interrupted by restart
+        self.backfill_next_chunk().await?;
 
         loop {
             if self.base_extractor.time_filter.ended {
@@ -195,6 +223,14 @@ impl MysqlCdcExtractor {
             EventData::WriteRows(mut w) => {
                 for event in w.rows.iter_mut() {
                     let table_map_event = ctx.table_map_event_map.get(&w.table_id).unwrap();
+                    if self.is_backfill_signal(table_map_event) {
+                        let col_values = self
+                            .parse_row_data(table_map_event, &w.included_columns, event)
+                            .await?;
+                        self.handle_backfill_signal(col_values, &position).await?;
+                        continue;
+                    }
+
                     if self.filter_event(table_map_event, RowType::Insert) {
                         continue;
                     }
@@ -290,6 +326,10 @@ impl MysqlCdcExtractor {
         row_data: RowData,
         position: Position,
     ) -> anyhow::Result<()> {
+        if let Some(backfill) = self.backfill.as_mut() {
+            backfill.on_row(&row_data);
+            backfill.flush_progress(&self.syncer);
+        }
         self.base_extractor.push_row(row_data, position).await
     }
 
@@ -383,6 +423,224 @@ impl MysqlCdcExtractor {
         filtered
     }
 
+    fn is_backfill_signal(&self, table_map_event: &TableMapEvent) -> bool {
+        self.backfill.as_ref().is_some_and(|i| {
+            i.is_signal_tb(&table_map_event.database_name, &table_map_event.table_name)
+        })
+    }
+
+    async fn handle_backfill_signal(
+        &mut self,
+        col_values: HashMap<String, ColValue>,
+        position: &Position,
+    ) -> anyhow::Result<()> {
+        if !self.base_extractor.time_filter.started {
+            return Ok(());
+        }
+
+        let backfill = self.backfill.as_mut().unwrap();
+        let (db, tb) = (backfill.signal_schema.clone(), backfill.signal_tb.clone());
+        let row_data = RowData::new(
+            db.clone(),
+            tb.clone(),
+            RowType::Insert,
+            None,
+            Some(col_values),
+        );
+        let signal = match CdcBackfill::parse_signal(&row_data) {
+            Some(signal) => signal,
+            None => return Ok(()),
+        };
+
+        backfill.flush_progress(&self.syncer);
+        if let Some(rows) = backfill.on_signal(&signal, position) {
+            for row_data in rows {
+                self.base_extractor
+                    .push_row(row_data, position.clone())
+                    .await?;
+            }
+
+            // watermarks are no longer needed
+            let sql = format!(
+                "DELETE FROM `{}`.`{}` WHERE data = ? AND type IN (?, ?)",
+                db, tb
+            );
+            let query: Query<MySql, MySqlArguments> = sqlx::query(&sql)
+                .bind(&signal.data)
+                .bind(SIGNAL_LOW_WATERMARK)
+                .bind(SIGNAL_HIGH_WATERMARK);
+            if let Err(err) = query.execute(&self.conn_pool).await {
+                log_error!("delete backfill watermarks failed: {:?}", err);
+            }
+        }
+
+        self.backfill_next_chunk().await
+    }
+
+    async fn backfill_next_chunk(&mut self) -> anyhow::Result<()> {
+        let (task, tb_meta, order_col) = loop {
+            let task = match self.backfill.as_mut() {
+                Some(backfill) => backfill.next_task(&mut self.filter),
+                None => None,
+            };
+            let task = match task {
+                Some(task) => task,
+                None => return Ok(()),
+            };
+
+            let tb_meta = self
+                .meta_manager
+                .get_tb_meta(&task.schema, &task.tb)
+                .await?
+                .to_owned();
+            if let Some(order_col) = tb_meta.basic.order_col.clone() {
+                break (task, tb_meta, order_col);
+            }
+            // selecting the whole table in one chunk may exhaust memory
+            log_error!(
+                "backfill skipped since tb has no single-column primary / unique key, db: {}, tb: {}",
+                task.schema,
+                task.tb
+            );
+            self.backfill
+                .as_mut()
+                .unwrap()
+                .skip_current_tb(&mut self.filter);
+        };
+
+        let (signal_db, signal_tb, batch_size) = {
+            let backfill = self.backfill.as_ref().unwrap();
+            (
+                backfill.signal_schema.clone(),
+                backfill.signal_tb.clone(),
+                backfill.batch_size,
+            )
+        };
+        let (db, tb) = (&task.schema, &task.tb);
+        let ignore_cols = self.filter.get_ignore_cols(db, tb);
+        let cols_str =
+            RdbQueryBuilder::new_for_mysql(&tb_meta, ignore_cols).build_extract_cols_str()?;
+        let order_col_type = tb_meta.get_col_type(&order_col)?;
+        let start_value = match (&task.start_value, &task.resume_value) {
+            (ColValue::None, Some(value)) => {
+                MysqlColValueConvertor::from_str(order_col_type, value)?
+            }
+            _ => task.start_value.clone(),
+        };
+
+        let chunk_id = CdcBackfill::new_chunk_id();
+        Self::write_signal(
+            &signal_db,
+            &signal_tb,
+            SIGNAL_LOW_WATERMARK,
+            &chunk_id,
+            &self.conn_pool,
+        )
+        .await?;
+
+        let sql1 = format!(
+            "SELECT {} FROM `{}`.`{}` ORDER BY `{}` ASC LIMIT {}",
+            cols_str, db, tb, order_col, batch_size
+        );
+        let sql2 = format!(
+            "SELECT {} FROM `{}`.`{}` WHERE `{}` > ? ORDER BY `{}` ASC LIMIT {}",
+            cols_str, db, tb, order_col, order_col, batch_size
+        );
+        let query = if let ColValue::None = start_value {
+            sqlx::query(&sql1)
+        } else {
+            sqlx::query(&sql2).bind_col_value(Some(&start_value), order_col_type)
+        };
+
+        let mut rows = Vec::new();
+        let mut last_value = start_value.clone();
+        let query_rows = query.fetch_all(&self.conn_pool).await?;
+        for row in query_rows.iter() {
+            last_value = MysqlColValueConvertor::from_query(row, &order_col, order_col_type)?;
+            rows.push(RowData::from_mysql_row(row, &tb_meta, &ignore_cols));
+        }
+        let finished = query_rows.len() < batch_size;
+
+        log_info!(
+            "backfill chunk selected, db: {}, tb: {}, start_value: {}, rows: {}",
+            db,
+            tb,
+            start_value,
+            rows.len()
+        );
+        self.backfill.as_mut().unwrap().open_chunk(
+            &chunk_id,
+            &tb_meta.basic.id_cols,
+            &order_col,
+            rows,
+            last_value,
+            finished,
+        );
+
+        Self::write_signal(
+            &signal_db,
+            &signal_tb,
+            SIGNAL_HIGH_WATERMARK,
+            &chunk_id,
+            &self.conn_pool,
+        )
+        .await
+    }
+
+    fn start_backfill(&mut self, shut_down: Arc<AtomicBool>) -> anyhow::Result<()> {
+        let (db, tb) = match &self.backfill {
+            Some(backfill) => (backfill.signal_schema.clone(), backfill.signal_tb.clone()),
+            None => return Ok(()),
+        };
+
+        // signals are handled before filtering, the signal_tb itself is never synced
+        self.filter.add_ignore_tb(&db, &tb);
+        self.backfill_requests.enable();
+        if let Some(backfill) = self.backfill.as_mut() {
+            backfill.resume(&self.resumer.backfill_positions, &mut self.filter);
+        }
+
+        // backfill requests from the admin server
+        let (requests, conn_pool) = (self.backfill_requests.clone(), self.conn_pool.clone());
+        tokio::spawn(async move {
+            while !shut_down.load(Ordering::Acquire) {
+                for tbs in requests.take() {
+                    if let Err(err) =
+                        Self::write_signal(&db, &tb, SIGNAL_BACKFILL, &tbs, &conn_pool).await
+                    {
+                        log_error!("write backfill signal failed, tbs: {}, {:?}", tbs, err);
+                    }
+                }
+                TimeUtil::sleep_millis(1000).await;
+            }
+        });
+        log_info!("backfill started");
+        Ok(())
+    }
+
+    async fn write_signal(
+        db: &str,
+        tb: &str,
+        signal_type: &str,
+        data: &str,
+        conn_pool: &Pool<MySql>,
+    ) -> anyhow::Result<()> {
+        // ids of watermarks: {chunk_id}_low_watermark, {chunk_id}_high_watermark
+        let id = if signal_type == SIGNAL_BACKFILL {
+            CdcBackfill::new_chunk_id()
+        } else {
+            format!("{}_{}", data, signal_type)
+        };
+        let sql = format!(
+            "INSERT INTO `{}`.`{}` (id, type, data) VALUES (?, ?, ?)",
+            db, tb
+        );
+        let query: Query<MySql, MySqlArguments> =
+            sqlx::query(&sql).bind(id).bind(signal_type).bind(data);
+        query.execute(conn_pool).await?;
+        Ok(())
+    }
+
     fn start_heartbeat(&mut self, shut_down: Arc<AtomicBool>) -> anyhow::Result<()> {
         let db_tb = self.base_extractor.precheck_heartbeat(
             self.heartbeat_interval_secs,
@@ -24,14 +24,17 @@ use dt_common::{
 };
 use futures::TryStreamExt;
 
-use sqlx::{MySql, Pool};
+use sqlx::MySql;
 
 use dt_common::{config::config_enums::DbType, log_info};
 use tokio::task::JoinHandle;
 
 use crate::{
     close_conn_pool,
-    extractor::{base_extractor::BaseExtractor, resumer::snapshot_resumer::SnapshotResumer},
+    extractor::{
+        base_extractor::BaseExtractor, resumer::snapshot_resumer::SnapshotResumer,
+        snapshot_conn_pool::ExtractorConnPool,
+    },
     rdb_query_builder::RdbQueryBuilder,
     rdb_router::RdbRouter,
     Extractor,
@@ -39,7 +42,7 @@ use crate::{
 
 pub struct MysqlSnapshotExtractor {
     pub base_extractor: BaseExtractor,
-    pub conn_pool: Pool<MySql>,
+    pub conn_pool: ExtractorConnPool<MySql>,
     pub meta_manager: MysqlMetaManager,
     pub filter: RdbFilter,
     pub resumer: SnapshotResumer,
@@ -142,7 +145,8 @@ impl MysqlSnapshotExtractor {
         let cols_str = self.build_extract_cols_str(tb_meta)?;
         let sql = format!("SELECT {} FROM `{}`.`{}`", cols_str, self.db, self.tb);
 
-        let mut rows = sqlx::query(&sql).fetch(&self.conn_pool);
+        let mut conn = self.conn_pool.acquire().await?;
+        let mut rows = sqlx::query(&sql).fetch(&mut *conn);
         while let Some(row) = rows.try_next().await.unwrap() {
             let row_data = RowData::from_mysql_row(&row, tb_meta, &ignore_cols);
             self.base_extractor
@@ -181,7 +185,8 @@ impl MysqlSnapshotExtractor {
                 sqlx::query(&sql2).bind_col_value(Some(&start_value_for_bind), order_col_type)
             };
 
-            let mut rows = query.fetch(&self.conn_pool);
+            let mut conn = self.conn_pool.acquire().await?;
+            let mut rows = query.fetch(&mut *conn);
             let mut slice_count = 0usize;
 
             while let Some(row) = rows.try_next().await.unwrap() {
@@ -260,7 +265,8 @@ impl MysqlSnapshotExtractor {
             if let ColValue::None = start_value {
                 let mut slice_count = 0;
                 let query = sqlx::query(&sql1);
-                let mut rows = query.fetch(&self.conn_pool);
+                let mut conn = self.conn_pool.acquire().await?;
+                let mut rows = query.fetch(&mut *conn);
                 while let Some(row) = rows.try_next().await.unwrap() {
                     start_value =
                         MysqlColValueConvertor::from_query(&row, order_col, order_col_type)?;
@@ -306,7 +312,8 @@ impl MysqlSnapshotExtractor {
                         if i < parallel_size - 1 {
                             query = query.bind_col_value(Some(&sub_end_value), &order_col_type);
                         }
-                        let mut rows = query.fetch(&conn_pool);
+                        let mut conn = conn_pool.acquire().await?;
+                        let mut rows = query.fetch(&mut *conn);
 
                         let mut order_col_value = ColValue::None;
                         let mut slice_count = 0;
@@ -1,4 +1,6 @@
+use crate::tls_util::TlsUtil;
 use anyhow::{bail, Ok};
+use dt_common::config::tls_config::TlsConfig;
 use dt_common::error::Error;
 use dt_common::utils::url_util::UrlUtil;
 use dt_common::{log_info, log_warn};
@@ -7,8 +9,17 @@ use tokio_postgres::NoTls;
 use tokio_postgres::SimpleQueryMessage::Row;
 use tokio_postgres::{replication::LogicalReplicationStream, Client};
 
+/// A snapshot exported by CREATE_REPLICATION_SLOT, it can be imported by
+/// SET TRANSACTION SNAPSHOT until the replication connection is dropped.
+pub struct PgExportedSnapshot {
+    pub start_lsn: String,
+    pub snapshot_name: String,
+    _client: Client,
+}
+
 pub struct PgCdcClient {
     pub url: String,
+    pub tls: TlsConfig,
     pub slot_name: String,
     pub pub_name: String,
     pub start_lsn: String,
@@ -17,28 +28,67 @@ pub struct PgCdcClient {
 
 impl PgCdcClient {
     pub async fn connect(&mut self) -> anyhow::Result<(LogicalReplicationStream, String)> {
+        let client = self.connect_client().await?;
+        self.start_replication(&client).await
+    }
+
+    /// Create the publication and recreate the replication slot with an exported snapshot,
+    /// without starting replication. Used by extract_type=snapshot_and_cdc: the snapshot reads
+    /// data exactly at start_lsn, and changes after it are replayed by cdc later.
+    pub async fn create_slot_with_snapshot(&mut self) -> anyhow::Result<PgExportedSnapshot> {
+        let client = self.connect_client().await?;
+        let (_, start_lsn, snapshot_name) = self.prepare_slot(&client, true).await?;
+        Ok(PgExportedSnapshot {
+            start_lsn,
+            snapshot_name,
+            _client: client,
+        })
+    }
+
+    async fn connect_client(&self) -> anyhow::Result<Client> {
         let url_info = UrlUtil::parse(&self.url)?;
         let host = url_info.host_str().unwrap().to_string();
         let port = format!("{}", url_info.port().unwrap());
         let dbname = url_info.path().trim_start_matches('/');
         let username = url_info.username().to_string();
         let password = url_info.password().unwrap().to_string();
-        let conn_info = format!(
+        let mut conn_info = format!(
             "host={} port={} dbname={} user={} password={} replication=database",
             host, port, dbname, username, password
         );
 
-        let (client, connection) = tokio_postgres::connect(&conn_info, NoTls).await?;
-        tokio::spawn(async move {
-            log_info!("postgres replication connection starts",);
-            if let Err(e) = connection.await {
-                log_info!("postgres replication connection drops, error: {}", e);
-            }
-        });
-        self.start_replication(&client).await
+        let client = if self.tls.is_enabled() {
+            // certificate verification is done by the connector, sslmode only forces tls
+            conn_info.push_str(" sslmode=require");
+            let connector = TlsUtil::build_pg_tls_connector(&self.tls)?;
+            let (client, connection) = tokio_postgres::connect(&conn_info, connector).await?;
+            tokio::spawn(async move {
+                log_info!("postgres replication connection starts with tls",);
+                if let Err(e) = connection.await {
+                    log_info!("postgres replication connection drops, error: {}", e);
+                }
+            });
+            client
+        } else {
+            let (client, connection) = tokio_postgres::connect(&conn_info, NoTls).await?;
+            tokio::spawn(async move {
+                log_info!("postgres replication connection starts",);
+                if let Err(e) = connection.await {
+                    log_info!("postgres replication connection drops, error: {}", e);
+                }
+            });
+            client
+        };
+        Ok(client)
     }
 
-    async fn prepare_slot(&self, client: &Client) -> anyhow::Result<(String, String)> {
+    /// Returns (pub_name, start_lsn, snapshot_name), the slot is always recreated to export
+    /// a snapshot since only CREATE_REPLICATION_SLOT can do that.
+    async fn prepare_slot(
+        &self,
+        client: &Client,
+        export_snapshot: bool,
+    ) -> anyhow::Result<(String, String, String)> {
         let mut start_lsn = self.start_lsn.clone();
 
         // create publication for all tables if not exists
@@ -85,7 +135,8 @@ impl PgCdcClient {
         }
 
         // create replication slot
-        if create_slot || self.recreate_slot_if_exists {
+        let mut snapshot_name = String::new();
+        if create_slot || self.recreate_slot_if_exists || export_snapshot {
             // should never happen
             if slot_exists {
                 let query = format!(
@@ -96,15 +147,21 @@ impl PgCdcClient {
                 client.simple_query(&query).await?;
             }
 
-            let query = format!(
+            let mut query = format!(
                 r#"CREATE_REPLICATION_SLOT {} LOGICAL "{}""#,
                 self.slot_name, "pgoutput"
             );
+            if export_snapshot {
+                query.push_str(" EXPORT_SNAPSHOT");
+            }
             log_info!("execute: {}", query);
 
             let res = client.simple_query(&query).await?;
             // get the lsn for the newly created slot
             start_lsn = if let Row(row) = &res[0] {
+                if export_snapshot {
+                    snapshot_name = row.get("snapshot_name").unwrap_or_default().to_string();
+                }
                 row.get("consistent_point").unwrap().to_string()
             } else {
                 bail! {Error::ExtractorError(format!(
@@ -119,7 +176,7 @@ impl PgCdcClient {
             );
         }
 
-        Ok((pub_name, start_lsn))
+        Ok((pub_name, start_lsn, snapshot_name))
     }
 
     async fn check_slot_status(&self, client: &Client) -> anyhow::Result<(bool, String)> {
@@ -146,7 +203,7 @@ impl PgCdcClient {
         &mut self,
         client: &Client,
     ) -> anyhow::Result<(LogicalReplicationStream, String)> {
-        let (pub_name, start_lsn) = self.prepare_slot(client).await?;
+        let (pub_name, start_lsn, _) = self.prepare_slot(client, false).await?;
 
         // set extra_float_digits to max so no precision will lose
         client.simple_query("SET extra_float_digits=3").await?;
@@ -27,7 +27,7 @@ use sqlx::{postgres::PgArguments, query::Query, Pool, Postgres};
 use tokio_postgres::replication::LogicalReplicationStream;
 
 use dt_common::{
-    config::{config_enums::DbType, config_token_parser::ConfigTokenParser},
+    config::{config_enums::DbType, config_token_parser::ConfigTokenParser, tls_config::TlsConfig},
     error::Error,
     log_error, log_info,
     rdb_filter::RdbFilter,
@@ -37,13 +37,19 @@ use dt_common::{
 use crate::{
     close_conn_pool,
     extractor::{
-        base_extractor::BaseExtractor, pg::pg_cdc_client::PgCdcClient,
+        base_extractor::BaseExtractor,
+        cdc_backfill::{
+            BackfillRequests, CdcBackfill, SIGNAL_BACKFILL, SIGNAL_HIGH_WATERMARK,
+            SIGNAL_LOW_WATERMARK,
+        },
+        pg::pg_cdc_client::PgCdcClient,
         resumer::cdc_resumer::CdcResumer,
     },
+    rdb_query_builder::RdbQueryBuilder,
     Extractor,
 };
 use dt_common::meta::{
-    adaptor::pg_col_value_convertor::PgColValueConvertor,
+    adaptor::{pg_col_value_convertor::PgColValueConvertor, sqlx_ext::SqlxPgExt},
     col_value::ColValue,
     dt_data::DtData,
     pg::{pg_meta_manager::PgMetaManager, pg_tb_meta::PgTbMeta},
@@ -60,6 +66,7 @@ pub struct PgCdcExtractor {
     pub conn_pool: Pool<Postgres>,
     pub filter: RdbFilter,
     pub url: String,
+    pub tls: TlsConfig,
     pub slot_name: String,
     pub pub_name: String,
     pub start_lsn: String,
@@ -70,6 +77,8 @@ pub struct PgCdcExtractor {
     pub ddl_meta_tb: String,
     pub syncer: Arc<Mutex<Syncer>>,
     pub resumer: CdcResumer,
+    pub backfill: Option<CdcBackfill>,
+    pub backfill_requests: BackfillRequests,
 }
 
 const SECS_FROM_1970_TO_2000: i64 = 946_684_800;
@@ -105,6 +114,7 @@ impl PgCdcExtractor {
     async fn extract_internal(&mut self) -> anyhow::Result<()> {
         let mut cdc_client = PgCdcClient {
             url: self.url.clone(),
+            tls: self.tls.clone(),
             pub_name: self.pub_name.clone(),
             slot_name: self.slot_name.clone(),
             start_lsn: self.start_lsn.clone(),
@@ -121,6 +131,9 @@ impl PgCdcExtractor {
 
         // start heartbeat
         self.start_heartbeat(self.base_extractor.shut_down.clone())?;
+        self.start_backfill(self.base_extractor.shut_down.clone())?;
+        // continue the backfill interrupted by restart
+        self.backfill_next_chunk().await?;
 
         let mut last_tx_end_lsn = actual_start_lsn.clone();
         let mut xid = String::new();
@@ -265,7 +278,10 @@ impl PgCdcExtractor {
         // if the tb is filtered, we won't try to get the tb_meta since we may get privilege errors,
         // but we need to keep the oid —— tb_meta map which may be used for decoding events,
         // the built-in object used by datamarker, although it is not in filter config, still needs to get tb_meta.
-        if self.filter.filter_tb(schema, tb) && !self.base_extractor.is_data_marker_info(schema, tb)
+        // so does the signal_tb of backfill.
+        if self.filter.filter_tb(schema, tb)
+            && !self.base_extractor.is_data_marker_info(schema, tb)
+            && !self.is_backfill_signal(schema, tb)
         {
             let tb_meta = Self::mock_pg_tb_meta(schema, tb, event.rel_id() as i32);
             self.meta_manager
@@ -309,6 +325,11 @@ impl PgCdcExtractor {
         let tb_meta = self
             .meta_manager
             .get_tb_meta_by_oid(event.rel_id() as i32)?;
+        if self.is_backfill_signal(&tb_meta.basic.schema, &tb_meta.basic.tb) {
+            let col_values = self.parse_row_data(&tb_meta, event.tuple().tuple_data())?;
+            return self.handle_backfill_signal(col_values, position).await;
+        }
+
         if self.filter_event(&tb_meta, RowType::Insert) {
             return Ok(());
         }
@@ -495,6 +516,10 @@ impl PgCdcExtractor {
         row_data: RowData,
         position: Position,
     ) -> anyhow::Result<()> {
+        if let Some(backfill) = self.backfill.as_mut() {
+            backfill.on_row(&row_data);
+            backfill.flush_progress(&self.syncer);
+        }
         self.base_extractor.push_row(row_data, position).await
     }
 
@@ -520,6 +545,225 @@ impl PgCdcExtractor {
         }
     }
 
+    fn is_backfill_signal(&self, schema: &str, tb: &str) -> bool {
+        self.backfill
+            .as_ref()
+            .is_some_and(|i| i.is_signal_tb(schema, tb))
+    }
+
+    async fn handle_backfill_signal(
+        &mut self,
+        col_values: HashMap<String, ColValue>,
+        position: &Position,
+    ) -> anyhow::Result<()> {
+        let backfill = self.backfill.as_mut().unwrap();
+        let (schema, tb) = (backfill.signal_schema.clone(), backfill.signal_tb.clone());
+        let row_data = RowData::new(
+            schema.clone(),
+            tb.clone(),
+            RowType::Insert,
+            None,
+            Some(col_values),
+        );
+        let signal = match CdcBackfill::parse_signal(&row_data) {
+            Some(signal) => signal,
+            None => return Ok(()),
+        };
+
+        backfill.flush_progress(&self.syncer);
+        if let Some(rows) = backfill.on_signal(&signal, position) {
+            for row_data in rows {
+                self.base_extractor
+                    .push_row(row_data, position.clone())
+                    .await?;
+            }
+
+            // watermarks are no longer needed
+            let sql = format!(
+                r#"DELETE FROM "{}"."{}" WHERE data = $1 AND type IN ($2, $3)"#,
+                schema, tb
+            );
+            let query: Query<Postgres, PgArguments> = sqlx::query(&sql)
+                .bind(&signal.data)
+                .bind(SIGNAL_LOW_WATERMARK)
+                .bind(SIGNAL_HIGH_WATERMARK);
+            if let Err(err) = query.execute(&self.conn_pool).await {
+                log_error!("delete backfill watermarks failed: {:?}", err);
+            }
+        }
+
+        self.backfill_next_chunk().await
+    }
+
+    async fn backfill_next_chunk(&mut self) -> anyhow::Result<()> {
+        let (task, tb_meta, order_col) = loop {
+            let task = match self.backfill.as_mut() {
+                Some(backfill) => backfill.next_task(&mut self.filter),
+                None => None,
+            };
+            let task = match task {
+                Some(task) => task,
+                None => return Ok(()),
+            };
+
+            if let ColValue::None = task.start_value {
+                // the tb was filtered before and its tb_meta may be mocked in decode_relation,
+                // reload it (by oid as well) so that the following wal events can be decoded
+                self.meta_manager.invalidate_cache(&task.schema, &task.tb);
+            }
+            let tb_meta = self
+                .meta_manager
+                .get_tb_meta(&task.schema, &task.tb)
+                .await?
+                .to_owned();
+            if let Some(order_col) = tb_meta.basic.order_col.clone() {
+                break (task, tb_meta, order_col);
+            }
+            // selecting the whole table in one chunk may exhaust memory
+            log_error!(
+                "backfill skipped since tb has no single-column primary / unique key, schema: {}, tb: {}",
+                task.schema,
+                task.tb
+            );
+            self.backfill
+                .as_mut()
+                .unwrap()
+                .skip_current_tb(&mut self.filter);
+        };
+
+        let (signal_schema, signal_tb, batch_size) = {
+            let backfill = self.backfill.as_ref().unwrap();
+            (
+                backfill.signal_schema.clone(),
+                backfill.signal_tb.clone(),
+                backfill.batch_size,
+            )
+        };
+        let (schema, tb) = (&task.schema, &task.tb);
+        let ignore_cols = self.filter.get_ignore_cols(schema, tb);
+        let cols_str =
+            RdbQueryBuilder::new_for_pg(&tb_meta, ignore_cols).build_extract_cols_str()?;
+        let order_col_type = tb_meta.get_col_type(&order_col)?;
+        let start_value = match (&task.start_value, &task.resume_value) {
+            (ColValue::None, Some(value)) => {
+                PgColValueConvertor::from_str(order_col_type, value, &mut self.meta_manager)?
+            }
+            _ => task.start_value.clone(),
+        };
+
+        let chunk_id = CdcBackfill::new_chunk_id();
+        Self::write_signal(
+            &signal_schema,
+            &signal_tb,
+            SIGNAL_LOW_WATERMARK,
+            &chunk_id,
+            &self.conn_pool,
+        )
+        .await?;
+
+        let sql1 = format!(
+            r#"SELECT {} FROM "{}"."{}" ORDER BY "{}" ASC LIMIT {}"#,
+            cols_str, schema, tb, order_col, batch_size
+        );
+        let sql2 = format!(
+            r#"SELECT {} FROM "{}"."{}" WHERE "{}" > $1::{} ORDER BY "{}" ASC LIMIT {}"#,
+            cols_str, schema, tb, order_col, order_col_type.alias, order_col, batch_size
+        );
+        let query = if let ColValue::None = start_value {
+            sqlx::query(&sql1)
+        } else {
+            sqlx::query(&sql2).bind_col_value(Some(&start_value), order_col_type)
+        };
+
+        let mut rows = Vec::new();
+        let mut last_value = start_value.clone();
+        let query_rows = query.fetch_all(&self.conn_pool).await?;
+        for row in query_rows.iter() {
+            last_value = PgColValueConvertor::from_query(row, &order_col, order_col_type)?;
+            rows.push(RowData::from_pg_row(row, &tb_meta, &ignore_cols));
+        }
+        let finished = query_rows.len() < batch_size;
+
+        log_info!(
+            "backfill chunk selected, schema: {}, tb: {}, start_value: {}, rows: {}",
+            schema,
+            tb,
+            start_value,
+            rows.len()
+        );
+        self.backfill.as_mut().unwrap().open_chunk(
+            &chunk_id,
+            &tb_meta.basic.id_cols,
+            &order_col,
+            rows,
+            last_value,
+            finished,
+        );
+
+        Self::write_signal(
+            &signal_schema,
+            &signal_tb,
+            SIGNAL_HIGH_WATERMARK,
+            &chunk_id,
+            &self.conn_pool,
+        )
+        .await
+    }
+
+    fn start_backfill(&mut self, shut_down: Arc<AtomicBool>) -> anyhow::Result<()> {
+        let (schema, tb) = match &self.backfill {
+            Some(backfill) => (backfill.signal_schema.clone(), backfill.signal_tb.clone()),
+            None => return Ok(()),
+        };
+
+        // signals are handled before filtering, the signal_tb itself is never synced
+        self.filter.add_ignore_tb(&schema, &tb);
+        self.backfill_requests.enable();
+        if let Some(backfill) = self.backfill.as_mut() {
+            backfill.resume(&self.resumer.backfill_positions, &mut self.filter);
+        }
+
+        // backfill requests from the admin server
+        let (requests, conn_pool) = (self.backfill_requests.clone(), self.conn_pool.clone());
+        tokio::spawn(async move {
+            while !shut_down.load(Ordering::Acquire) {
+                for tbs in requests.take() {
+                    if let Err(err) =
+                        Self::write_signal(&schema, &tb, SIGNAL_BACKFILL, &tbs, &conn_pool).await
+                    {
+                        log_error!("write backfill signal failed, tbs: {}, {:?}", tbs, err);
+                    }
+                }
+                TimeUtil::sleep_millis(1000).await;
+            }
+        });
+        log_info!("backfill started");
+        Ok(())
+    }
+
+    async fn write_signal(
+        schema: &str,
+        tb: &str,
+        signal_type: &str,
+        data: &str,
+        conn_pool: &Pool<Postgres>,
+    ) -> anyhow::Result<()> {
+        // ids of watermarks: {chunk_id}_low_watermark, {chunk_id}_high_watermark
+        let id = if signal_type == SIGNAL_BACKFILL {
+            CdcBackfill::new_chunk_id()
+        } else {
+            format!("{}_{}", data, signal_type)
+        };
+        let sql = format!(
+            r#"INSERT INTO "{}"."{}" (id, type, data) VALUES ($1, $2, $3)"#,
+            schema, tb
+        );
+        let query: Query<Postgres, PgArguments> =
+            sqlx::query(&sql).bind(id).bind(signal_type).bind(data);
+        query.execute(conn_pool).await?;
+        Ok(())
+    }
+
     fn start_heartbeat(&mut self, shut_down: Arc<AtomicBool>) -> anyhow::Result<()> {
         let schema_tb = self.base_extractor.precheck_heartbeat(
             self.heartbeat_interval_secs,
@@ -0,0 +1,125 @@
+use anyhow::bail;
+use byteorder::{BigEndian, ByteOrder};
+use dt_common::error::Error;
+
+const SIGNATURE: &[u8] = b"PGCOPY\n\xff\r\n\0";
+// signature, flags: i32, header extension length: i32
+const HEADER_LEN: usize = 19;
+
+/// Splits the stream of COPY ... TO STDOUT (FORMAT binary) into tuples,
+/// the data messages received are not necessarily aligned with tuples.
+#[derive(Default)]
+pub struct PgCopyDecoder {
+    buf: Vec<u8>,
+    offset: usize,
+    header_read: bool,
+    finished: bool,
+}
+
+impl PgCopyDecoder {
+    pub fn feed(&mut self, data: &[u8]) {
+        self.buf.drain(..self.offset);
+        self.offset = 0;
+        self.buf.extend_from_slice(data);
+    }
+
+    /// Returns the next complete tuple, NULL fields are None.
+    /// Returns None if more data needs to be fed, or the trailer is reached.
+    pub fn next_tuple(&mut self) -> anyhow::Result<Option<Vec<Option<Vec<u8>>>>> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        if !self.header_read {
+            let data = &self.buf[self.offset..];
+            if data.len() < HEADER_LEN {
+                return Ok(None);
+            }
+            if &data[..SIGNATURE.len()] != SIGNATURE {
+                bail! {Error::ExtractorError("invalid copy binary signature".into())}
+            }
+            let extension_len = BigEndian::read_i32(&data[15..HEADER_LEN]) as usize;
+            if data.len() < HEADER_LEN + extension_len {
+                return Ok(None);
+            }
+            self.offset += HEADER_LEN + extension_len;
+            self.header_read = true;
+        }
+
+        let data = &self.buf[self.offset..];
+        if data.len() < 2 {
+            return Ok(None);
+        }
+        let field_count = BigEndian::read_i16(data);
+        // trailer
+        if field_count == -1 {
+            self.offset += 2;
+            self.finished = true;
+            return Ok(None);
+        }
+
+        let mut pos = 2;
+        let mut fields = Vec::with_capacity(field_count as usize);
+        for _ in 0..field_count {
+            if data.len() < pos + 4 {
+                return Ok(None);
+            }
+            let len = BigEndian::read_i32(&data[pos..]);
+            pos += 4;
+            if len < 0 {
+                fields.push(None);
+                continue;
+            }
+
+            let len = len as usize;
+            if data.len() < pos + len {
+                return Ok(None);
+            }
+            fields.push(Some(data[pos..pos + len].to_vec()));
+            pos += len;
+        }
+        self.offset += pos;
+        Ok(Some(fields))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_tuple() {
+        let mut stream = SIGNATURE.to_vec();
+        stream.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 0]);
+        // (1, NULL)
+        stream.extend_from_slice(&[0, 2, 0, 0, 0, 4, 0, 0, 0, 1, 0xff, 0xff, 0xff, 0xff]);
+        // (2, 'ab')
+        stream.extend_from_slice(&[0, 2, 0, 0, 0, 4, 0, 0, 0, 2, 0, 0, 0, 2, b'a', b'b']);
+        // trailer
+        stream.extend_from_slice(&[0xff, 0xff]);
+
+        // feed by chunks not aligned with tuples
+        for chunk_size in [1, 3, 7, stream.len()] {
+            let mut decoder = PgCopyDecoder::default();
+            let mut tuples = Vec::new();
+            for chunk in stream.chunks(chunk_size) {
+                decoder.feed(chunk);
+                while let Some(tuple) = decoder.next_tuple().unwrap() {
+                    tuples.push(tuple);
+                }
+            }
+            assert!(decoder.is_finished());
+            assert_eq!(
+                tuples,
+                vec![
+                    vec![Some(vec![0, 0, 0, 1]), None],
+                    vec![Some(vec![0, 0, 0, 2]), Some(b"ab".to_vec())],
+                ]
+            );
+        }
+    }
+}
@@ -1,10 +1,13 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Context};
 use async_trait::async_trait;
 use dt_common::rdb_filter::RdbFilter;
 use futures::TryStreamExt;
 
-use sqlx::{Pool, Postgres};
+use sqlx::Postgres;
 
-use dt_common::{config::config_enums::DbType, log_info};
+use dt_common::{config::config_enums::DbType, error::Error, log_info};
 
 use dt_common::meta::{
     adaptor::{pg_col_value_convertor::PgColValueConvertor, sqlx_ext::SqlxPgExt},
@@ -16,19 +19,23 @@ use dt_common::meta::{
 
 use crate::close_conn_pool;
 use crate::{
-    extractor::{base_extractor::BaseExtractor, resumer::snapshot_resumer::SnapshotResumer},
+    extractor::{
+        base_extractor::BaseExtractor, pg::pg_copy_decoder::PgCopyDecoder,
+        resumer::snapshot_resumer::SnapshotResumer, snapshot_conn_pool::ExtractorConnPool,
+    },
     rdb_query_builder::RdbQueryBuilder,
     Extractor,
 };
 
 pub struct PgSnapshotExtractor {
     pub base_extractor: BaseExtractor,
-    pub conn_pool: Pool<Postgres>,
+    pub conn_pool: ExtractorConnPool<Postgres>,
     pub meta_manager: PgMetaManager,
     pub filter: RdbFilter,
     pub resumer: SnapshotResumer,
     pub batch_size: usize,
     pub sample_interval: usize,
+    pub extract_by_copy: bool,
     pub schema: String,
     pub tb: String,
 }
@@ -37,16 +44,19 @@ pub struct PgSnapshotExtractor {
 impl Extractor for PgSnapshotExtractor {
     async fn extract(&mut self) -> anyhow::Result<()> {
         log_info!(
-            r#"PgSnapshotExtractor starts, schema: "{}", tb: "{}", batch_size: {}"#,
+            r#"PgSnapshotExtractor starts, schema: "{}", tb: "{}", batch_size: {}, extract_by_copy: {}"#,
             self.schema,
             self.tb,
-            self.batch_size
+            self.batch_size,
+            self.extract_by_copy
         );
         self.extract_internal().await?;
         self.base_extractor.wait_task_finish().await
     }
 
     async fn close(&mut self) -> anyhow::Result<()> {
+        // the meta_manager has its own pool in consistent snapshot
+        self.meta_manager.close().await?;
         close_conn_pool!(self)
     }
 }
@@ -71,14 +81,182 @@ impl PgSnapshotExtractor {
                 ColValue::None
             };
 
-            self.extract_by_batch(&tb_meta, order_col, order_col_type, resume_value)
-                .await?;
+            if self.extract_by_copy {
+                self.extract_by_copy_batch(&tb_meta, order_col, resume_value)
+                    .await?;
+            } else {
+                self.extract_by_batch(&tb_meta, order_col, order_col_type, resume_value)
+                    .await?;
+            }
+        } else if self.extract_by_copy {
+            self.extract_all_by_copy(&tb_meta).await?;
         } else {
             self.extract_all(&tb_meta).await?;
         }
         Ok(())
     }
 
+    async fn extract_all_by_copy(&mut self, tb_meta: &PgTbMeta) -> anyhow::Result<()> {
+        log_info!(
+            r#"start extracting data from "{}"."{}" by copy without batch"#,
+            self.schema,
+            self.tb
+        );
+
+        let sql = self.build_extract_sql(tb_meta, None)?;
+        let mut extracted_count = 0;
+        self.copy_out(tb_meta, &sql, None, &mut extracted_count)
+            .await?;
+
+        log_info!(
+            r#"end extracting data from "{}"."{}", all count: {}"#,
+            self.schema,
+            self.tb,
+            extracted_count
+        );
+        Ok(())
+    }
+
+    /// Copy the table by chunks of batch_size rows ordered by order_col, each chunk starts
+    /// after the last order_col value of the previous one as extract_by_batch does,
+    /// so the positions of rows still work for resuming.
+    async fn extract_by_copy_batch(
+        &mut self,
+        tb_meta: &PgTbMeta,
+        order_col: &str,
+        resume_value: ColValue,
+    ) -> anyhow::Result<()> {
+        log_info!(
+            r#"start extracting data from "{}"."{}" by copy, order_col: {}, start_value: {}"#,
+            self.schema,
+            self.tb,
+            order_col,
+            resume_value.to_string()
+        );
+
+        let order_col_type = tb_meta.get_col_type(order_col)?;
+        let mut extracted_count = 0;
+        let mut start_value = resume_value;
+        loop {
+            let condition = if let ColValue::None = start_value {
+                None
+            } else {
+                // COPY does not accept parameters, inline the start value as a literal
+                Some(RdbQueryBuilder::get_pg_resume_condition(
+                    order_col,
+                    order_col_type,
+                    &start_value,
+                    &self.meta_manager.type_registry,
+                )?)
+            };
+            let sql = self.build_extract_sql(tb_meta, condition)?;
+
+            let (slice_count, last_value) = self
+                .copy_out(tb_meta, &sql, Some(order_col), &mut extracted_count)
+                .await?;
+            start_value = last_value;
+
+            // all data extracted
+            if slice_count < self.batch_size {
+                break;
+            }
+        }
+
+        log_info!(
+            r#"end extracting data from "{}"."{}" by copy, all count: {}"#,
+            self.schema,
+            self.tb,
+            extracted_count
+        );
+        Ok(())
+    }
+
+    /// Run COPY (sql) TO STDOUT (FORMAT binary) and push the decoded rows,
+    /// returns the count of rows copied and the order_col value of the last row.
+    async fn copy_out(
+        &mut self,
+        tb_meta: &PgTbMeta,
+        sql: &str,
+        order_col: Option<&str>,
+        extracted_count: &mut usize,
+    ) -> anyhow::Result<(usize, ColValue)> {
+        let ignore_cols = self.filter.get_ignore_cols(&self.schema, &self.tb);
+        // the same columns as selected by build_extract_cols_str
+        let cols: Vec<String> = tb_meta
+            .basic
+            .cols
+            .iter()
+            .filter(|col| !ignore_cols.is_some_and(|ignore_cols| ignore_cols.contains(*col)))
+            .cloned()
+            .collect();
+        if let Some(order_col) = order_col {
+            if !cols.iter().any(|col| col == order_col) {
+                bail! {Error::ExtractorError(format!(
+                    r#"order_col: {} of "{}"."{}" can not be ignored when extract_by_copy"#,
+                    order_col, self.schema, self.tb
+                ))}
+            }
+        }
+
+        let copy_sql = format!("COPY ({}) TO STDOUT (FORMAT binary)", sql);
+        let mut conn = self.conn_pool.acquire().await?;
+        let mut stream = conn.copy_out_raw(&copy_sql).await?;
+        let mut decoder = PgCopyDecoder::default();
+        let mut count = 0;
+        let mut last_value = ColValue::None;
+        while let Some(data) = stream.try_next().await? {
+            decoder.feed(&data);
+            while let Some(fields) = decoder.next_tuple()? {
+                let mut after = HashMap::new();
+                for (col, field) in cols.iter().zip(fields) {
+                    let col_type = tb_meta.get_col_type(col)?;
+                    let col_value = match field {
+                        Some(value) => PgColValueConvertor::from_copy_binary(col_type, &value)
+                            .with_context(|| {
+                                format!(
+                                    "schema: {}, tb: {}, col: {}, col_type: {}",
+                                    self.schema, self.tb, col, col_type
+                                )
+                            })?,
+                        None => ColValue::None,
+                    };
+                    after.insert(col.to_owned(), col_value);
+                }
+
+                count += 1;
+                *extracted_count += 1;
+                let mut position = Position::None;
+                if let Some(order_col) = order_col {
+                    last_value = after.get(order_col).cloned().unwrap_or(ColValue::None);
+                    if let Some(value) = last_value.to_option_string() {
+                        position = Position::RdbSnapshot {
+                            db_type: DbType::Pg.to_string(),
+                            schema: self.schema.clone(),
+                            tb: self.tb.clone(),
+                            order_col: order_col.into(),
+                            value,
+                        };
+                    }
+                }
+
+                // sampling may be used in check scenario
+                if *extracted_count % self.sample_interval != 0 {
+                    continue;
+                }
+                let row_data = RowData::build_insert_row_data(after, &tb_meta.basic);
+                self.base_extractor.push_row(row_data, position).await?;
+            }
+        }
+
+        if !decoder.is_finished() {
+            bail! {Error::ExtractorError(format!(
+                r#"copy from "{}"."{}" ended without the trailer"#,
+                self.schema, self.tb
+            ))}
+        }
+        Ok((count, last_value))
+    }
+
     async fn extract_all(&mut self, tb_meta: &PgTbMeta) -> anyhow::Result<()> {
         log_info!(
             r#"start extracting data from "{}"."{}" without batch"#,
@@ -86,9 +264,10 @@ impl PgSnapshotExtractor {
             self.tb
         );
 
-        let sql = self.build_extract_sql(tb_meta, false)?;
+        let sql = self.build_extract_sql(tb_meta, None)?;
         let ignore_cols = self.filter.get_ignore_cols(&self.schema, &self.tb);
-        let mut rows = sqlx::query(&sql).fetch(&self.conn_pool);
+        let mut conn = self.conn_pool.acquire().await?;
+        let mut rows = sqlx::query(&sql).fetch(&mut *conn);
         while let Some(row) = rows.try_next().await.unwrap() {
             let row_data = RowData::from_pg_row(&row, tb_meta, &ignore_cols);
             self.base_extractor
@@ -122,8 +301,11 @@ impl PgSnapshotExtractor {
 
         let mut extracted_count = 0;
         let mut start_value = resume_value;
-        let sql1 = self.build_extract_sql(tb_meta, false)?;
-        let sql2 = self.build_extract_sql(tb_meta, true)?;
+        let sql1 = self.build_extract_sql(tb_meta, None)?;
+        let sql2 = self.build_extract_sql(
+            tb_meta,
+            Some(format!(r#""{}" > $1::{}"#, order_col, order_col_type.alias)),
+        )?;
         let ignore_cols = self.filter.get_ignore_cols(&self.schema, &self.tb);
         loop {
             let start_value_for_bind = start_value.clone();
@@ -133,7 +315,8 @@ impl PgSnapshotExtractor {
                 sqlx::query(&sql2).bind_col_value(Some(&start_value_for_bind), order_col_type)
             };
 
-            let mut rows = query.fetch(&self.conn_pool);
+            let mut conn = self.conn_pool.acquire().await?;
+            let mut rows = query.fetch(&mut *conn);
             let mut slice_count = 0usize;
             while let Some(row) = rows.try_next().await.unwrap() {
                 start_value = PgColValueConvertor::from_query(&row, order_col, order_col_type)?;
@@ -175,10 +358,11 @@ impl PgSnapshotExtractor {
         Ok(())
     }
 
+    /// condition: rows after the start value of order_col, e.g. "id" > $1::int4
     fn build_extract_sql(
         &mut self,
         tb_meta: &PgTbMeta,
-        has_start_value: bool,
+        condition: Option<String>,
     ) -> anyhow::Result<String> {
         let ignore_cols = self.filter.get_ignore_cols(&self.schema, &self.tb);
         let query_builder = RdbQueryBuilder::new_for_pg(tb_meta, ignore_cols);
@@ -186,17 +370,10 @@ impl PgSnapshotExtractor {
 
         // SELECT col_1, col_2::text FROM tb_1 WHERE col_1 > $1 ORDER BY col_1;
         if let Some(order_col) = &tb_meta.basic.order_col {
-            if has_start_value {
-                let order_col_type = tb_meta.get_col_type(order_col)?;
+            if let Some(condition) = condition {
                 Ok(format!(
-                    r#"SELECT {} FROM "{}"."{}" WHERE "{}" > $1::{} ORDER BY "{}" ASC LIMIT {}"#,
-                    cols_str,
-                    self.schema,
-                    self.tb,
-                    order_col,
-                    order_col_type.alias,
-                    order_col,
-                    self.batch_size
+                    r#"SELECT {} FROM "{}"."{}" WHERE {} ORDER BY "{}" ASC LIMIT {}"#,
+                    cols_str, self.schema, self.tb, condition, order_col, self.batch_size
                 ))
             } else {
                 Ok(format!(
@@ -1,6 +1,7 @@
 use std::io::{Cursor, Read};
 
 pub mod rdb;
+pub mod redis_aof_file_extractor;
 pub mod redis_cdc_extractor;
 pub mod redis_client;
 pub mod redis_psync_extractor;
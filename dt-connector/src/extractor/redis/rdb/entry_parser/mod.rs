@@ -30,3 +30,23 @@ const RDB_TYPE_LIST_QUICKLIST_2: u8 = 18;
 const RDB_TYPE_STREAM_LISTPACKS_2: u8 = 19;
 const RDB_TYPE_SET_LISTPACK: u8 = 20;
 const RDB_TYPE_STREAM_LISTPACKS_3: u8 = 21;
+
+/// encoding name of a rdb value type, same as the output of `OBJECT ENCODING`
+pub fn get_encoding(type_byte: u8) -> &'static str {
+    match type_byte {
+        RDB_TYPE_STRING => "string",
+        RDB_TYPE_LIST => "linkedlist",
+        RDB_TYPE_SET | RDB_TYPE_HASH => "hashtable",
+        RDB_TYPE_ZSET | RDB_TYPE_ZSET_2 => "skiplist",
+        RDB_TYPE_MODULE | RDB_TYPE_MODULE_2 => "module",
+        RDB_TYPE_HASH_ZIPMAP => "zipmap",
+        RDB_TYPE_LIST_ZIPLIST | RDB_TYPE_ZSET_ZIPLIST | RDB_TYPE_HASH_ZIPLIST => "ziplist",
+        RDB_TYPE_SET_INTSET => "intset",
+        RDB_TYPE_LIST_QUICKLIST | RDB_TYPE_LIST_QUICKLIST_2 => "quicklist",
+        RDB_TYPE_STREAM_LISTPACKS | RDB_TYPE_STREAM_LISTPACKS_2 | RDB_TYPE_STREAM_LISTPACKS_3 => {
+            "stream"
+        }
+        RDB_TYPE_HASH_LISTPACK | RDB_TYPE_ZSET_LISTPACK | RDB_TYPE_SET_LISTPACK => "listpack",
+        _ => "unknown",
+    }
+}
@@ -1,6 +1,8 @@
 use anyhow::bail;
+use byteorder::{ByteOrder, LittleEndian};
 use dt_common::error::Error;
-use dt_common::meta::redis::redis_object::{ModuleObject, RedisString};
+use dt_common::log_warn;
+use dt_common::meta::redis::redis_object::{ModuleObject, RedisCmd, RedisString};
 
 use crate::extractor::redis::rdb::reader::rdb_reader::RdbReader;
 
@@ -9,6 +11,44 @@ pub struct ModuleParser {}
 const MODULE_TYPE_NAME_CHAR_SET: &str =
     "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
 
+const RDB_MODULE_OPCODE_EOF: u64 = 0; // End of module value.
+const RDB_MODULE_OPCODE_SINT: u64 = 1; // Signed integer.
+const RDB_MODULE_OPCODE_UINT: u64 = 2; // Unsigned integer.
+const RDB_MODULE_OPCODE_FLOAT: u64 = 3; // Float.
+const RDB_MODULE_OPCODE_DOUBLE: u64 = 4; // Double.
+const RDB_MODULE_OPCODE_STRING: u64 = 5; // String.
+
+const MODULE_REJSON: &str = "ReJSON-RL";
+const MODULE_BLOOM: &str = "MBbloom--";
+const MODULE_CUCKOO: &str = "MBbloomCF";
+const MODULE_TIMESERIES: &str = "TSDB-TYPE";
+
+// refer: https://github.com/RedisJSON/RedisJSON/blob/master/redis_json/src/redisjson.rs
+// since 2.0, json value is saved as a serialized string
+const REJSON_MIN_STRING_ENC: u64 = 2;
+// refer: https://github.com/RedisBloom/RedisBloom/blob/master/src/rebloom.c
+const BF_MIN_OPTIONS_ENC: u64 = 2;
+const BF_MIN_GROWTH_ENC: u64 = 4;
+const CF_MIN_EXPANSION_ENC: u64 = 4;
+const CF_DEFAULT_BUCKET_SIZE: u64 = 2;
+const CF_DEFAULT_MAX_ITERATIONS: u64 = 20;
+const CF_DEFAULT_EXPANSION: u64 = 1;
+// max size of a BF.LOADCHUNK / CF.LOADCHUNK payload, same as SCANDUMP
+const MAX_CHUNK_SIZE: usize = 16 * 1024 * 1024;
+// refer: https://github.com/RedisTimeSeries/RedisTimeSeries/blob/master/src/rdb.c
+const TS_SAMPLE_SIZE: usize = 16;
+const TS_DUPLICATE_POLICIES: [&str; 7] = ["", "BLOCK", "LAST", "FIRST", "MIN", "MAX", "SUM"];
+
+/// a value saved by RedisModule_SaveXXX, each value is prefixed by an opcode in module2 format
+#[derive(Debug, Clone)]
+pub enum ModuleValue {
+    Sint(i64),
+    Uint(u64),
+    Float(f32),
+    Double(f64),
+    String(RedisString),
+}
+
 impl ModuleParser {
     pub fn load_from_buffer(
         reader: &mut RdbReader,
@@ -24,11 +64,59 @@ impl ModuleParser {
 
         let module_id = reader.read_length()?;
         let module_name = Self::module_type_name_by_id(module_id);
-        // Not supported
-        bail! {Error::RedisRdbError(format!(
-            "unsupported module type: [{}]",
-            module_name
-        ))}
+        let values = Self::load_module_values(reader, &module_name)?;
+
+        let mut obj = ModuleObject::new();
+        obj.key = key;
+        obj.enc_ver = module_id & 1023;
+        obj.module_name = module_name;
+
+        // the raw payload is always kept, if the value can not be decoded,
+        // leave cmds empty and the sinker will fall back to restore
+        let cmds = match obj.module_name.as_str() {
+            MODULE_REJSON => Self::decode_rejson(&obj, &values),
+            MODULE_BLOOM => Self::decode_bloom(&obj, &values),
+            MODULE_CUCKOO => Self::decode_cuckoo(&obj, &values),
+            MODULE_TIMESERIES => Self::decode_timeseries(&obj, &values),
+            _ => Ok(Vec::new()),
+        };
+        match cmds {
+            Ok(cmds) => obj.cmds = cmds,
+            Err(err) => log_warn!(
+                "failed to decode module value, module_name: {}, enc_ver: {}, key: {}, error: {}",
+                obj.module_name,
+                obj.enc_ver,
+                obj.key,
+                err
+            ),
+        }
+        Ok(obj)
+    }
+
+    pub fn load_module_values(
+        reader: &mut RdbReader,
+        module_name: &str,
+    ) -> anyhow::Result<Vec<ModuleValue>> {
+        let mut values = Vec::new();
+        loop {
+            let opcode = reader.read_length()?;
+            let value = match opcode {
+                RDB_MODULE_OPCODE_EOF => break,
+                RDB_MODULE_OPCODE_SINT => ModuleValue::Sint(reader.read_length()? as i64),
+                RDB_MODULE_OPCODE_UINT => ModuleValue::Uint(reader.read_length()?),
+                RDB_MODULE_OPCODE_FLOAT => ModuleValue::Float(reader.read_binary_float()?),
+                RDB_MODULE_OPCODE_DOUBLE => ModuleValue::Double(reader.read_double()?),
+                RDB_MODULE_OPCODE_STRING => ModuleValue::String(reader.read_string()?),
+                _ => {
+                    bail! {Error::RedisRdbError(format!(
+                        "unknown module opcode. module_name=[{}], opcode=[{}]",
+                        module_name, opcode
+                    ))}
+                }
+            };
+            values.push(value);
+        }
+        Ok(values)
     }
 
     pub fn module_type_name_by_id(module_id: u64) -> String {
@@ -42,4 +130,471 @@ impl ModuleParser {
         }
         String::from_utf8(name_list).unwrap()
     }
+
+    fn decode_rejson(obj: &ModuleObject, values: &[ModuleValue]) -> anyhow::Result<Vec<RedisCmd>> {
+        if obj.enc_ver < REJSON_MIN_STRING_ENC {
+            bail! {Error::RedisRdbError(format!(
+                "rejson enc_ver: {} is not supported",
+                obj.enc_ver
+            ))}
+        }
+
+        let mut values = ModuleValues::new(values);
+        let json = values.next_string()?;
+        values.finish()?;
+
+        let mut cmd = Self::new_cmd("JSON.SET", &obj.key);
+        cmd.add_str_arg(".");
+        cmd.add_redis_arg(&json);
+        Ok(vec![cmd])
+    }
+
+    fn decode_bloom(obj: &ModuleObject, values: &[ModuleValue]) -> anyhow::Result<Vec<RedisCmd>> {
+        if obj.enc_ver < BF_MIN_OPTIONS_ENC {
+            bail! {Error::RedisRdbError(format!(
+                "bloom filter enc_ver: {} is not supported",
+                obj.enc_ver
+            ))}
+        }
+
+        let mut values = ModuleValues::new(values);
+        let size = values.next_uint()?;
+        let n_filters = values.next_uint()?;
+        let options = values.next_uint()?;
+        let growth = if obj.enc_ver >= BF_MIN_GROWTH_ENC {
+            values.next_uint()?
+        } else {
+            2
+        };
+
+        // the header has the same layout with dumpedChainHeader returned by BF.SCANDUMP
+        let mut header = Vec::new();
+        header.extend_from_slice(&size.to_le_bytes());
+        header.extend_from_slice(&(n_filters as u32).to_le_bytes());
+        header.extend_from_slice(&(options as u32).to_le_bytes());
+        header.extend_from_slice(&(growth as u32).to_le_bytes());
+
+        let mut filters = Vec::new();
+        for _ in 0..n_filters {
+            let entries = values.next_uint()?;
+            let error = values.next_double()?;
+            let hashes = values.next_uint()?;
+            let bpe = values.next_double()?;
+            let bits = values.next_uint()?;
+            let n2 = values.next_uint()?;
+            let bf = values.next_string()?;
+            let link_size = values.next_uint()?;
+
+            // dumpedChainLink
+            header.extend_from_slice(&(bf.bytes.len() as u64).to_le_bytes());
+            header.extend_from_slice(&bits.to_le_bytes());
+            header.extend_from_slice(&link_size.to_le_bytes());
+            header.extend_from_slice(&error.to_le_bytes());
+            header.extend_from_slice(&bpe.to_le_bytes());
+            header.extend_from_slice(&(hashes as u32).to_le_bytes());
+            header.extend_from_slice(&entries.to_le_bytes());
+            header.push(n2 as u8);
+            filters.push(bf);
+        }
+        values.finish()?;
+
+        Ok(Self::build_load_chunk_cmds(
+            "BF.LOADCHUNK",
+            &obj.key,
+            header,
+            &filters,
+        ))
+    }
+
+    fn decode_cuckoo(obj: &ModuleObject, values: &[ModuleValue]) -> anyhow::Result<Vec<RedisCmd>> {
+        let mut values = ModuleValues::new(values);
+        let n_filters = values.next_uint()?;
+        let n_buckets = values.next_uint()?;
+        let n_items = values.next_uint()?;
+        let n_deletes = values.next_uint()?;
+        let (bucket_size, max_iterations, expansion) = if obj.enc_ver >= CF_MIN_EXPANSION_ENC {
+            (
+                values.next_uint()?,
+                values.next_uint()?,
+                values.next_uint()?,
+            )
+        } else {
+            (
+                CF_DEFAULT_BUCKET_SIZE,
+                CF_DEFAULT_MAX_ITERATIONS,
+                CF_DEFAULT_EXPANSION,
+            )
+        };
+
+        let mut filters = Vec::new();
+        for _ in 0..n_filters {
+            // since expansion was introduced, bucket count of each sub filter is saved before its data
+            if let Some(ModuleValue::Uint(_)) = values.peek() {
+                values.next_uint()?;
+            }
+            filters.push(values.next_string()?);
+        }
+        values.finish()?;
+
+        // the header has the same layout with CFHeader returned by CF.SCANDUMP
+        let mut header = Vec::new();
+        header.extend_from_slice(&n_items.to_le_bytes());
+        header.extend_from_slice(&n_buckets.to_le_bytes());
+        header.extend_from_slice(&n_deletes.to_le_bytes());
+        header.extend_from_slice(&n_filters.to_le_bytes());
+        header.extend_from_slice(&(bucket_size as u16).to_le_bytes());
+        header.extend_from_slice(&(max_iterations as u16).to_le_bytes());
+        header.extend_from_slice(&(expansion as u16).to_le_bytes());
+
+        Ok(Self::build_load_chunk_cmds(
+            "CF.LOADCHUNK",
+            &obj.key,
+            header,
+            &filters,
+        ))
+    }
+
+    fn decode_timeseries(
+        obj: &ModuleObject,
+        values: &[ModuleValue],
+    ) -> anyhow::Result<Vec<RedisCmd>> {
+        // only uncompressed series without compaction rules can be decoded,
+        // samples in gorilla compressed chunks can only be migrated by restore
+        let mut values = ModuleValues::new(values);
+        let _key_name = values.next_string()?;
+        let retention = values.next_uint()?;
+        let chunk_size = values.next_uint()?;
+        let _options = values.next_uint()?;
+        let _last_timestamp = values.next_uint()?;
+        let _last_value = values.next_double()?;
+        let _total_samples = values.next_uint()?;
+        let duplicate_policy = values.next_uint()?;
+        if values.next_uint()? != 0 {
+            // src key, this series is the destination of a compaction rule
+            values.next_string()?;
+        }
+
+        let mut cmd = Self::new_cmd("TS.CREATE", &obj.key);
+        cmd.add_str_arg("RETENTION");
+        cmd.add_str_arg(&retention.to_string());
+        cmd.add_str_arg("ENCODING");
+        cmd.add_str_arg("UNCOMPRESSED");
+        cmd.add_str_arg("CHUNK_SIZE");
+        cmd.add_str_arg(&chunk_size.to_string());
+        if let Some(policy) = TS_DUPLICATE_POLICIES.get(duplicate_policy as usize) {
+            if !policy.is_empty() {
+                cmd.add_str_arg("DUPLICATE_POLICY");
+                cmd.add_str_arg(policy);
+            }
+        }
+
+        let n_labels = values.next_uint()?;
+        if n_labels > 0 {
+            cmd.add_str_arg("LABELS");
+        }
+        for _ in 0..n_labels {
+            cmd.add_redis_arg(&values.next_string()?);
+            cmd.add_redis_arg(&values.next_string()?);
+        }
+        let mut cmds = vec![cmd];
+
+        let n_rules = values.next_uint()?;
+        if n_rules > 0 {
+            bail! {Error::RedisRdbError(
+                "timeseries with compaction rules is not supported".into()
+            )}
+        }
+
+        let n_chunks = values.next_uint()?;
+        for _ in 0..n_chunks {
+            let _base_timestamp = values.next_uint()?;
+            let n_samples = values.next_uint()? as usize;
+            let size = values.next_uint()? as usize;
+            let samples = match values.peek() {
+                Some(ModuleValue::String(samples)) => samples.clone(),
+                _ => {
+                    bail! {Error::RedisRdbError(
+                        "timeseries with compressed chunks is not supported".into()
+                    )}
+                }
+            };
+            values.next_string()?;
+
+            if samples.bytes.len() != size || n_samples * TS_SAMPLE_SIZE > size {
+                bail! {Error::RedisRdbError(format!(
+                    "invalid timeseries chunk, samples: {}, size: {}, buffer size: {}",
+                    n_samples,
+                    size,
+                    samples.bytes.len()
+                ))}
+            }
+
+            if n_samples == 0 {
+                continue;
+            }
+            let mut cmd = RedisCmd::new();
+            cmd.add_str_arg("TS.MADD");
+            for i in 0..n_samples {
+                let sample = &samples.bytes[i * TS_SAMPLE_SIZE..(i + 1) * TS_SAMPLE_SIZE];
+                let timestamp = LittleEndian::read_u64(&sample[..8]);
+                let value = LittleEndian::read_f64(&sample[8..]);
+                cmd.add_redis_arg(&obj.key);
+                cmd.key_indexes.push(cmd.args.len());
+                cmd.keys.push(obj.key.to_string());
+                cmd.add_str_arg(&timestamp.to_string());
+                cmd.add_str_arg(&value.to_string());
+            }
+            cmds.push(cmd);
+        }
+        values.finish()?;
+        Ok(cmds)
+    }
+
+    fn build_load_chunk_cmds(
+        cmd_name: &str,
+        key: &RedisString,
+        header: Vec<u8>,
+        filters: &[RedisString],
+    ) -> Vec<RedisCmd> {
+        // refer to SCANDUMP: iter 1 carries the header, for data chunks,
+        // iter = (offset of the chunk in all filters) + (chunk size) + 1,
+        // and a chunk should never cross 2 filters
+        let mut cmds = Vec::new();
+        let mut cmd = Self::new_cmd(cmd_name, key);
+        cmd.add_str_arg("1");
+        cmd.add_arg(header);
+        cmds.push(cmd);
+
+        let mut offset = 0;
+        for filter in filters.iter() {
+            for chunk in filter.bytes.chunks(MAX_CHUNK_SIZE) {
+                offset += chunk.len();
+                let mut cmd = Self::new_cmd(cmd_name, key);
+                cmd.add_str_arg(&(offset + 1).to_string());
+                cmd.add_arg(chunk.to_vec());
+                cmds.push(cmd);
+            }
+        }
+        cmds
+    }
+
+    fn new_cmd(cmd_name: &str, key: &RedisString) -> RedisCmd {
+        // set keys here since module cmds may be unknown to KeyParser
+        let mut cmd = RedisCmd::new();
+        cmd.add_str_arg(cmd_name);
+        cmd.add_redis_arg(key);
+        cmd.keys.push(key.to_string());
+        cmd.key_indexes.push(2);
+        cmd
+    }
+}
+
+struct ModuleValues<'a> {
+    values: &'a [ModuleValue],
+    inx: usize,
+}
+
+impl<'a> ModuleValues<'a> {
+    fn new(values: &'a [ModuleValue]) -> Self {
+        Self { values, inx: 0 }
+    }
+
+    fn peek(&self) -> Option<&ModuleValue> {
+        self.values.get(self.inx)
+    }
+
+    fn next(&mut self) -> anyhow::Result<&ModuleValue> {
+        if let Some(value) = self.values.get(self.inx) {
+            self.inx += 1;
+            return Ok(value);
+        }
+        bail! {Error::RedisRdbError("unexpected end of module values".into())}
+    }
+
+    fn next_uint(&mut self) -> anyhow::Result<u64> {
+        match self.next()? {
+            ModuleValue::Uint(v) => Ok(*v),
+            ModuleValue::Sint(v) => Ok(*v as u64),
+            v => bail! {Error::RedisRdbError(format!("expect uint, got: {:?}", v))},
+        }
+    }
+
+    fn next_double(&mut self) -> anyhow::Result<f64> {
+        match self.next()? {
+            ModuleValue::Double(v) => Ok(*v),
+            ModuleValue::Float(v) => Ok(*v as f64),
+            v => bail! {Error::RedisRdbError(format!("expect double, got: {:?}", v))},
+        }
+    }
+
+    fn next_string(&mut self) -> anyhow::Result<RedisString> {
+        match self.next()? {
+            ModuleValue::String(v) => Ok(v.clone()),
+            v => bail! {Error::RedisRdbError(format!("expect string, got: {:?}", v))},
+        }
+    }
+
+    fn finish(&self) -> anyhow::Result<()> {
+        if self.inx != self.values.len() {
+            bail! {Error::RedisRdbError(format!(
+                "unexpected module values left, decoded: {}, total: {}",
+                self.inx,
+                self.values.len()
+            ))}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_obj(module_name: &str, enc_ver: u64) -> ModuleObject {
+        let mut obj = ModuleObject::new();
+        obj.key = RedisString::from("k".to_string());
+        obj.module_name = module_name.into();
+        obj.enc_ver = enc_ver;
+        obj
+    }
+
+    fn str_value(bytes: &[u8]) -> ModuleValue {
+        ModuleValue::String(RedisString::from(bytes.to_vec()))
+    }
+
+    #[test]
+    fn test_module_type_name_by_id() {
+        // module id of ReJSON-RL with enc_ver 3
+        let name_char_set: Vec<char> = MODULE_TYPE_NAME_CHAR_SET.chars().collect();
+        let mut module_id = 0u64;
+        for c in MODULE_REJSON.chars() {
+            let i = name_char_set.iter().position(|i| *i == c).unwrap() as u64;
+            module_id = (module_id << 6) | i;
+        }
+        module_id = (module_id << 10) | 3;
+        assert_eq!(
+            ModuleParser::module_type_name_by_id(module_id),
+            MODULE_REJSON
+        );
+    }
+
+    #[test]
+    fn test_decode_rejson() {
+        let obj = new_obj(MODULE_REJSON, 3);
+        let values = vec![str_value(br#"{"a":1}"#)];
+        let cmds = ModuleParser::decode_rejson(&obj, &values).unwrap();
+        assert_eq!(cmds.len(), 1);
+        assert_eq!(cmds[0].to_string(), r#"JSON.SET k . {"a":1}"#);
+        assert_eq!(cmds[0].key_indexes, vec![2]);
+
+        // legacy node based encoding
+        let obj = new_obj(MODULE_REJSON, 0);
+        assert!(ModuleParser::decode_rejson(&obj, &values).is_err());
+    }
+
+    #[test]
+    fn test_decode_bloom() {
+        let obj = new_obj(MODULE_BLOOM, 4);
+        let values = vec![
+            ModuleValue::Uint(3),   // size
+            ModuleValue::Uint(1),   // n_filters
+            ModuleValue::Uint(0),   // options
+            ModuleValue::Uint(2),   // growth
+            ModuleValue::Uint(100), // entries
+            ModuleValue::Double(0.01),
+            ModuleValue::Uint(7), // hashes
+            ModuleValue::Double(9.58),
+            ModuleValue::Uint(1024), // bits
+            ModuleValue::Uint(10),   // n2
+            str_value(&[1u8; 128]),
+            ModuleValue::Uint(3), // link size
+        ];
+        let cmds = ModuleParser::decode_bloom(&obj, &values).unwrap();
+        assert_eq!(cmds.len(), 2);
+        assert_eq!(cmds[0].get_str_arg(0), "BF.LOADCHUNK");
+        assert_eq!(cmds[0].get_str_arg(2), "1");
+        // dumpedChainHeader + 1 dumpedChainLink
+        assert_eq!(cmds[0].args[3].len(), 20 + 53);
+        assert_eq!(cmds[1].get_str_arg(2), "129");
+        assert_eq!(cmds[1].args[3], vec![1u8; 128]);
+
+        // values left
+        let mut values = values;
+        values.push(ModuleValue::Uint(0));
+        assert!(ModuleParser::decode_bloom(&obj, &values).is_err());
+    }
+
+    #[test]
+    fn test_decode_cuckoo() {
+        let obj = new_obj(MODULE_CUCKOO, 4);
+        let values = vec![
+            ModuleValue::Uint(2),  // n_filters
+            ModuleValue::Uint(4),  // n_buckets
+            ModuleValue::Uint(5),  // n_items
+            ModuleValue::Uint(0),  // n_deletes
+            ModuleValue::Uint(2),  // bucket_size
+            ModuleValue::Uint(20), // max_iterations
+            ModuleValue::Uint(2),  // expansion
+            ModuleValue::Uint(4),  // n_buckets of filter 1
+            str_value(&[1u8; 8]),
+            ModuleValue::Uint(8), // n_buckets of filter 2
+            str_value(&[2u8; 16]),
+        ];
+        let cmds = ModuleParser::decode_cuckoo(&obj, &values).unwrap();
+        assert_eq!(cmds.len(), 3);
+        assert_eq!(
+            cmds[0].to_string().split(' ').next().unwrap(),
+            "CF.LOADCHUNK"
+        );
+        assert_eq!(cmds[0].args[3].len(), 38);
+        assert_eq!(cmds[1].get_str_arg(2), "9");
+        assert_eq!(cmds[2].get_str_arg(2), "25");
+        assert_eq!(cmds[2].args[3], vec![2u8; 16]);
+    }
+
+    #[test]
+    fn test_decode_timeseries() {
+        let obj = new_obj(MODULE_TIMESERIES, 6);
+        let mut samples = Vec::new();
+        for (ts, v) in [(1000u64, 1.5f64), (2000, 2.0)] {
+            samples.extend_from_slice(&ts.to_le_bytes());
+            samples.extend_from_slice(&v.to_le_bytes());
+        }
+        samples.extend_from_slice(&[0u8; 32]);
+        let mut values = vec![
+            str_value(b"k"),
+            ModuleValue::Uint(0),    // retention
+            ModuleValue::Uint(64),   // chunk size
+            ModuleValue::Uint(1),    // options
+            ModuleValue::Uint(2000), // last timestamp
+            ModuleValue::Double(2.0),
+            ModuleValue::Uint(2), // total samples
+            ModuleValue::Uint(2), // duplicate policy
+            ModuleValue::Uint(0), // no src key
+            ModuleValue::Uint(1), // labels
+            str_value(b"l1"),
+            str_value(b"v1"),
+            ModuleValue::Uint(0), // rules
+            ModuleValue::Uint(1), // chunks
+            ModuleValue::Uint(1000),
+            ModuleValue::Uint(2),
+            ModuleValue::Uint(64),
+            str_value(&samples),
+        ];
+        let cmds = ModuleParser::decode_timeseries(&obj, &values).unwrap();
+        assert_eq!(cmds.len(), 2);
+        assert_eq!(
+            cmds[0].to_string(),
+            "TS.CREATE k RETENTION 0 ENCODING UNCOMPRESSED CHUNK_SIZE 64 DUPLICATE_POLICY LAST LABELS l1 v1"
+        );
+        assert_eq!(cmds[1].to_string(), "TS.MADD k 1000 1.5 k 2000 2");
+        assert_eq!(cmds[1].key_indexes, vec![2, 5]);
+
+        // compressed chunk
+        values.truncate(14);
+        values.push(ModuleValue::Uint(64)); // size
+        values.push(ModuleValue::Uint(2)); // count
+        values.push(ModuleValue::Uint(0)); // idx
+        assert!(ModuleParser::decode_timeseries(&obj, &values).is_err());
+    }
 }
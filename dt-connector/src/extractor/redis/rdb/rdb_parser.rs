@@ -18,13 +18,7 @@ const K_FLAG_EXPIRE_MS: u8 = 0xfc; // Expire time in milliseconds.
 const K_FLAG_EXPIRE: u8 = 0xfd; // Old expire time in seconds.
 const K_FLAG_SELECT: u8 = 0xfe; // DB number of the following keys.
 const K_EOF: u8 = 0xff; // End of the RDB file.
-
-const RDB_MODULE_OPCODE_EOF: u64 = 0; // End of module value.
-const RDB_MODULE_OPCODE_SINT: u64 = 1; // Signed integer.
-const RDB_MODULE_OPCODE_UINT: u64 = 2; // Unsigned integer.
-const RDB_MODULE_OPCODE_FLOAT: u64 = 3; // Float.
-const RDB_MODULE_OPCODE_DOUBLE: u64 = 4; // Double.
-const RDB_MODULE_OPCODE_STRING: u64 = 5; // String.
+const RDB_CHECKSUM_LEN: usize = 8;
 
 pub struct RdbParser<'a> {
     pub reader: RdbReader<'a>,
@@ -68,30 +62,7 @@ impl RdbParser<'_> {
                 // refer: https://github.com/redis/redis/blob/unstable/src/rdb.c#L3183
                 let _when_opcode = self.reader.read_length()?;
                 let _when = self.reader.read_length()?;
-                let mut opcode = self.reader.read_length()?;
-                while opcode != RDB_MODULE_OPCODE_EOF {
-                    match opcode {
-                        RDB_MODULE_OPCODE_SINT | RDB_MODULE_OPCODE_UINT => {
-                            self.reader.read_length()?;
-                        }
-                        RDB_MODULE_OPCODE_FLOAT => {
-                            self.reader.read_float()?;
-                        }
-                        RDB_MODULE_OPCODE_DOUBLE => {
-                            self.reader.read_double()?;
-                        }
-                        RDB_MODULE_OPCODE_STRING => {
-                            self.reader.read_string()?;
-                        }
-                        _ => {
-                            bail! {Error::RedisRdbError(format!(
-                                "module aux opcode not found. module_name=[{}], opcode=[{}]",
-                                module_name, opcode
-                            ))}
-                        }
-                    }
-                    opcode = self.reader.read_length()?;
-                }
+                ModuleParser::load_module_values(&mut self.reader, &module_name)?;
             }
 
             K_FLAG_IDLE => {
@@ -168,8 +139,14 @@ impl RdbParser<'_> {
 
             K_EOF => {
                 self.is_end = true;
-                self.reader
-                    .read_bytes(self.reader.rdb_length - self.reader.position)?;
+                // rdb_length is 0 if the rdb is followed by other data whose length is unknown,
+                // eg: aof with rdb preamble, then only the checksum is left
+                let left = if self.reader.rdb_length > 0 {
+                    self.reader.rdb_length - self.reader.position
+                } else {
+                    RDB_CHECKSUM_LEN
+                };
+                self.reader.read_bytes(left)?;
             }
 
             _ => {
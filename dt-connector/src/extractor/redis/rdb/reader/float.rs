@@ -21,6 +21,12 @@ impl RdbReader<'_> {
         Ok(v)
     }
 
+    /// float saved by RedisModule_SaveFloat
+    pub fn read_binary_float(&mut self) -> anyhow::Result<f32> {
+        let buf = self.read_bytes(4)?;
+        Ok(LittleEndian::read_f32(&buf))
+    }
+
     pub fn read_double(&mut self) -> anyhow::Result<f64> {
         let buf = self.read_bytes(8)?;
         Ok(LittleEndian::read_f64(&buf))
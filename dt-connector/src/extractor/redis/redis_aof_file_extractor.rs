@@ -0,0 +1,470 @@
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, ErrorKind, Read};
+use std::path::{Path, PathBuf};
+
+use super::StreamReader;
+use crate::extractor::base_extractor::BaseExtractor;
+use crate::extractor::redis::rdb::rdb_parser::RdbParser;
+use crate::extractor::redis::rdb::reader::rdb_reader::RdbReader;
+use crate::extractor::redis::redis_psync_extractor::RedisPsyncExtractor;
+use crate::Extractor;
+use anyhow::bail;
+use async_trait::async_trait;
+use dt_common::error::Error;
+use dt_common::log_info;
+use dt_common::log_warn;
+use dt_common::meta::dt_data::DtData;
+use dt_common::meta::position::Position;
+use dt_common::meta::redis::command::key_parser::KeyParser;
+use dt_common::meta::redis::redis_entry::RedisEntry;
+use dt_common::meta::redis::redis_object::RedisCmd;
+use dt_common::rdb_filter::RdbFilter;
+
+const RDB_MAGIC: &[u8] = b"REDIS";
+const MANIFEST_SUFFIX: &str = ".manifest";
+const MANIFEST_FILE_TYPE_BASE: &str = "b";
+const MANIFEST_FILE_TYPE_HISTORY: &str = "h";
+const MANIFEST_FILE_TYPE_INCR: &str = "i";
+
+/// Extract data from:
+/// 1, a single aof file, which may start with a rdb preamble.
+/// 2, the multi-part aof of redis 7.0+, file_path is the manifest file or the directory holding it,
+/// the base file (rdb or aof) is loaded first, then the incr files ordered by seq.
+pub struct RedisAofFileExtractor {
+    pub file_path: String,
+    pub filter: RdbFilter,
+    pub base_extractor: BaseExtractor,
+    pub key_parser: KeyParser,
+}
+
+struct AofFileReader {
+    pub reader: BufReader<File>,
+}
+
+#[derive(Debug)]
+struct ManifestItem {
+    file_name: String,
+    seq: u64,
+    file_type: String,
+}
+
+#[async_trait]
+impl Extractor for RedisAofFileExtractor {
+    async fn extract(&mut self) -> anyhow::Result<()> {
+        let aof_files = Self::get_aof_files(&self.file_path)?;
+        log_info!("RedisAofFileExtractor starts, aof files: {:?}", aof_files);
+
+        for aof_file in aof_files.iter() {
+            self.extract_aof_file(aof_file).await?;
+        }
+
+        log_info!(
+            "end extracting data from aof, all count: {}",
+            self.base_extractor.monitor.counters.record_count
+        );
+        self.base_extractor.wait_task_finish().await
+    }
+}
+
+impl RedisAofFileExtractor {
+    async fn extract_aof_file(&mut self, aof_file: &Path) -> anyhow::Result<()> {
+        log_info!("start extracting aof file: {}", aof_file.display());
+        let file = File::open(aof_file)?;
+        let mut file_reader = AofFileReader {
+            reader: BufReader::new(file),
+        };
+
+        // every aof file starts with db 0
+        let mut now_db_id = 0;
+        if file_reader.reader.fill_buf()?.starts_with(RDB_MAGIC) {
+            now_db_id = self.extract_rdb(&mut file_reader).await?;
+        }
+        self.extract_cmds(&mut file_reader, now_db_id).await
+    }
+
+    /// load the base rdb file or the rdb preamble of an aof file, return the last selected db
+    async fn extract_rdb(&mut self, file_reader: &mut AofFileReader) -> anyhow::Result<i64> {
+        let mut stream_reader: Box<&mut (dyn StreamReader + Send)> = Box::new(file_reader);
+        let reader = RdbReader {
+            conn: &mut stream_reader,
+            // the rdb preamble may be followed by aof commands
+            rdb_length: 0,
+            position: 0,
+            copy_raw: false,
+            raw_bytes: Vec::new(),
+        };
+
+        let mut parser = RdbParser {
+            reader,
+            repl_stream_db_id: 0,
+            now_db_id: 0,
+            expire_ms: 0,
+            idle: 0,
+            freq: 0,
+            is_end: false,
+        };
+
+        let version = parser.load_meta()?;
+        log_info!("source rdb version: {:?}", version);
+
+        loop {
+            if let Some(entry) = parser.load_entry()? {
+                RedisPsyncExtractor::push_to_buf(
+                    &mut self.base_extractor,
+                    &mut self.filter,
+                    &self.key_parser,
+                    entry,
+                    Position::None,
+                )
+                .await?;
+            }
+
+            if parser.is_end {
+                break;
+            }
+        }
+        Ok(parser.now_db_id)
+    }
+
+    async fn extract_cmds(
+        &mut self,
+        file_reader: &mut AofFileReader,
+        mut now_db_id: i64,
+    ) -> anyhow::Result<()> {
+        while let Some(cmd) = Self::read_cmd(&mut file_reader.reader)? {
+            let cmd_name = cmd.get_name().to_ascii_lowercase();
+
+            // switch db
+            if cmd_name == "select" {
+                now_db_id = Self::parse_select_db(&cmd)?;
+                continue;
+            }
+
+            if cmd_name == "multi" {
+                self.base_extractor
+                    .push_dt_data(DtData::Begin {}, Position::None)
+                    .await?;
+                continue;
+            }
+
+            if cmd_name == "exec" {
+                self.base_extractor
+                    .push_dt_data(DtData::Commit { xid: String::new() }, Position::None)
+                    .await?;
+                continue;
+            }
+
+            // filter dangerous cmds, eg: flushdb, flushall
+            if self.filter.filter_cmd(&cmd_name) {
+                continue;
+            }
+
+            let mut entry = RedisEntry::new();
+            entry.cmd = cmd;
+            entry.db_id = now_db_id;
+            RedisPsyncExtractor::push_to_buf(
+                &mut self.base_extractor,
+                &mut self.filter,
+                &self.key_parser,
+                entry,
+                Position::None,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    fn parse_select_db(cmd: &RedisCmd) -> anyhow::Result<i64> {
+        if cmd.args.len() != 2 {
+            bail! {Error::RedisCmdError(format!("invalid select cmd in aof: {}", cmd))}
+        }
+        match cmd.get_str_arg(1).parse::<i64>() {
+            Ok(db_id) => Ok(db_id),
+            Err(_) => bail! {Error::RedisCmdError(format!("invalid select cmd in aof: {}", cmd))},
+        }
+    }
+
+    /// read a command in RESP format, return None if reaching the end of file,
+    /// a truncated command at the end of file is ignored, same as aof-load-truncated of redis
+    fn read_cmd<R: BufRead>(reader: &mut R) -> anyhow::Result<Option<RedisCmd>> {
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            if reader.read_until(b'\n', &mut line)? == 0 {
+                return Ok(None);
+            }
+            // annotations, eg: #TS:1628217470 if aof-timestamp-enabled
+            if line[0] != b'#' {
+                break;
+            }
+        }
+
+        let arg_count = Self::parse_resp_len(&line, b'*')?;
+        if arg_count == 0 {
+            bail! {Error::RedisCmdError("invalid aof format: empty cmd".into())}
+        }
+        let mut cmd = RedisCmd::new();
+        for _ in 0..arg_count {
+            line.clear();
+            if reader.read_until(b'\n', &mut line)? == 0 || !line.ends_with(b"\n") {
+                return Self::truncated_cmd(&cmd);
+            }
+
+            let arg_len = Self::parse_resp_len(&line, b'$')?;
+            let mut arg = vec![0; arg_len + 2];
+            if let Err(err) = reader.read_exact(&mut arg) {
+                if err.kind() == ErrorKind::UnexpectedEof {
+                    return Self::truncated_cmd(&cmd);
+                }
+                return Err(err.into());
+            }
+            arg.truncate(arg_len);
+            cmd.add_arg(arg);
+        }
+        Ok(Some(cmd))
+    }
+
+    fn truncated_cmd(cmd: &RedisCmd) -> anyhow::Result<Option<RedisCmd>> {
+        log_warn!(
+            "aof file truncated, ignore the last incomplete cmd: {}",
+            cmd.to_string()
+        );
+        Ok(None)
+    }
+
+    fn parse_resp_len(line: &[u8], prefix: u8) -> anyhow::Result<usize> {
+        let str = String::from_utf8_lossy(line);
+        let str = str.trim_end();
+        if line[0] != prefix {
+            bail! {Error::RedisCmdError(format!("invalid aof format: {}", str))}
+        }
+        match str[1..].parse::<usize>() {
+            Ok(len) => Ok(len),
+            Err(_) => bail! {Error::RedisCmdError(format!("invalid aof format: {}", str))},
+        }
+    }
+
+    fn get_aof_files(file_path: &str) -> anyhow::Result<Vec<PathBuf>> {
+        let path = Path::new(file_path);
+        let manifest = if path.is_dir() {
+            Self::find_manifest(path)?
+        } else if file_path.ends_with(MANIFEST_SUFFIX) {
+            path.to_path_buf()
+        } else {
+            return Ok(vec![path.to_path_buf()]);
+        };
+
+        let dir = manifest.parent().unwrap_or(Path::new("."));
+        let items = Self::parse_manifest(&fs::read_to_string(&manifest)?)?;
+        Ok(items
+            .into_iter()
+            .map(|item| dir.join(item.file_name))
+            .collect())
+    }
+
+    fn find_manifest(dir: &Path) -> anyhow::Result<PathBuf> {
+        let mut manifests = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.to_string_lossy().ends_with(MANIFEST_SUFFIX) {
+                manifests.push(path);
+            }
+        }
+
+        if manifests.len() != 1 {
+            bail! {Error::ConfigError(format!(
+                "expect exactly 1 aof manifest in dir: {}, found: {:?}",
+                dir.display(),
+                manifests
+            ))}
+        }
+        Ok(manifests.remove(0))
+    }
+
+    /// manifest format, refer: https://github.com/redis/redis/blob/7.0/src/aof.c
+    /// file appendonly.aof.1.base.rdb seq 1 type b
+    /// file appendonly.aof.1.incr.aof seq 1 type i
+    /// return the base file followed by the incr files ordered by seq, history files are ignored
+    fn parse_manifest(content: &str) -> anyhow::Result<Vec<ManifestItem>> {
+        let mut base = None;
+        let mut incrs = Vec::new();
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let tokens = Self::split_manifest_line(line)?;
+            if tokens.len() % 2 != 0 {
+                bail! {Error::ConfigError(format!("invalid aof manifest line: {}", line))}
+            }
+
+            let mut item = ManifestItem {
+                file_name: String::new(),
+                seq: 0,
+                file_type: String::new(),
+            };
+            for kv in tokens.chunks(2) {
+                match kv[0].as_str() {
+                    "file" => item.file_name = kv[1].clone(),
+                    "seq" => item.seq = kv[1].parse()?,
+                    "type" => item.file_type = kv[1].clone(),
+                    // unknown keys are ignored for forward compatibility, same as redis
+                    _ => {}
+                }
+            }
+
+            if item.file_name.is_empty() {
+                bail! {Error::ConfigError(format!("invalid aof manifest line: {}", line))}
+            }
+
+            match item.file_type.as_str() {
+                MANIFEST_FILE_TYPE_BASE => base = Some(item),
+                MANIFEST_FILE_TYPE_INCR => incrs.push(item),
+                MANIFEST_FILE_TYPE_HISTORY => {}
+                _ => {
+                    bail! {Error::ConfigError(format!("invalid aof manifest line: {}", line))}
+                }
+            }
+        }
+
+        incrs.sort_by_key(|item| item.seq);
+        let mut items = Vec::new();
+        if let Some(base) = base {
+            items.push(base);
+        }
+        items.extend(incrs);
+        Ok(items)
+    }
+
+    /// file names with spaces or special chars are quoted in manifest
+    fn split_manifest_line(line: &str) -> anyhow::Result<Vec<String>> {
+        let mut tokens = Vec::new();
+        let mut chars = line.chars().peekable();
+        loop {
+            while chars.peek().is_some_and(|c| c.is_whitespace()) {
+                chars.next();
+            }
+
+            let mut token = String::new();
+            match chars.peek() {
+                None => break,
+                Some('"') => {
+                    chars.next();
+                    loop {
+                        match chars.next() {
+                            Some('"') => break,
+                            Some('\\') => match chars.next() {
+                                Some('n') => token.push('\n'),
+                                Some('r') => token.push('\r'),
+                                Some('t') => token.push('\t'),
+                                Some(c) => token.push(c),
+                                None => break,
+                            },
+                            Some(c) => token.push(c),
+                            None => {
+                                bail! {Error::ConfigError(format!("unbalanced quotes in aof manifest line: {}", line))}
+                            }
+                        }
+                    }
+                }
+                Some(_) => {
+                    while let Some(c) = chars.peek() {
+                        if c.is_whitespace() {
+                            break;
+                        }
+                        token.push(*c);
+                        chars.next();
+                    }
+                }
+            }
+            tokens.push(token);
+        }
+        Ok(tokens)
+    }
+}
+
+impl StreamReader for AofFileReader {
+    fn read_bytes(&mut self, size: usize) -> anyhow::Result<Vec<u8>> {
+        let mut buf = vec![0; size];
+        self.reader.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_manifest() {
+        let content = "file appendonly.aof.2.incr.aof seq 2 type i\n\
+            file appendonly.aof.1.base.rdb seq 1 type b\n\
+            file appendonly.aof.1.incr.aof seq 1 type i\n\
+            file appendonly.aof.0.base.rdb seq 0 type h\n\
+            file \"append only.aof.3.incr.aof\" seq 3 type i\n";
+        let items = RedisAofFileExtractor::parse_manifest(content).unwrap();
+        let file_names: Vec<&str> = items.iter().map(|i| i.file_name.as_str()).collect();
+        assert_eq!(
+            file_names,
+            vec![
+                "appendonly.aof.1.base.rdb",
+                "appendonly.aof.1.incr.aof",
+                "appendonly.aof.2.incr.aof",
+                "append only.aof.3.incr.aof"
+            ]
+        );
+
+        assert!(RedisAofFileExtractor::parse_manifest("file a.aof seq 1 type x").is_err());
+        assert!(RedisAofFileExtractor::parse_manifest("file \"a.aof seq 1 type i").is_err());
+    }
+
+    #[test]
+    fn test_read_cmd() {
+        let aof = b"*2\r\n$6\r\nSELECT\r\n$1\r\n0\r\n\
+            #TS:1628217470\r\n\
+            *3\r\n$3\r\nSET\r\n$2\r\nk1\r\n$4\r\nv\r\n1\r\n\
+            *3\r\n$3\r\nSET\r\n$2\r\nk2";
+        let mut reader = Cursor::new(aof.to_vec());
+
+        let cmd = RedisAofFileExtractor::read_cmd(&mut reader)
+            .unwrap()
+            .unwrap();
+        assert_eq!(cmd.args, vec![b"SELECT".to_vec(), b"0".to_vec()]);
+
+        // binary safe
+        let cmd = RedisAofFileExtractor::read_cmd(&mut reader)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            cmd.args,
+            vec![b"SET".to_vec(), b"k1".to_vec(), b"v\r\n1".to_vec()]
+        );
+
+        // truncated
+        assert!(RedisAofFileExtractor::read_cmd(&mut reader)
+            .unwrap()
+            .is_none());
+
+        let mut reader = Cursor::new(b"SET k1 v1\r\n".to_vec());
+        assert!(RedisAofFileExtractor::read_cmd(&mut reader).is_err());
+
+        let mut reader = Cursor::new(b"*0\r\n".to_vec());
+        assert!(RedisAofFileExtractor::read_cmd(&mut reader).is_err());
+    }
+
+    #[test]
+    fn test_parse_select_db() {
+        let cmd = RedisCmd::from_str_args(&["SELECT", "2"]);
+        assert_eq!(RedisAofFileExtractor::parse_select_db(&cmd).unwrap(), 2);
+
+        // bare select
+        let cmd = RedisCmd::from_str_args(&["SELECT"]);
+        assert!(RedisAofFileExtractor::parse_select_db(&cmd).is_err());
+
+        let cmd = RedisCmd::from_str_args(&["SELECT", "a"]);
+        assert!(RedisAofFileExtractor::parse_select_db(&cmd).is_err());
+    }
+}
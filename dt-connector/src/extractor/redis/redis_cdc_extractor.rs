@@ -14,6 +14,7 @@ use dt_common::log_info;
 use dt_common::log_warn;
 use dt_common::meta::dt_data::DtData;
 use dt_common::meta::position::Position;
+use dt_common::meta::redis::command::key_parser::KeyParser;
 use dt_common::meta::redis::redis_entry::RedisEntry;
 use dt_common::meta::redis::redis_object::RedisCmd;
 use dt_common::meta::syncer::Syncer;
@@ -41,6 +42,7 @@ pub struct RedisCdcExtractor {
     pub syncer: Arc<Mutex<Syncer>>,
     pub filter: RdbFilter,
     pub resumer: CdcResumer,
+    pub key_parser: KeyParser,
 }
 
 #[async_trait]
@@ -76,6 +78,7 @@ impl Extractor for RedisCdcExtractor {
             repl_port: self.repl_port,
             now_db_id: self.now_db_id,
             filter: self.filter.clone(),
+            key_parser: &self.key_parser,
         };
 
         // receive rdb data if needed
@@ -199,6 +202,7 @@ impl RedisCdcExtractor {
                 RedisPsyncExtractor::push_to_buf(
                     &mut self.base_extractor,
                     &mut self.filter,
+                    &self.key_parser,
                     entry,
                     position,
                 )
@@ -269,7 +273,7 @@ impl RedisCdcExtractor {
             return Ok(());
         }
 
-        let mut conn = RedisClient::new(&self.conn.url).await?;
+        let mut conn = RedisClient::new(&self.conn.url, &self.conn.tls).await?;
         let heartbeat_interval_secs = self.heartbeat_interval_secs;
         let key = key.to_string();
 
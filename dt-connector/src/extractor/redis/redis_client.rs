@@ -3,17 +3,26 @@ use super::redis_resp_types::Value;
 use super::StreamReader;
 use anyhow::bail;
 use async_std::io::BufReader;
+use async_std::io::{Read, Write};
 use async_std::net::TcpStream;
 use async_std::prelude::*;
+use dt_common::config::tls_config::TlsConfig;
 use dt_common::error::Error;
 use dt_common::meta::redis::command::cmd_encoder::CmdEncoder;
 use dt_common::meta::redis::redis_object::RedisCmd;
 use dt_common::utils::url_util::UrlUtil;
 use futures::executor::block_on;
 
+use crate::tls_util::TlsUtil;
+
+pub trait RedisStream: Read + Write + Unpin + Send {}
+
+impl<T: Read + Write + Unpin + Send> RedisStream for T {}
+
 pub struct RedisClient {
     pub url: String,
-    stream: BufReader<TcpStream>,
+    pub tls: TlsConfig,
+    stream: BufReader<Box<dyn RedisStream>>,
 }
 
 impl StreamReader for RedisClient {
@@ -23,16 +32,23 @@ impl StreamReader for RedisClient {
 }
 
 impl RedisClient {
-    pub async fn new(url: &str) -> anyhow::Result<Self> {
+    pub async fn new(url: &str, tls: &TlsConfig) -> anyhow::Result<Self> {
         let url_info = UrlUtil::parse(url)?;
         let host = url_info.host_str().unwrap();
         let port = url_info.port().unwrap();
         let username = url_info.username();
         let password = url_info.password();
 
-        let stream = TcpStream::connect(format!("{}:{}", host, port)).await?;
+        let tcp_stream = TcpStream::connect(format!("{}:{}", host, port)).await?;
+        let stream: Box<dyn RedisStream> = if tls.is_enabled() {
+            let connector = TlsUtil::build_async_tls_connector(tls)?;
+            Box::new(connector.connect(host, tcp_stream).await?)
+        } else {
+            Box::new(tcp_stream)
+        };
         let mut me = Self {
             url: url.into(),
+            tls: tls.clone(),
             stream: BufReader::new(stream),
         };
 
@@ -58,7 +74,7 @@ impl RedisClient {
     }
 
     pub async fn close(&mut self) -> anyhow::Result<()> {
-        self.stream.get_mut().shutdown(std::net::Shutdown::Both)?;
+        self.stream.get_mut().close().await?;
         Ok(())
     }
 
@@ -1,10 +1,12 @@
-use anyhow::bail;
+use anyhow::{bail, Context};
 use async_trait::async_trait;
 use dt_common::log_position;
+use dt_common::log_warn;
 use dt_common::meta::dt_data::DtData;
 use dt_common::meta::position::Position;
+use dt_common::meta::redis::command::key_parser::KeyParser;
 use dt_common::meta::redis::redis_entry::RedisEntry;
-use dt_common::meta::redis::redis_object::RedisCmd;
+use dt_common::meta::redis::redis_object::{RedisCmd, RedisObject};
 use dt_common::rdb_filter::RdbFilter;
 use dt_common::{error::Error, log_info};
 
@@ -26,6 +28,7 @@ pub struct RedisPsyncExtractor<'a> {
     pub now_db_id: i64,
     pub repl_port: u64,
     pub filter: RdbFilter,
+    pub key_parser: &'a KeyParser,
 }
 
 #[async_trait]
@@ -143,8 +146,14 @@ impl RedisPsyncExtractor<'_> {
         loop {
             if let Some(entry) = parser.load_entry()? {
                 self.now_db_id = entry.db_id;
-                Self::push_to_buf(self.base_extractor, &mut self.filter, entry, Position::None)
-                    .await?;
+                Self::push_to_buf(
+                    self.base_extractor,
+                    &mut self.filter,
+                    self.key_parser,
+                    entry,
+                    Position::None,
+                )
+                .await?;
             }
 
             if parser.is_end {
@@ -171,17 +180,82 @@ impl RedisPsyncExtractor<'_> {
     pub async fn push_to_buf(
         base_extractor: &mut BaseExtractor,
         filter: &mut RdbFilter,
+        key_parser: &KeyParser,
         mut entry: RedisEntry,
         position: Position,
     ) -> anyhow::Result<()> {
-        // currently only support db filter
         if filter.filter_schema(&entry.db_id.to_string()) {
             return Ok(());
         }
 
         entry.data_size = entry.get_data_malloc_size();
+        let route_key = !base_extractor.router.key_prefix_map.is_empty();
+        if Self::filter_entry(filter, key_parser, &mut entry, route_key)? {
+            return Ok(());
+        }
+
+        let entry = base_extractor.router.route_redis_entry(entry);
         base_extractor
             .push_dt_data(DtData::Redis { entry }, position)
             .await
     }
+
+    fn filter_entry(
+        filter: &RdbFilter,
+        key_parser: &KeyParser,
+        entry: &mut RedisEntry,
+        route_key: bool,
+    ) -> anyhow::Result<bool> {
+        // entry decoded from rdb, it contains exactly 1 key
+        if entry.is_base {
+            if filter.filter_redis_key_type(&entry.get_type())
+                || filter.filter_redis_key_size(entry.data_size)
+                || filter.filter_redis_key(&entry.key.to_string())
+            {
+                return Ok(true);
+            }
+
+            // stream entry is rewritten as cmds, keys in them are needed by router
+            if let RedisObject::Stream(obj) = &mut entry.value {
+                if route_key {
+                    for cmd in obj.cmds.iter_mut() {
+                        cmd.parse_keys(key_parser)
+                            .with_context(|| format!("failed to parse keys for cmd: [{}]", cmd))?;
+                    }
+                }
+            }
+            return Ok(false);
+        }
+
+        // entry from aof, the cmd may contain 0 or multiple keys
+        if !filter.has_redis_key_filter() && !filter.has_redis_key_type_filter() && !route_key {
+            return Ok(false);
+        }
+
+        entry
+            .cmd
+            .parse_keys(key_parser)
+            .with_context(|| format!("failed to parse keys for cmd: [{}]", entry.cmd))?;
+
+        if let Some(key_type) = entry.cmd.get_key_type() {
+            if filter.filter_redis_key_type(key_type) {
+                return Ok(true);
+            }
+        }
+
+        let filtered_count = entry
+            .cmd
+            .keys
+            .iter()
+            .filter(|key| filter.filter_redis_key(key))
+            .count();
+        if filtered_count > 0 && filtered_count < entry.cmd.keys.len() {
+            // a multi-key cmd can not be split, drop it if any of its keys is filtered
+            log_warn!(
+                "cmd dropped since some of its keys are filtered, cmd: [{}]",
+                entry.cmd
+            );
+        }
+        Ok(filtered_count > 0)
+    }
 }
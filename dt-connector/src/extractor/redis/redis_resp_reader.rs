@@ -1,10 +1,9 @@
 use anyhow::{bail, Ok};
 use async_recursion::async_recursion;
 use async_std::io::BufReader;
-use async_std::net::TcpStream;
 use async_std::prelude::*;
 
-use super::redis_resp_types::Value;
+use super::{redis_client::RedisStream, redis_resp_types::Value};
 
 pub struct RedisRespReader {
     pub read_len: usize,
@@ -16,7 +15,10 @@ const OK_RESPONSE: &[u8] = &[79, 75];
 
 impl RedisRespReader {
     #[async_recursion]
-    pub async fn decode(&mut self, reader: &mut BufReader<TcpStream>) -> anyhow::Result<Value> {
+    pub async fn decode(
+        &mut self,
+        reader: &mut BufReader<Box<dyn RedisStream>>,
+    ) -> anyhow::Result<Value> {
         let mut res: Vec<u8> = Vec::new();
         reader.read_until(b'\n', &mut res).await?;
 
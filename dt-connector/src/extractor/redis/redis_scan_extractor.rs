@@ -64,6 +64,12 @@ impl Extractor for RedisScanExtractor {
                     match self.statistic_type {
                         RedisStatisticType::HotKey => self.analyze_hot_key(db_id, key).await?,
                         RedisStatisticType::BigKey => self.analyze_big_key(db_id, key).await?,
+                        RedisStatisticType::MemoryReport => {
+                            bail! {Error::ConfigError(
+                                "memory_report is only supported by extract_type=snapshot or snapshot_file"
+                                    .into()
+                            )}
+                        }
                     }
                 }
 
@@ -3,6 +3,7 @@ use super::redis_psync_extractor::RedisPsyncExtractor;
 use crate::extractor::base_extractor::BaseExtractor;
 use crate::Extractor;
 use async_trait::async_trait;
+use dt_common::meta::redis::command::key_parser::KeyParser;
 use dt_common::rdb_filter::RdbFilter;
 
 pub struct RedisSnapshotExtractor {
@@ -10,6 +11,7 @@ pub struct RedisSnapshotExtractor {
     pub conn: RedisClient,
     pub repl_port: u64,
     pub filter: RdbFilter,
+    pub key_parser: KeyParser,
 }
 
 #[async_trait]
@@ -23,6 +25,7 @@ impl Extractor for RedisSnapshotExtractor {
             repl_port: self.repl_port,
             now_db_id: 0,
             filter: self.filter.clone(),
+            key_parser: &self.key_parser,
         };
         psync_extractor.extract().await?;
         self.base_extractor.wait_task_finish().await
@@ -10,12 +10,14 @@ use crate::Extractor;
 use async_trait::async_trait;
 use dt_common::log_info;
 use dt_common::meta::position::Position;
+use dt_common::meta::redis::command::key_parser::KeyParser;
 use dt_common::rdb_filter::RdbFilter;
 
 pub struct RedisSnapshotFileExtractor {
     pub file_path: String,
     pub filter: RdbFilter,
     pub base_extractor: BaseExtractor,
+    pub key_parser: KeyParser,
 }
 
 struct RdbFileReader {
@@ -56,6 +58,7 @@ impl Extractor for RedisSnapshotFileExtractor {
                 RedisPsyncExtractor::push_to_buf(
                     &mut self.base_extractor,
                     &mut self.filter,
+                    &self.key_parser,
                     entry,
                     Position::None,
                 )
@@ -8,12 +8,19 @@ use dt_common::{
 };
 use serde_json::json;
 
-use super::{CURRENT_POSITION_LOG_FLAG, TAIL_POSITION_COUNT};
+use super::{
+    BACKFILL_POSITION_LOG_FLAG, CURRENT_POSITION_LOG_FLAG, HANDOFF_POSITION_LOG_FLAG,
+    TAIL_POSITION_COUNT,
+};
 
 #[derive(Clone, Default)]
 pub struct CdcResumer {
     pub current_position: Position,
     pub checkpoint_position: Position,
+    /// the cdc position recorded before the snapshot of extract_type=snapshot_and_cdc
+    pub handoff_position: Position,
+    /// committed progress of cdc backfill in order, refer to CdcBackfill::resume
+    pub backfill_positions: Vec<Position>,
 }
 
 impl CdcResumer {
@@ -34,6 +41,17 @@ impl CdcResumer {
                     me.load_resume_line(line)
                 }
             }
+
+            let finished_log = format!("{}/finished.log", config.resume_log_dir);
+            if let Ok(file) = File::open(finished_log) {
+                for line in BufReader::new(file).lines().map_while(Result::ok) {
+                    if line.contains(HANDOFF_POSITION_LOG_FLAG) {
+                        me.handoff_position = Position::from_log(&line);
+                    } else if line.contains(BACKFILL_POSITION_LOG_FLAG) {
+                        me.backfill_positions.push(Position::from_log(&line));
+                    }
+                }
+            }
         }
         Ok(me)
     }
@@ -3,3 +3,9 @@ pub mod snapshot_resumer;
 
 const CURRENT_POSITION_LOG_FLAG: &str = "| current_position |";
 const TAIL_POSITION_COUNT: usize = 30;
+/// logged in finished.log by extract_type=snapshot_and_cdc before the snapshot starts
+pub const HANDOFF_POSITION_LOG_FLAG: &str = "| handoff_position |";
+/// logged in finished.log by mysql consistent snapshot with flush_tables_with_read_lock=true
+pub const SNAPSHOT_POSITION_LOG_FLAG: &str = "| snapshot_position |";
+/// logged in finished.log by cdc backfill once the progress is committed
+pub const BACKFILL_POSITION_LOG_FLAG: &str = "| backfill_position |";
@@ -7,7 +7,7 @@ use std::{
 use dt_common::{config::resumer_config::ResumerConfig, utils::file_util::FileUtil};
 use dt_common::{log_info, meta::position::Position};
 
-use super::{CURRENT_POSITION_LOG_FLAG, TAIL_POSITION_COUNT};
+use super::{BACKFILL_POSITION_LOG_FLAG, CURRENT_POSITION_LOG_FLAG, TAIL_POSITION_COUNT};
 
 #[derive(Clone, Default)]
 pub struct SnapshotResumer {
@@ -42,6 +42,10 @@ impl SnapshotResumer {
             let finished_log = format!("{}/finished.log", config.resume_log_dir);
             if let Ok(file) = File::open(finished_log) {
                 for line in BufReader::new(file).lines().map_while(Result::ok) {
+                    // progress of cdc backfill, not of the snapshot
+                    if line.contains(BACKFILL_POSITION_LOG_FLAG) {
+                        continue;
+                    }
                     me.load_resume_line(&line)
                 }
             }
@@ -0,0 +1,179 @@
+use std::{
+    ops::{Deref, DerefMut},
+    sync::{Arc, Mutex},
+};
+
+use anyhow::bail;
+use dt_common::error::Error;
+use sqlx::{pool::PoolConnection, Connection, Database, MySql, Pool, Postgres};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Connections of a consistent snapshot, each of them holds an open transaction started
+/// under the same view until the snapshot is closed. They are never returned to the
+/// underlying pool, so a lost connection fails queries instead of being replaced by
+/// a new one out of the view.
+pub struct SnapshotConnPool<DB: Database> {
+    conn_pool: Pool<DB>,
+    conns: Arc<Mutex<Vec<PoolConnection<DB>>>>,
+    semaphore: Arc<Semaphore>,
+}
+
+impl<DB: Database> Clone for SnapshotConnPool<DB> {
+    fn clone(&self) -> Self {
+        Self {
+            conn_pool: self.conn_pool.clone(),
+            conns: self.conns.clone(),
+            semaphore: self.semaphore.clone(),
+        }
+    }
+}
+
+impl<DB: Database> SnapshotConnPool<DB> {
+    /// conns: connections acquired from conn_pool, which have started their transactions
+    pub fn new(conn_pool: Pool<DB>, conns: Vec<PoolConnection<DB>>) -> Self {
+        let semaphore = Arc::new(Semaphore::new(conns.len()));
+        Self {
+            conn_pool,
+            conns: Arc::new(Mutex::new(conns)),
+            semaphore,
+        }
+    }
+
+    /// Wait until a connection is idle, it is given back when the returned SnapshotConn drops.
+    pub async fn acquire(&self) -> anyhow::Result<SnapshotConn<DB>> {
+        let permit = match self.semaphore.clone().acquire_owned().await {
+            Ok(permit) => permit,
+            Err(_) => bail! {Error::ExtractorError("consistent snapshot is closed".into())},
+        };
+        let conn = self.conns.lock().unwrap().pop();
+        if conn.is_none() {
+            bail! {Error::ExtractorError(
+                "no idle connection in consistent snapshot".into()
+            )}
+        }
+        Ok(SnapshotConn {
+            conn,
+            conns: self.conns.clone(),
+            _permit: permit,
+        })
+    }
+
+    /// Close the connections, which rolls back the transactions and releases the view.
+    pub async fn close(&self) -> anyhow::Result<()> {
+        self.semaphore.close();
+        let conns: Vec<PoolConnection<DB>> = self.conns.lock().unwrap().drain(..).collect();
+        for conn in conns {
+            conn.detach().close().await?;
+        }
+        self.conn_pool.close().await;
+        Ok(())
+    }
+}
+
+pub struct SnapshotConn<DB: Database> {
+    conn: Option<PoolConnection<DB>>,
+    conns: Arc<Mutex<Vec<PoolConnection<DB>>>>,
+    // released after the connection is given back
+    _permit: OwnedSemaphorePermit,
+}
+
+impl<DB: Database> Drop for SnapshotConn<DB> {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            self.conns.lock().unwrap().push(conn);
+        }
+    }
+}
+
+impl<DB: Database> Deref for SnapshotConn<DB> {
+    type Target = DB::Connection;
+
+    fn deref(&self) -> &Self::Target {
+        self.conn.as_ref().unwrap()
+    }
+}
+
+impl<DB: Database> DerefMut for SnapshotConn<DB> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.conn.as_mut().unwrap()
+    }
+}
+
+/// Connections of a snapshot extractor, either from a regular pool, or from the
+/// consistent snapshot shared by all tables of the task.
+pub enum ExtractorConnPool<DB: Database> {
+    Pool(Pool<DB>),
+    Snapshot(SnapshotConnPool<DB>),
+}
+
+impl<DB: Database> Clone for ExtractorConnPool<DB> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Pool(conn_pool) => Self::Pool(conn_pool.clone()),
+            Self::Snapshot(conn_pool) => Self::Snapshot(conn_pool.clone()),
+        }
+    }
+}
+
+impl<DB: Database> ExtractorConnPool<DB> {
+    pub async fn acquire(&self) -> anyhow::Result<ExtractorConn<DB>> {
+        match self {
+            Self::Pool(conn_pool) => Ok(ExtractorConn::Pool(conn_pool.acquire().await?)),
+            Self::Snapshot(conn_pool) => Ok(ExtractorConn::Snapshot(conn_pool.acquire().await?)),
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        match self {
+            Self::Pool(conn_pool) => conn_pool.is_closed(),
+            Self::Snapshot(conn_pool) => conn_pool.conn_pool.is_closed(),
+        }
+    }
+
+    /// The consistent snapshot is shared by tables and closed by the task.
+    pub async fn close(&self) {
+        if let Self::Pool(conn_pool) = self {
+            conn_pool.close().await
+        }
+    }
+}
+
+pub enum ExtractorConn<DB: Database> {
+    Pool(PoolConnection<DB>),
+    Snapshot(SnapshotConn<DB>),
+}
+
+impl<DB: Database> Deref for ExtractorConn<DB> {
+    type Target = DB::Connection;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Pool(conn) => &**conn,
+            Self::Snapshot(conn) => &**conn,
+        }
+    }
+}
+
+impl<DB: Database> DerefMut for ExtractorConn<DB> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            Self::Pool(conn) => &mut **conn,
+            Self::Snapshot(conn) => &mut **conn,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub enum ConsistentSnapshot {
+    MySQL(SnapshotConnPool<MySql>),
+    PostgreSQL(SnapshotConnPool<Postgres>),
+}
+
+impl ConsistentSnapshot {
+    pub async fn close(&self) -> anyhow::Result<()> {
+        match self {
+            Self::MySQL(conn_pool) => conn_pool.close().await,
+            Self::PostgreSQL(conn_pool) => conn_pool.close().await,
+        }
+    }
+}
@@ -10,6 +10,7 @@ pub mod meta_fetcher;
 pub mod rdb_query_builder;
 pub mod rdb_router;
 pub mod sinker;
+pub mod tls_util;
 
 use async_trait::async_trait;
 use check_log::check_log::CheckLog;
@@ -256,6 +256,7 @@ impl PgStructCheckFetcher {
 
     fn mock_col_type(alias: &str) -> PgColType {
         let mut col_type = PgColType {
+            schema: String::new(),
             name: String::new(),
             alias: "varchar".into(),
             value_type: PgValueType::String,
@@ -8,7 +8,7 @@ use dt_common::meta::{
     },
     col_value::ColValue,
     mysql::{mysql_col_type::MysqlColType, mysql_tb_meta::MysqlTbMeta},
-    pg::pg_tb_meta::PgTbMeta,
+    pg::{pg_col_type::PgColType, pg_tb_meta::PgTbMeta, type_registry::TypeRegistry},
     rdb_tb_meta::RdbTbMeta,
     row_data::RowData,
     row_type::RowType,
@@ -505,6 +505,38 @@ impl RdbQueryBuilder<'_> {
         Ok(self.get_pg_sql_value(col_value.unwrap()))
     }
 
+    /// Condition to extract rows after start_value, with start_value inlined as a literal
+    /// cast to the type of order_col, for sqls which can not take parameters, e.g. COPY.
+    pub fn get_pg_resume_condition(
+        order_col: &str,
+        order_col_type: &PgColType,
+        start_value: &ColValue,
+        type_registry: &TypeRegistry,
+    ) -> anyhow::Result<String> {
+        let cast_type = type_registry.get_cast_type(order_col_type.oid)?;
+        Ok(format!(
+            "{} > {}",
+            SqlUtil::escape_by_db_type(order_col, &DbType::Pg),
+            Self::get_pg_literal(start_value, &cast_type)
+        ))
+    }
+
+    /// E'...' is parsed the same whatever standard_conforming_strings is,
+    /// backslashes and quotes are escaped.
+    pub fn get_pg_literal(col_value: &ColValue, cast_type: &str) -> String {
+        let value = match col_value {
+            ColValue::None => return "NULL".to_string(),
+            // bytea hex format, the backslash is escaped
+            ColValue::Blob(v) => format!(r"\\x{}", hex::encode(v)),
+            _ => col_value
+                .to_option_string()
+                .unwrap_or_default()
+                .replace('\\', r"\\")
+                .replace('\'', "''"),
+        };
+        format!("E'{}'::{}", value, cast_type)
+    }
+
     fn get_pg_sql_value(&self, col_value: &ColValue) -> String {
         match col_value {
             ColValue::Blob(v) => format!(r#"'\x{}'"#, hex::encode(v)),
@@ -585,3 +617,42 @@ impl RdbQueryBuilder<'_> {
         SqlUtil::escape_cols(cols, &self.db_type)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_pg_literal() {
+        assert_eq!(
+            RdbQueryBuilder::get_pg_literal(&ColValue::Long(10), "int4"),
+            "E'10'::int4"
+        );
+        assert_eq!(
+            RdbQueryBuilder::get_pg_literal(&ColValue::String(r"a'b\c".into()), "varchar"),
+            r"E'a''b\\c'::varchar"
+        );
+        assert_eq!(
+            RdbQueryBuilder::get_pg_literal(
+                &ColValue::DateTime("2024-01-01 00:00:01".into()),
+                "timestamp"
+            ),
+            "E'2024-01-01 00:00:01'::timestamp"
+        );
+        assert_eq!(
+            RdbQueryBuilder::get_pg_literal(&ColValue::Blob(vec![0, 255]), "bytea"),
+            r"E'\\x00ff'::bytea"
+        );
+        assert_eq!(
+            RdbQueryBuilder::get_pg_literal(
+                &ColValue::String("ok".into()),
+                r#""my schema"."mood""#
+            ),
+            r#"E'ok'::"my schema"."mood""#
+        );
+        assert_eq!(
+            RdbQueryBuilder::get_pg_literal(&ColValue::None, "int4"),
+            "NULL"
+        );
+    }
+}
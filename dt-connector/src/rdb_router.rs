@@ -5,6 +5,10 @@ use dt_common::{
     },
     meta::{
         ddl_meta::{ddl_data::DdlData, ddl_statement::DdlStatement},
+        redis::{
+            redis_entry::RedisEntry,
+            redis_object::{RedisCmd, RedisObject, RedisString},
+        },
         struct_meta::{statement::struct_statement::StructStatement, struct_data::StructData},
     },
     utils::sql_util::SqlUtil,
@@ -30,6 +34,10 @@ pub struct RdbRouter {
     pub col_map: TbColMap,
     // HashMap<(src_schema, src_tb), String>
     pub topic_map: HashMap<(String, String), String>,
+    // HashMap<src_key_prefix, dst_key_prefix>, only for redis
+    pub key_prefix_map: HashMap<String, String>,
+    // HashMap<(dst_schema, dst_tb), endpoint>, only for webhook
+    pub endpoint_map: HashMap<(String, String), String>,
 }
 
 impl RdbRouter {
@@ -40,16 +48,22 @@ impl RdbRouter {
                 tb_map,
                 col_map,
                 topic_map,
+                key_prefix_map,
+                endpoint_map,
             } => {
                 let schema_map = Self::parse_schema_map(schema_map, db_type)?;
                 let tb_map = Self::parse_tb_map(tb_map, db_type)?;
                 let col_map = Self::parse_col_map(col_map)?;
                 let topic_map = Self::parse_topic_map(topic_map, db_type)?;
+                let key_prefix_map = Self::parse_key_prefix_map(key_prefix_map)?;
+                let endpoint_map = Self::parse_endpoint_map(endpoint_map)?;
                 Ok(Self {
                     schema_map,
                     tb_map,
                     col_map,
                     topic_map,
+                    key_prefix_map,
+                    endpoint_map,
                 })
             }
         }
@@ -88,6 +102,19 @@ impl RdbRouter {
         return self.topic_map.get(&("*".into(), "*".into())).unwrap();
     }
 
+    pub fn get_endpoint<'a>(&'a self, schema: &str, tb: &str) -> Option<&'a str> {
+        // tb can be *, the exact match wins
+        if let Some(endpoint) = self.endpoint_map.get(&(schema.into(), tb.into())) {
+            return Some(endpoint);
+        }
+        if let Some(endpoint) = self.endpoint_map.get(&(schema.into(), "*".into())) {
+            return Some(endpoint);
+        }
+        self.endpoint_map
+            .get(&("*".into(), "*".into()))
+            .map(|i| i.as_str())
+    }
+
     pub fn reverse(&self) -> Self {
         let mut reverse_schema_map = HashMap::new();
         let mut reverse_tb_map = HashMap::new();
@@ -110,12 +137,21 @@ impl RdbRouter {
             reverse_schema_map.insert(dst_db.to_owned(), src_schema.to_owned());
         }
 
+        let reverse_key_prefix_map = self
+            .key_prefix_map
+            .iter()
+            .map(|(src, dst)| (dst.to_owned(), src.to_owned()))
+            .collect();
+
         Self {
             schema_map: reverse_schema_map,
             tb_map: reverse_tb_map,
             col_map: reverse_tb_col_map,
             // topic_map should not be reversed
             topic_map: self.topic_map.clone(),
+            key_prefix_map: reverse_key_prefix_map,
+            // endpoint_map should not be reversed
+            endpoint_map: self.endpoint_map.clone(),
         }
     }
 
@@ -217,6 +253,83 @@ impl RdbRouter {
         struct_data
     }
 
+    pub fn route_redis_entry(&self, mut entry: RedisEntry) -> RedisEntry {
+        // db_map=0:1,2:3
+        if let Some(dst_db_id) = self
+            .schema_map
+            .get(&entry.db_id.to_string())
+            .and_then(|i| i.parse().ok())
+        {
+            entry.db_id = dst_db_id;
+        }
+
+        if self.key_prefix_map.is_empty() {
+            return entry;
+        }
+
+        if entry.is_base {
+            entry.key = self.route_redis_key(&entry.key);
+            let dst_key = entry.key.clone();
+            match &mut entry.value {
+                RedisObject::String(obj) => obj.key = dst_key,
+                RedisObject::List(obj) => obj.key = dst_key,
+                RedisObject::Hash(obj) => obj.key = dst_key,
+                RedisObject::Set(obj) => obj.key = dst_key,
+                RedisObject::Zset(obj) => obj.key = dst_key,
+                RedisObject::Stream(obj) => {
+                    obj.key = dst_key;
+                    for cmd in obj.cmds.iter_mut() {
+                        self.route_redis_cmd(cmd);
+                    }
+                }
+                RedisObject::Module(obj) => {
+                    obj.key = dst_key;
+                    for cmd in obj.cmds.iter_mut() {
+                        self.route_redis_cmd(cmd);
+                    }
+                }
+                RedisObject::Unknown => {}
+            }
+        } else {
+            self.route_redis_cmd(&mut entry.cmd);
+        }
+        entry
+    }
+
+    /// rewrite key args of the cmd, cmd.parse_keys should be called before this
+    pub fn route_redis_cmd(&self, cmd: &mut RedisCmd) {
+        for (i, key_index) in cmd.key_indexes.iter().enumerate() {
+            // key_indexes are 1-based
+            if *key_index == 0 || *key_index > cmd.args.len() {
+                continue;
+            }
+            let dst_key = self.route_redis_key(&RedisString::from(cmd.args[key_index - 1].clone()));
+            if let Some(key) = cmd.keys.get_mut(i) {
+                *key = dst_key.to_string();
+            }
+            cmd.args[key_index - 1] = dst_key.bytes;
+        }
+    }
+
+    pub fn route_redis_key(&self, key: &RedisString) -> RedisString {
+        // the longest matched prefix wins
+        let mut matched: Option<(&String, &String)> = None;
+        for (src_prefix, dst_prefix) in self.key_prefix_map.iter() {
+            if key.as_bytes().starts_with(src_prefix.as_bytes())
+                && matched.map_or(true, |(m, _)| src_prefix.len() > m.len())
+            {
+                matched = Some((src_prefix, dst_prefix));
+            }
+        }
+
+        if let Some((src_prefix, dst_prefix)) = matched {
+            let mut bytes = dst_prefix.as_bytes().to_vec();
+            bytes.extend_from_slice(&key.as_bytes()[src_prefix.len()..]);
+            return RedisString::from(bytes);
+        }
+        key.clone()
+    }
+
     fn parse_schema_map(config_str: &str, db_type: &DbType) -> anyhow::Result<SchemaMap> {
         // db_map=src_db_1:dst_db_1,src_db_2:dst_db_2
         let mut schema_map = HashMap::new();
@@ -283,6 +396,36 @@ impl RdbRouter {
         Ok(results)
     }
 
+    fn parse_key_prefix_map(config_str: &str) -> anyhow::Result<HashMap<String, String>> {
+        if config_str.trim().is_empty() {
+            return Ok(HashMap::new());
+        }
+        // key_prefix_map=json:{"app1:":"app2:","app1:user:":"app2:u:"}
+        let key_prefix_map = serde_json::from_str(config_str.trim_start_matches(JSON_PREFIX))?;
+        Ok(key_prefix_map)
+    }
+
+    fn parse_endpoint_map(config_str: &str) -> anyhow::Result<HashMap<(String, String), String>> {
+        let mut results = HashMap::new();
+        if config_str.trim().is_empty() {
+            return Ok(results);
+        }
+
+        #[derive(Serialize, Deserialize)]
+        struct EndpointMapType {
+            db: String,
+            tb: String,
+            endpoint: String,
+        }
+        // endpoint_map=json:[{"db":"test_db","tb":"orders","endpoint":"http://127.0.0.1:8080/orders"}]
+        let config: Vec<EndpointMapType> =
+            serde_json::from_str(config_str.trim_start_matches(JSON_PREFIX))?;
+        for i in config {
+            results.insert((i.db, i.tb), i.endpoint);
+        }
+        Ok(results)
+    }
+
     fn parse_config(config_str: &str, db_type: &DbType) -> anyhow::Result<Vec<String>> {
         let delimiters = vec![',', '.', ':'];
         let tokens = ConfigTokenParser::parse_config(config_str, db_type, &delimiters)?;
@@ -303,7 +446,13 @@ impl RdbRouter {
 mod tests {
     use std::collections::HashMap;
 
-    use dt_common::config::{config_enums::DbType, router_config::RouterConfig};
+    use dt_common::{
+        config::{config_enums::DbType, router_config::RouterConfig},
+        meta::redis::{
+            redis_entry::RedisEntry,
+            redis_object::{RedisCmd, RedisObject, StringObject},
+        },
+    };
 
     use super::{RdbRouter, TbColMap, TbMap};
 
@@ -418,12 +567,15 @@ mod tests {
         let tb_map_str = "`src_db,2'`.`src_tb,2'`:dst_db_2.dst_tb_2,`src_db:3,`.`src_tb:3,`:`dst_db:3,`.`dst_tb:3,`";
         let col_map_str = r#"[{"db":"src_db:3,","tb":"src_tb:3,","col_map":{"src_col:1,":"dst_col:1,","src_col:2,":"dst_col:2,"}}]"#;
         let topic_map = "*.*:test,`db:1`.*:test2,`db:1`.`tb:1`:test3";
+        let endpoint_map = r#"json:[{"db":"db_1","tb":"*","endpoint":"http://127.0.0.1:8080/db_1"},{"db":"db_1","tb":"tb_1","endpoint":"http://127.0.0.1:8080/tb_1"}]"#;
 
         let config = RouterConfig::Rdb {
             schema_map: db_map_str.into(),
             tb_map: tb_map_str.into(),
             col_map: col_map_str.into(),
             topic_map: topic_map.into(),
+            key_prefix_map: String::new(),
+            endpoint_map: endpoint_map.into(),
         };
         let router = RdbRouter::from_config(&config, &DbType::Mysql).unwrap();
 
@@ -449,5 +601,72 @@ mod tests {
         assert_eq!(router.get_topic("db:1", "tb:1"), "test3");
         assert_eq!(router.get_topic("db:1", "tb:2"), "test2");
         assert_eq!(router.get_topic("db:2", "tb:1"), "test");
+        // endpoint_map
+        assert_eq!(
+            router.get_endpoint("db_1", "tb_1"),
+            Some("http://127.0.0.1:8080/tb_1")
+        );
+        assert_eq!(
+            router.get_endpoint("db_1", "tb_2"),
+            Some("http://127.0.0.1:8080/db_1")
+        );
+        assert_eq!(router.get_endpoint("db_2", "tb_1"), None);
+    }
+
+    #[test]
+    fn test_route_redis_entry() {
+        let config = RouterConfig::Rdb {
+            schema_map: "0:1,2:3".into(),
+            tb_map: String::new(),
+            col_map: String::new(),
+            topic_map: String::new(),
+            key_prefix_map: r#"json:{"app1:":"app2:","app1:user:":"app2:u:"}"#.into(),
+            endpoint_map: String::new(),
+        };
+        let router = RdbRouter::from_config(&config, &DbType::Redis).unwrap();
+
+        let route_key = |key: &str| router.route_redis_key(&key.to_string().into()).to_string();
+        assert_eq!(route_key("app1:order:1"), "app2:order:1");
+        assert_eq!(route_key("app1:user:1"), "app2:u:1");
+        assert_eq!(route_key("app3:user:1"), "app3:user:1");
+
+        // rdb entry
+        let mut entry = RedisEntry::new();
+        entry.is_base = true;
+        entry.key = "app1:k".to_string().into();
+        let mut obj = StringObject::new();
+        obj.key = entry.key.clone();
+        entry.value = RedisObject::String(obj);
+        let entry = router.route_redis_entry(entry);
+        assert_eq!(entry.db_id, 1);
+        assert_eq!(entry.key.to_string(), "app2:k");
+        if let RedisObject::String(obj) = &entry.value {
+            assert_eq!(obj.key.to_string(), "app2:k");
+        } else {
+            panic!("unexpected redis object");
+        }
+
+        // cdc cmd with multiple keys
+        let mut entry = RedisEntry::new();
+        entry.db_id = 5;
+        entry.cmd = RedisCmd::from_str_args(&["MSET", "app1:a", "1", "app3:b", "2"]);
+        entry.cmd.keys = vec!["app1:a".into(), "app3:b".into()];
+        entry.cmd.key_indexes = vec![2, 4];
+        let entry = router.route_redis_entry(entry);
+        assert_eq!(entry.db_id, 5);
+        assert_eq!(
+            entry.cmd.args_to_string(),
+            vec!["MSET", "app2:a", "1", "app3:b", "2"]
+        );
+        assert_eq!(entry.cmd.keys, vec!["app2:a", "app3:b"]);
+
+        // reverse
+        let reverse_router = router.reverse();
+        assert_eq!(
+            reverse_router
+                .route_redis_key(&"app2:u:1".to_string().into())
+                .to_string(),
+            "app1:user:1"
+        );
     }
 }
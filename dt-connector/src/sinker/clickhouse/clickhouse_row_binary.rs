@@ -0,0 +1,611 @@
+use anyhow::{bail, Context};
+use chrono::{NaiveDate, NaiveDateTime};
+use dt_common::{error::Error, meta::col_value::ColValue};
+
+// column types in system.columns of clickhouse
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClickhouseColType {
+    Nullable(Box<ClickhouseColType>),
+    Bool,
+    Int8,
+    Int16,
+    Int32,
+    Int64,
+    Int128,
+    UInt8,
+    UInt16,
+    UInt32,
+    UInt64,
+    UInt128,
+    Float32,
+    Float64,
+    Decimal { precision: u32, scale: u32 },
+    String,
+    FixedString(usize),
+    Date,
+    Date32,
+    DateTime,
+    DateTime64(u32),
+    Uuid,
+    Enum8(Vec<(String, i8)>),
+    Enum16(Vec<(String, i16)>),
+    Unknown(String),
+}
+
+impl ClickhouseColType {
+    pub fn parse(type_str: &str) -> Self {
+        let type_str = type_str.trim();
+        let (name, args) = match type_str.find('(') {
+            Some(i) if type_str.ends_with(')') => {
+                (&type_str[..i], &type_str[i + 1..type_str.len() - 1])
+            }
+            _ => (type_str, ""),
+        };
+        let int_args: Vec<u32> = args
+            .split(',')
+            .filter_map(|i| i.trim().parse::<u32>().ok())
+            .collect();
+
+        match name {
+            "Nullable" => Self::Nullable(Box::new(Self::parse(args))),
+            // LowCardinality is transparent in RowBinary
+            "LowCardinality" => Self::parse(args),
+            "Bool" | "Boolean" => Self::Bool,
+            "Int8" => Self::Int8,
+            "Int16" => Self::Int16,
+            "Int32" => Self::Int32,
+            "Int64" => Self::Int64,
+            "Int128" => Self::Int128,
+            "UInt8" => Self::UInt8,
+            "UInt16" => Self::UInt16,
+            "UInt32" => Self::UInt32,
+            "UInt64" => Self::UInt64,
+            "UInt128" => Self::UInt128,
+            "Float32" => Self::Float32,
+            "Float64" => Self::Float64,
+            "Decimal" if int_args.len() == 2 => Self::Decimal {
+                precision: int_args[0],
+                scale: int_args[1],
+            },
+            "Decimal32" | "Decimal64" | "Decimal128" | "Decimal256" if int_args.len() == 1 => {
+                let precision = match name {
+                    "Decimal32" => 9,
+                    "Decimal64" => 18,
+                    "Decimal128" => 38,
+                    _ => 76,
+                };
+                Self::Decimal {
+                    precision,
+                    scale: int_args[0],
+                }
+            }
+            "String" => Self::String,
+            "FixedString" if int_args.len() == 1 => Self::FixedString(int_args[0] as usize),
+            "Date" => Self::Date,
+            "Date32" => Self::Date32,
+            // DateTime('Asia/Shanghai')
+            "DateTime" => Self::DateTime,
+            // DateTime64(6), DateTime64(6, 'Asia/Shanghai')
+            "DateTime64" if !int_args.is_empty() => Self::DateTime64(int_args[0]),
+            "UUID" => Self::Uuid,
+            "Enum8" => Self::Enum8(Self::parse_enum_items(args)),
+            "Enum16" => Self::Enum16(Self::parse_enum_items(args)),
+            _ => Self::Unknown(type_str.to_string()),
+        }
+    }
+
+    // 'a' = 1, 'b' = 2
+    fn parse_enum_items<T: std::str::FromStr>(args: &str) -> Vec<(String, T)> {
+        let mut items = Vec::new();
+        for item in args.split(',') {
+            if let Some((name, value)) = item.rsplit_once('=') {
+                if let Ok(value) = value.trim().parse::<T>() {
+                    let name = name.trim().trim_matches('\'').replace("\\'", "'");
+                    items.push((name, value));
+                }
+            }
+        }
+        items
+    }
+}
+
+pub struct ClickhouseRowBinary {}
+
+impl ClickhouseRowBinary {
+    // https://clickhouse.com/docs/en/interfaces/formats#rowbinary
+    pub fn write_value(
+        buf: &mut Vec<u8>,
+        col_type: &ClickhouseColType,
+        col_value: Option<&ColValue>,
+    ) -> anyhow::Result<()> {
+        let col_value = match col_value {
+            Some(ColValue::None) | None => None,
+            Some(v) => Some(v),
+        };
+
+        if let ClickhouseColType::Nullable(inner_type) = col_type {
+            return match col_value {
+                None => {
+                    buf.push(1);
+                    Ok(())
+                }
+                Some(v) => {
+                    buf.push(0);
+                    Self::write_value(buf, inner_type, Some(v))
+                }
+            };
+        }
+
+        match col_value {
+            None => Self::write_default(buf, col_type),
+            Some(v) => Self::write_not_null(buf, col_type, v)
+                .with_context(|| format!("col_type: {:?}, col_value: {:?}", col_type, v)),
+        }
+    }
+
+    fn write_not_null(
+        buf: &mut Vec<u8>,
+        col_type: &ClickhouseColType,
+        col_value: &ColValue,
+    ) -> anyhow::Result<()> {
+        match col_type {
+            ClickhouseColType::Bool => {
+                let v = match col_value {
+                    ColValue::Bool(v) => *v,
+                    ColValue::String(v) => matches!(v.as_str(), "true" | "t" | "1"),
+                    _ => Self::to_i128(col_value)? != 0,
+                };
+                buf.push(v as u8);
+            }
+
+            ClickhouseColType::Int8 => buf.push(i8::try_from(Self::to_i128(col_value)?)? as u8),
+            ClickhouseColType::Int16 => {
+                buf.extend_from_slice(&i16::try_from(Self::to_i128(col_value)?)?.to_le_bytes())
+            }
+            ClickhouseColType::Int32 => {
+                buf.extend_from_slice(&i32::try_from(Self::to_i128(col_value)?)?.to_le_bytes())
+            }
+            ClickhouseColType::Int64 => {
+                buf.extend_from_slice(&i64::try_from(Self::to_i128(col_value)?)?.to_le_bytes())
+            }
+            ClickhouseColType::Int128 => {
+                buf.extend_from_slice(&Self::to_i128(col_value)?.to_le_bytes())
+            }
+            ClickhouseColType::UInt8 => buf.push(u8::try_from(Self::to_i128(col_value)?)?),
+            ClickhouseColType::UInt16 => {
+                buf.extend_from_slice(&u16::try_from(Self::to_i128(col_value)?)?.to_le_bytes())
+            }
+            ClickhouseColType::UInt32 => {
+                buf.extend_from_slice(&u32::try_from(Self::to_i128(col_value)?)?.to_le_bytes())
+            }
+            ClickhouseColType::UInt64 => {
+                buf.extend_from_slice(&u64::try_from(Self::to_i128(col_value)?)?.to_le_bytes())
+            }
+            ClickhouseColType::UInt128 => {
+                buf.extend_from_slice(&u128::try_from(Self::to_i128(col_value)?)?.to_le_bytes())
+            }
+
+            ClickhouseColType::Float32 => {
+                buf.extend_from_slice(&(Self::to_f64(col_value)? as f32).to_le_bytes())
+            }
+            ClickhouseColType::Float64 => {
+                buf.extend_from_slice(&Self::to_f64(col_value)?.to_le_bytes())
+            }
+
+            ClickhouseColType::Decimal { precision, scale } => {
+                let (negative, digits) = Self::parse_decimal(&Self::to_string(col_value), *scale)?;
+                Self::write_decimal(buf, *precision, negative, &digits)?;
+            }
+
+            ClickhouseColType::String => {
+                let bytes = Self::to_bytes(col_value);
+                Self::write_var_uint(buf, bytes.len() as u64);
+                buf.extend_from_slice(&bytes);
+            }
+
+            ClickhouseColType::FixedString(length) => {
+                let bytes = Self::to_bytes(col_value);
+                if bytes.len() > *length {
+                    bail! {Error::SinkerError(format!(
+                        "value too long for FixedString({}), length: {}",
+                        length,
+                        bytes.len()
+                    ))}
+                }
+                buf.extend_from_slice(&bytes);
+                buf.resize(buf.len() + length - bytes.len(), 0);
+            }
+
+            ClickhouseColType::Date => {
+                let days = Self::parse_days(&Self::to_string(col_value))?;
+                buf.extend_from_slice(&u16::try_from(days)?.to_le_bytes());
+            }
+            ClickhouseColType::Date32 => {
+                let days = Self::parse_days(&Self::to_string(col_value))?;
+                buf.extend_from_slice(&i32::try_from(days)?.to_le_bytes());
+            }
+
+            ClickhouseColType::DateTime => {
+                let (secs, _) = Self::parse_datetime(&Self::to_string(col_value))?;
+                buf.extend_from_slice(&u32::try_from(secs)?.to_le_bytes());
+            }
+            ClickhouseColType::DateTime64(precision) => {
+                let (secs, nanos) = Self::parse_datetime(&Self::to_string(col_value))?;
+                if *precision > 9 {
+                    bail! {Error::SinkerError(format!(
+                        "unsupported precision of DateTime64: {}",
+                        precision
+                    ))}
+                }
+                let ticks =
+                    secs * 10_i64.pow(*precision) + (nanos / 10_u32.pow(9 - precision)) as i64;
+                buf.extend_from_slice(&ticks.to_le_bytes());
+            }
+
+            ClickhouseColType::Uuid => {
+                let str = Self::to_string(col_value).replace('-', "");
+                let v = u128::from_str_radix(&str, 16)?;
+                // the high 64 bits first, both in little endian
+                buf.extend_from_slice(&((v >> 64) as u64).to_le_bytes());
+                buf.extend_from_slice(&(v as u64).to_le_bytes());
+            }
+
+            ClickhouseColType::Enum8(items) => {
+                let v = Self::get_enum_value(items, col_value)?;
+                buf.push(i8::try_from(v)? as u8);
+            }
+            ClickhouseColType::Enum16(items) => {
+                let v = Self::get_enum_value(items, col_value)?;
+                buf.extend_from_slice(&i16::try_from(v)?.to_le_bytes());
+            }
+
+            ClickhouseColType::Nullable(_) | ClickhouseColType::Unknown(_) => {
+                bail! {Error::SinkerError(format!(
+                    "unsupported clickhouse type: {:?}",
+                    col_type
+                ))}
+            }
+        }
+        Ok(())
+    }
+
+    // default values of non-nullable columns, which are written when the source value is NULL
+    fn write_default(buf: &mut Vec<u8>, col_type: &ClickhouseColType) -> anyhow::Result<()> {
+        let size = match col_type {
+            ClickhouseColType::Nullable(_) => {
+                buf.push(1);
+                return Ok(());
+            }
+            ClickhouseColType::Enum8(items) => {
+                let v = items.first().map(|i| i.1).unwrap_or(0);
+                buf.push(v as u8);
+                return Ok(());
+            }
+            ClickhouseColType::Enum16(items) => {
+                let v = items.first().map(|i| i.1).unwrap_or(0);
+                buf.extend_from_slice(&v.to_le_bytes());
+                return Ok(());
+            }
+            ClickhouseColType::Unknown(_) => {
+                bail! {Error::SinkerError(format!(
+                    "unsupported clickhouse type: {:?}",
+                    col_type
+                ))}
+            }
+            // length of empty strings
+            ClickhouseColType::String => 1,
+            ClickhouseColType::Bool | ClickhouseColType::Int8 | ClickhouseColType::UInt8 => 1,
+            ClickhouseColType::Int16 | ClickhouseColType::UInt16 | ClickhouseColType::Date => 2,
+            ClickhouseColType::Int32
+            | ClickhouseColType::UInt32
+            | ClickhouseColType::Float32
+            | ClickhouseColType::Date32
+            | ClickhouseColType::DateTime => 4,
+            ClickhouseColType::Int64
+            | ClickhouseColType::UInt64
+            | ClickhouseColType::Float64
+            | ClickhouseColType::DateTime64(_) => 8,
+            ClickhouseColType::Int128 | ClickhouseColType::UInt128 | ClickhouseColType::Uuid => 16,
+            ClickhouseColType::Decimal { precision, .. } => Self::get_decimal_size(*precision),
+            ClickhouseColType::FixedString(length) => *length,
+        };
+        buf.resize(buf.len() + size, 0);
+        Ok(())
+    }
+
+    pub fn write_var_uint(buf: &mut Vec<u8>, mut v: u64) {
+        // LEB128
+        while v >= 0x80 {
+            buf.push((v as u8) | 0x80);
+            v >>= 7;
+        }
+        buf.push(v as u8);
+    }
+
+    fn write_decimal(
+        buf: &mut Vec<u8>,
+        precision: u32,
+        negative: bool,
+        digits: &str,
+    ) -> anyhow::Result<()> {
+        let size = Self::get_decimal_size(precision);
+        if size == 32 {
+            // Decimal256 may exceed i128, encode the digits directly
+            let v = Self::to_le_bytes_256(negative, digits)?;
+            buf.extend_from_slice(&v);
+            return Ok(());
+        }
+
+        let v: i128 = digits.parse()?;
+        let v = if negative { -v } else { v };
+        match size {
+            4 => buf.extend_from_slice(&i32::try_from(v)?.to_le_bytes()),
+            8 => buf.extend_from_slice(&i64::try_from(v)?.to_le_bytes()),
+            _ => buf.extend_from_slice(&v.to_le_bytes()),
+        }
+        Ok(())
+    }
+
+    // decimal digits -> little-endian two's complement Int256
+    fn to_le_bytes_256(negative: bool, digits: &str) -> anyhow::Result<[u8; 32]> {
+        let mut bytes = [0_u8; 32];
+        for c in digits.bytes() {
+            let mut carry = (c - b'0') as u32;
+            for b in bytes.iter_mut() {
+                let v = *b as u32 * 10 + carry;
+                *b = v as u8;
+                carry = v >> 8;
+            }
+            // the sign bit is reserved
+            if carry != 0 || bytes[31] & 0x80 != 0 {
+                bail! {Error::SinkerError(format!("decimal out of Int256 range: {}", digits))}
+            }
+        }
+
+        if negative {
+            let mut carry = 1_u32;
+            for b in bytes.iter_mut() {
+                let v = (!*b) as u32 + carry;
+                *b = v as u8;
+                carry = v >> 8;
+            }
+        }
+        Ok(bytes)
+    }
+
+    fn get_decimal_size(precision: u32) -> usize {
+        match precision {
+            0..=9 => 4,
+            10..=18 => 8,
+            19..=38 => 16,
+            _ => 32,
+        }
+    }
+
+    // "-123.4567" with scale 2 -> (true, "12345"), extra fractional digits are truncated
+    fn parse_decimal(str: &str, scale: u32) -> anyhow::Result<(bool, String)> {
+        let str = str.trim();
+        let (negative, str) = match str.strip_prefix('-') {
+            Some(s) => (true, s),
+            None => (false, str.strip_prefix('+').unwrap_or(str)),
+        };
+        let (int_part, frac_part) = str.split_once('.').unwrap_or((str, ""));
+        let mut frac_part = frac_part.to_string();
+        frac_part.truncate(scale as usize);
+        while frac_part.len() < scale as usize {
+            frac_part.push('0');
+        }
+
+        let digits = format!("{}{}", int_part, frac_part);
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+            bail! {Error::SinkerError(format!("invalid decimal: {}", str))}
+        }
+        Ok((negative, digits))
+    }
+
+    // days since 1970-01-01
+    fn parse_days(str: &str) -> anyhow::Result<i64> {
+        let date_str = str.trim().get(..10).unwrap_or(str);
+        if date_str.starts_with("0000-00-00") {
+            return Ok(0);
+        }
+        let date = NaiveDate::parse_from_str(date_str, "%Y-%m-%d")?;
+        let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+        Ok((date - epoch).num_days())
+    }
+
+    // (seconds, nanoseconds) since 1970-01-01 00:00:00, values are taken as UTC
+    fn parse_datetime(str: &str) -> anyhow::Result<(i64, u32)> {
+        let str = str.trim().trim_end_matches("+00");
+        if str.starts_with("0000-00-00") {
+            return Ok((0, 0));
+        }
+
+        let datetime = NaiveDateTime::parse_from_str(str, "%Y-%m-%d %H:%M:%S%.f")
+            .or_else(|_| NaiveDateTime::parse_from_str(str, "%Y-%m-%dT%H:%M:%S%.f"))
+            .or_else(|_| {
+                NaiveDate::parse_from_str(str, "%Y-%m-%d").map(|d| d.and_hms_opt(0, 0, 0).unwrap())
+            })?
+            .and_utc();
+        Ok((datetime.timestamp(), datetime.timestamp_subsec_nanos()))
+    }
+
+    fn get_enum_value<T: Copy + Into<i128>>(
+        items: &[(String, T)],
+        col_value: &ColValue,
+    ) -> anyhow::Result<i128> {
+        let str = Self::to_string(col_value);
+        if let Some((_, v)) = items.iter().find(|(name, _)| *name == str) {
+            return Ok((*v).into());
+        }
+        match str.parse::<i128>() {
+            Ok(v) => Ok(v),
+            Err(_) => bail! {Error::SinkerError(format!("unknown enum value: {}", str))},
+        }
+    }
+
+    fn to_i128(col_value: &ColValue) -> anyhow::Result<i128> {
+        let v = match col_value {
+            ColValue::Bool(v) => *v as i128,
+            ColValue::Tiny(v) => *v as i128,
+            ColValue::UnsignedTiny(v) => *v as i128,
+            ColValue::Short(v) => *v as i128,
+            ColValue::UnsignedShort(v) => *v as i128,
+            ColValue::Long(v) => *v as i128,
+            ColValue::UnsignedLong(v) => *v as i128,
+            ColValue::LongLong(v) => *v as i128,
+            ColValue::UnsignedLongLong(v) => *v as i128,
+            ColValue::Year(v) => *v as i128,
+            ColValue::Bit(v) | ColValue::Set(v) => *v as i128,
+            ColValue::Enum(v) => *v as i128,
+            ColValue::Float(v) => *v as i128,
+            ColValue::Double(v) => *v as i128,
+            _ => Self::to_string(col_value).trim().parse::<i128>()?,
+        };
+        Ok(v)
+    }
+
+    fn to_f64(col_value: &ColValue) -> anyhow::Result<f64> {
+        let v = match col_value {
+            ColValue::Float(v) => *v as f64,
+            ColValue::Double(v) => *v,
+            _ => Self::to_string(col_value).trim().parse::<f64>()?,
+        };
+        Ok(v)
+    }
+
+    fn to_string(col_value: &ColValue) -> String {
+        col_value.to_option_string().unwrap_or_default()
+    }
+
+    fn to_bytes(col_value: &ColValue) -> Vec<u8> {
+        match col_value {
+            ColValue::RawString(v) | ColValue::Blob(v) | ColValue::Json(v) => v.clone(),
+            _ => Self::to_string(col_value).into_bytes(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(type_str: &str, col_value: ColValue) -> Vec<u8> {
+        let mut buf = Vec::new();
+        let col_type = ClickhouseColType::parse(type_str);
+        ClickhouseRowBinary::write_value(&mut buf, &col_type, Some(&col_value)).unwrap();
+        buf
+    }
+
+    #[test]
+    fn test_parse_col_type() {
+        assert_eq!(
+            ClickhouseColType::parse("Nullable(Decimal(10, 2))"),
+            ClickhouseColType::Nullable(Box::new(ClickhouseColType::Decimal {
+                precision: 10,
+                scale: 2
+            }))
+        );
+        assert_eq!(
+            ClickhouseColType::parse("LowCardinality(Nullable(String))"),
+            ClickhouseColType::Nullable(Box::new(ClickhouseColType::String))
+        );
+        assert_eq!(
+            ClickhouseColType::parse("DateTime64(6, 'Asia/Shanghai')"),
+            ClickhouseColType::DateTime64(6)
+        );
+        assert_eq!(
+            ClickhouseColType::parse("Enum8('a' = 1, 'b' = -2)"),
+            ClickhouseColType::Enum8(vec![("a".into(), 1), ("b".into(), -2)])
+        );
+        assert_eq!(
+            ClickhouseColType::parse("Array(Int32)"),
+            ClickhouseColType::Unknown("Array(Int32)".into())
+        );
+    }
+
+    #[test]
+    fn test_write_value() {
+        assert_eq!(encode("Int32", ColValue::Long(-2)), (-2_i32).to_le_bytes());
+        assert_eq!(
+            encode("UInt64", ColValue::String("18446744073709551615".into())),
+            u64::MAX.to_le_bytes()
+        );
+        assert_eq!(encode("String", ColValue::String("abc".into())), b"\x03abc");
+        assert_eq!(
+            encode("FixedString(4)", ColValue::String("ab".into())),
+            b"ab\0\0"
+        );
+        assert_eq!(
+            encode("Decimal(10,2)", ColValue::Decimal("-123.456".into())),
+            (-12345_i64).to_le_bytes()
+        );
+        assert_eq!(
+            encode("Decimal(40,2)", ColValue::Decimal("-1.5".into())),
+            [(-150_i128).to_le_bytes(), [0xff; 16]].concat()
+        );
+        // 2^200 has 61 digits, wider than i128
+        let mut expected = [0_u8; 32];
+        expected[25] = 1;
+        assert_eq!(
+            encode(
+                "Decimal256(0)",
+                ColValue::Decimal(
+                    "1606938044258990275541962092341162602522202993782792835301376".into()
+                )
+            ),
+            expected
+        );
+        let col_type = ClickhouseColType::parse("Decimal256(0)");
+        assert!(ClickhouseRowBinary::write_value(
+            &mut Vec::new(),
+            &col_type,
+            Some(&ColValue::Decimal("9".repeat(77).into()))
+        )
+        .is_err());
+        assert_eq!(
+            encode("Date32", ColValue::Date("1969-12-31".into())),
+            (-1_i32).to_le_bytes()
+        );
+        assert_eq!(
+            encode(
+                "DateTime64(3)",
+                ColValue::DateTime("1970-01-01 00:00:01.5".into())
+            ),
+            1500_i64.to_le_bytes()
+        );
+        assert_eq!(
+            encode("Nullable(Int8)", ColValue::Tiny(1)),
+            vec![0_u8, 1_u8]
+        );
+        assert_eq!(
+            encode("Enum8('a' = 1, 'b' = 2)", ColValue::String("b".into())),
+            vec![2]
+        );
+
+        let mut buf = Vec::new();
+        ClickhouseRowBinary::write_value(
+            &mut buf,
+            &ClickhouseColType::Nullable(Box::new(ClickhouseColType::String)),
+            None,
+        )
+        .unwrap();
+        ClickhouseRowBinary::write_value(
+            &mut buf,
+            &ClickhouseColType::Int16,
+            Some(&ColValue::None),
+        )
+        .unwrap();
+        assert_eq!(buf, vec![1, 0, 0]);
+
+        let mut buf = Vec::new();
+        ClickhouseRowBinary::write_var_uint(&mut buf, 300);
+        assert_eq!(buf, vec![0xac, 0x02]);
+
+        let col_type = ClickhouseColType::parse("UInt8");
+        assert!(
+            ClickhouseRowBinary::write_value(&mut buf, &col_type, Some(&ColValue::Long(-1)))
+                .is_err()
+        );
+    }
+}
@@ -11,21 +11,28 @@ use chrono::Utc;
 use dt_common::{
     config::config_enums::DbType,
     error::Error,
-    meta::{col_value::ColValue, row_data::RowData, row_type::RowType},
+    meta::{
+        col_value::ColValue, ddl_meta::ddl_data::DdlData, row_data::RowData, row_type::RowType,
+    },
     monitor::monitor::Monitor,
     utils::sql_util::SqlUtil,
 };
 use reqwest::{Client, Method, Response, StatusCode};
-use serde_json::json;
+use serde_json::Value;
+use xxhash_rust::xxh64::xxh64;
 
 use crate::{call_batch_fn, sinker::base_sinker::BaseSinker, Sinker};
 
-const SIGN_COL_NAME: &str = "_ape_dts_is_deleted";
-const TIMESTAMP_COL_NAME: &str = "_ape_dts_timestamp";
+use super::{
+    clickhouse_row_binary::{ClickhouseColType, ClickhouseRowBinary},
+    clickhouse_struct_sinker::{SIGN_COL_NAME, TIMESTAMP_COL_NAME},
+    clickhouse_tb_meta::{ClickhouseEngine, ClickhouseTbMeta},
+};
 
 #[derive(Clone)]
 pub struct ClickhouseSinker {
     pub http_client: Client,
+    pub scheme: String,
     pub batch_size: usize,
     pub host: String,
     pub port: String,
@@ -33,6 +40,18 @@ pub struct ClickhouseSinker {
     pub password: String,
     pub monitor: Arc<Mutex<Monitor>>,
     pub sync_timestamp: i64,
+    pub async_insert: bool,
+    pub tb_metas: HashMap<(String, String), ClickhouseTbMeta>,
+}
+
+enum WriteOp<'a> {
+    // sign: 1 for new images, -1 for deleted / old images
+    Insert {
+        col_values: &'a mut HashMap<String, ColValue>,
+        sign: i8,
+    },
+    // by lightweight DELETE
+    Delete(&'a HashMap<String, ColValue>),
 }
 
 #[async_trait]
@@ -45,6 +64,15 @@ impl Sinker for ClickhouseSinker {
         call_batch_fn!(self, data, Self::batch_sink);
         Ok(())
     }
+
+    async fn refresh_meta(&mut self, data: Vec<DdlData>) -> anyhow::Result<()> {
+        for ddl_data in data.iter() {
+            let (schema, tb) = ddl_data.get_schema_tb();
+            self.tb_metas
+                .retain(|(s, t), _| !(*s == schema && (tb.is_empty() || *t == tb)));
+        }
+        Ok(())
+    }
 }
 
 impl ClickhouseSinker {
@@ -67,45 +95,364 @@ impl ClickhouseSinker {
         start_index: usize,
         batch_size: usize,
     ) -> anyhow::Result<usize> {
-        let db = SqlUtil::escape_by_db_type(&data[start_index].schema, &DbType::ClickHouse);
-        let tb = SqlUtil::escape_by_db_type(&data[start_index].tb, &DbType::ClickHouse);
+        let tb_meta = self
+            .get_tb_meta(&data[start_index].schema, &data[start_index].tb)
+            .await?;
         self.sync_timestamp = cmp::max(Utc::now().timestamp_millis(), self.sync_timestamp + 1);
 
         let mut data_size = 0;
-        // build stream load data
-        let mut load_data = Vec::new();
+        // consecutive inserts are sent in one RowBinary request, consecutive deletes in one DELETE,
+        // so the order of changes is kept
+        let mut insert_buf = Vec::new();
+        let mut delete_conditions = Vec::new();
         for row_data in data.iter_mut().skip(start_index).take(batch_size) {
             data_size += row_data.data_size;
 
             Self::convert_row_data(row_data)?;
 
-            let col_values = if row_data.row_type == RowType::Delete {
-                let before = row_data.before.as_mut().unwrap();
-                // SIGN_COL value
-                before.insert(SIGN_COL_NAME.into(), ColValue::Long(1));
-                before
-            } else {
-                row_data.after.as_mut().unwrap()
-            };
-
-            col_values.insert(
-                TIMESTAMP_COL_NAME.into(),
-                ColValue::LongLong(self.sync_timestamp),
-            );
-            load_data.push(col_values);
-        }
-
-        // curl -X POST -d @data.json 'http://localhost:8123/?query=INSERT%20INTO%test_db.tb_1%20FORMAT%20JSON' --user admin:123456
-        let body = json!(load_data).to_string();
-        let url = format!(
-            "http://{}:{}/?query=INSERT INTO {}.{} FORMAT JSON",
-            self.host, self.port, db, tb
-        );
-        let request = self.build_request(&url, &body)?;
+            for op in Self::get_write_ops(&tb_meta, row_data) {
+                match op {
+                    WriteOp::Insert { col_values, sign } => {
+                        if !delete_conditions.is_empty() {
+                            self.execute_delete(&tb_meta, &mut delete_conditions)
+                                .await?;
+                        }
+                        self.append_insert_row(&tb_meta, col_values, sign, &mut insert_buf)?;
+                    }
+
+                    WriteOp::Delete(col_values) => {
+                        if !insert_buf.is_empty() {
+                            self.execute_insert(&tb_meta, &mut insert_buf).await?;
+                        }
+                        delete_conditions.push(Self::get_delete_condition(&tb_meta, col_values));
+                    }
+                }
+            }
+        }
+
+        if !insert_buf.is_empty() {
+            self.execute_insert(&tb_meta, &mut insert_buf).await?;
+        }
+        if !delete_conditions.is_empty() {
+            self.execute_delete(&tb_meta, &mut delete_conditions)
+                .await?;
+        }
+        Ok(data_size)
+    }
+
+    fn get_write_ops<'a>(
+        tb_meta: &ClickhouseTbMeta,
+        row_data: &'a mut RowData,
+    ) -> Vec<WriteOp<'a>> {
+        // whether deletes can be written as rows
+        let soft_delete = match &tb_meta.engine {
+            ClickhouseEngine::ReplacingMergeTree => {
+                tb_meta.col_type_map.contains_key(SIGN_COL_NAME)
+            }
+            ClickhouseEngine::CollapsingMergeTree { .. }
+            | ClickhouseEngine::VersionedCollapsingMergeTree { .. } => true,
+            ClickhouseEngine::MergeTree => false,
+        };
+        // whether the old image of an update should be removed,
+        // for ReplacingMergeTree, the new image replaces the old one unless the key changed
+        let remove_before = match &tb_meta.engine {
+            ClickhouseEngine::ReplacingMergeTree => {
+                row_data.row_type == RowType::Update && Self::is_key_changed(tb_meta, row_data)
+            }
+            _ => true,
+        };
+
+        let row_type = row_data.row_type.clone();
+        let before = row_data.before.as_mut();
+        let after = row_data.after.as_mut();
+        match row_type {
+            RowType::Insert => vec![WriteOp::Insert {
+                col_values: after.unwrap(),
+                sign: 1,
+            }],
+
+            RowType::Delete => {
+                let before = before.unwrap();
+                if soft_delete {
+                    vec![WriteOp::Insert {
+                        col_values: before,
+                        sign: -1,
+                    }]
+                } else {
+                    vec![WriteOp::Delete(before)]
+                }
+            }
+
+            RowType::Update => {
+                let mut ops = Vec::new();
+                if remove_before {
+                    let before = before.unwrap();
+                    if soft_delete {
+                        ops.push(WriteOp::Insert {
+                            col_values: before,
+                            sign: -1,
+                        });
+                    } else {
+                        ops.push(WriteOp::Delete(before));
+                    }
+                }
+                ops.push(WriteOp::Insert {
+                    col_values: after.unwrap(),
+                    sign: 1,
+                });
+                ops
+            }
+        }
+    }
+
+    fn is_key_changed(tb_meta: &ClickhouseTbMeta, row_data: &RowData) -> bool {
+        if let (Some(before), Some(after)) = (&row_data.before, &row_data.after) {
+            return tb_meta
+                .key_cols
+                .iter()
+                .any(|col| before.get(col) != after.get(col));
+        }
+        false
+    }
+
+    fn append_insert_row(
+        &self,
+        tb_meta: &ClickhouseTbMeta,
+        col_values: &mut HashMap<String, ColValue>,
+        sign: i8,
+        buf: &mut Vec<u8>,
+    ) -> anyhow::Result<()> {
+        match &tb_meta.engine {
+            ClickhouseEngine::ReplacingMergeTree => {
+                let is_deleted = sign < 0;
+                col_values.insert(SIGN_COL_NAME.into(), ColValue::Long(is_deleted as i32));
+            }
+
+            ClickhouseEngine::CollapsingMergeTree { sign_col } => {
+                col_values.insert(sign_col.into(), ColValue::Tiny(sign));
+            }
+
+            ClickhouseEngine::VersionedCollapsingMergeTree {
+                sign_col,
+                version_col,
+            } => {
+                // the cancel row must have the same version as the state row it cancels,
+                // the before image of a change is the after image of the previous change,
+                // so the version is the hash of the row content instead of a sequence
+                let version = Self::get_row_version(tb_meta, col_values)?;
+                col_values.insert(sign_col.into(), ColValue::Tiny(sign));
+                col_values.insert(version_col.into(), ColValue::LongLong(version));
+            }
+
+            ClickhouseEngine::MergeTree => {}
+        }
+
+        col_values.insert(
+            TIMESTAMP_COL_NAME.into(),
+            ColValue::LongLong(self.sync_timestamp),
+        );
+
+        for col in tb_meta.cols.iter() {
+            let col_type = tb_meta.col_type_map.get(col).unwrap();
+            ClickhouseRowBinary::write_value(buf, col_type, col_values.get(col))?;
+        }
+        Ok(())
+    }
+
+    fn get_row_version(
+        tb_meta: &ClickhouseTbMeta,
+        col_values: &HashMap<String, ColValue>,
+    ) -> anyhow::Result<i64> {
+        // hash the encoded values, which are the same whether parsed from binlog or snapshot
+        let mut buf = Vec::new();
+        for col in Self::get_data_cols(tb_meta) {
+            let col_type = tb_meta.col_type_map.get(col).unwrap();
+            ClickhouseRowBinary::write_value(&mut buf, col_type, col_values.get(col))?;
+        }
+        // xxh64 with a fixed seed is stable across processes and releases, so a cancel row written
+        // after a restart still matches its state row, the shift fits both Int64 and UInt64 columns
+        Ok((xxh64(&buf, 0) >> 1) as i64)
+    }
+
+    // columns from the source, not written by ape_dts
+    fn get_data_cols(tb_meta: &ClickhouseTbMeta) -> Vec<&String> {
+        let (sign_col, version_col) = match &tb_meta.engine {
+            ClickhouseEngine::CollapsingMergeTree { sign_col } => (sign_col.as_str(), ""),
+            ClickhouseEngine::VersionedCollapsingMergeTree {
+                sign_col,
+                version_col,
+            } => (sign_col.as_str(), version_col.as_str()),
+            _ => ("", ""),
+        };
+        tb_meta
+            .cols
+            .iter()
+            .filter(|col| {
+                *col != SIGN_COL_NAME
+                    && *col != TIMESTAMP_COL_NAME
+                    && *col != sign_col
+                    && *col != version_col
+            })
+            .collect()
+    }
+
+    fn get_delete_condition(
+        tb_meta: &ClickhouseTbMeta,
+        col_values: &HashMap<String, ColValue>,
+    ) -> String {
+        // match all columns if the table has no primary key
+        let key_cols = if tb_meta.key_cols.is_empty() {
+            Self::get_data_cols(tb_meta)
+        } else {
+            tb_meta.key_cols.iter().collect()
+        };
+
+        let mut conditions = Vec::new();
+        for col in key_cols {
+            let escaped_col = SqlUtil::escape_by_db_type(col, &DbType::ClickHouse);
+            match col_values.get(col) {
+                Some(ColValue::None) | None => conditions.push(format!("isNull({})", escaped_col)),
+                Some(v) => {
+                    conditions.push(format!("{} = {}", escaped_col, Self::to_sql_literal(v)))
+                }
+            }
+        }
+        format!("({})", conditions.join(" AND "))
+    }
+
+    fn to_sql_literal(col_value: &ColValue) -> String {
+        let str = col_value.to_option_string().unwrap_or_default();
+        match col_value {
+            ColValue::Bool(_)
+            | ColValue::Tiny(_)
+            | ColValue::UnsignedTiny(_)
+            | ColValue::Short(_)
+            | ColValue::UnsignedShort(_)
+            | ColValue::Long(_)
+            | ColValue::UnsignedLong(_)
+            | ColValue::LongLong(_)
+            | ColValue::UnsignedLongLong(_)
+            | ColValue::Float(_)
+            | ColValue::Double(_)
+            | ColValue::Year(_)
+            | ColValue::Bit(_) => str,
+            // match raw bytes, which may not be valid utf8
+            ColValue::RawString(v) | ColValue::Blob(v) => format!("unhex('{}')", hex::encode(v)),
+            _ => Self::quote_str(&str),
+        }
+    }
+
+    fn quote_str(str: &str) -> String {
+        format!("'{}'", str.replace('\\', "\\\\").replace('\'', "\\'"))
+    }
+
+    async fn execute_insert(
+        &self,
+        tb_meta: &ClickhouseTbMeta,
+        buf: &mut Vec<u8>,
+    ) -> anyhow::Result<()> {
+        let cols = tb_meta
+            .cols
+            .iter()
+            .map(|col| SqlUtil::escape_by_db_type(col, &DbType::ClickHouse))
+            .collect::<Vec<String>>()
+            .join(",");
+        let query = format!(
+            "INSERT INTO {}.{} ({}) FORMAT RowBinary",
+            SqlUtil::escape_by_db_type(&tb_meta.schema, &DbType::ClickHouse),
+            SqlUtil::escape_by_db_type(&tb_meta.tb, &DbType::ClickHouse),
+            cols
+        );
+
+        let mut params = vec![("query", query)];
+        if self.async_insert {
+            // wait for the flush of the async insert buffer, so the data is persisted before checkpoints
+            params.push(("async_insert", "1".into()));
+            params.push(("wait_for_async_insert", "1".into()));
+        }
+        let request = self.build_request(&params, std::mem::take(buf))?;
         let response = self.http_client.execute(request).await?;
         Self::check_response(response).await?;
+        Ok(())
+    }
 
-        Ok(data_size)
+    async fn execute_delete(
+        &self,
+        tb_meta: &ClickhouseTbMeta,
+        conditions: &mut Vec<String>,
+    ) -> anyhow::Result<()> {
+        // lightweight delete, https://clickhouse.com/docs/en/sql-reference/statements/delete
+        let sql = format!(
+            "DELETE FROM {}.{} WHERE {}",
+            SqlUtil::escape_by_db_type(&tb_meta.schema, &DbType::ClickHouse),
+            SqlUtil::escape_by_db_type(&tb_meta.tb, &DbType::ClickHouse),
+            conditions.join(" OR ")
+        );
+        conditions.clear();
+        self.query(&sql).await?;
+        Ok(())
+    }
+
+    async fn get_tb_meta(&mut self, schema: &str, tb: &str) -> anyhow::Result<ClickhouseTbMeta> {
+        let key = (schema.to_string(), tb.to_string());
+        if let Some(tb_meta) = self.tb_metas.get(&key) {
+            return Ok(tb_meta.clone());
+        }
+
+        let sql = format!(
+            "SELECT name, type, default_kind FROM system.columns WHERE database = {} AND table = {} ORDER BY position FORMAT JSONEachRow",
+            Self::quote_str(schema),
+            Self::quote_str(tb)
+        );
+        let mut cols = Vec::new();
+        let mut col_type_map = HashMap::new();
+        for line in self.query(&sql).await?.lines() {
+            let row: Value = serde_json::from_str(line)?;
+            let col = row["name"].as_str().unwrap_or_default().to_string();
+            // values of MATERIALIZED and ALIAS columns are computed by clickhouse
+            let default_kind = row["default_kind"].as_str().unwrap_or_default();
+            if default_kind == "MATERIALIZED" || default_kind == "ALIAS" {
+                continue;
+            }
+            let col_type = ClickhouseColType::parse(row["type"].as_str().unwrap_or_default());
+            col_type_map.insert(col.clone(), col_type);
+            cols.push(col);
+        }
+        if cols.is_empty() {
+            bail! {Error::SinkerError(format!(
+                "table not found in clickhouse, schema: {}, tb: {}",
+                schema, tb
+            ))}
+        }
+
+        let sql = format!(
+            "SELECT engine, engine_full, primary_key FROM system.tables WHERE database = {} AND name = {} FORMAT JSONEachRow",
+            Self::quote_str(schema),
+            Self::quote_str(tb)
+        );
+        let response_text = self.query(&sql).await?;
+        let row: Value = serde_json::from_str(response_text.lines().next().unwrap_or("{}"))?;
+        let engine = ClickhouseEngine::parse(
+            row["engine"].as_str().unwrap_or_default(),
+            row["engine_full"].as_str().unwrap_or_default(),
+        );
+        // primary key expressions which are not columns, like toDate(create_time), are ignored
+        let key_cols =
+            ClickhouseTbMeta::parse_key_cols(row["primary_key"].as_str().unwrap_or_default())
+                .into_iter()
+                .filter(|col| col_type_map.contains_key(col))
+                .collect();
+
+        let tb_meta = ClickhouseTbMeta {
+            schema: schema.to_string(),
+            tb: tb.to_string(),
+            cols,
+            col_type_map,
+            engine,
+            key_cols,
+        };
+        self.tb_metas.insert(key, tb_meta.clone());
+        Ok(tb_meta)
     }
 
     fn convert_row_data(row_data: &mut RowData) -> anyhow::Result<()> {
@@ -118,26 +465,11 @@ impl ClickhouseSinker {
         Ok(())
     }
 
+    // binary values are kept as raw bytes, which are written into String / FixedString as they are
     fn convert_col_values(col_values: &mut HashMap<String, ColValue>) -> anyhow::Result<()> {
         let mut new_col_values: HashMap<String, ColValue> = HashMap::new();
         for (col, col_value) in col_values.iter() {
             match col_value {
-                ColValue::RawString(v) => {
-                    let (str, is_hex) = SqlUtil::binary_to_str(v);
-                    if is_hex {
-                        new_col_values
-                            .insert(col.to_owned(), ColValue::String(format!("0x{}", str)));
-                    } else {
-                        new_col_values.insert(col.to_owned(), ColValue::String(str));
-                    }
-                }
-
-                ColValue::Blob(v) => {
-                    let hex_str = hex::encode(v);
-                    new_col_values
-                        .insert(col.to_owned(), ColValue::String(format!("0x{}", hex_str)));
-                }
-
                 ColValue::Time(v) | ColValue::Timestamp(v) => {
                     // postgres: 10:20:12+00 -> 10:20:12, 2019-02-10 11:35:00+00 -> 2019-02-10 11:35:00
                     new_col_values.insert(
@@ -156,30 +488,276 @@ impl ClickhouseSinker {
         Ok(())
     }
 
-    fn build_request(&self, url: &str, body: &str) -> anyhow::Result<reqwest::Request> {
+    async fn query(&self, sql: &str) -> anyhow::Result<String> {
+        let request = self.build_request(&[], sql.as_bytes().to_vec())?;
+        let response = self.http_client.execute(request).await?;
+        Self::check_response(response).await
+    }
+
+    fn build_request(
+        &self,
+        params: &[(&str, String)],
+        body: Vec<u8>,
+    ) -> anyhow::Result<reqwest::Request> {
         let password = if self.password.is_empty() {
             None
         } else {
             Some(self.password.clone())
         };
 
+        // curl -X POST --data-binary @data.bin 'http://localhost:8123/?query=INSERT%20INTO%20test_db.tb_1%20FORMAT%20RowBinary' --user admin:123456
+        let url = format!("{}://{}:{}/", self.scheme, self.host, self.port);
         let post = self
             .http_client
             .request(Method::POST, url)
             .basic_auth(&self.username, password)
-            .body(body.to_string());
+            .query(params)
+            .body(body);
         Ok(post.build()?)
     }
 
-    async fn check_response(response: Response) -> anyhow::Result<()> {
+    async fn check_response(response: Response) -> anyhow::Result<String> {
         let status_code = response.status();
-        let response_text = &response.text().await?;
+        let response_text = response.text().await?;
         if status_code != StatusCode::OK {
             bail! {Error::HttpError(format!(
                 "data load request failed, status_code: {}, response_text: {:?}",
                 status_code, response_text
             ))}
         }
-        Ok(())
+        Ok(response_text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_tb_meta(engine: ClickhouseEngine) -> ClickhouseTbMeta {
+        let mut col_type_map = HashMap::from([
+            ("id".to_string(), ClickhouseColType::Int32),
+            ("name".to_string(), ClickhouseColType::String),
+            (TIMESTAMP_COL_NAME.to_string(), ClickhouseColType::Int64),
+        ]);
+        match &engine {
+            ClickhouseEngine::ReplacingMergeTree => {
+                col_type_map.insert(SIGN_COL_NAME.into(), ClickhouseColType::UInt8);
+            }
+            ClickhouseEngine::CollapsingMergeTree { sign_col } => {
+                col_type_map.insert(sign_col.into(), ClickhouseColType::Int8);
+            }
+            ClickhouseEngine::VersionedCollapsingMergeTree {
+                sign_col,
+                version_col,
+            } => {
+                col_type_map.insert(sign_col.into(), ClickhouseColType::Int8);
+                col_type_map.insert(version_col.into(), ClickhouseColType::UInt64);
+            }
+            ClickhouseEngine::MergeTree => {}
+        }
+
+        let mut cols: Vec<String> = col_type_map.keys().cloned().collect();
+        cols.sort();
+        ClickhouseTbMeta {
+            schema: "test_db".into(),
+            tb: "tb_1".into(),
+            cols,
+            col_type_map,
+            engine,
+            key_cols: vec!["id".into()],
+        }
+    }
+
+    fn mock_col_values(id: i32, name: &str) -> HashMap<String, ColValue> {
+        HashMap::from([
+            ("id".to_string(), ColValue::Long(id)),
+            ("name".to_string(), ColValue::String(name.into())),
+        ])
+    }
+
+    fn mock_row_data(
+        row_type: RowType,
+        before: Option<(i32, &str)>,
+        after: Option<(i32, &str)>,
+    ) -> RowData {
+        RowData::new(
+            "test_db".into(),
+            "tb_1".into(),
+            row_type,
+            before.map(|(id, name)| mock_col_values(id, name)),
+            after.map(|(id, name)| mock_col_values(id, name)),
+        )
+    }
+
+    // (is_insert, sign, id) of each write op
+    fn get_write_ops(tb_meta: &ClickhouseTbMeta, mut row_data: RowData) -> Vec<(bool, i8, i32)> {
+        let get_id = |col_values: &HashMap<String, ColValue>| match col_values.get("id") {
+            Some(ColValue::Long(v)) => *v,
+            _ => panic!("id not found"),
+        };
+        ClickhouseSinker::get_write_ops(tb_meta, &mut row_data)
+            .into_iter()
+            .map(|op| match op {
+                WriteOp::Insert { col_values, sign } => (true, sign, get_id(col_values)),
+                WriteOp::Delete(col_values) => (false, 0, get_id(col_values)),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_get_write_ops_replacing_merge_tree() {
+        let tb_meta = mock_tb_meta(ClickhouseEngine::ReplacingMergeTree);
+
+        let row_data = mock_row_data(RowType::Insert, None, Some((1, "a")));
+        assert_eq!(get_write_ops(&tb_meta, row_data), vec![(true, 1, 1)]);
+
+        // the new image replaces the old one
+        let row_data = mock_row_data(RowType::Update, Some((1, "a")), Some((1, "b")));
+        assert_eq!(get_write_ops(&tb_meta, row_data), vec![(true, 1, 1)]);
+
+        let row_data = mock_row_data(RowType::Update, Some((1, "a")), Some((2, "a")));
+        assert_eq!(
+            get_write_ops(&tb_meta, row_data),
+            vec![(true, -1, 1), (true, 1, 2)]
+        );
+
+        let row_data = mock_row_data(RowType::Delete, Some((1, "a")), None);
+        assert_eq!(get_write_ops(&tb_meta, row_data), vec![(true, -1, 1)]);
+
+        // without the is_deleted column, deletes are written by DELETE
+        let mut tb_meta = tb_meta;
+        tb_meta.col_type_map.remove(SIGN_COL_NAME);
+        tb_meta.cols.retain(|col| col != SIGN_COL_NAME);
+
+        let row_data = mock_row_data(RowType::Update, Some((1, "a")), Some((2, "a")));
+        assert_eq!(
+            get_write_ops(&tb_meta, row_data),
+            vec![(false, 0, 1), (true, 1, 2)]
+        );
+
+        let row_data = mock_row_data(RowType::Delete, Some((1, "a")), None);
+        assert_eq!(get_write_ops(&tb_meta, row_data), vec![(false, 0, 1)]);
+    }
+
+    #[test]
+    fn test_get_write_ops_collapsing_merge_tree() {
+        let tb_meta = mock_tb_meta(ClickhouseEngine::CollapsingMergeTree {
+            sign_col: "sign".into(),
+        });
+
+        let row_data = mock_row_data(RowType::Insert, None, Some((1, "a")));
+        assert_eq!(get_write_ops(&tb_meta, row_data), vec![(true, 1, 1)]);
+
+        let row_data = mock_row_data(RowType::Update, Some((1, "a")), Some((1, "b")));
+        assert_eq!(
+            get_write_ops(&tb_meta, row_data),
+            vec![(true, -1, 1), (true, 1, 1)]
+        );
+
+        let row_data = mock_row_data(RowType::Delete, Some((1, "a")), None);
+        assert_eq!(get_write_ops(&tb_meta, row_data), vec![(true, -1, 1)]);
+    }
+
+    #[test]
+    fn test_get_write_ops_versioned_collapsing_merge_tree() {
+        let tb_meta = mock_tb_meta(ClickhouseEngine::VersionedCollapsingMergeTree {
+            sign_col: "sign".into(),
+            version_col: "version".into(),
+        });
+
+        let row_data = mock_row_data(RowType::Insert, None, Some((1, "a")));
+        assert_eq!(get_write_ops(&tb_meta, row_data), vec![(true, 1, 1)]);
+
+        let row_data = mock_row_data(RowType::Update, Some((1, "a")), Some((1, "b")));
+        assert_eq!(
+            get_write_ops(&tb_meta, row_data),
+            vec![(true, -1, 1), (true, 1, 1)]
+        );
+
+        let row_data = mock_row_data(RowType::Delete, Some((1, "a")), None);
+        assert_eq!(get_write_ops(&tb_meta, row_data), vec![(true, -1, 1)]);
+    }
+
+    #[test]
+    fn test_get_write_ops_merge_tree() {
+        let tb_meta = mock_tb_meta(ClickhouseEngine::MergeTree);
+
+        let row_data = mock_row_data(RowType::Insert, None, Some((1, "a")));
+        assert_eq!(get_write_ops(&tb_meta, row_data), vec![(true, 1, 1)]);
+
+        let row_data = mock_row_data(RowType::Update, Some((1, "a")), Some((1, "b")));
+        assert_eq!(
+            get_write_ops(&tb_meta, row_data),
+            vec![(false, 0, 1), (true, 1, 1)]
+        );
+
+        let row_data = mock_row_data(RowType::Delete, Some((1, "a")), None);
+        assert_eq!(get_write_ops(&tb_meta, row_data), vec![(false, 0, 1)]);
+    }
+
+    #[test]
+    fn test_get_row_version() {
+        let tb_meta = mock_tb_meta(ClickhouseEngine::VersionedCollapsingMergeTree {
+            sign_col: "sign".into(),
+            version_col: "version".into(),
+        });
+
+        // xxh64 of the RowBinary values [1, 0, 0, 0, 1, b'a'], shifted right by 1
+        let state_row = mock_col_values(1, "a");
+        assert_eq!(
+            ClickhouseSinker::get_row_version(&tb_meta, &state_row).unwrap(),
+            1409263944003074233
+        );
+
+        // the cancel row carries columns written by ape_dts, which are excluded from the hash
+        let mut cancel_row = mock_col_values(1, "a");
+        cancel_row.insert("sign".into(), ColValue::Tiny(-1));
+        cancel_row.insert("version".into(), ColValue::LongLong(1));
+        cancel_row.insert(TIMESTAMP_COL_NAME.into(), ColValue::LongLong(1));
+        assert_eq!(
+            ClickhouseSinker::get_row_version(&tb_meta, &cancel_row).unwrap(),
+            1409263944003074233
+        );
+
+        let new_row = mock_col_values(1, "b");
+        assert_ne!(
+            ClickhouseSinker::get_row_version(&tb_meta, &new_row).unwrap(),
+            1409263944003074233
+        );
+    }
+
+    #[test]
+    fn test_convert_binary_values() {
+        let mut row_data = RowData::new(
+            String::new(),
+            String::new(),
+            RowType::Insert,
+            None,
+            Some(HashMap::from([
+                ("name".to_string(), ColValue::RawString(vec![0xc4, 0xe3])),
+                (
+                    "ts".to_string(),
+                    ColValue::Timestamp("2019-02-10 11:35:00+00".into()),
+                ),
+            ])),
+        );
+        ClickhouseSinker::convert_row_data(&mut row_data).unwrap();
+        let after = row_data.after.as_ref().unwrap();
+        assert_eq!(after["name"], ColValue::RawString(vec![0xc4, 0xe3]));
+        assert_eq!(after["ts"], ColValue::String("2019-02-10 11:35:00".into()));
+
+        let tb_meta = mock_tb_meta(ClickhouseEngine::MergeTree);
+        let mut buf = Vec::new();
+        ClickhouseRowBinary::write_value(
+            &mut buf,
+            &tb_meta.col_type_map["name"],
+            after.get("name"),
+        )
+        .unwrap();
+        assert_eq!(buf, vec![2, 0xc4, 0xe3]);
+        assert_eq!(
+            ClickhouseSinker::to_sql_literal(&after["name"]),
+            "unhex('c4e3')"
+        );
     }
 }
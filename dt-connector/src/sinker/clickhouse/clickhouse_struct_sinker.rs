@@ -4,6 +4,7 @@ use anyhow::bail;
 use clickhouse::Client;
 use dt_common::{
     config::config_enums::ConflictPolicyEnum,
+    error::Error,
     log_error, log_info,
     meta::{
         mysql::{mysql_col_type::MysqlColType, mysql_tb_meta::MysqlTbMeta},
@@ -20,10 +21,15 @@ use dt_common::{
 
 use async_trait::async_trait;
 
-const SIGN_COL_NAME: &str = "_ape_dts_is_deleted";
+pub const SIGN_COL_NAME: &str = "_ape_dts_is_deleted";
 const SIGN_COL_TYPE: &str = "Int8";
-const TIMESTAMP_COL_NAME: &str = "_ape_dts_timestamp";
+pub const TIMESTAMP_COL_NAME: &str = "_ape_dts_timestamp";
 const TIMESTAMP_COL_TYPE: &str = "Int64";
+// sign and version columns of CollapsingMergeTree / VersionedCollapsingMergeTree
+const COLLAPSING_SIGN_COL_NAME: &str = "_ape_dts_sign";
+const COLLAPSING_SIGN_COL_TYPE: &str = "Int8";
+const VERSION_COL_NAME: &str = "_ape_dts_version";
+const VERSION_COL_TYPE: &str = "UInt64";
 
 #[derive(Clone)]
 pub struct ClickhouseStructSinker {
@@ -55,8 +61,12 @@ impl Sinker for ClickhouseStructSinker {
                         self.extractor_meta_manager.mysql_meta_manager.as_mut()
                     {
                         let tb_meta = meta_manager.get_tb_meta(schema, tb).await?;
-                        let sql =
-                            Self::get_create_table_sql(&statement.table, Some(tb_meta), None)?;
+                        let sql = Self::get_create_table_sql(
+                            &statement.table,
+                            &self.engine,
+                            Some(tb_meta),
+                            None,
+                        )?;
                         self.execute_sql(&sql).await?;
                     }
                 }
@@ -72,8 +82,12 @@ impl Sinker for ClickhouseStructSinker {
                     if let Some(meta_manager) = self.extractor_meta_manager.pg_meta_manager.as_mut()
                     {
                         let tb_meta = meta_manager.get_tb_meta(schema, tb).await?.to_owned();
-                        let sql =
-                            Self::get_create_table_sql(&statement.table, None, Some(&tb_meta))?;
+                        let sql = Self::get_create_table_sql(
+                            &statement.table,
+                            &self.engine,
+                            None,
+                            Some(&tb_meta),
+                        )?;
                         self.execute_sql(&sql).await?;
                     }
                 }
@@ -89,6 +103,7 @@ impl Sinker for ClickhouseStructSinker {
 impl ClickhouseStructSinker {
     fn get_create_table_sql(
         table: &Table,
+        engine: &str,
         mysql_tb_meta: Option<&MysqlTbMeta>,
         pg_tb_meta: Option<&PgTbMeta>,
     ) -> anyhow::Result<String> {
@@ -103,17 +118,45 @@ impl ClickhouseStructSinker {
             dst_cols.push(Self::get_dst_col(column, mysql_tb_meta, pg_tb_meta)?);
         }
 
-        // sign and timestamp cols
-        dst_cols.push(format!("`{}` {}", SIGN_COL_NAME, SIGN_COL_TYPE));
+        // sign / version and timestamp cols, which decide how deletes are written by the sinker
+        let engine_def = match engine {
+            "ReplacingMergeTree" => {
+                dst_cols.push(format!("`{}` {}", SIGN_COL_NAME, SIGN_COL_TYPE));
+                format!("ReplacingMergeTree(`{}`)", TIMESTAMP_COL_NAME)
+            }
+            "CollapsingMergeTree" => {
+                dst_cols.push(format!(
+                    "`{}` {}",
+                    COLLAPSING_SIGN_COL_NAME, COLLAPSING_SIGN_COL_TYPE
+                ));
+                format!("CollapsingMergeTree(`{}`)", COLLAPSING_SIGN_COL_NAME)
+            }
+            "VersionedCollapsingMergeTree" => {
+                dst_cols.push(format!(
+                    "`{}` {}",
+                    COLLAPSING_SIGN_COL_NAME, COLLAPSING_SIGN_COL_TYPE
+                ));
+                dst_cols.push(format!("`{}` {}", VERSION_COL_NAME, VERSION_COL_TYPE));
+                format!(
+                    "VersionedCollapsingMergeTree(`{}`, `{}`)",
+                    COLLAPSING_SIGN_COL_NAME, VERSION_COL_NAME
+                )
+            }
+            // deletes by lightweight DELETE
+            "MergeTree" => "MergeTree".to_string(),
+            _ => bail! {Error::ConfigError(format!(
+                "unsupported clickhouse engine: {}, supported: ReplacingMergeTree, CollapsingMergeTree, VersionedCollapsingMergeTree, MergeTree",
+                engine
+            ))},
+        };
         dst_cols.push(format!("`{}` {}", TIMESTAMP_COL_NAME, TIMESTAMP_COL_TYPE));
 
-        // engine, default: ReplacingMergeTree
         let mut sql = format!(
-            "CREATE TABLE IF NOT EXISTS `{}`.`{}` ({}) ENGINE = ReplacingMergeTree(`{}`)",
+            "CREATE TABLE IF NOT EXISTS `{}`.`{}` ({}) ENGINE = {}",
             rdb_tb_meta.schema,
             rdb_tb_meta.tb,
             dst_cols.join(", "),
-            TIMESTAMP_COL_NAME
+            engine_def
         );
 
         if !rdb_tb_meta.id_cols.is_empty() {
@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use super::clickhouse_row_binary::ClickhouseColType;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClickhouseEngine {
+    // deletes are written as rows with _ape_dts_is_deleted=1
+    ReplacingMergeTree,
+    // deletes and old images of updates are written as cancel rows with sign=-1
+    CollapsingMergeTree {
+        sign_col: String,
+    },
+    VersionedCollapsingMergeTree {
+        sign_col: String,
+        version_col: String,
+    },
+    // MergeTree and others, deletes are executed by lightweight DELETE
+    MergeTree,
+}
+
+impl ClickhouseEngine {
+    // engine: CollapsingMergeTree, ReplicatedCollapsingMergeTree, SharedCollapsingMergeTree...
+    // engine_full: ReplicatedCollapsingMergeTree('/clickhouse/tables/{shard}/tb_1', '{replica}', sign) ORDER BY id ...
+    pub fn parse(engine: &str, engine_full: &str) -> Self {
+        // params of the engine except zookeeper path and replica name
+        let params: Vec<String> = engine_full
+            .split_once('(')
+            .and_then(|(_, s)| s.split_once(')'))
+            .map(|(s, _)| s)
+            .unwrap_or_default()
+            .split(',')
+            .map(|i| i.trim())
+            .filter(|i| !i.is_empty() && !i.starts_with('\''))
+            .map(|i| i.trim_matches('`').to_string())
+            .collect();
+
+        if engine.ends_with("VersionedCollapsingMergeTree") && params.len() >= 2 {
+            Self::VersionedCollapsingMergeTree {
+                sign_col: params[0].clone(),
+                version_col: params[1].clone(),
+            }
+        } else if engine.ends_with("CollapsingMergeTree") && !params.is_empty() {
+            Self::CollapsingMergeTree {
+                sign_col: params[0].clone(),
+            }
+        } else if engine.ends_with("ReplacingMergeTree") {
+            Self::ReplacingMergeTree
+        } else {
+            Self::MergeTree
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ClickhouseTbMeta {
+    pub schema: String,
+    pub tb: String,
+    // insertable columns in order, MATERIALIZED and ALIAS columns excluded
+    pub cols: Vec<String>,
+    pub col_type_map: HashMap<String, ClickhouseColType>,
+    pub engine: ClickhouseEngine,
+    // columns of the primary key, which identify rows for lightweight deletes
+    pub key_cols: Vec<String>,
+}
+
+impl ClickhouseTbMeta {
+    // primary_key in system.tables: "id, name"
+    pub fn parse_key_cols(primary_key: &str) -> Vec<String> {
+        primary_key
+            .split(',')
+            .map(|i| i.trim().trim_matches('`').to_string())
+            .filter(|i| !i.is_empty())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_engine() {
+        assert_eq!(
+            ClickhouseEngine::parse(
+                "ReplacingMergeTree",
+                "ReplacingMergeTree(`_ape_dts_timestamp`) PRIMARY KEY id ORDER BY id"
+            ),
+            ClickhouseEngine::ReplacingMergeTree
+        );
+        assert_eq!(
+            ClickhouseEngine::parse(
+                "ReplicatedCollapsingMergeTree",
+                "ReplicatedCollapsingMergeTree('/clickhouse/tables/{shard}/tb_1', '{replica}', sign) ORDER BY id"
+            ),
+            ClickhouseEngine::CollapsingMergeTree {
+                sign_col: "sign".into()
+            }
+        );
+        assert_eq!(
+            ClickhouseEngine::parse(
+                "VersionedCollapsingMergeTree",
+                "VersionedCollapsingMergeTree(`_ape_dts_sign`, `_ape_dts_version`) ORDER BY id"
+            ),
+            ClickhouseEngine::VersionedCollapsingMergeTree {
+                sign_col: "_ape_dts_sign".into(),
+                version_col: "_ape_dts_version".into()
+            }
+        );
+        assert_eq!(
+            ClickhouseEngine::parse("MergeTree", "MergeTree ORDER BY id"),
+            ClickhouseEngine::MergeTree
+        );
+        assert_eq!(
+            ClickhouseTbMeta::parse_key_cols("id, `name`"),
+            vec!["id".to_string(), "name".to_string()]
+        );
+    }
+}
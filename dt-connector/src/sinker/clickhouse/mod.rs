@@ -1,2 +1,4 @@
+pub mod clickhouse_row_binary;
 pub mod clickhouse_sinker;
 pub mod clickhouse_struct_sinker;
+pub mod clickhouse_tb_meta;
@@ -0,0 +1,479 @@
+use std::collections::HashSet;
+
+use anyhow::bail;
+use dt_common::{
+    config::config_enums::DbType,
+    error::Error,
+    log_warn,
+    meta::ddl_meta::{ddl_data::DdlData, ddl_statement::DdlStatement, ddl_type::DdlType},
+};
+
+use super::doris_struct_sinker::DorisStructSinker;
+
+/// Converts source ddl to doris statements, schema changes are split to one statement for each
+/// column since doris runs them as separate light schema change jobs.
+pub struct DorisDdlConverter {}
+
+impl DorisDdlConverter {
+    /// ALTER TABLE of postgres (including renames) is not converted, do_ddls of postgres
+    /// sources are checked before the task starts instead of ignoring ddl at runtime.
+    pub fn check_do_ddls(src_db_type: &DbType, do_ddls: &HashSet<String>) -> anyhow::Result<()> {
+        if *src_db_type != DbType::Pg {
+            return Ok(());
+        }
+
+        let supported: Vec<String> = [
+            DdlType::CreateSchema,
+            DdlType::DropSchema,
+            DdlType::DropTable,
+            DdlType::TruncateTable,
+        ]
+        .iter()
+        .map(|i| i.to_string())
+        .collect();
+        if let Some(ddl) = do_ddls.iter().find(|i| !supported.contains(i)) {
+            bail! {Error::ConfigError(format!(
+                "ddl: {} of postgres is not supported by doris sinker, do_ddls can only include: {}",
+                ddl,
+                supported.join(",")
+            ))}
+        }
+        Ok(())
+    }
+
+    pub fn convert(ddl_data: &DdlData) -> Vec<String> {
+        let (db, tb) = ddl_data.get_schema_tb();
+        let full_tb = format!("`{}`.`{}`", db, tb);
+
+        match &ddl_data.statement {
+            DdlStatement::CreateDatabase(_) | DdlStatement::CreateSchema(_) => {
+                vec![format!("CREATE DATABASE IF NOT EXISTS `{}`", db)]
+            }
+
+            DdlStatement::DropDatabase(_) | DdlStatement::DropSchema(_) => {
+                vec![format!("DROP DATABASE IF EXISTS `{}`", db)]
+            }
+
+            DdlStatement::DropTable(_) => vec![format!("DROP TABLE IF EXISTS {}", full_tb)],
+
+            DdlStatement::MysqlTruncateTable(_) | DdlStatement::PgTruncateTable(_) => {
+                vec![format!("TRUNCATE TABLE {}", full_tb)]
+            }
+
+            DdlStatement::RenameTable(_)
+            | DdlStatement::MysqlAlterTableRename(_)
+            | DdlStatement::PgAlterTableRename(_) => {
+                let (new_db, new_tb) = ddl_data.get_rename_to_schema_tb();
+                if new_db != db {
+                    log_warn!(
+                        "doris can not move tables across databases, ddl ignored: {}",
+                        ddl_data.query
+                    );
+                    return Vec::new();
+                }
+                vec![format!("ALTER TABLE {} RENAME `{}`", full_tb, new_tb)]
+            }
+
+            DdlStatement::MysqlAlterTable(s) if ddl_data.db_type == DbType::Mysql => {
+                let mut sqls = Vec::new();
+                for spec in Self::split_top_level(&s.unparsed, ',') {
+                    match Self::convert_alter_spec(&spec) {
+                        Some(converted) => sqls.extend(
+                            converted
+                                .into_iter()
+                                .map(|i| format!("ALTER TABLE {} {}", full_tb, i)),
+                        ),
+                        None => log_warn!("alter spec not supported by doris, ignored: {}", spec),
+                    }
+                }
+                sqls
+            }
+
+            _ => {
+                log_warn!(
+                    "ddl not supported by doris sinker, ignored, create tables by struct tasks: {}",
+                    ddl_data.query
+                );
+                Vec::new()
+            }
+        }
+    }
+
+    /// add column / drop column / modify column / change column / rename column
+    fn convert_alter_spec(spec: &str) -> Option<Vec<String>> {
+        let tokens = Self::tokenize(spec);
+        let keywords: Vec<String> = tokens.iter().map(|i| i.to_lowercase()).collect();
+        let keyword = |i: usize| keywords.get(i).map(|i| i.as_str()).unwrap_or_default();
+        // skip the optional COLUMN
+        let start = if keyword(1) == "column" { 2 } else { 1 };
+
+        match keyword(0) {
+            "add" => {
+                if matches!(
+                    keyword(1),
+                    "index"
+                        | "key"
+                        | "unique"
+                        | "primary"
+                        | "constraint"
+                        | "fulltext"
+                        | "spatial"
+                        | "foreign"
+                        | "partition"
+                        | "check"
+                ) {
+                    return None;
+                }
+
+                let defs = match tokens.get(start) {
+                    // ADD COLUMN (c1 INT, c2 INT)
+                    Some(token) if token.starts_with('(') && token.ends_with(')') => {
+                        Self::split_top_level(&token[1..token.len() - 1], ',')
+                    }
+                    Some(_) => vec![tokens[start..].join(" ")],
+                    None => return None,
+                };
+                let mut sqls = Vec::new();
+                for def in defs {
+                    sqls.push(format!("ADD COLUMN {}", Self::convert_col_def(&def)?));
+                }
+                Some(sqls)
+            }
+
+            "drop" => {
+                if matches!(
+                    keyword(1),
+                    "index" | "key" | "primary" | "foreign" | "constraint" | "check" | "partition"
+                ) {
+                    return None;
+                }
+                let col = Self::unescape(tokens.get(start)?);
+                Some(vec![format!("DROP COLUMN `{}`", col)])
+            }
+
+            "modify" => {
+                let def = tokens.get(start..)?.join(" ");
+                Some(vec![format!(
+                    "MODIFY COLUMN {}",
+                    Self::convert_col_def(&def)?
+                )])
+            }
+
+            // CHANGE COLUMN old_col new_col type ...
+            "change" => {
+                let old_col = Self::unescape(tokens.get(start)?);
+                let def = tokens.get(start + 1..)?.join(" ");
+                let new_col = Self::unescape(tokens.get(start + 1)?);
+                let mut sqls = Vec::new();
+                if old_col != new_col {
+                    sqls.push(format!("RENAME COLUMN `{}` `{}`", old_col, new_col));
+                }
+                sqls.push(format!("MODIFY COLUMN {}", Self::convert_col_def(&def)?));
+                Some(sqls)
+            }
+
+            // RENAME COLUMN old_col TO new_col
+            "rename" if keyword(1) == "column" && keyword(3) == "to" => {
+                let old_col = Self::unescape(tokens.get(2)?);
+                let new_col = Self::unescape(tokens.get(4)?);
+                Some(vec![format!("RENAME COLUMN `{}` `{}`", old_col, new_col)])
+            }
+
+            _ => None,
+        }
+    }
+
+    /// `c` int(11) unsigned NOT NULL DEFAULT '0' COMMENT 'x' AFTER `b`
+    /// -> `c` BIGINT NOT NULL DEFAULT '0' COMMENT 'x' AFTER `b`,
+    /// attributes not supported by doris like: AUTO_INCREMENT, CHARACTER SET, ON UPDATE are removed.
+    fn convert_col_def(def: &str) -> Option<String> {
+        let tokens = Self::tokenize(def);
+        let col = Self::unescape(tokens.first()?);
+        let mut column_type = tokens.get(1)?.to_string();
+
+        let mut nullable = None;
+        let mut default = None;
+        let mut comment = None;
+        let mut position = None;
+        let mut i = 2;
+        while i < tokens.len() {
+            let keyword = tokens[i].to_lowercase();
+            let next = tokens
+                .get(i + 1)
+                .map(|i| i.to_lowercase())
+                .unwrap_or_default();
+            match keyword.as_str() {
+                "unsigned" | "signed" | "zerofill" => {
+                    column_type = format!("{} {}", column_type, keyword);
+                }
+                "not" if next == "null" => {
+                    nullable = Some("NOT NULL");
+                    i += 1;
+                }
+                "null" => nullable = Some("NULL"),
+                "default" => {
+                    default = tokens.get(i + 1).cloned();
+                    i += 1;
+                }
+                "comment" => {
+                    comment = tokens.get(i + 1).cloned();
+                    i += 1;
+                }
+                "after" => {
+                    position = Some(format!("AFTER `{}`", Self::unescape(tokens.get(i + 1)?)));
+                    i += 1;
+                }
+                // ON UPDATE CURRENT_TIMESTAMP
+                "on" => i += 2,
+                "character" | "collate" | "charset" => {
+                    i += if keyword == "character" { 2 } else { 1 };
+                }
+                // auto_increment, first, key, visible ...
+                _ => {}
+            }
+            i += 1;
+        }
+
+        let dst_col_type = DorisStructSinker::get_dst_col_type_from_mysql(&column_type);
+        let mut sql = format!("`{}` {}", col, dst_col_type);
+        if let Some(nullable) = nullable {
+            sql = format!("{} {}", sql, nullable);
+        }
+        // defaults not supported by doris are removed
+        if let Some(default) = default.and_then(|i| Self::convert_default(&i, &dst_col_type)) {
+            sql = format!("{} DEFAULT {}", sql, default);
+        }
+        if let Some(comment) = comment {
+            sql = format!("{} COMMENT {}", sql, comment);
+        }
+        if let Some(position) = position {
+            sql = format!("{} {}", sql, position);
+        }
+        Some(sql)
+    }
+
+    // doris only accepts literals, NULL and CURRENT_TIMESTAMP as defaults
+    fn convert_default(default: &str, dst_col_type: &str) -> Option<String> {
+        let lower = default.to_lowercase();
+        if lower == "null" {
+            return Some("NULL".into());
+        }
+        if lower.starts_with("current_timestamp") || lower.starts_with("now(") {
+            if !dst_col_type.starts_with("DATETIME") {
+                return None;
+            }
+            // keep the precision: CURRENT_TIMESTAMP(3)
+            let precision = lower
+                .split_once('(')
+                .map(|(_, i)| i.trim_end_matches(')').trim())
+                .unwrap_or_default();
+            if precision.is_empty() {
+                return Some("CURRENT_TIMESTAMP".into());
+            }
+            return Some(format!("CURRENT_TIMESTAMP({})", precision));
+        }
+        if default.starts_with('\'') || default.starts_with('"') {
+            return Some(default.to_string());
+        }
+        if default.parse::<f64>().is_ok() {
+            return Some(format!("'{}'", default));
+        }
+        // expressions like: (uuid())
+        None
+    }
+
+    /// Splits by whitespace, quoted strings, identifiers and parentheses are kept as a whole,
+    /// parentheses right after a word are part of the word, like: varchar(10), decimal(10, 2).
+    fn tokenize(s: &str) -> Vec<String> {
+        let mut tokens: Vec<String> = Vec::new();
+        let mut token = String::new();
+        let mut chars = s.chars().peekable();
+        let mut depth = 0;
+        let mut quote = None;
+
+        while let Some(c) = chars.next() {
+            if let Some(q) = quote {
+                token.push(c);
+                if c == '\\' {
+                    if let Some(escaped) = chars.next() {
+                        token.push(escaped);
+                    }
+                } else if c == q {
+                    // '' in a quoted string
+                    if chars.peek() == Some(&q) {
+                        token.push(chars.next().unwrap());
+                    } else {
+                        quote = None;
+                    }
+                }
+                continue;
+            }
+
+            match c {
+                '\'' | '"' | '`' => {
+                    quote = Some(c);
+                    token.push(c);
+                }
+                '(' => {
+                    depth += 1;
+                    token.push(c);
+                }
+                ')' => {
+                    depth -= 1;
+                    token.push(c);
+                }
+                c if c.is_whitespace() && depth == 0 => {
+                    if !token.is_empty() {
+                        tokens.push(std::mem::take(&mut token));
+                    }
+                }
+                _ => token.push(c),
+            }
+        }
+        if !token.is_empty() {
+            tokens.push(token);
+        }
+        tokens
+    }
+
+    fn split_top_level(s: &str, delimiter: char) -> Vec<String> {
+        let mut items = Vec::new();
+        let mut item = String::new();
+        let mut depth = 0;
+        let mut quote = None;
+        let mut chars = s.chars();
+
+        while let Some(c) = chars.next() {
+            item.push(c);
+            if let Some(q) = quote {
+                if c == '\\' {
+                    if let Some(escaped) = chars.next() {
+                        item.push(escaped);
+                    }
+                } else if c == q {
+                    quote = None;
+                }
+                continue;
+            }
+
+            match c {
+                '\'' | '"' | '`' => quote = Some(c),
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                c if c == delimiter && depth == 0 => {
+                    item.pop();
+                    items.push(item.trim().to_string());
+                    item.clear();
+                }
+                _ => {}
+            }
+        }
+        if !item.trim().is_empty() {
+            items.push(item.trim().to_string());
+        }
+        items
+    }
+
+    fn unescape(token: &str) -> String {
+        token.trim_matches('`').to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dt_common::meta::ddl_meta::{
+        ddl_statement::{MysqlAlterTableRenameStatement, MysqlAlterTableStatement},
+        ddl_type::DdlType,
+    };
+
+    use super::*;
+
+    fn mock_alter_table(unparsed: &str) -> DdlData {
+        DdlData {
+            default_schema: "db_1".into(),
+            query: format!("ALTER TABLE tb_1 {}", unparsed),
+            ddl_type: DdlType::AlterTable,
+            db_type: DbType::Mysql,
+            statement: DdlStatement::MysqlAlterTable(MysqlAlterTableStatement {
+                db: String::new(),
+                tb: "tb_1".into(),
+                unparsed: unparsed.into(),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_convert_alter_table() {
+        let cases = [
+            (
+                "ADD COLUMN `age` int(11) unsigned NOT NULL DEFAULT 0 COMMENT 'it''s age' AFTER `id`",
+                vec!["ALTER TABLE `db_1`.`tb_1` ADD COLUMN `age` BIGINT NOT NULL DEFAULT '0' COMMENT 'it''s age' AFTER `id`"],
+            ),
+            (
+                "add (c1 varchar(10) CHARACTER SET utf8mb4 COLLATE utf8mb4_bin, c2 decimal(10, 2) default null)",
+                vec![
+                    "ALTER TABLE `db_1`.`tb_1` ADD COLUMN `c1` VARCHAR(40)",
+                    "ALTER TABLE `db_1`.`tb_1` ADD COLUMN `c2` DECIMAL(10,2) DEFAULT NULL",
+                ],
+            ),
+            (
+                "modify column update_time datetime(3) DEFAULT CURRENT_TIMESTAMP(3) ON UPDATE CURRENT_TIMESTAMP(3)",
+                vec!["ALTER TABLE `db_1`.`tb_1` MODIFY COLUMN `update_time` DATETIME(3) DEFAULT CURRENT_TIMESTAMP(3)"],
+            ),
+            (
+                "CHANGE `name` `full_name` text, DROP COLUMN `c1`, ADD INDEX idx_c2 (c2)",
+                vec![
+                    "ALTER TABLE `db_1`.`tb_1` RENAME COLUMN `name` `full_name`",
+                    "ALTER TABLE `db_1`.`tb_1` MODIFY COLUMN `full_name` STRING",
+                    "ALTER TABLE `db_1`.`tb_1` DROP COLUMN `c1`",
+                ],
+            ),
+            (
+                "rename column c2 to c3",
+                vec!["ALTER TABLE `db_1`.`tb_1` RENAME COLUMN `c2` `c3`"],
+            ),
+        ];
+
+        for (unparsed, expected) in cases {
+            assert_eq!(
+                DorisDdlConverter::convert(&mock_alter_table(unparsed)),
+                expected,
+                "{}",
+                unparsed
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_do_ddls() {
+        let do_ddls = |ddls: &[&str]| ddls.iter().map(|i| i.to_string()).collect::<HashSet<_>>();
+        let check = DorisDdlConverter::check_do_ddls;
+
+        assert!(check(&DbType::Pg, &do_ddls(&[])).is_ok());
+        assert!(check(&DbType::Pg, &do_ddls(&["drop_table", "truncate_table"])).is_ok());
+        assert!(check(&DbType::Pg, &do_ddls(&["*"])).is_err());
+        assert!(check(&DbType::Pg, &do_ddls(&["truncate_table", "alter_table"])).is_err());
+        assert!(check(&DbType::Mysql, &do_ddls(&["*"])).is_ok());
+    }
+
+    #[test]
+    fn test_convert_rename_table() {
+        let ddl_data = DdlData {
+            default_schema: "db_1".into(),
+            ddl_type: DdlType::AlterTable,
+            db_type: DbType::Mysql,
+            statement: DdlStatement::MysqlAlterTableRename(MysqlAlterTableRenameStatement {
+                db: "db_1".into(),
+                tb: "tb_1".into(),
+                new_db: "db_1".into(),
+                new_tb: "tb_2".into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            DorisDdlConverter::convert(&ddl_data),
+            vec!["ALTER TABLE `db_1`.`tb_1` RENAME `tb_2`"]
+        );
+    }
+}
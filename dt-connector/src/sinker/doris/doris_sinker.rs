@@ -0,0 +1,428 @@
+use std::{
+    cmp,
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use crate::{
+    call_batch_fn,
+    sinker::{base_sinker::BaseSinker, starrocks::starrocks_sinker::StarRocksSinker},
+    Sinker,
+};
+use anyhow::bail;
+use async_trait::async_trait;
+use chrono::Utc;
+use dt_common::{
+    error::Error,
+    log_error, log_info,
+    meta::{
+        col_value::ColValue,
+        ddl_meta::ddl_data::DdlData,
+        mysql::{mysql_meta_manager::MysqlMetaManager, mysql_tb_meta::MysqlTbMeta},
+        row_data::RowData,
+        row_type::RowType,
+    },
+    monitor::monitor::Monitor,
+};
+use reqwest::{header, Client, Method, Response, StatusCode};
+use serde_json::{json, Value};
+use sqlx::{MySql, Pool};
+
+use super::{
+    doris_ddl_converter::DorisDdlConverter,
+    doris_struct_sinker::{SIGN_COL_NAME, TIMESTAMP_COL_NAME},
+};
+
+// the hidden column of unique key tables, rows with 1 are deleted
+const DELETE_SIGN_COL_NAME: &str = "__DORIS_DELETE_SIGN__";
+
+#[derive(Clone)]
+pub struct DorisSinker {
+    pub batch_size: usize,
+    pub http_client: Client,
+    pub scheme: String,
+    pub host: String,
+    pub port: String,
+    pub username: String,
+    pub password: String,
+    pub conn_pool: Pool<MySql>,
+    pub meta_manager: MysqlMetaManager,
+    pub monitor: Arc<Mutex<Monitor>>,
+    pub sync_timestamp: i64,
+    pub hard_delete: bool,
+    pub group_commit: String,
+}
+
+#[async_trait]
+impl Sinker for DorisSinker {
+    async fn sink_dml(&mut self, mut data: Vec<RowData>, batch: bool) -> anyhow::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        if !batch {
+            self.serial_sink(data).await?;
+        } else {
+            call_batch_fn!(self, data, Self::batch_sink);
+        }
+        Ok(())
+    }
+
+    async fn sink_ddl(&mut self, data: Vec<DdlData>, _batch: bool) -> anyhow::Result<()> {
+        for ddl_data in data {
+            for sql in DorisDdlConverter::convert(&ddl_data) {
+                log_info!("sink ddl: {}", sql);
+                let query = sqlx::query(&sql).disable_arguments();
+                if let Err(error) = query.execute(&self.conn_pool).await {
+                    // CHANGE COLUMN which only renames the column
+                    if error.to_string().contains("Nothing is changed") {
+                        log_info!("ddl ignored since nothing is changed: {}", sql);
+                        continue;
+                    }
+                    bail! {error}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn close(&mut self) -> anyhow::Result<()> {
+        self.meta_manager.close().await
+    }
+
+    async fn refresh_meta(&mut self, data: Vec<DdlData>) -> anyhow::Result<()> {
+        for ddl_data in data.iter() {
+            self.meta_manager.invalidate_cache_by_ddl_data(ddl_data);
+        }
+        Ok(())
+    }
+}
+
+impl DorisSinker {
+    async fn serial_sink(&mut self, mut data: Vec<RowData>) -> anyhow::Result<()> {
+        let start_time = Instant::now();
+        let mut data_size = 0;
+
+        let data = data.as_mut_slice();
+        for i in 0..data.len() {
+            data_size += data[i].data_size;
+            self.send_data(data, i, 1).await?;
+        }
+
+        BaseSinker::update_serial_monitor(&mut self.monitor, data.len(), data_size, start_time)
+    }
+
+    async fn batch_sink(
+        &mut self,
+        data: &mut [RowData],
+        start_index: usize,
+        batch_size: usize,
+    ) -> anyhow::Result<()> {
+        let start_time = Instant::now();
+
+        let data_size = self.send_data(data, start_index, batch_size).await?;
+
+        BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, data_size, start_time)
+    }
+
+    async fn send_data(
+        &mut self,
+        data: &mut [RowData],
+        start_index: usize,
+        batch_size: usize,
+    ) -> anyhow::Result<usize> {
+        let db = data[start_index].schema.clone();
+        let tb = data[start_index].tb.clone();
+        let tb_meta = self.meta_manager.get_tb_meta(&db, &tb).await?;
+        self.sync_timestamp = cmp::max(Utc::now().timestamp_millis(), self.sync_timestamp + 1);
+
+        // the meta fetcher takes the UNIQUE KEY of doris tables as the primary key
+        let unique_key = tb_meta.basic.key_map.contains_key("primary");
+        let end_index = cmp::min(start_index + batch_size, data.len());
+        let (load_data, data_size) = Self::build_load_data(
+            &mut data[start_index..end_index],
+            tb_meta,
+            self.hard_delete,
+            self.sync_timestamp,
+        )?;
+
+        let body = json!(load_data).to_string();
+        // do stream load
+        let url = format!(
+            "{}://{}:{}/api/{}/{}/_stream_load",
+            self.scheme, self.host, self.port, db, tb
+        );
+        let request = self.build_request(&url, unique_key, &body)?;
+        let response = self.http_client.execute(request).await?;
+        Self::check_response(response).await?;
+
+        Ok(data_size)
+    }
+
+    /// Rows of unique key tables are upserted with __DORIS_DELETE_SIGN__=0, deleted rows are:
+    /// soft deleted by _ape_dts_is_deleted=1 if the table has it and hard_delete=false,
+    /// or hard deleted by __DORIS_DELETE_SIGN__=1.
+    /// _ape_dts_timestamp, also the sequence column of tables created by the struct sinker,
+    /// is set to sync_timestamp, which increases by batch.
+    fn build_load_data<'a>(
+        data: &'a mut [RowData],
+        tb_meta: &MysqlTbMeta,
+        hard_delete: bool,
+        sync_timestamp: i64,
+    ) -> anyhow::Result<(Vec<&'a HashMap<String, ColValue>>, usize)> {
+        let unique_key = tb_meta.basic.key_map.contains_key("primary");
+        let col_origin_type_map = &tb_meta.basic.col_origin_type_map;
+        let has_timestamp_col = col_origin_type_map.contains_key(TIMESTAMP_COL_NAME);
+        let hard_delete = hard_delete || !col_origin_type_map.contains_key(SIGN_COL_NAME);
+
+        let mut data_size = 0;
+        let mut load_data = Vec::new();
+        for row_data in data.iter_mut() {
+            data_size += row_data.data_size;
+
+            StarRocksSinker::convert_row_data(row_data, tb_meta)?;
+
+            let mut delete_sign = 0;
+            let col_values = if row_data.row_type == RowType::Delete {
+                if hard_delete && !unique_key {
+                    bail! {Error::SinkerError(format!(
+                        "deletes are only supported by unique key tables, schema: {}, tb: {}",
+                        row_data.schema, row_data.tb
+                    ))}
+                }
+
+                let before = row_data.before.as_mut().unwrap();
+                if hard_delete {
+                    delete_sign = 1;
+                } else {
+                    // SIGN_COL value
+                    before.insert(SIGN_COL_NAME.into(), ColValue::Long(1));
+                }
+                before
+            } else {
+                row_data.after.as_mut().unwrap()
+            };
+
+            if has_timestamp_col {
+                col_values.insert(
+                    TIMESTAMP_COL_NAME.into(),
+                    ColValue::LongLong(sync_timestamp),
+                );
+            }
+            if unique_key {
+                col_values.insert(DELETE_SIGN_COL_NAME.into(), ColValue::Long(delete_sign));
+            }
+            load_data.push(&*col_values);
+        }
+        Ok((load_data, data_size))
+    }
+
+    fn build_request(
+        &self,
+        url: &str,
+        unique_key: bool,
+        body: &str,
+    ) -> anyhow::Result<reqwest::Request> {
+        let password = if self.password.is_empty() {
+            None
+        } else {
+            Some(self.password.clone())
+        };
+
+        // https://doris.apache.org/docs/data-operate/import/import-way/stream-load-manual
+        let mut put = self
+            .http_client
+            .request(Method::PUT, url)
+            .basic_auth(&self.username, password)
+            .header(header::EXPECT, "100-continue")
+            .header("format", "json")
+            .header("strip_outer_array", "true")
+            .body(body.to_string());
+        // rows are upserted or deleted by __DORIS_DELETE_SIGN__
+        if unique_key {
+            put = put.header("hidden_columns", DELETE_SIGN_COL_NAME);
+        }
+        // off_mode / sync_mode / async_mode
+        if !self.group_commit.is_empty() {
+            put = put.header("group_commit", &self.group_commit);
+        }
+        Ok(put.build()?)
+    }
+
+    async fn check_response(response: Response) -> anyhow::Result<()> {
+        let status_code = response.status();
+        let response_text = &response.text().await?;
+        if status_code != StatusCode::OK {
+            bail! {Error::HttpError(format!(
+                "data load request failed, status_code: {}, response_text: {:?}",
+                status_code, response_text
+            ))}
+        }
+
+        // response example:
+        // {
+        //     "TxnId": 1003,
+        //     "Label": "b6f3bc78-0d2c-45d9-9e4c-faa0a0149bee",
+        //     "Comment": "",
+        //     "TwoPhaseCommit": "false",
+        //     "Status": "Success",
+        //     "Message": "OK",
+        //     "NumberTotalRows": 1000000,
+        //     "NumberLoadedRows": 1000000,
+        //     "NumberFilteredRows": 1,
+        //     "NumberUnselectedRows": 0,
+        //     "LoadBytes": 40888898,
+        //     "LoadTimeMs": 2144,
+        //     ...
+        // }
+        // "Publish Timeout" means the load is committed but not visible yet
+        let json_value: Value = serde_json::from_str(response_text)?;
+        if json_value["Status"] != "Success" && json_value["Status"] != "Publish Timeout" {
+            let err = format!(
+                "stream load request failed, status_code: {}, load_result: {}",
+                status_code, response_text,
+            );
+            log_error!("{}", err);
+            bail! {Error::HttpError(err)}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dt_common::meta::{mysql::mysql_col_type::MysqlColType, rdb_tb_meta::RdbTbMeta};
+    use serde_json::json;
+
+    use super::*;
+
+    fn mock_tb_meta(unique_key: bool, extra_cols: &[&str]) -> MysqlTbMeta {
+        let mut col_origin_type_map = HashMap::from([
+            ("id".to_string(), "int".to_string()),
+            ("name".to_string(), "varchar(10)".to_string()),
+        ]);
+        for col in extra_cols {
+            col_origin_type_map.insert(col.to_string(), "bigint".to_string());
+        }
+        let mut key_map = HashMap::new();
+        if unique_key {
+            key_map.insert("primary".to_string(), vec!["id".to_string()]);
+        }
+        MysqlTbMeta {
+            basic: RdbTbMeta {
+                schema: "db_1".into(),
+                tb: "tb_1".into(),
+                col_origin_type_map,
+                key_map,
+                ..Default::default()
+            },
+            col_type_map: HashMap::from([
+                ("id".to_string(), MysqlColType::Int { unsigned: false }),
+                (
+                    "name".to_string(),
+                    MysqlColType::Varchar {
+                        length: 10,
+                        charset: "utf8mb4".into(),
+                    },
+                ),
+            ]),
+        }
+    }
+
+    fn mock_rows() -> Vec<RowData> {
+        let col_values = |id: i32| {
+            HashMap::from([
+                ("id".to_string(), ColValue::Long(id)),
+                ("name".to_string(), ColValue::String("a".into())),
+            ])
+        };
+        vec![
+            RowData::new(
+                "db_1".into(),
+                "tb_1".into(),
+                RowType::Insert,
+                None,
+                Some(col_values(1)),
+            ),
+            RowData::new(
+                "db_1".into(),
+                "tb_1".into(),
+                RowType::Delete,
+                Some(col_values(2)),
+                None,
+            ),
+        ]
+    }
+
+    fn build_load_data(
+        tb_meta: &MysqlTbMeta,
+        hard_delete: bool,
+    ) -> anyhow::Result<serde_json::Value> {
+        let mut data = mock_rows();
+        let (load_data, _) = DorisSinker::build_load_data(&mut data, tb_meta, hard_delete, 100)?;
+        Ok(json!(load_data))
+    }
+
+    #[test]
+    fn test_build_load_data_delete_sign() {
+        // unique key table without the sign col, deletes are hard deletes
+        let tb_meta = mock_tb_meta(true, &[]);
+        assert_eq!(
+            build_load_data(&tb_meta, false).unwrap(),
+            json!([
+                {"id": 1, "name": "a", "__DORIS_DELETE_SIGN__": 0},
+                {"id": 2, "name": "a", "__DORIS_DELETE_SIGN__": 1},
+            ])
+        );
+
+        // soft delete by the sign col
+        let tb_meta = mock_tb_meta(true, &[SIGN_COL_NAME]);
+        assert_eq!(
+            build_load_data(&tb_meta, false).unwrap(),
+            json!([
+                {"id": 1, "name": "a", "__DORIS_DELETE_SIGN__": 0},
+                {"id": 2, "name": "a", "_ape_dts_is_deleted": 1, "__DORIS_DELETE_SIGN__": 0},
+            ])
+        );
+
+        // hard_delete=true overrides the sign col
+        assert_eq!(
+            build_load_data(&tb_meta, true).unwrap(),
+            json!([
+                {"id": 1, "name": "a", "__DORIS_DELETE_SIGN__": 0},
+                {"id": 2, "name": "a", "__DORIS_DELETE_SIGN__": 1},
+            ])
+        );
+
+        // duplicate key tables can only soft delete
+        let tb_meta = mock_tb_meta(false, &[]);
+        assert!(build_load_data(&tb_meta, false).is_err());
+        let tb_meta = mock_tb_meta(false, &[SIGN_COL_NAME]);
+        assert_eq!(
+            build_load_data(&tb_meta, false).unwrap(),
+            json!([
+                {"id": 1, "name": "a"},
+                {"id": 2, "name": "a", "_ape_dts_is_deleted": 1},
+            ])
+        );
+    }
+
+    #[test]
+    fn test_build_load_data_sequence_col() {
+        let tb_meta = mock_tb_meta(true, &[SIGN_COL_NAME, TIMESTAMP_COL_NAME]);
+        assert_eq!(
+            build_load_data(&tb_meta, false).unwrap(),
+            json!([
+                {"id": 1, "name": "a", "_ape_dts_timestamp": 100, "__DORIS_DELETE_SIGN__": 0},
+                {
+                    "id": 2,
+                    "name": "a",
+                    "_ape_dts_is_deleted": 1,
+                    "_ape_dts_timestamp": 100,
+                    "__DORIS_DELETE_SIGN__": 0
+                },
+            ])
+        );
+    }
+}
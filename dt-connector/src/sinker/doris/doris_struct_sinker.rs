@@ -0,0 +1,364 @@
+use crate::Sinker;
+
+use anyhow::bail;
+use dt_common::{
+    config::config_enums::ConflictPolicyEnum,
+    log_error, log_info,
+    meta::{
+        pg::{pg_tb_meta::PgTbMeta, pg_value_type::PgValueType},
+        rdb_meta_manager::RdbMetaManager,
+        rdb_tb_meta::RdbTbMeta,
+        struct_meta::{
+            statement::struct_statement::StructStatement,
+            struct_data::StructData,
+            structure::{column::Column, table::Table},
+        },
+    },
+};
+
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use sqlx::{MySql, Pool};
+
+pub const SIGN_COL_NAME: &str = "_ape_dts_is_deleted";
+const SIGN_COL_TYPE: &str = "BOOLEAN";
+pub const TIMESTAMP_COL_NAME: &str = "_ape_dts_timestamp";
+const TIMESTAMP_COL_TYPE: &str = "BIGINT";
+// the max length of doris varchar in bytes
+const MAX_VARCHAR_LENGTH: u64 = 65533;
+// the max precision of doris decimal v3
+const MAX_DECIMAL_PRECISION: u64 = 38;
+
+#[derive(Clone)]
+pub struct DorisStructSinker {
+    pub conn_pool: Pool<MySql>,
+    pub conflict_policy: ConflictPolicyEnum,
+    pub extractor_meta_manager: RdbMetaManager,
+    pub backend_count: i32,
+}
+
+#[async_trait]
+impl Sinker for DorisStructSinker {
+    async fn sink_struct(&mut self, data: Vec<StructData>) -> anyhow::Result<()> {
+        if self.backend_count == 0 {
+            self.backend_count = self.get_backend_count().await?;
+        }
+
+        for i in data {
+            match i.statement {
+                StructStatement::MysqlCreateDatabase(statement) => {
+                    let sql = format!(
+                        "CREATE DATABASE IF NOT EXISTS `{}`",
+                        statement.database.name
+                    );
+                    self.execute_sql(&sql).await?;
+                }
+
+                StructStatement::MysqlCreateTable(statement) => {
+                    let schema = &statement.table.database_name;
+                    let tb = &statement.table.table_name;
+                    if let Some(meta_manager) =
+                        self.extractor_meta_manager.mysql_meta_manager.as_mut()
+                    {
+                        let tb_meta = meta_manager.get_tb_meta(schema, tb).await?.to_owned();
+                        let sql = self.get_create_table_sql(&statement.table, &tb_meta.basic, None);
+                        self.execute_sql(&sql).await?;
+                    }
+                }
+
+                StructStatement::PgCreateSchema(statement) => {
+                    let sql = format!("CREATE DATABASE IF NOT EXISTS `{}`", statement.schema.name);
+                    self.execute_sql(&sql).await?;
+                }
+
+                StructStatement::PgCreateTable(statement) => {
+                    let schema = &statement.table.schema_name;
+                    let tb = &statement.table.table_name;
+                    if let Some(meta_manager) = self.extractor_meta_manager.pg_meta_manager.as_mut()
+                    {
+                        let tb_meta = meta_manager.get_tb_meta(schema, tb).await?.to_owned();
+                        let sql = self.get_create_table_sql(
+                            &statement.table,
+                            &tb_meta.basic,
+                            Some(&tb_meta),
+                        );
+                        self.execute_sql(&sql).await?;
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl DorisStructSinker {
+    /// Tables with primary / unique keys are created as merge-on-write unique key tables, the sync
+    /// timestamp column is the sequence column so rows loaded out of order never overwrite newer ones.
+    /// Other tables are created as duplicate key tables.
+    fn get_create_table_sql(
+        &self,
+        table: &Table,
+        rdb_tb_meta: &RdbTbMeta,
+        pg_tb_meta: Option<&PgTbMeta>,
+    ) -> String {
+        // id_cols are the primary key, or the unique key with least cols
+        let has_key = !rdb_tb_meta.key_map.is_empty();
+        let key_cols = if has_key {
+            rdb_tb_meta.id_cols.clone()
+        } else {
+            Vec::new()
+        };
+
+        let mut dst_cols = vec![];
+        // In Doris:
+        // Key columns must be the first few columns of the schema and the order
+        // of the key columns must be consistent with the order of the schema.
+        for col in key_cols.iter() {
+            for column in table.columns.iter() {
+                if column.column_name == *col {
+                    dst_cols.push(Self::get_dst_col(column, pg_tb_meta, true));
+                }
+            }
+        }
+
+        for column in table.columns.iter() {
+            if !key_cols.contains(&column.column_name) {
+                dst_cols.push(Self::get_dst_col(column, pg_tb_meta, false));
+            }
+        }
+
+        // sign and timestamp cols
+        dst_cols.push(format!("`{}` {}", SIGN_COL_NAME, SIGN_COL_TYPE));
+        dst_cols.push(format!("`{}` {}", TIMESTAMP_COL_NAME, TIMESTAMP_COL_TYPE));
+
+        let mut sql = format!(
+            "CREATE TABLE IF NOT EXISTS `{}`.`{}` ({})",
+            rdb_tb_meta.schema,
+            rdb_tb_meta.tb,
+            dst_cols.join(", "),
+        );
+
+        let escaped_key_cols = key_cols
+            .iter()
+            .map(|i| format!("`{}`", i))
+            .collect::<Vec<String>>()
+            .join(",");
+        if has_key {
+            sql = format!("{} UNIQUE KEY({})", sql, escaped_key_cols);
+        }
+        if !table.table_comment.is_empty() {
+            sql = format!(
+                "{} COMMENT '{}'",
+                sql,
+                table.table_comment.replace('\'', "''")
+            );
+        }
+        if has_key {
+            sql = format!(
+                "{} DISTRIBUTED BY HASH({}) BUCKETS AUTO",
+                sql, escaped_key_cols
+            );
+        } else {
+            sql = format!("{} DISTRIBUTED BY RANDOM BUCKETS AUTO", sql);
+        }
+
+        let mut properties = vec![r#""light_schema_change" = "true""#.to_string()];
+        if has_key {
+            properties.push(r#""enable_unique_key_merge_on_write" = "true""#.into());
+            properties.push(format!(
+                r#""function_column.sequence_col" = "{}""#,
+                TIMESTAMP_COL_NAME
+            ));
+        }
+        if self.backend_count < 3 {
+            properties.push(r#""replication_num" = "1""#.into());
+        }
+        format!("{} PROPERTIES ({})", sql, properties.join(", "))
+    }
+
+    fn get_dst_col(column: &Column, pg_tb_meta: Option<&PgTbMeta>, is_key: bool) -> String {
+        let col = &column.column_name;
+        let mut dst_col_type = if let Some(tb_meta) = pg_tb_meta {
+            Self::get_dst_col_type_from_pg(col, tb_meta)
+        } else {
+            Self::get_dst_col_type_from_mysql(&column.column_type)
+        };
+        // STRING can not be key columns
+        if is_key && dst_col_type == "STRING" {
+            dst_col_type = format!("VARCHAR({})", MAX_VARCHAR_LENGTH);
+        }
+
+        let mut dst_col = if column.is_nullable {
+            format!("`{}` {}", col, dst_col_type)
+        } else {
+            format!("`{}` {} NOT NULL", col, dst_col_type)
+        };
+
+        if !column.column_comment.is_empty() {
+            dst_col = format!(
+                "{} COMMENT '{}'",
+                dst_col,
+                column.column_comment.replace('\'', "''")
+            );
+        }
+        dst_col
+    }
+
+    /// Maps a mysql column type like: int unsigned, varchar(100), datetime(3) to the doris type.
+    /// Also used to convert column definitions in ddl.
+    pub fn get_dst_col_type_from_mysql(column_type: &str) -> String {
+        let column_type = column_type.trim().to_lowercase();
+        let unsigned = column_type.contains("unsigned");
+        let (name, args) = match column_type.split_once('(') {
+            Some((name, remaining)) => {
+                (name.trim(), remaining.split(')').next().unwrap_or_default())
+            }
+            None => (
+                column_type.split_whitespace().next().unwrap_or_default(),
+                "",
+            ),
+        };
+        let args: Vec<u64> = args
+            .split(',')
+            .filter_map(|i| i.trim().parse().ok())
+            .collect();
+
+        match name {
+            "tinyint" if unsigned => "SMALLINT".into(),
+            "tinyint" => "TINYINT".into(),
+            "smallint" if unsigned => "INT".into(),
+            "smallint" => "SMALLINT".into(),
+            "mediumint" => "INT".into(),
+            "int" | "integer" if unsigned => "BIGINT".into(),
+            "int" | "integer" => "INT".into(),
+            "bigint" if unsigned => "LARGEINT".into(),
+            "bigint" => "BIGINT".into(),
+            "bool" | "boolean" => "BOOLEAN".into(),
+
+            "float" => "FLOAT".into(),
+            "double" | "real" => "DOUBLE".into(),
+            "decimal" | "numeric" => {
+                let precision = args.first().cloned().unwrap_or(10);
+                let scale = args.get(1).cloned().unwrap_or(0);
+                if precision > MAX_DECIMAL_PRECISION {
+                    "STRING".into()
+                } else {
+                    format!("DECIMAL({},{})", precision, scale)
+                }
+            }
+
+            // doris has no time type for columns
+            "time" => "VARCHAR(64)".into(),
+            "date" => "DATE".into(),
+            "datetime" | "timestamp" => match args.first() {
+                Some(precision) if *precision > 0 => format!("DATETIME({})", precision),
+                _ => "DATETIME".into(),
+            },
+            "year" => "INT".into(),
+
+            // lengths of doris varchar are in bytes, 4 bytes for each utf8mb4 char
+            "char" | "varchar" => {
+                let length = args.first().cloned().unwrap_or(1) * 4;
+                if length > MAX_VARCHAR_LENGTH {
+                    "STRING".into()
+                } else {
+                    format!("VARCHAR({})", length)
+                }
+            }
+
+            "bit" => "BIGINT".into(),
+            "json" => "JSON".into(),
+            // text, blob, set, enum, geometry ...
+            _ => "STRING".into(),
+        }
+    }
+
+    fn get_dst_col_type_from_pg(col: &str, tb_meta: &PgTbMeta) -> String {
+        let col_type = match tb_meta.col_type_map.get(col) {
+            Some(col_type) => col_type,
+            None => return "STRING".into(),
+        };
+        let dst_col = match col_type.value_type {
+            PgValueType::Boolean => "BOOLEAN",
+            PgValueType::Int16 => "SMALLINT",
+            PgValueType::Int32 => "INT",
+            PgValueType::Int64 => "BIGINT",
+            PgValueType::Float32 => "FLOAT",
+            PgValueType::Float64 => "DOUBLE",
+
+            // TODO, set precision / scale according to source
+            PgValueType::Numeric => "DECIMAL(38,9)",
+            PgValueType::JSON => "JSON",
+
+            PgValueType::Time | PgValueType::TimeTZ | PgValueType::Interval => "VARCHAR(64)",
+            PgValueType::Timestamp | PgValueType::TimestampTZ => "DATETIME(6)",
+            PgValueType::Date => "DATE",
+            _ => "STRING",
+        };
+        dst_col.to_string()
+    }
+
+    async fn get_backend_count(&self) -> anyhow::Result<i32> {
+        let sql = "SHOW BACKENDS";
+        let mut count = 0;
+        let mut rows = sqlx::query(sql).disable_arguments().fetch(&self.conn_pool);
+        while (rows.try_next().await?).is_some() {
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    async fn execute_sql(&self, sql: &str) -> anyhow::Result<()> {
+        log_info!("ddl begin: {}", sql);
+        let query = sqlx::query(sql).disable_arguments();
+        match query.execute(&self.conn_pool).await {
+            Ok(_) => {
+                log_info!("ddl succeed");
+            }
+
+            Err(error) => {
+                log_error!("ddl failed, error: {}", error);
+                match self.conflict_policy {
+                    ConflictPolicyEnum::Interrupt => bail! {error},
+                    ConflictPolicyEnum::Ignore => {}
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_dst_col_type_from_mysql() {
+        let cases = [
+            ("tinyint(1)", "TINYINT"),
+            ("int unsigned", "BIGINT"),
+            ("int(10) unsigned", "BIGINT"),
+            ("bigint unsigned", "LARGEINT"),
+            ("decimal(10,2)", "DECIMAL(10,2)"),
+            ("decimal(65,30)", "STRING"),
+            ("datetime(3)", "DATETIME(3)"),
+            ("timestamp", "DATETIME"),
+            ("varchar(100)", "VARCHAR(400)"),
+            ("varchar(20000)", "STRING"),
+            ("enum('a','b')", "STRING"),
+            ("longblob", "STRING"),
+            ("JSON", "JSON"),
+        ];
+        for (column_type, expected) in cases {
+            assert_eq!(
+                DorisStructSinker::get_dst_col_type_from_mysql(column_type),
+                expected,
+                "{}",
+                column_type
+            );
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod doris_ddl_converter;
+pub mod doris_sinker;
+pub mod doris_struct_sinker;
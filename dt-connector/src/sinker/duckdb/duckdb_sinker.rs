@@ -0,0 +1,449 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use anyhow::bail;
+use async_trait::async_trait;
+use dt_common::{
+    config::config_enums::DbType,
+    error::Error,
+    log_error,
+    meta::{
+        col_value::ColValue, ddl_meta::ddl_data::DdlData, row_data::RowData, row_type::RowType,
+    },
+    monitor::monitor::Monitor,
+    utils::sql_util::SqlUtil,
+};
+use duckdb::{params_from_iter, types::Value, Connection};
+use mongodb::bson::Bson;
+
+use crate::{sinker::base_sinker::BaseSinker, sync_call_batch_fn, Sinker};
+
+/// Columns and primary keys of a target table, read from the duckdb catalog.
+#[derive(Debug, Clone)]
+pub struct DuckDbTbMeta {
+    pub schema: String,
+    pub tb: String,
+    pub cols: Vec<String>,
+    pub id_cols: Vec<String>,
+}
+
+/// Writes rows to a local duckdb file, snapshot inserts are loaded by the appender.
+pub struct DuckDbSinker {
+    pub conn: Connection,
+    pub batch_size: usize,
+    pub monitor: Arc<Mutex<Monitor>>,
+    // (schema, tb) -> meta
+    pub tb_metas: HashMap<(String, String), DuckDbTbMeta>,
+}
+
+// duckdb is embedded, rows are written synchronously in the sinker thread
+#[async_trait]
+impl Sinker for DuckDbSinker {
+    async fn sink_dml(&mut self, mut data: Vec<RowData>, batch: bool) -> anyhow::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        if batch && data[0].row_type == RowType::Insert {
+            sync_call_batch_fn!(self, data, Self::batch_insert);
+        } else {
+            self.serial_sink(&data)?;
+        }
+        Ok(())
+    }
+
+    async fn refresh_meta(&mut self, data: Vec<DdlData>) -> anyhow::Result<()> {
+        for ddl_data in data.iter() {
+            let (schema, tb) = ddl_data.get_schema_tb();
+            self.tb_metas
+                .retain(|(s, t), _| !(*s == schema && (tb.is_empty() || *t == tb)));
+        }
+        Ok(())
+    }
+}
+
+impl DuckDbSinker {
+    /// Inserts are upserts for tables with primary keys, updates and deletes are by primary keys,
+    /// or by all columns if the table has no primary key.
+    fn serial_sink(&mut self, data: &[RowData]) -> anyhow::Result<()> {
+        let start_time = Instant::now();
+        let mut data_size = 0;
+
+        let tx = self.conn.transaction()?;
+        for row_data in data.iter() {
+            data_size += row_data.data_size;
+            let tb_meta =
+                Self::get_tb_meta(&tx, &mut self.tb_metas, &row_data.schema, &row_data.tb)?;
+            for (sql, values) in Self::get_queries(tb_meta, row_data)? {
+                tx.prepare_cached(&sql)?.execute(params_from_iter(values))?;
+            }
+        }
+        tx.commit()?;
+
+        BaseSinker::update_serial_monitor(&mut self.monitor, data.len(), data_size, start_time)
+    }
+
+    fn batch_insert(
+        &mut self,
+        data: &mut [RowData],
+        start_index: usize,
+        batch_size: usize,
+    ) -> anyhow::Result<()> {
+        let start_time = Instant::now();
+
+        let sub_data = &data[start_index..start_index + batch_size];
+        let data_size = sub_data.iter().map(|i| i.data_size).sum();
+        let tb_meta = Self::get_tb_meta(
+            &self.conn,
+            &mut self.tb_metas,
+            &sub_data[0].schema,
+            &sub_data[0].tb,
+        )?
+        .clone();
+
+        if let Err(error) = self.append(&tb_meta, sub_data) {
+            // rows may exist if the snapshot task restarted
+            log_error!(
+                "batch insert failed, will insert one by one, schema: {}, tb: {}, error: {}",
+                tb_meta.schema,
+                tb_meta.tb,
+                error.to_string()
+            );
+            self.serial_sink(sub_data)?;
+        }
+
+        BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, data_size, start_time)
+    }
+
+    fn append(&mut self, tb_meta: &DuckDbTbMeta, data: &[RowData]) -> anyhow::Result<()> {
+        let tx = self.conn.transaction()?;
+        {
+            let mut appender = tx.appender_to_db(&tb_meta.tb, &tb_meta.schema)?;
+            for row_data in data.iter() {
+                let after = row_data.after.as_ref().unwrap();
+                // the appender takes values of all columns in the table order
+                let values = tb_meta
+                    .cols
+                    .iter()
+                    .map(|col| Self::to_duckdb_value(after.get(col).unwrap_or(&ColValue::None)));
+                appender.append_row(params_from_iter(values))?;
+            }
+            appender.flush()?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn get_queries(
+        tb_meta: &DuckDbTbMeta,
+        row_data: &RowData,
+    ) -> anyhow::Result<Vec<(String, Vec<Value>)>> {
+        let queries = match row_data.row_type {
+            RowType::Insert => vec![Self::get_insert_query(tb_meta, row_data)],
+
+            RowType::Update => {
+                let before = row_data.before.as_ref().unwrap();
+                let after = row_data.after.as_ref().unwrap();
+                let id_changed = tb_meta
+                    .id_cols
+                    .iter()
+                    .any(|col| before.get(col) != after.get(col));
+                // duckdb may report constraint violations when updating primary keys in place,
+                // so rows with changed primary keys are deleted and inserted again
+                if id_changed {
+                    vec![
+                        Self::get_delete_query(tb_meta, row_data)?,
+                        Self::get_insert_query(tb_meta, row_data),
+                    ]
+                } else {
+                    Self::get_update_query(tb_meta, row_data)?
+                        .into_iter()
+                        .collect()
+                }
+            }
+
+            RowType::Delete => vec![Self::get_delete_query(tb_meta, row_data)?],
+        };
+        Ok(queries)
+    }
+
+    fn get_insert_query(tb_meta: &DuckDbTbMeta, row_data: &RowData) -> (String, Vec<Value>) {
+        let after = row_data.after.as_ref().unwrap();
+        let cols = Self::get_cols(tb_meta, after);
+        let values = cols
+            .iter()
+            .map(|col| Self::to_duckdb_value(&after[*col]))
+            .collect();
+
+        let insert = if tb_meta.id_cols.is_empty() {
+            "INSERT"
+        } else {
+            "INSERT OR REPLACE"
+        };
+        let sql = format!(
+            "{} INTO {}({}) VALUES({})",
+            insert,
+            Self::get_full_tb(tb_meta),
+            cols.iter()
+                .map(|i| SqlUtil::escape_by_db_type(i, &DbType::DuckDb))
+                .collect::<Vec<_>>()
+                .join(","),
+            vec!["?"; cols.len()].join(",")
+        );
+        (sql, values)
+    }
+
+    fn get_update_query(
+        tb_meta: &DuckDbTbMeta,
+        row_data: &RowData,
+    ) -> anyhow::Result<Option<(String, Vec<Value>)>> {
+        let after = row_data.after.as_ref().unwrap();
+        let cols: Vec<&String> = Self::get_cols(tb_meta, after)
+            .into_iter()
+            .filter(|col| !tb_meta.id_cols.contains(*col))
+            .collect();
+        if cols.is_empty() {
+            return Ok(None);
+        }
+
+        let mut values: Vec<Value> = cols
+            .iter()
+            .map(|col| Self::to_duckdb_value(&after[*col]))
+            .collect();
+        let set = cols
+            .iter()
+            .map(|i| format!("{}=?", SqlUtil::escape_by_db_type(i, &DbType::DuckDb)))
+            .collect::<Vec<_>>()
+            .join(",");
+        let where_sql = Self::get_where_sql(tb_meta, row_data, &mut values)?;
+        let sql = format!(
+            "UPDATE {} SET {} WHERE {}",
+            Self::get_full_tb(tb_meta),
+            set,
+            where_sql
+        );
+        Ok(Some((sql, values)))
+    }
+
+    fn get_delete_query(
+        tb_meta: &DuckDbTbMeta,
+        row_data: &RowData,
+    ) -> anyhow::Result<(String, Vec<Value>)> {
+        let mut values = Vec::new();
+        let where_sql = Self::get_where_sql(tb_meta, row_data, &mut values)?;
+        let sql = format!(
+            "DELETE FROM {} WHERE {}",
+            Self::get_full_tb(tb_meta),
+            where_sql
+        );
+        Ok((sql, values))
+    }
+
+    fn get_full_tb(tb_meta: &DuckDbTbMeta) -> String {
+        format!(
+            "{}.{}",
+            SqlUtil::escape_by_db_type(&tb_meta.schema, &DbType::DuckDb),
+            SqlUtil::escape_by_db_type(&tb_meta.tb, &DbType::DuckDb)
+        )
+    }
+
+    fn get_where_sql(
+        tb_meta: &DuckDbTbMeta,
+        row_data: &RowData,
+        values: &mut Vec<Value>,
+    ) -> anyhow::Result<String> {
+        let before = row_data.before.as_ref().unwrap();
+        let mut conditions = Vec::new();
+        if tb_meta.id_cols.is_empty() {
+            for col in Self::get_cols(tb_meta, before) {
+                let escaped_col = SqlUtil::escape_by_db_type(col, &DbType::DuckDb);
+                conditions.push(format!("{} IS NOT DISTINCT FROM ?", escaped_col));
+                values.push(Self::to_duckdb_value(&before[col]));
+            }
+        } else {
+            for col in tb_meta.id_cols.iter() {
+                let col_value = match before.get(col) {
+                    Some(col_value) => col_value,
+                    None => bail! {Error::SinkerError(format!(
+                        "primary key: {} not found in the before image of {}.{}",
+                        col, tb_meta.schema, tb_meta.tb
+                    ))},
+                };
+                let escaped_col = SqlUtil::escape_by_db_type(col, &DbType::DuckDb);
+                conditions.push(format!("{}=?", escaped_col));
+                values.push(Self::to_duckdb_value(col_value));
+            }
+        }
+        Ok(conditions.join(" AND "))
+    }
+
+    // columns of the row which exist in the table, in the table order
+    fn get_cols<'a>(
+        tb_meta: &'a DuckDbTbMeta,
+        col_values: &HashMap<String, ColValue>,
+    ) -> Vec<&'a String> {
+        tb_meta
+            .cols
+            .iter()
+            .filter(|col| col_values.contains_key(*col))
+            .collect()
+    }
+
+    /// Values are cast to column types by duckdb, so datetimes, decimals, etc. are bound as strings.
+    pub fn to_duckdb_value(col_value: &ColValue) -> Value {
+        match col_value {
+            ColValue::None => Value::Null,
+            ColValue::Bool(v) => Value::Boolean(*v),
+            ColValue::Tiny(v) => Value::TinyInt(*v),
+            ColValue::UnsignedTiny(v) => Value::UTinyInt(*v),
+            ColValue::Short(v) => Value::SmallInt(*v),
+            ColValue::UnsignedShort(v) => Value::USmallInt(*v),
+            ColValue::Long(v) => Value::Int(*v),
+            ColValue::UnsignedLong(v) => Value::UInt(*v),
+            ColValue::LongLong(v) => Value::BigInt(*v),
+            ColValue::UnsignedLongLong(v) | ColValue::Bit(v) | ColValue::Set(v) => {
+                Value::UBigInt(*v)
+            }
+            ColValue::Enum(v) => Value::UInt(*v),
+            ColValue::Year(v) => Value::Int(*v as i32),
+            ColValue::Float(v) => Value::Float(*v),
+            ColValue::Double(v) => Value::Double(*v),
+            ColValue::Blob(v) => Value::Blob(v.clone()),
+            ColValue::RawString(v) => Value::Text(String::from_utf8_lossy(v).to_string()),
+            ColValue::MongoDoc(v) => {
+                Value::Text(Bson::Document(v.clone()).into_relaxed_extjson().to_string())
+            }
+            _ => match col_value.to_option_string() {
+                Some(v) => Value::Text(v),
+                None => Value::Null,
+            },
+        }
+    }
+
+    fn get_tb_meta<'a>(
+        conn: &Connection,
+        tb_metas: &'a mut HashMap<(String, String), DuckDbTbMeta>,
+        schema: &str,
+        tb: &str,
+    ) -> anyhow::Result<&'a DuckDbTbMeta> {
+        let key = (schema.to_string(), tb.to_string());
+        if !tb_metas.contains_key(&key) {
+            let tb_meta = Self::fetch_tb_meta(conn, schema, tb)?;
+            tb_metas.insert(key.clone(), tb_meta);
+        }
+        Ok(tb_metas.get(&key).unwrap())
+    }
+
+    fn fetch_tb_meta(conn: &Connection, schema: &str, tb: &str) -> anyhow::Result<DuckDbTbMeta> {
+        let sql = "SELECT column_name FROM duckdb_columns()
+            WHERE database_name = current_database() AND schema_name = ? AND table_name = ?
+            ORDER BY column_index";
+        let cols = conn
+            .prepare(sql)?
+            .query_map([schema, tb], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        if cols.is_empty() {
+            bail! {Error::SinkerError(format!(
+                "table not found in duckdb: {}.{}",
+                schema, tb
+            ))}
+        }
+
+        let sql = "SELECT unnest(constraint_column_names) FROM duckdb_constraints()
+            WHERE database_name = current_database() AND schema_name = ? AND table_name = ?
+            AND constraint_type = 'PRIMARY KEY'";
+        let id_cols = conn
+            .prepare(sql)?
+            .query_map([schema, tb], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(DuckDbTbMeta {
+            schema: schema.to_string(),
+            tb: tb.to_string(),
+            cols,
+            id_cols,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_row(row_type: RowType, id: i32, name: Option<&str>) -> RowData {
+        let col_values = HashMap::from([
+            ("id".to_string(), ColValue::Long(id)),
+            (
+                "name".to_string(),
+                name.map_or(ColValue::None, |v| ColValue::String(v.into())),
+            ),
+            ("amount".to_string(), ColValue::Decimal("1.50".into())),
+        ]);
+        let (before, after) = match row_type {
+            RowType::Insert => (None, Some(col_values)),
+            RowType::Update => (Some(col_values.clone()), Some(col_values)),
+            RowType::Delete => (Some(col_values), None),
+        };
+        RowData::new("db_1".into(), "tb_1".into(), row_type, before, after)
+    }
+
+    fn query_rows(conn: &Connection) -> Vec<(i32, Option<String>, String)> {
+        conn.prepare(r#"SELECT id, name, CAST(amount AS VARCHAR) FROM "db_1"."tb_1" ORDER BY id"#)
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_sink_dml() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            r#"CREATE SCHEMA "db_1";
+            CREATE TABLE "db_1"."tb_1" (id INTEGER PRIMARY KEY, name VARCHAR, amount DECIMAL(10,2))"#,
+        )
+        .unwrap();
+
+        let mut sinker = DuckDbSinker {
+            conn: conn.try_clone().unwrap(),
+            batch_size: 2,
+            monitor: Arc::new(Mutex::new(Monitor::new("sinker", 1, 1, 1))),
+            tb_metas: HashMap::new(),
+        };
+
+        // snapshot
+        let data = vec![
+            mock_row(RowType::Insert, 1, Some("a")),
+            mock_row(RowType::Insert, 2, None),
+            mock_row(RowType::Insert, 3, Some("c")),
+        ];
+        sinker.sink_dml(data, true).await.unwrap();
+        assert_eq!(
+            sinker.tb_metas[&("db_1".into(), "tb_1".into())].id_cols,
+            vec!["id"]
+        );
+
+        // rows written again by appender fall back to upserts
+        let data = vec![mock_row(RowType::Insert, 3, Some("c2"))];
+        sinker.sink_dml(data, true).await.unwrap();
+
+        // cdc
+        let data = vec![
+            mock_row(RowType::Update, 2, Some("b")),
+            mock_row(RowType::Delete, 1, Some("a")),
+        ];
+        sinker.sink_dml(data, false).await.unwrap();
+
+        assert_eq!(
+            query_rows(&conn),
+            vec![
+                (2, Some("b".to_string()), "1.50".to_string()),
+                (3, Some("c2".to_string()), "1.50".to_string()),
+            ]
+        );
+    }
+}
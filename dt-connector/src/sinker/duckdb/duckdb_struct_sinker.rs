@@ -0,0 +1,446 @@
+use crate::Sinker;
+
+use anyhow::bail;
+use dt_common::{
+    config::config_enums::{ConflictPolicyEnum, DbType},
+    log_error, log_info,
+    meta::{
+        mysql::{mysql_col_type::MysqlColType, mysql_tb_meta::MysqlTbMeta},
+        pg::{pg_tb_meta::PgTbMeta, pg_value_type::PgValueType},
+        rdb_meta_manager::RdbMetaManager,
+        struct_meta::{
+            statement::struct_statement::StructStatement,
+            struct_data::StructData,
+            structure::{column::Column, table::Table},
+        },
+    },
+    utils::sql_util::SqlUtil,
+};
+
+use async_trait::async_trait;
+use duckdb::Connection;
+
+// the max precision of duckdb decimals
+const MAX_DECIMAL_PRECISION: u32 = 38;
+
+pub struct DuckDbStructSinker {
+    pub conn: Connection,
+    pub conflict_policy: ConflictPolicyEnum,
+    pub extractor_meta_manager: RdbMetaManager,
+}
+
+#[async_trait]
+impl Sinker for DuckDbStructSinker {
+    async fn sink_struct(&mut self, data: Vec<StructData>) -> anyhow::Result<()> {
+        for i in data {
+            match i.statement {
+                StructStatement::MysqlCreateDatabase(statement) => {
+                    let sql = Self::get_create_schema_sql(&statement.database.name);
+                    self.execute_sql(&sql)?;
+                }
+
+                StructStatement::MysqlCreateTable(statement) => {
+                    let schema = &statement.table.database_name;
+                    let tb = &statement.table.table_name;
+                    if let Some(meta_manager) =
+                        self.extractor_meta_manager.mysql_meta_manager.as_mut()
+                    {
+                        let tb_meta = meta_manager.get_tb_meta(schema, tb).await?.to_owned();
+                        let sql =
+                            Self::get_create_table_sql(&statement.table, Some(&tb_meta), None)?;
+                        self.execute_sql(&sql)?;
+                    }
+                }
+
+                StructStatement::PgCreateSchema(statement) => {
+                    let sql = Self::get_create_schema_sql(&statement.schema.name);
+                    self.execute_sql(&sql)?;
+                }
+
+                StructStatement::PgCreateTable(statement) => {
+                    let schema = &statement.table.schema_name;
+                    let tb = &statement.table.table_name;
+                    if let Some(meta_manager) = self.extractor_meta_manager.pg_meta_manager.as_mut()
+                    {
+                        let tb_meta = meta_manager.get_tb_meta(schema, tb).await?.to_owned();
+                        let sql =
+                            Self::get_create_table_sql(&statement.table, None, Some(&tb_meta))?;
+                        self.execute_sql(&sql)?;
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl DuckDbStructSinker {
+    fn get_create_schema_sql(schema: &str) -> String {
+        format!(
+            "CREATE SCHEMA IF NOT EXISTS {}",
+            SqlUtil::escape_by_db_type(schema, &DbType::DuckDb)
+        )
+    }
+
+    fn get_create_table_sql(
+        table: &Table,
+        mysql_tb_meta: Option<&MysqlTbMeta>,
+        pg_tb_meta: Option<&PgTbMeta>,
+    ) -> anyhow::Result<String> {
+        let rdb_tb_meta = if let Some(tb_meta) = pg_tb_meta {
+            &tb_meta.basic
+        } else {
+            &mysql_tb_meta.as_ref().unwrap().basic
+        };
+
+        let mut dst_cols = vec![];
+        for column in table.columns.iter() {
+            dst_cols.push(Self::get_dst_col(column, mysql_tb_meta, pg_tb_meta)?);
+        }
+
+        // the sinker writes inserts by upsert and updates / deletes by primary keys
+        if !rdb_tb_meta.id_cols.is_empty() {
+            let primary_keys = rdb_tb_meta
+                .id_cols
+                .iter()
+                .map(|i| SqlUtil::escape_by_db_type(i, &DbType::DuckDb))
+                .collect::<Vec<String>>()
+                .join(",");
+            dst_cols.push(format!("PRIMARY KEY ({})", primary_keys));
+        }
+
+        Ok(format!(
+            "CREATE TABLE IF NOT EXISTS {}.{} ({})",
+            SqlUtil::escape_by_db_type(&rdb_tb_meta.schema, &DbType::DuckDb),
+            SqlUtil::escape_by_db_type(&rdb_tb_meta.tb, &DbType::DuckDb),
+            dst_cols.join(", "),
+        ))
+    }
+
+    fn get_dst_col(
+        column: &Column,
+        mysql_tb_meta: Option<&MysqlTbMeta>,
+        pg_tb_meta: Option<&PgTbMeta>,
+    ) -> anyhow::Result<String> {
+        let col = &column.column_name;
+        let dst_col_type = if let Some(tb_meta) = mysql_tb_meta {
+            Self::get_dst_col_type_from_mysql(col, tb_meta)
+        } else {
+            Self::get_dst_col_type_from_pg(column, pg_tb_meta.unwrap())
+        }?;
+
+        let escaped_col = SqlUtil::escape_by_db_type(col, &DbType::DuckDb);
+        if column.is_nullable {
+            Ok(format!("{} {}", escaped_col, dst_col_type))
+        } else {
+            Ok(format!("{} {} NOT NULL", escaped_col, dst_col_type))
+        }
+    }
+
+    fn get_dst_col_type_from_mysql(col: &str, tb_meta: &MysqlTbMeta) -> anyhow::Result<String> {
+        let mysql_col_type = tb_meta.get_col_type(col)?;
+        let dst_col = match mysql_col_type {
+            MysqlColType::TinyInt { unsigned: false } => "TINYINT",
+            MysqlColType::TinyInt { unsigned: true } => "UTINYINT",
+            MysqlColType::SmallInt { unsigned: false } => "SMALLINT",
+            MysqlColType::SmallInt { unsigned: true } => "USMALLINT",
+            MysqlColType::MediumInt { unsigned: false } => "INTEGER",
+            MysqlColType::MediumInt { unsigned: true } => "UINTEGER",
+            MysqlColType::Int { unsigned: false } => "INTEGER",
+            MysqlColType::Int { unsigned: true } => "UINTEGER",
+            MysqlColType::BigInt { unsigned: false } => "BIGINT",
+            MysqlColType::BigInt { unsigned: true } => "UBIGINT",
+
+            MysqlColType::Float => "FLOAT",
+            MysqlColType::Double => "DOUBLE",
+            MysqlColType::Decimal { precision, scale } => {
+                if *precision <= MAX_DECIMAL_PRECISION {
+                    return Ok(format!("DECIMAL({},{})", precision, scale));
+                }
+                "VARCHAR"
+            }
+
+            // duckdb TIME is 00:00:00 ~ 24:00:00, while mysql TIME is -838:59:59 ~ 838:59:59
+            MysqlColType::Time => "VARCHAR",
+            MysqlColType::Date => "DATE",
+            MysqlColType::DateTime => "TIMESTAMP",
+            MysqlColType::Timestamp { timezone_offset: _ } => "TIMESTAMP",
+            MysqlColType::Year => "INTEGER",
+
+            MysqlColType::Char { .. }
+            | MysqlColType::Varchar { .. }
+            | MysqlColType::TinyText { .. }
+            | MysqlColType::MediumText { .. }
+            | MysqlColType::Text { .. }
+            | MysqlColType::LongText { .. } => "VARCHAR",
+
+            MysqlColType::Binary { .. }
+            | MysqlColType::VarBinary { .. }
+            | MysqlColType::TinyBlob
+            | MysqlColType::MediumBlob
+            | MysqlColType::Blob
+            | MysqlColType::LongBlob => "BLOB",
+
+            MysqlColType::Bit => "UBIGINT",
+            MysqlColType::Set { items: _ } => "VARCHAR",
+            MysqlColType::Enum { items: _ } => "VARCHAR",
+            MysqlColType::Json => "VARCHAR",
+            MysqlColType::Unknown => "VARCHAR",
+        };
+        Ok(dst_col.to_string())
+    }
+
+    fn get_dst_col_type_from_pg(column: &Column, tb_meta: &PgTbMeta) -> anyhow::Result<String> {
+        let pg_col_type = tb_meta.get_col_type(&column.column_name)?;
+        let dst_col = match pg_col_type.value_type {
+            PgValueType::Boolean => "BOOLEAN",
+            PgValueType::Int16 => "SMALLINT",
+            PgValueType::Int32 => "INTEGER",
+            PgValueType::Int64 => "BIGINT",
+            PgValueType::Float32 => "FLOAT",
+            PgValueType::Float64 => "DOUBLE",
+            PgValueType::Numeric => {
+                return Ok(Self::get_dst_decimal_type_from_pg(&column.column_type))
+            }
+            PgValueType::JSON => "VARCHAR",
+
+            PgValueType::Time | PgValueType::TimeTZ | PgValueType::Interval => "VARCHAR",
+            PgValueType::Timestamp => "TIMESTAMP",
+            PgValueType::TimestampTZ => "TIMESTAMPTZ",
+            PgValueType::Date => "DATE",
+
+            PgValueType::Bytes => "BLOB",
+            _ => "VARCHAR",
+        };
+        Ok(dst_col.to_string())
+    }
+
+    /// column_type is from format_type, like: numeric(10,2).
+    /// Unconstrained numerics keep values of any precision / scale, they are VARCHAR,
+    /// so are numerics beyond the max precision of duckdb decimals, or with scales out of
+    /// 0 ~ precision, which are allowed since postgres 15.
+    fn get_dst_decimal_type_from_pg(column_type: &str) -> String {
+        let parse = || -> Option<(u32, u32)> {
+            let args = column_type.strip_prefix("numeric(")?.strip_suffix(')')?;
+            let (precision, scale) = args.split_once(',').unwrap_or((args, "0"));
+            Some((precision.trim().parse().ok()?, scale.trim().parse().ok()?))
+        };
+
+        match parse() {
+            Some((precision, scale))
+                if precision <= MAX_DECIMAL_PRECISION && scale <= precision =>
+            {
+                format!("DECIMAL({},{})", precision, scale)
+            }
+            _ => "VARCHAR".to_string(),
+        }
+    }
+
+    fn execute_sql(&self, sql: &str) -> anyhow::Result<()> {
+        log_info!("ddl begin: {}", sql);
+        match self.conn.execute_batch(sql) {
+            Ok(_) => {
+                log_info!("ddl succeed");
+            }
+
+            Err(error) => {
+                log_error!("ddl failed, error: {}", error);
+                match self.conflict_policy {
+                    ConflictPolicyEnum::Interrupt => bail! {error},
+                    ConflictPolicyEnum::Ignore => {}
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dt_common::meta::{pg::pg_col_type::PgColType, rdb_tb_meta::RdbTbMeta};
+
+    use super::*;
+
+    fn mock_table(columns: &[(&str, &str, bool)]) -> Table {
+        Table {
+            database_name: "db_1".into(),
+            schema_name: "db_1".into(),
+            table_name: "tb_1".into(),
+            columns: columns
+                .iter()
+                .map(|(name, column_type, is_nullable)| Column {
+                    column_name: name.to_string(),
+                    column_type: column_type.to_string(),
+                    is_nullable: *is_nullable,
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn mock_basic(cols: &[&str], id_cols: &[&str]) -> RdbTbMeta {
+        RdbTbMeta {
+            schema: "db_1".into(),
+            tb: "tb_1".into(),
+            cols: cols.iter().map(|c| c.to_string()).collect(),
+            id_cols: id_cols.iter().map(|c| c.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn mock_pg_col_type(value_type: PgValueType, name: &str) -> PgColType {
+        PgColType {
+            value_type,
+            schema: "pg_catalog".into(),
+            name: name.into(),
+            alias: name.into(),
+            oid: 0,
+            parent_oid: 0,
+            element_oid: 0,
+            category: String::new(),
+            enum_values: None,
+        }
+    }
+
+    fn mock_sinker() -> DuckDbStructSinker {
+        DuckDbStructSinker {
+            conn: Connection::open_in_memory().unwrap(),
+            conflict_policy: ConflictPolicyEnum::Interrupt,
+            extractor_meta_manager: RdbMetaManager {
+                mysql_meta_manager: None,
+                pg_meta_manager: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_get_dst_decimal_type_from_pg() {
+        let cases = [
+            ("numeric(10,2)", "DECIMAL(10,2)"),
+            ("numeric(5)", "DECIMAL(5,0)"),
+            ("numeric(38,38)", "DECIMAL(38,38)"),
+            ("numeric(39,2)", "VARCHAR"),
+            ("numeric(5,-2)", "VARCHAR"),
+            ("numeric(2,3)", "VARCHAR"),
+            ("numeric", "VARCHAR"),
+            ("numeric(10,2)[]", "VARCHAR"),
+        ];
+        for (column_type, expected) in cases {
+            assert_eq!(
+                DuckDbStructSinker::get_dst_decimal_type_from_pg(column_type),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_create_table_from_pg() {
+        let table = mock_table(&[
+            ("id", "integer", false),
+            ("price", "numeric(10,2)", true),
+            ("amount", "numeric", true),
+            ("created_at", "timestamp with time zone", true),
+        ]);
+        let tb_meta = PgTbMeta {
+            basic: mock_basic(&["id", "price", "amount", "created_at"], &["id"]),
+            oid: 0,
+            col_type_map: HashMap::from([
+                ("id".into(), mock_pg_col_type(PgValueType::Int32, "int4")),
+                (
+                    "price".into(),
+                    mock_pg_col_type(PgValueType::Numeric, "numeric"),
+                ),
+                (
+                    "amount".into(),
+                    mock_pg_col_type(PgValueType::Numeric, "numeric"),
+                ),
+                (
+                    "created_at".into(),
+                    mock_pg_col_type(PgValueType::TimestampTZ, "timestamptz"),
+                ),
+            ]),
+        };
+
+        let sql = DuckDbStructSinker::get_create_table_sql(&table, None, Some(&tb_meta)).unwrap();
+        assert_eq!(
+            sql,
+            r#"CREATE TABLE IF NOT EXISTS "db_1"."tb_1" ("id" INTEGER NOT NULL, "price" DECIMAL(10,2), "amount" VARCHAR, "created_at" TIMESTAMPTZ, PRIMARY KEY ("id"))"#
+        );
+
+        // unconstrained numerics keep values beyond DECIMAL(38,9)
+        let sinker = mock_sinker();
+        sinker
+            .execute_sql(&DuckDbStructSinker::get_create_schema_sql("db_1"))
+            .unwrap();
+        sinker.execute_sql(&sql).unwrap();
+        let amount = "12345678901234567890123456789012345678901234567890.0123456789";
+        sinker
+            .conn
+            .execute(
+                r#"INSERT INTO "db_1"."tb_1" VALUES (1, 12345678.99, ?, NULL)"#,
+                [amount],
+            )
+            .unwrap();
+        let (price, stored): (String, String) = sinker
+            .conn
+            .query_row(
+                r#"SELECT CAST(price AS VARCHAR), amount FROM "db_1"."tb_1""#,
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(price, "12345678.99");
+        assert_eq!(stored, amount);
+    }
+
+    #[test]
+    fn test_create_table_from_mysql() {
+        let table = mock_table(&[
+            ("id", "bigint unsigned", false),
+            ("amount", "decimal(10,2)", true),
+            ("big_amount", "decimal(65,30)", true),
+            ("t", "time", true),
+        ]);
+        let tb_meta = MysqlTbMeta {
+            basic: mock_basic(&["id", "amount", "big_amount", "t"], &["id"]),
+            col_type_map: HashMap::from([
+                ("id".into(), MysqlColType::BigInt { unsigned: true }),
+                (
+                    "amount".into(),
+                    MysqlColType::Decimal {
+                        precision: 10,
+                        scale: 2,
+                    },
+                ),
+                (
+                    "big_amount".into(),
+                    MysqlColType::Decimal {
+                        precision: 65,
+                        scale: 30,
+                    },
+                ),
+                ("t".into(), MysqlColType::Time),
+            ]),
+        };
+
+        let sql = DuckDbStructSinker::get_create_table_sql(&table, Some(&tb_meta), None).unwrap();
+        assert_eq!(
+            sql,
+            r#"CREATE TABLE IF NOT EXISTS "db_1"."tb_1" ("id" UBIGINT NOT NULL, "amount" DECIMAL(10,2), "big_amount" VARCHAR, "t" VARCHAR, PRIMARY KEY ("id"))"#
+        );
+
+        let sinker = mock_sinker();
+        sinker
+            .execute_sql(&DuckDbStructSinker::get_create_schema_sql("db_1"))
+            .unwrap();
+        sinker.execute_sql(&sql).unwrap();
+        // created again with conflict_policy=interrupt
+        assert!(sinker.execute_sql(&sql).is_ok());
+    }
+}
@@ -0,0 +1,2 @@
+pub mod duckdb_sinker;
+pub mod duckdb_struct_sinker;
@@ -0,0 +1,184 @@
+use anyhow::bail;
+use dt_common::{error::Error, log_error};
+use reqwest::{header, Client, Method, RequestBuilder, StatusCode};
+use serde_json::Value;
+
+/// Http client for the rest apis of Elasticsearch / OpenSearch.
+#[derive(Clone)]
+pub struct ElasticsearchClient {
+    pub http_client: Client,
+    // scheme://host:port, without credentials
+    pub url: String,
+    pub username: String,
+    pub password: String,
+}
+
+impl ElasticsearchClient {
+    /// Send actions in NDJSON to the _bulk api, fails if any item of the bulk fails.
+    pub async fn bulk(&self, body: String) -> anyhow::Result<()> {
+        let response = self
+            .build_request(Method::POST, "_bulk")
+            .header(header::CONTENT_TYPE, "application/x-ndjson")
+            .body(body)
+            .send()
+            .await?;
+
+        let status_code = response.status();
+        let response_text = response.text().await?;
+        if status_code != StatusCode::OK {
+            bail! {Error::HttpError(format!(
+                "bulk request failed, status_code: {}, response_text: {:?}",
+                status_code, response_text
+            ))}
+        }
+        Self::check_bulk_response(&response_text)
+    }
+
+    pub async fn create_index(&self, index: &str, body: &Value) -> anyhow::Result<()> {
+        let response = self
+            .build_request(Method::PUT, index)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body.to_string())
+            .send()
+            .await?;
+
+        let status_code = response.status();
+        let response_text = response.text().await?;
+        if status_code != StatusCode::OK {
+            bail! {Error::HttpError(format!(
+                "create index failed, index: {}, status_code: {}, response_text: {:?}",
+                index, status_code, response_text
+            ))}
+        }
+        Ok(())
+    }
+
+    fn build_request(&self, method: Method, path: &str) -> RequestBuilder {
+        let url = format!("{}/{}", self.url.trim_end_matches('/'), path);
+        let request = self.http_client.request(method, url);
+        if self.username.is_empty() {
+            return request;
+        }
+
+        let password = if self.password.is_empty() {
+            None
+        } else {
+            Some(self.password.clone())
+        };
+        request.basic_auth(&self.username, password)
+    }
+
+    /// The _bulk api responds 200 even if some items failed, errors are reported per item:
+    /// {"errors":true,"items":[{"index":{"_index":"db.tb","_id":"1","status":400,"error":{...}}}]}
+    fn check_bulk_response(response_text: &str) -> anyhow::Result<()> {
+        let response: Value = serde_json::from_str(response_text)?;
+        if !response["errors"].as_bool().unwrap_or(false) {
+            return Ok(());
+        }
+
+        let mut failed_count = 0;
+        let mut first_error = String::new();
+        if let Some(items) = response["items"].as_array() {
+            for item in items {
+                // each item is {"<action>": {...}}
+                let (action, result) = match item.as_object().and_then(|i| i.iter().next()) {
+                    Some(i) => i,
+                    None => continue,
+                };
+                // deleting a missing document responds 404 without error
+                if let Some(error) = result.get("error") {
+                    failed_count += 1;
+                    log_error!(
+                        "bulk {} failed, index: {}, id: {}, status: {}, error: {}",
+                        action,
+                        result["_index"],
+                        result["_id"],
+                        result["status"],
+                        error
+                    );
+                    if first_error.is_empty() {
+                        first_error = error.to_string();
+                    }
+                }
+            }
+        }
+
+        if failed_count > 0 {
+            bail! {Error::SinkerError(format!(
+                "bulk request failed, failed items: {}, first error: {}",
+                failed_count, first_error
+            ))}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sinker::mock_http_server::MockHttpServer;
+
+    use super::*;
+
+    fn mock_client(url: String) -> ElasticsearchClient {
+        ElasticsearchClient {
+            http_client: Client::new(),
+            url,
+            username: "elastic".into(),
+            password: "123456".into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bulk() {
+        let body = "{\"index\":{\"_index\":\"db_1.tb_1\",\"_id\":\"1\"}}\n{\"id\":1}\n";
+
+        let response =
+            MockHttpServer::response("200 OK", &[], r#"{"took":1,"errors":false,"items":[]}"#);
+        let (url, handle) = MockHttpServer::start(vec![response]).await;
+        mock_client(url).bulk(body.into()).await.unwrap();
+        let request = &handle.await.unwrap()[0];
+        assert!(request.starts_with("POST /_bulk HTTP/1.1"));
+        assert!(request
+            .to_lowercase()
+            .contains("content-type: application/x-ndjson"));
+        // elastic:123456
+        assert!(request.contains("Basic ZWxhc3RpYzoxMjM0NTY="));
+        assert!(request.ends_with(body));
+
+        // per-item errors
+        let response = MockHttpServer::response(
+            "200 OK",
+            &[],
+            r#"{"took":1,"errors":true,"items":[{"index":{"_index":"db_1.tb_1","_id":"1","status":400,"error":{"type":"mapper_parsing_exception","reason":"failed to parse field [id]"}}},{"delete":{"_index":"db_1.tb_1","_id":"2","status":404,"result":"not_found"}}]}"#,
+        );
+        let (url, handle) = MockHttpServer::start(vec![response]).await;
+        let err = mock_client(url).bulk(body.into()).await.unwrap_err();
+        handle.await.unwrap();
+        assert!(err.to_string().contains("failed items: 1"));
+        assert!(err.to_string().contains("mapper_parsing_exception"));
+
+        // request errors
+        let response = MockHttpServer::response(
+            "401 Unauthorized",
+            &[],
+            r#"{"error":{"type":"security_exception"},"status":401}"#,
+        );
+        let (url, handle) = MockHttpServer::start(vec![response]).await;
+        assert!(mock_client(url).bulk(body.into()).await.is_err());
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_create_index() {
+        let response = MockHttpServer::response("200 OK", &[], r#"{"acknowledged":true}"#);
+        let (url, handle) = MockHttpServer::start(vec![response]).await;
+        let body = serde_json::json!({"mappings": {"properties": {"id": {"type": "integer"}}}});
+        mock_client(url)
+            .create_index("db_1.tb_1", &body)
+            .await
+            .unwrap();
+        let request = &handle.await.unwrap()[0];
+        assert!(request.starts_with("PUT /db_1.tb_1 HTTP/1.1"));
+        assert!(request.ends_with(&body.to_string()));
+    }
+}
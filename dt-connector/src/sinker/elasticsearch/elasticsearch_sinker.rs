@@ -0,0 +1,457 @@
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use anyhow::bail;
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{DateTime, NaiveDateTime, SecondsFormat, Utc};
+use dt_common::{
+    error::Error,
+    meta::{
+        col_value::ColValue, ddl_meta::ddl_data::DdlData, mongo::mongo_constant::MongoConstants,
+        rdb_meta_manager::RdbMetaManager, row_data::RowData, row_type::RowType,
+    },
+    monitor::monitor::Monitor,
+    utils::sql_util::SqlUtil,
+};
+use mongodb::bson::{Bson, Document};
+use serde_json::{json, Map, Value};
+
+use super::elasticsearch_client::ElasticsearchClient;
+use crate::{call_batch_fn, rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Sinker};
+
+#[derive(Clone)]
+pub struct ElasticsearchSinker {
+    pub client: ElasticsearchClient,
+    pub batch_size: usize,
+    pub partial_update: bool,
+    // None if the source is mongo, whose documents are identified by _id
+    pub meta_manager: Option<RdbMetaManager>,
+    // rows are routed by the extractor, source table names are needed to get id cols
+    pub reverse_router: RdbRouter,
+    pub monitor: Arc<Mutex<Monitor>>,
+}
+
+#[async_trait]
+impl Sinker for ElasticsearchSinker {
+    async fn sink_dml(&mut self, mut data: Vec<RowData>, _batch: bool) -> anyhow::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        // actions of a bulk are applied in order, so rows of any type can be sent together
+        call_batch_fn!(self, data, Self::batch_sink);
+        Ok(())
+    }
+
+    async fn close(&mut self) -> anyhow::Result<()> {
+        if let Some(meta_manager) = &self.meta_manager {
+            meta_manager.close().await?;
+        }
+        Ok(())
+    }
+
+    async fn refresh_meta(&mut self, data: Vec<DdlData>) -> anyhow::Result<()> {
+        if let Some(meta_manager) = self.meta_manager.as_mut() {
+            for ddl_data in data.iter() {
+                meta_manager.invalidate_cache_by_ddl_data(ddl_data);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ElasticsearchSinker {
+    async fn batch_sink(
+        &mut self,
+        data: &mut [RowData],
+        start_index: usize,
+        batch_size: usize,
+    ) -> anyhow::Result<()> {
+        let start_time = Instant::now();
+        let mut data_size = 0;
+
+        let mut body = String::new();
+        for row_data in data.iter().skip(start_index).take(batch_size) {
+            data_size += row_data.data_size;
+            let id_cols = self.get_id_cols(row_data).await?;
+            Self::append_actions(row_data, &id_cols, self.partial_update, &mut body)?;
+        }
+        self.client.bulk(body).await?;
+
+        BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, data_size, start_time)
+    }
+
+    async fn get_id_cols(&mut self, row_data: &RowData) -> anyhow::Result<Vec<String>> {
+        if let Some(meta_manager) = self.meta_manager.as_mut() {
+            let (schema, tb) = self
+                .reverse_router
+                .get_tb_map(&row_data.schema, &row_data.tb);
+            let tb_meta = meta_manager.get_tb_meta(schema, tb).await?;
+            return Ok(tb_meta.id_cols.clone());
+        }
+        Ok(Vec::new())
+    }
+
+    /// Index name of the routed table, index names must be lowercase.
+    pub fn get_index(schema: &str, tb: &str) -> String {
+        if schema.is_empty() {
+            return tb.to_lowercase();
+        }
+        format!("{}.{}", schema, tb).to_lowercase()
+    }
+
+    fn append_actions(
+        row_data: &RowData,
+        id_cols: &[String],
+        partial_update: bool,
+        body: &mut String,
+    ) -> anyhow::Result<()> {
+        let index = Self::get_index(&row_data.schema, &row_data.tb);
+        match row_data.row_type {
+            RowType::Insert => {
+                let after = row_data.after.as_ref().unwrap();
+                let id = Self::get_doc_id(after, id_cols)?;
+                Self::append_action(body, "index", &index, &id, Some(Self::build_doc(after)));
+            }
+
+            RowType::Delete => {
+                let before = row_data.before.as_ref().unwrap();
+                let id = Self::get_doc_id(before, id_cols)?;
+                Self::append_action(body, "delete", &index, &id, None);
+            }
+
+            RowType::Update => {
+                let before = row_data.before.as_ref().unwrap();
+                let after = row_data.after.as_ref().unwrap();
+                let before_id = Self::get_doc_id(before, id_cols)?;
+
+                // for Update row_data from oplog (NOT change stream), after contains diff_doc instead of doc
+                if let Some(ColValue::MongoDoc(diff_doc)) = after.get(MongoConstants::DIFF_DOC) {
+                    let source =
+                        json!({"doc": Self::build_diff_doc(diff_doc), "doc_as_upsert": true});
+                    Self::append_action(body, "update", &index, &before_id, Some(source));
+                    return Ok(());
+                }
+
+                let id = Self::get_doc_id(after, id_cols)?;
+                if id != before_id {
+                    Self::append_action(body, "delete", &index, &before_id, None);
+                }
+
+                let doc = Self::build_doc(after);
+                if partial_update {
+                    // merge into the existing document, fields not from the source are kept
+                    let source = json!({"doc": doc, "doc_as_upsert": true});
+                    Self::append_action(body, "update", &index, &id, Some(source));
+                } else {
+                    Self::append_action(body, "index", &index, &id, Some(doc));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn append_action(
+        body: &mut String,
+        action: &str,
+        index: &str,
+        id: &str,
+        source: Option<Value>,
+    ) {
+        let mut meta = Map::new();
+        meta.insert(action.into(), json!({"_index": index, "_id": id}));
+        body.push_str(&Value::Object(meta).to_string());
+        body.push('\n');
+        if let Some(source) = source {
+            body.push_str(&source.to_string());
+            body.push('\n');
+        }
+    }
+
+    /// Mongo documents are identified by _id, rows by the value of the single id col,
+    /// or by a json array of values if there are multiple id cols, e.g. [1,"a"],
+    /// so that neither a delimiter in values nor NULL makes different rows share an id.
+    fn get_doc_id(
+        col_values: &HashMap<String, ColValue>,
+        id_cols: &[String],
+    ) -> anyhow::Result<String> {
+        if let Some(ColValue::MongoDoc(doc)) = col_values.get(MongoConstants::DOC) {
+            let id = match doc.get(MongoConstants::ID) {
+                Some(Bson::ObjectId(v)) => v.to_hex(),
+                Some(Bson::String(v)) => v.to_owned(),
+                Some(v) => v.to_string(),
+                None => bail! {Error::SinkerError("mongo document has no _id".into())},
+            };
+            return Ok(id);
+        }
+
+        match id_cols {
+            [] => bail! {Error::SinkerError("no id cols to build document id".into())},
+            [col] => match col_values.get(col).and_then(|v| v.to_option_string()) {
+                Some(id) => Ok(id),
+                None => bail! {Error::SinkerError(format!(
+                    "id col: {} is NULL, can not build document id",
+                    col
+                ))},
+            },
+            _ => {
+                let values = id_cols
+                    .iter()
+                    .map(|col| match col_values.get(col) {
+                        Some(col_value) => Self::col_value_to_json(col_value),
+                        None => Value::Null,
+                    })
+                    .collect();
+                Ok(Value::Array(values).to_string())
+            }
+        }
+    }
+
+    fn build_doc(col_values: &HashMap<String, ColValue>) -> Value {
+        if let Some(ColValue::MongoDoc(doc)) = col_values.get(MongoConstants::DOC) {
+            return Self::mongo_doc_to_json(doc);
+        }
+
+        let mut doc = Map::new();
+        for (col, col_value) in col_values.iter() {
+            doc.insert(col.to_owned(), Self::col_value_to_json(col_value));
+        }
+        Value::Object(doc)
+    }
+
+    /// {"$set": {"a": 1}, "$unset": {"b": true}} -> {"a": 1, "b": null}
+    fn build_diff_doc(diff_doc: &Document) -> Value {
+        let mut doc = Map::new();
+        if let Some(Bson::Document(set)) = diff_doc.get(MongoConstants::SET) {
+            if let Value::Object(fields) = Self::mongo_doc_to_json(set) {
+                doc.extend(fields);
+            }
+        }
+        if let Some(Bson::Document(unset)) = diff_doc.get(MongoConstants::UNSET) {
+            for key in unset.keys() {
+                doc.insert(key.to_owned(), Value::Null);
+            }
+        }
+        Value::Object(doc)
+    }
+
+    fn mongo_doc_to_json(doc: &Document) -> Value {
+        let mut value = Bson::Document(doc.clone()).into_relaxed_extjson();
+        // _id is a metadata field which can not be in the document source
+        if let Value::Object(fields) = &mut value {
+            fields.remove(MongoConstants::ID);
+        }
+        value
+    }
+
+    fn col_value_to_json(col_value: &ColValue) -> Value {
+        match col_value {
+            // binary fields are base64 encoded
+            ColValue::Blob(v) => Value::String(STANDARD.encode(v)),
+            ColValue::RawString(v) => Value::String(SqlUtil::binary_to_str(v).0),
+            ColValue::Json(v) => serde_json::from_slice(v)
+                .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(v).to_string())),
+            ColValue::Json2(v) => {
+                Value::from_str(v).unwrap_or_else(|_| Value::String(v.to_owned()))
+            }
+            ColValue::Timestamp(v) => Value::String(Self::timestamp_to_rfc3339(v)),
+            _ => json!(col_value),
+        }
+    }
+
+    /// Timestamps are converted to UTC in RFC 3339, which is accepted by date fields:
+    /// postgres: 2019-02-10 19:35:00.5+08 -> 2019-02-10T11:35:00.500Z
+    /// mysql (already in UTC): 2019-02-10 11:35:00 -> 2019-02-10T11:35:00Z
+    /// values that can not be parsed (e.g. postgres infinity) are kept as they are.
+    fn timestamp_to_rfc3339(v: &str) -> String {
+        let datetime = if let Ok(dt) = DateTime::parse_from_str(v, "%Y-%m-%d %H:%M:%S%.f%#z") {
+            dt.with_timezone(&Utc)
+        } else if let Ok(dt) = NaiveDateTime::parse_from_str(v, "%Y-%m-%d %H:%M:%S%.f") {
+            dt.and_utc()
+        } else {
+            return v.to_string();
+        };
+        datetime.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::{doc, oid::ObjectId};
+
+    use super::*;
+
+    fn mock_row_data(
+        row_type: RowType,
+        before: Option<Vec<(&str, ColValue)>>,
+        after: Option<Vec<(&str, ColValue)>>,
+    ) -> RowData {
+        let to_map = |values: Vec<(&str, ColValue)>| {
+            values
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect::<HashMap<String, ColValue>>()
+        };
+        RowData::new(
+            "DB_1".into(),
+            "tb_1".into(),
+            row_type,
+            before.map(to_map),
+            after.map(to_map),
+        )
+    }
+
+    fn get_lines(row_data: &RowData, partial_update: bool) -> Vec<Value> {
+        let id_cols = vec!["id".to_string(), "k".to_string()];
+        let mut body = String::new();
+        ElasticsearchSinker::append_actions(row_data, &id_cols, partial_update, &mut body).unwrap();
+        assert!(body.ends_with('\n'));
+        body.lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_append_actions() {
+        let insert = mock_row_data(
+            RowType::Insert,
+            None,
+            Some(vec![
+                ("id", ColValue::Long(1)),
+                ("k", ColValue::String("a".into())),
+                ("data", ColValue::Blob(vec![1, 2, 3])),
+                ("info", ColValue::Json2("{\"x\":1}".into())),
+                ("v", ColValue::None),
+            ]),
+        );
+        assert_eq!(
+            get_lines(&insert, false),
+            vec![
+                json!({"index": {"_index": "db_1.tb_1", "_id": "[1,\"a\"]"}}),
+                json!({"id": 1, "k": "a", "data": "AQID", "info": {"x": 1}, "v": null}),
+            ]
+        );
+
+        let delete = mock_row_data(
+            RowType::Delete,
+            Some(vec![("id", ColValue::Long(1)), ("k", ColValue::None)]),
+            None,
+        );
+        assert_eq!(
+            get_lines(&delete, false),
+            vec![json!({"delete": {"_index": "db_1.tb_1", "_id": "[1,null]"}})]
+        );
+
+        // id changed
+        let update = mock_row_data(
+            RowType::Update,
+            Some(vec![
+                ("id", ColValue::Long(1)),
+                ("k", ColValue::String("a".into())),
+            ]),
+            Some(vec![
+                ("id", ColValue::Long(2)),
+                ("k", ColValue::String("a".into())),
+            ]),
+        );
+        assert_eq!(
+            get_lines(&update, false),
+            vec![
+                json!({"delete": {"_index": "db_1.tb_1", "_id": "[1,\"a\"]"}}),
+                json!({"index": {"_index": "db_1.tb_1", "_id": "[2,\"a\"]"}}),
+                json!({"id": 2, "k": "a"}),
+            ]
+        );
+        assert_eq!(
+            get_lines(&update, true),
+            vec![
+                json!({"delete": {"_index": "db_1.tb_1", "_id": "[1,\"a\"]"}}),
+                json!({"update": {"_index": "db_1.tb_1", "_id": "[2,\"a\"]"}}),
+                json!({"doc": {"id": 2, "k": "a"}, "doc_as_upsert": true}),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_doc_id() {
+        let col_values: HashMap<String, ColValue> = [
+            ("id".to_string(), ColValue::Long(1)),
+            ("k".to_string(), ColValue::String("a_b".into())),
+            ("v".to_string(), ColValue::String("".into())),
+            ("n".to_string(), ColValue::None),
+        ]
+        .into_iter()
+        .collect();
+        let get_id = |id_cols: &[&str]| {
+            let id_cols: Vec<String> = id_cols.iter().map(|c| c.to_string()).collect();
+            ElasticsearchSinker::get_doc_id(&col_values, &id_cols)
+        };
+
+        assert_eq!(get_id(&["id"]).unwrap(), "1");
+        assert_eq!(get_id(&["k"]).unwrap(), "a_b");
+        assert!(get_id(&["n"]).is_err());
+        assert!(get_id(&[]).is_err());
+        // no collision between values containing the delimiter, empty strings and NULL
+        assert_eq!(get_id(&["id", "k"]).unwrap(), r#"[1,"a_b"]"#);
+        assert_eq!(get_id(&["id", "v"]).unwrap(), r#"[1,""]"#);
+        assert_eq!(get_id(&["id", "n"]).unwrap(), "[1,null]");
+    }
+
+    #[test]
+    fn test_timestamp_to_rfc3339() {
+        let cases = [
+            ("2019-02-10 11:35:00+00", "2019-02-10T11:35:00Z"),
+            ("2019-02-10 19:35:00.5+08", "2019-02-10T11:35:00.500Z"),
+            ("2019-02-10 06:05:00+05:30", "2019-02-10T00:35:00Z"),
+            ("2019-02-10 11:35:00.123456", "2019-02-10T11:35:00.123456Z"),
+            ("infinity", "infinity"),
+        ];
+        for (v, expected) in cases {
+            assert_eq!(ElasticsearchSinker::timestamp_to_rfc3339(v), expected);
+        }
+    }
+
+    #[test]
+    fn test_append_mongo_actions() {
+        let oid = ObjectId::parse_str("65a1b2c3d4e5f60718293a4b").unwrap();
+        let insert = mock_row_data(
+            RowType::Insert,
+            None,
+            Some(vec![(
+                MongoConstants::DOC,
+                ColValue::MongoDoc(doc! {"_id": oid, "name": "a", "n": 1}),
+            )]),
+        );
+        assert_eq!(
+            get_lines(&insert, false),
+            vec![
+                json!({"index": {"_index": "db_1.tb_1", "_id": "65a1b2c3d4e5f60718293a4b"}}),
+                json!({"name": "a", "n": 1}),
+            ]
+        );
+
+        let update = mock_row_data(
+            RowType::Update,
+            Some(vec![(
+                MongoConstants::DOC,
+                ColValue::MongoDoc(doc! {"_id": "k1"}),
+            )]),
+            Some(vec![(
+                MongoConstants::DIFF_DOC,
+                ColValue::MongoDoc(doc! {"$set": {"name": "b"}, "$unset": {"n": true}}),
+            )]),
+        );
+        assert_eq!(
+            get_lines(&update, false),
+            vec![
+                json!({"update": {"_index": "db_1.tb_1", "_id": "k1"}}),
+                json!({"doc": {"name": "b", "n": null}, "doc_as_upsert": true}),
+            ]
+        );
+    }
+}
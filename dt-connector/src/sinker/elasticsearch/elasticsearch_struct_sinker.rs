@@ -0,0 +1,212 @@
+use anyhow::bail;
+use async_trait::async_trait;
+use dt_common::{
+    config::config_enums::ConflictPolicyEnum,
+    log_error, log_info,
+    meta::{
+        mysql::{mysql_col_type::MysqlColType, mysql_tb_meta::MysqlTbMeta},
+        pg::{pg_tb_meta::PgTbMeta, pg_value_type::PgValueType},
+        rdb_meta_manager::RdbMetaManager,
+        rdb_tb_meta::RdbTbMeta,
+        struct_meta::{statement::struct_statement::StructStatement, struct_data::StructData},
+    },
+};
+use serde_json::{json, Map, Value};
+
+use super::{elasticsearch_client::ElasticsearchClient, elasticsearch_sinker::ElasticsearchSinker};
+use crate::{rdb_router::RdbRouter, Sinker};
+
+// values of datetime / timestamp columns, with 0 ~ 6 fractional digits
+const DATETIME_FORMAT: &str = "yyyy-MM-dd HH:mm:ss||yyyy-MM-dd HH:mm:ss.S||yyyy-MM-dd HH:mm:ss.SS||yyyy-MM-dd HH:mm:ss.SSS||yyyy-MM-dd HH:mm:ss.SSSS||yyyy-MM-dd HH:mm:ss.SSSSS||yyyy-MM-dd HH:mm:ss.SSSSSS||strict_date_optional_time";
+const DATE_FORMAT: &str = "yyyy-MM-dd";
+
+#[derive(Clone)]
+pub struct ElasticsearchStructSinker {
+    pub client: ElasticsearchClient,
+    pub conflict_policy: ConflictPolicyEnum,
+    pub router: RdbRouter,
+    // struct statements are routed by the extractor, source table names are needed to get meta
+    pub reverse_router: RdbRouter,
+    pub extractor_meta_manager: RdbMetaManager,
+}
+
+#[async_trait]
+impl Sinker for ElasticsearchStructSinker {
+    async fn sink_struct(&mut self, data: Vec<StructData>) -> anyhow::Result<()> {
+        for i in data {
+            match i.statement {
+                StructStatement::MysqlCreateTable(statement) => {
+                    let (schema, tb) = self
+                        .reverse_router
+                        .get_tb_map(&statement.table.database_name, &statement.table.table_name);
+                    if let Some(meta_manager) =
+                        self.extractor_meta_manager.mysql_meta_manager.as_mut()
+                    {
+                        let tb_meta = meta_manager.get_tb_meta(schema, tb).await?.to_owned();
+                        let properties = self.get_mysql_properties(&tb_meta)?;
+                        let index = ElasticsearchSinker::get_index(
+                            &statement.table.database_name,
+                            &statement.table.table_name,
+                        );
+                        self.create_index(&index, properties).await?;
+                    }
+                }
+
+                StructStatement::PgCreateTable(statement) => {
+                    let (schema, tb) = self
+                        .reverse_router
+                        .get_tb_map(&statement.table.schema_name, &statement.table.table_name);
+                    if let Some(meta_manager) = self.extractor_meta_manager.pg_meta_manager.as_mut()
+                    {
+                        let tb_meta = meta_manager.get_tb_meta(schema, tb).await?.to_owned();
+                        let properties = self.get_pg_properties(&tb_meta)?;
+                        let index = ElasticsearchSinker::get_index(
+                            &statement.table.schema_name,
+                            &statement.table.table_name,
+                        );
+                        self.create_index(&index, properties).await?;
+                    }
+                }
+
+                // databases / schemas have no counterpart, indexes are created by tables
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ElasticsearchStructSinker {
+    fn get_mysql_properties(&self, tb_meta: &MysqlTbMeta) -> anyhow::Result<Map<String, Value>> {
+        let mut properties = Map::new();
+        for col in tb_meta.basic.cols.iter() {
+            let field_type = Self::get_field_type_from_mysql(tb_meta.get_col_type(col)?);
+            properties.insert(self.get_field_name(&tb_meta.basic, col), field_type);
+        }
+        Ok(properties)
+    }
+
+    fn get_pg_properties(&self, tb_meta: &PgTbMeta) -> anyhow::Result<Map<String, Value>> {
+        let mut properties = Map::new();
+        for col in tb_meta.basic.cols.iter() {
+            let col_type = tb_meta.get_col_type(col)?;
+            // array values are in the text form of postgres, like: {1,2}
+            let field_type = if col_type.is_array() {
+                json!({"type": "keyword"})
+            } else {
+                Self::get_field_type_from_pg(&col_type.value_type)
+            };
+            properties.insert(self.get_field_name(&tb_meta.basic, col), field_type);
+        }
+        Ok(properties)
+    }
+
+    fn get_field_name(&self, tb_meta: &RdbTbMeta, col: &str) -> String {
+        if let Some(col_map) = self.router.get_col_map(&tb_meta.schema, &tb_meta.tb) {
+            if let Some(dst_col) = col_map.get(col) {
+                return dst_col.to_owned();
+            }
+        }
+        col.to_string()
+    }
+
+    fn get_field_type_from_mysql(col_type: &MysqlColType) -> Value {
+        match col_type {
+            MysqlColType::TinyInt { unsigned: false } => json!({"type": "byte"}),
+            MysqlColType::TinyInt { unsigned: true }
+            | MysqlColType::SmallInt { unsigned: false }
+            | MysqlColType::Year => json!({"type": "short"}),
+            MysqlColType::SmallInt { unsigned: true }
+            | MysqlColType::MediumInt { .. }
+            | MysqlColType::Int { unsigned: false } => json!({"type": "integer"}),
+            MysqlColType::Int { unsigned: true } | MysqlColType::BigInt { unsigned: false } => {
+                json!({"type": "long"})
+            }
+            MysqlColType::BigInt { unsigned: true } | MysqlColType::Bit => {
+                json!({"type": "unsigned_long"})
+            }
+
+            MysqlColType::Float => json!({"type": "float"}),
+            MysqlColType::Double | MysqlColType::Decimal { .. } => json!({"type": "double"}),
+
+            MysqlColType::Date => json!({"type": "date", "format": DATE_FORMAT}),
+            MysqlColType::DateTime | MysqlColType::Timestamp { .. } => {
+                json!({"type": "date", "format": DATETIME_FORMAT})
+            }
+
+            MysqlColType::Char { .. }
+            | MysqlColType::Varchar { .. }
+            | MysqlColType::TinyText { .. }
+            | MysqlColType::MediumText { .. }
+            | MysqlColType::Text { .. }
+            | MysqlColType::LongText { .. } => Self::get_text_field_type(),
+
+            MysqlColType::Binary { .. }
+            | MysqlColType::VarBinary { .. }
+            | MysqlColType::TinyBlob
+            | MysqlColType::MediumBlob
+            | MysqlColType::Blob
+            | MysqlColType::LongBlob => json!({"type": "binary"}),
+
+            MysqlColType::Json => Self::get_json_field_type(),
+
+            MysqlColType::Time
+            | MysqlColType::Set { .. }
+            | MysqlColType::Enum { .. }
+            | MysqlColType::Unknown => json!({"type": "keyword"}),
+        }
+    }
+
+    fn get_field_type_from_pg(value_type: &PgValueType) -> Value {
+        match value_type {
+            PgValueType::Boolean => json!({"type": "boolean"}),
+            PgValueType::Int16 => json!({"type": "short"}),
+            PgValueType::Int32 => json!({"type": "integer"}),
+            PgValueType::Int64 => json!({"type": "long"}),
+            PgValueType::Float32 => json!({"type": "float"}),
+            PgValueType::Float64 | PgValueType::Numeric => json!({"type": "double"}),
+            PgValueType::Char | PgValueType::String => Self::get_text_field_type(),
+            PgValueType::JSON => Self::get_json_field_type(),
+            PgValueType::Date => json!({"type": "date", "format": DATE_FORMAT}),
+            PgValueType::Timestamp | PgValueType::TimestampTZ => {
+                json!({"type": "date", "format": DATETIME_FORMAT})
+            }
+            PgValueType::Bytes => json!({"type": "binary"}),
+            _ => json!({"type": "keyword"}),
+        }
+    }
+
+    /// Same as dynamic mapping of strings: full-text searchable, exact match by the keyword sub-field.
+    fn get_text_field_type() -> Value {
+        json!({"type": "text", "fields": {"keyword": {"type": "keyword", "ignore_above": 256}}})
+    }
+
+    /// Json values may be objects, arrays or scalars, keep them in _source without indexing.
+    fn get_json_field_type() -> Value {
+        json!({"type": "object", "enabled": false})
+    }
+
+    async fn create_index(
+        &self,
+        index: &str,
+        properties: Map<String, Value>,
+    ) -> anyhow::Result<()> {
+        let body = json!({"mappings": {"properties": properties}});
+        log_info!("create index begin: {}, body: {}", index, body);
+        match self.client.create_index(index, &body).await {
+            Ok(()) => {
+                log_info!("create index succeed");
+            }
+
+            Err(error) => {
+                log_error!("create index failed, error: {}", error);
+                match self.conflict_policy {
+                    ConflictPolicyEnum::Interrupt => bail! {error},
+                    ConflictPolicyEnum::Ignore => {}
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,3 @@
+pub mod elasticsearch_client;
+pub mod elasticsearch_sinker;
+pub mod elasticsearch_struct_sinker;
@@ -0,0 +1,133 @@
+use std::{
+    collections::HashMap,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::Path,
+};
+
+use dt_common::meta::position::Position;
+use serde::{Deserialize, Serialize};
+
+/// A completed file, appended to manifest.jsonl in the root directory when the file is closed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileManifestEntry {
+    // relative to the root directory, like: db_1/tb_1/part-0.csv
+    pub file: String,
+    pub schema: String,
+    pub tb: String,
+    pub row_count: usize,
+    pub file_bytes: u64,
+    // positions of the first and the last rows in the file
+    pub start_position: Position,
+    pub end_position: Position,
+}
+
+/// Shared by all file sinkers of a task, allocates part ids and records completed files.
+pub struct FileManifest {
+    pub root_dir: String,
+    // table dir -> next part id
+    part_ids: HashMap<String, u64>,
+}
+
+impl FileManifest {
+    pub const FILE_NAME: &'static str = "manifest.jsonl";
+
+    pub fn new(root_dir: &str) -> Self {
+        Self {
+            root_dir: root_dir.to_string(),
+            part_ids: HashMap::new(),
+        }
+    }
+
+    pub fn next_part_id(&mut self, dir: &str) -> anyhow::Result<u64> {
+        if !self.part_ids.contains_key(dir) {
+            // continue after existing files, which may be written before the task restarted
+            let path = Path::new(&self.root_dir).join(dir);
+            fs::create_dir_all(&path)?;
+            let mut next_part_id = 0;
+            for entry in fs::read_dir(&path)? {
+                let file_name = entry?.file_name().to_string_lossy().to_string();
+                if let Some(part_id) = Self::parse_part_id(&file_name) {
+                    next_part_id = next_part_id.max(part_id + 1);
+                }
+            }
+            self.part_ids.insert(dir.to_string(), next_part_id);
+        }
+
+        let next_part_id = self.part_ids.get_mut(dir).unwrap();
+        let part_id = *next_part_id;
+        *next_part_id += 1;
+        Ok(part_id)
+    }
+
+    pub fn append(&mut self, entry: &FileManifestEntry) -> anyhow::Result<()> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(Path::new(&self.root_dir).join(Self::FILE_NAME))?;
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        file.sync_all()?;
+        Ok(())
+    }
+
+    pub fn load(root_dir: &str) -> anyhow::Result<Vec<FileManifestEntry>> {
+        let path = Path::new(root_dir).join(Self::FILE_NAME);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut entries = Vec::new();
+        for line in fs::read_to_string(path)?.lines() {
+            if !line.trim().is_empty() {
+                entries.push(serde_json::from_str(line)?);
+            }
+        }
+        Ok(entries)
+    }
+
+    // part-12.csv.gz -> 12
+    pub fn parse_part_id(file_name: &str) -> Option<u64> {
+        file_name
+            .strip_prefix("part-")?
+            .split('.')
+            .next()?
+            .parse()
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_part_id() {
+        let root_dir =
+            std::env::temp_dir().join(format!("ape_dts_manifest_{}", std::process::id()));
+        let root_dir = root_dir.to_str().unwrap();
+        fs::create_dir_all(format!("{}/db_1/tb_1", root_dir)).unwrap();
+        fs::write(format!("{}/db_1/tb_1/part-3.csv.gz", root_dir), "").unwrap();
+        fs::write(format!("{}/db_1/tb_1/other.csv", root_dir), "").unwrap();
+
+        let mut manifest = FileManifest::new(root_dir);
+        assert_eq!(manifest.next_part_id("db_1/tb_1").unwrap(), 4);
+        assert_eq!(manifest.next_part_id("db_1/tb_1").unwrap(), 5);
+        assert_eq!(manifest.next_part_id("db_1/tb_2").unwrap(), 0);
+
+        let entry = FileManifestEntry {
+            file: "db_1/tb_1/part-4.csv.gz".into(),
+            schema: "db_1".into(),
+            tb: "tb_1".into(),
+            row_count: 2,
+            file_bytes: 100,
+            start_position: Position::None,
+            end_position: Position::PgCdc {
+                lsn: "0/16B3748".into(),
+                timestamp: String::new(),
+            },
+        };
+        manifest.append(&entry).unwrap();
+        assert_eq!(FileManifest::load(root_dir).unwrap(), vec![entry]);
+        fs::remove_dir_all(root_dir).unwrap();
+    }
+}
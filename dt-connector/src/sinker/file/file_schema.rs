@@ -0,0 +1,298 @@
+use std::collections::HashMap;
+
+use anyhow::bail;
+use dt_common::{
+    error::Error,
+    meta::{
+        col_value::ColValue,
+        mongo::mongo_constant::MongoConstants,
+        mysql::{mysql_col_type::MysqlColType, mysql_tb_meta::MysqlTbMeta},
+        pg::{pg_col_type::PgColType, pg_tb_meta::PgTbMeta, pg_value_type::PgValueType},
+    },
+};
+use mongodb::bson::Bson;
+use serde_json::Value;
+
+/// Value kinds of file columns, decide the physical types of parquet columns.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FileColKind {
+    Bool,
+    Int32,
+    Int64,
+    UInt64,
+    Float,
+    Double,
+    String,
+    Json,
+    Bytes,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileCol {
+    pub name: String,
+    pub kind: FileColKind,
+}
+
+pub struct FileSchemaUtil {}
+
+impl FileSchemaUtil {
+    /// Column names are the names after col_map since rows are routed by the extractor.
+    pub fn from_mysql(
+        tb_meta: &MysqlTbMeta,
+        col_map: Option<&HashMap<String, String>>,
+    ) -> anyhow::Result<Vec<FileCol>> {
+        let mut cols = Vec::new();
+        for col in tb_meta.basic.cols.iter() {
+            cols.push(FileCol {
+                name: Self::get_col_name(col, col_map),
+                kind: Self::get_kind_from_mysql(tb_meta.get_col_type(col)?),
+            });
+        }
+        Ok(cols)
+    }
+
+    pub fn from_pg(
+        tb_meta: &PgTbMeta,
+        col_map: Option<&HashMap<String, String>>,
+    ) -> anyhow::Result<Vec<FileCol>> {
+        let mut cols = Vec::new();
+        for col in tb_meta.basic.cols.iter() {
+            cols.push(FileCol {
+                name: Self::get_col_name(col, col_map),
+                kind: Self::get_kind_from_pg(tb_meta.get_col_type(col)?),
+            });
+        }
+        Ok(cols)
+    }
+
+    /// Mongo rows have no meta, documents are kept in doc / diff_doc.
+    pub fn from_mongo() -> Vec<FileCol> {
+        vec![
+            FileCol {
+                name: MongoConstants::DOC.to_string(),
+                kind: FileColKind::Json,
+            },
+            FileCol {
+                name: MongoConstants::DIFF_DOC.to_string(),
+                kind: FileColKind::Json,
+            },
+        ]
+    }
+
+    /// Key cols of the table after col_map, all cols if the table has no primary / unique key.
+    pub fn get_id_cols(
+        id_cols: &[String],
+        col_map: Option<&HashMap<String, String>>,
+    ) -> Vec<String> {
+        id_cols
+            .iter()
+            .map(|col| Self::get_col_name(col, col_map))
+            .collect()
+    }
+
+    fn get_col_name(col: &str, col_map: Option<&HashMap<String, String>>) -> String {
+        if let Some(dst_col) = col_map.and_then(|m| m.get(col)) {
+            return dst_col.to_owned();
+        }
+        col.to_string()
+    }
+
+    fn get_kind_from_mysql(col_type: &MysqlColType) -> FileColKind {
+        match col_type {
+            MysqlColType::TinyInt { .. }
+            | MysqlColType::SmallInt { .. }
+            | MysqlColType::MediumInt { .. }
+            | MysqlColType::Int { unsigned: false }
+            | MysqlColType::Year => FileColKind::Int32,
+            MysqlColType::Int { unsigned: true } | MysqlColType::BigInt { unsigned: false } => {
+                FileColKind::Int64
+            }
+            MysqlColType::BigInt { unsigned: true } | MysqlColType::Bit => FileColKind::UInt64,
+            MysqlColType::Float => FileColKind::Float,
+            MysqlColType::Double => FileColKind::Double,
+            MysqlColType::Binary { .. }
+            | MysqlColType::VarBinary { .. }
+            | MysqlColType::TinyBlob
+            | MysqlColType::MediumBlob
+            | MysqlColType::Blob
+            | MysqlColType::LongBlob => FileColKind::Bytes,
+            MysqlColType::Json => FileColKind::Json,
+            // decimals are kept as strings to avoid losing precision
+            _ => FileColKind::String,
+        }
+    }
+
+    fn get_kind_from_pg(col_type: &PgColType) -> FileColKind {
+        // array values are in the text form of postgres, like: {1,2}
+        if col_type.is_array() {
+            return FileColKind::String;
+        }
+
+        match col_type.value_type {
+            PgValueType::Boolean => FileColKind::Bool,
+            PgValueType::Int16 | PgValueType::Int32 => FileColKind::Int32,
+            PgValueType::Int64 => FileColKind::Int64,
+            PgValueType::Float32 => FileColKind::Float,
+            PgValueType::Float64 => FileColKind::Double,
+            PgValueType::Bytes => FileColKind::Bytes,
+            PgValueType::JSON => FileColKind::Json,
+            _ => FileColKind::String,
+        }
+    }
+}
+
+impl FileCol {
+    /// Text form of values in csv files and string / json columns of parquet files,
+    /// binary values are in the hex format of postgres bytea, like: \x0102.
+    pub fn to_text(col_value: &ColValue) -> Option<String> {
+        match col_value {
+            ColValue::Blob(v) => Some(format!("\\x{}", hex::encode(v))),
+            ColValue::Json(v) => Some(String::from_utf8_lossy(v).to_string()),
+            ColValue::MongoDoc(v) => {
+                Some(Bson::Document(v.clone()).into_relaxed_extjson().to_string())
+            }
+            _ => col_value.to_option_string(),
+        }
+    }
+
+    /// Values in jsonl files.
+    pub fn to_json(col_value: &ColValue) -> Value {
+        match col_value {
+            ColValue::None => Value::Null,
+            ColValue::Json(v) => serde_json::from_slice(v)
+                .unwrap_or_else(|_| Value::String(Self::text_or_empty(col_value))),
+            ColValue::Json2(v) => {
+                serde_json::from_str(v).unwrap_or_else(|_| Value::String(v.to_owned()))
+            }
+            ColValue::Json3(v) => v.clone(),
+            ColValue::MongoDoc(v) => Bson::Document(v.clone()).into_relaxed_extjson(),
+            ColValue::Blob(_) | ColValue::RawString(_) => {
+                Value::String(Self::text_or_empty(col_value))
+            }
+            // NaN and infinity are written as null by serde_json
+            _ => serde_json::to_value(col_value).unwrap_or(Value::Null),
+        }
+    }
+
+    pub fn to_bool(&self, col_value: &ColValue) -> anyhow::Result<bool> {
+        match col_value {
+            ColValue::Bool(v) => Ok(*v),
+            _ => match Self::to_text(col_value).as_deref() {
+                Some("true") | Some("t") | Some("1") => Ok(true),
+                Some("false") | Some("f") | Some("0") => Ok(false),
+                _ => self.unexpected_value(col_value),
+            },
+        }
+    }
+
+    pub fn to_i32(&self, col_value: &ColValue) -> anyhow::Result<i32> {
+        match i32::try_from(self.to_i64(col_value)?) {
+            Ok(v) => Ok(v),
+            Err(_) => self.unexpected_value(col_value),
+        }
+    }
+
+    /// Unsigned bigints are written by their bits, which are read back by the UINT_64 annotation.
+    pub fn to_i64(&self, col_value: &ColValue) -> anyhow::Result<i64> {
+        let v = match col_value {
+            ColValue::Bool(v) => *v as i64,
+            ColValue::Tiny(v) => *v as i64,
+            ColValue::UnsignedTiny(v) => *v as i64,
+            ColValue::Short(v) => *v as i64,
+            ColValue::UnsignedShort(v) => *v as i64,
+            ColValue::Long(v) => *v as i64,
+            ColValue::UnsignedLong(v) => *v as i64,
+            ColValue::LongLong(v) => *v,
+            ColValue::UnsignedLongLong(v) | ColValue::Bit(v) | ColValue::Set(v) => *v as i64,
+            ColValue::Year(v) => *v as i64,
+            ColValue::Enum(v) => *v as i64,
+            _ => match Self::to_text(col_value).map(|s| s.parse::<i64>()) {
+                Some(Ok(v)) => v,
+                _ => return self.unexpected_value(col_value),
+            },
+        };
+        Ok(v)
+    }
+
+    pub fn to_f64(&self, col_value: &ColValue) -> anyhow::Result<f64> {
+        match col_value {
+            ColValue::Float(v) => Ok(*v as f64),
+            ColValue::Double(v) => Ok(*v),
+            _ => match Self::to_text(col_value).map(|s| s.parse::<f64>()) {
+                Some(Ok(v)) => Ok(v),
+                _ => self.unexpected_value(col_value),
+            },
+        }
+    }
+
+    pub fn to_bytes(&self, col_value: &ColValue) -> Vec<u8> {
+        match col_value {
+            ColValue::Blob(v) | ColValue::RawString(v) => v.clone(),
+            _ => Self::text_or_empty(col_value).into_bytes(),
+        }
+    }
+
+    fn text_or_empty(col_value: &ColValue) -> String {
+        Self::to_text(col_value).unwrap_or_default()
+    }
+
+    fn unexpected_value<T>(&self, col_value: &ColValue) -> anyhow::Result<T> {
+        bail! {Error::SinkerError(format!(
+            "can not write value {:?} to column {} of kind {:?}",
+            col_value, self.name, self.kind
+        ))}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::doc;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_to_text_and_json() {
+        let blob = ColValue::Blob(vec![1, 2, 255]);
+        assert_eq!(FileCol::to_text(&blob), Some("\\x0102ff".to_string()));
+        assert_eq!(FileCol::to_json(&blob), json!("\\x0102ff"));
+
+        let json = ColValue::Json2(r#"{"a":[1,2]}"#.into());
+        assert_eq!(FileCol::to_text(&json), Some(r#"{"a":[1,2]}"#.to_string()));
+        assert_eq!(FileCol::to_json(&json), json!({"a": [1, 2]}));
+
+        let doc = ColValue::MongoDoc(doc! {"_id": 1, "name": "a"});
+        assert_eq!(FileCol::to_json(&doc), json!({"_id": 1, "name": "a"}));
+
+        assert_eq!(FileCol::to_text(&ColValue::None), None);
+        assert_eq!(FileCol::to_json(&ColValue::None), Value::Null);
+        assert_eq!(
+            FileCol::to_json(&ColValue::UnsignedLongLong(u64::MAX)),
+            json!(u64::MAX)
+        );
+        assert_eq!(
+            FileCol::to_json(&ColValue::Decimal("1.10".into())),
+            json!("1.10")
+        );
+    }
+
+    #[test]
+    fn test_to_typed() {
+        let col = FileCol {
+            name: "f_1".into(),
+            kind: FileColKind::Int32,
+        };
+        assert_eq!(col.to_i32(&ColValue::UnsignedShort(65535)).unwrap(), 65535);
+        assert_eq!(col.to_i32(&ColValue::Year(2024)).unwrap(), 2024);
+        assert!(col.to_i32(&ColValue::LongLong(i64::MAX)).is_err());
+        assert!(col.to_i32(&ColValue::String("abc".into())).is_err());
+
+        assert_eq!(
+            col.to_i64(&ColValue::UnsignedLongLong(u64::MAX)).unwrap(),
+            -1
+        );
+        assert_eq!(col.to_f64(&ColValue::Decimal("1.5".into())).unwrap(), 1.5);
+        assert!(col.to_bool(&ColValue::String("t".into())).unwrap());
+        assert_eq!(col.to_bytes(&ColValue::String("ab".into())), b"ab".to_vec());
+    }
+}
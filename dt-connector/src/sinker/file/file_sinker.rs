@@ -0,0 +1,415 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use anyhow::bail;
+use async_trait::async_trait;
+use dt_common::{
+    config::config_enums::{FileCompressionEnum, FileFormatEnum},
+    error::Error,
+    log_info,
+    meta::{
+        col_value::ColValue,
+        ddl_meta::ddl_data::DdlData,
+        dt_data::{DtData, DtItem},
+        position::Position,
+        rdb_meta_manager::RdbMetaManager,
+        row_data::RowData,
+        row_type::RowType,
+    },
+    monitor::monitor::Monitor,
+};
+
+use super::{
+    file_manifest::FileManifest,
+    file_schema::{FileCol, FileSchemaUtil},
+    file_writer::{FileRow, FileWriter},
+};
+use crate::{rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Sinker};
+
+/// Writes rows to files: root_dir/schema/tb/part-N.{csv,jsonl,parquet}.
+pub struct FileSinker {
+    pub root_dir: String,
+    pub format: FileFormatEnum,
+    pub compression: FileCompressionEnum,
+    pub max_file_bytes: u64,
+    pub rotate_interval_secs: u64,
+    // None if the source is mongo
+    pub meta_manager: Option<RdbMetaManager>,
+    pub router: RdbRouter,
+    // rows are routed by the extractor, source table names are needed to get meta
+    pub reverse_router: RdbRouter,
+    pub manifest: Arc<Mutex<FileManifest>>,
+    // (schema, tb) -> the open file of the table
+    pub writers: HashMap<(String, String), FileWriter>,
+    pub monitor: Arc<Mutex<Monitor>>,
+}
+
+#[async_trait]
+impl Sinker for FileSinker {
+    // positions are needed by the manifest, so rows are received with DtItem
+    async fn sink_raw(&mut self, data: Vec<DtItem>, _batch: bool) -> anyhow::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let start_time = Instant::now();
+        self.rotate_expired()?;
+
+        let mut batch_size = 0;
+        let mut data_size = 0;
+        for item in data {
+            if let DtData::Dml { mut row_data } = item.dt_data {
+                batch_size += 1;
+                data_size += row_data.data_size;
+                row_data.convert_raw_string();
+                self.write_row(row_data, item.position).await?;
+            }
+        }
+
+        // rows of the batch are completed before their positions are checkpointed,
+        // an open parquet file has no footer, so parquet files are closed after each batch
+        let keys: Vec<(String, String)> = self.writers.keys().cloned().collect();
+        for key in keys {
+            if self.format == FileFormatEnum::Parquet {
+                self.close_writer(&key)?;
+            } else {
+                self.writers.get_mut(&key).unwrap().flush()?;
+            }
+        }
+        BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, data_size, start_time)
+    }
+
+    async fn close(&mut self) -> anyhow::Result<()> {
+        let keys: Vec<(String, String)> = self.writers.keys().cloned().collect();
+        for key in keys {
+            self.close_writer(&key)?;
+        }
+
+        if let Some(meta_manager) = &self.meta_manager {
+            meta_manager.close().await?;
+        }
+        Ok(())
+    }
+
+    // all sinkers refresh meta, the next files of changed tables are written with new columns
+    async fn refresh_meta(&mut self, data: Vec<DdlData>) -> anyhow::Result<()> {
+        for ddl_data in data.iter() {
+            if let Some(meta_manager) = self.meta_manager.as_mut() {
+                meta_manager.invalidate_cache_by_ddl_data(ddl_data);
+            }
+
+            let (schema, tb) = ddl_data.get_schema_tb();
+            let keys: Vec<(String, String)> = self
+                .writers
+                .keys()
+                .filter(|(s, t)| *s == schema && (tb.is_empty() || *t == tb))
+                .cloned()
+                .collect();
+            for key in keys {
+                self.close_writer(&key)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FileSinker {
+    async fn write_row(&mut self, row_data: RowData, position: Position) -> anyhow::Result<()> {
+        let key = (row_data.schema.clone(), row_data.tb.clone());
+        if !self.writers.contains_key(&key) {
+            let (cols, id_cols) = self.get_cols(&row_data.schema, &row_data.tb).await?;
+            let mut writer = self.open_writer(&row_data.schema, &row_data.tb, cols)?;
+            writer.id_cols = id_cols;
+            self.writers.insert(key.clone(), writer);
+        }
+
+        let writer = self.writers.get_mut(&key).unwrap();
+        let mut data_size = row_data.data_size;
+        for row in Self::get_file_rows(row_data, &writer.id_cols) {
+            writer.write(row, position.clone(), data_size)?;
+            data_size = 0;
+        }
+
+        if writer.get_bytes() >= self.max_file_bytes {
+            self.close_writer(&key)?;
+        }
+        Ok(())
+    }
+
+    /// insert: the after image, delete: the before image,
+    /// update: the after image, with cols missing in it taken from the before image.
+    /// Files keep a single image per row, so an update changing id cols is written as
+    /// a delete of the before image and an insert of the after image, which can be replayed.
+    fn get_file_rows(row_data: RowData, id_cols: &[String]) -> Vec<FileRow> {
+        let file_row = |row_type: RowType, col_values| FileRow {
+            op: row_type.to_string(),
+            col_values,
+        };
+        match row_data.row_type {
+            RowType::Insert => vec![file_row(
+                RowType::Insert,
+                row_data.after.unwrap_or_default(),
+            )],
+            RowType::Delete => vec![file_row(
+                RowType::Delete,
+                row_data.before.unwrap_or_default(),
+            )],
+            RowType::Update => {
+                let before = row_data.before.unwrap_or_default();
+                let after = row_data.after.unwrap_or_default();
+                let id_changed = id_cols.iter().any(
+                    |col| matches!((before.get(col), after.get(col)), (Some(b), Some(a)) if b != a),
+                );
+                if id_changed {
+                    return vec![
+                        file_row(RowType::Delete, before),
+                        file_row(RowType::Insert, after),
+                    ];
+                }
+
+                let mut col_values: HashMap<String, ColValue> = before;
+                col_values.extend(after);
+                vec![file_row(RowType::Update, col_values)]
+            }
+        }
+    }
+
+    /// Returns cols and id cols of the table.
+    async fn get_cols(
+        &mut self,
+        schema: &str,
+        tb: &str,
+    ) -> anyhow::Result<(Vec<FileCol>, Vec<String>)> {
+        let (src_schema, src_tb) = self.reverse_router.get_tb_map(schema, tb);
+        let (src_schema, src_tb) = (src_schema.to_string(), src_tb.to_string());
+        let col_map = self.router.get_col_map(&src_schema, &src_tb);
+
+        let meta_manager = match self.meta_manager.as_mut() {
+            Some(meta_manager) => meta_manager,
+            None => return Ok((FileSchemaUtil::from_mongo(), Vec::new())),
+        };
+
+        if let Some(mysql_meta_manager) = meta_manager.mysql_meta_manager.as_mut() {
+            let tb_meta = mysql_meta_manager.get_tb_meta(&src_schema, &src_tb).await?;
+            let id_cols = FileSchemaUtil::get_id_cols(&tb_meta.basic.id_cols, col_map);
+            return Ok((FileSchemaUtil::from_mysql(tb_meta, col_map)?, id_cols));
+        }
+        if let Some(pg_meta_manager) = meta_manager.pg_meta_manager.as_mut() {
+            let tb_meta = pg_meta_manager.get_tb_meta(&src_schema, &src_tb).await?;
+            let id_cols = FileSchemaUtil::get_id_cols(&tb_meta.basic.id_cols, col_map);
+            return Ok((FileSchemaUtil::from_pg(tb_meta, col_map)?, id_cols));
+        }
+        bail! {Error::SinkerError(format!(
+            "no meta found for table: {}.{}",
+            src_schema, src_tb
+        ))}
+    }
+
+    fn open_writer(
+        &self,
+        schema: &str,
+        tb: &str,
+        cols: Vec<FileCol>,
+    ) -> anyhow::Result<FileWriter> {
+        let dir = format!("{}/{}", schema, tb);
+        let part_id = self.manifest.lock().unwrap().next_part_id(&dir)?;
+        let relative_path = format!(
+            "{}/part-{}.{}",
+            dir,
+            part_id,
+            FileWriter::get_extension(&self.format, &self.compression)
+        );
+        log_info!("open file: {}", relative_path);
+        FileWriter::new(
+            &self.root_dir,
+            relative_path,
+            schema,
+            tb,
+            cols,
+            &self.format,
+            &self.compression,
+        )
+    }
+
+    fn close_writer(&mut self, key: &(String, String)) -> anyhow::Result<()> {
+        if let Some(writer) = self.writers.remove(key) {
+            let entry = writer.finish()?;
+            log_info!(
+                "close file: {}, row_count: {}, file_bytes: {}",
+                entry.file,
+                entry.row_count,
+                entry.file_bytes
+            );
+            self.manifest.lock().unwrap().append(&entry)?;
+        }
+        Ok(())
+    }
+
+    fn rotate_expired(&mut self) -> anyhow::Result<()> {
+        if self.rotate_interval_secs == 0 {
+            return Ok(());
+        }
+
+        let keys: Vec<(String, String)> = self
+            .writers
+            .iter()
+            .filter(|(_, w)| w.open_time.elapsed().as_secs() >= self.rotate_interval_secs)
+            .map(|(k, _)| k.clone())
+            .collect();
+        for key in keys {
+            self.close_writer(&key)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use dt_common::config::{config_enums::DbType, router_config::RouterConfig};
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+
+    use super::*;
+
+    fn mock_sinker(root_dir: &str) -> FileSinker {
+        let config = RouterConfig::Rdb {
+            schema_map: String::new(),
+            tb_map: String::new(),
+            col_map: String::new(),
+            topic_map: String::new(),
+            key_prefix_map: String::new(),
+            endpoint_map: String::new(),
+        };
+        let router = RdbRouter::from_config(&config, &DbType::Mongo).unwrap();
+        FileSinker {
+            root_dir: root_dir.into(),
+            format: FileFormatEnum::Parquet,
+            compression: FileCompressionEnum::None,
+            max_file_bytes: u64::MAX,
+            rotate_interval_secs: 0,
+            meta_manager: None,
+            router: router.clone(),
+            reverse_router: router,
+            manifest: Arc::new(Mutex::new(FileManifest::new(root_dir))),
+            writers: HashMap::new(),
+            monitor: Arc::new(Mutex::new(Monitor::new("test", 10, 100, 10))),
+        }
+    }
+
+    fn mock_items(ids: &[i32]) -> Vec<DtItem> {
+        ids.iter()
+            .map(|id| DtItem {
+                dt_data: DtData::Dml {
+                    row_data: RowData::new(
+                        "db_1".into(),
+                        "tb_1".into(),
+                        RowType::Insert,
+                        None,
+                        mock_values(vec![(
+                            "doc",
+                            ColValue::String(format!("{{\"_id\":{}}}", id)),
+                        )]),
+                    ),
+                },
+                position: Position::None,
+                data_origin_node: String::new(),
+            })
+            .collect()
+    }
+
+    fn mock_values(values: Vec<(&str, ColValue)>) -> Option<HashMap<String, ColValue>> {
+        Some(
+            values
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_get_file_rows() {
+        let id_cols = vec!["id".to_string()];
+        let get_rows = |before, after| {
+            let row_data =
+                RowData::new("db_1".into(), "tb_1".into(), RowType::Update, before, after);
+            FileSinker::get_file_rows(row_data, &id_cols)
+                .into_iter()
+                .map(|row| (row.op, row.col_values))
+                .collect::<Vec<_>>()
+        };
+
+        // id not changed, cols missing in the after image are taken from the before image
+        let rows = get_rows(
+            mock_values(vec![("id", ColValue::Long(1)), ("v", ColValue::Long(1))]),
+            mock_values(vec![("id", ColValue::Long(1))]),
+        );
+        assert_eq!(
+            rows,
+            vec![(
+                "update".to_string(),
+                mock_values(vec![("id", ColValue::Long(1)), ("v", ColValue::Long(1))]).unwrap()
+            )]
+        );
+
+        // id changed
+        let rows = get_rows(
+            mock_values(vec![("id", ColValue::Long(1)), ("v", ColValue::Long(1))]),
+            mock_values(vec![("id", ColValue::Long(2)), ("v", ColValue::Long(1))]),
+        );
+        assert_eq!(
+            rows,
+            vec![
+                (
+                    "delete".to_string(),
+                    mock_values(vec![("id", ColValue::Long(1)), ("v", ColValue::Long(1))]).unwrap()
+                ),
+                (
+                    "insert".to_string(),
+                    mock_values(vec![("id", ColValue::Long(2)), ("v", ColValue::Long(1))]).unwrap()
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sink_parquet_by_batches() {
+        let root_dir = std::env::temp_dir().join(format!(
+            "ape_dts_file_sinker_parquet_{}",
+            std::process::id()
+        ));
+        let root_dir = root_dir.to_str().unwrap();
+        let mut sinker = mock_sinker(root_dir);
+
+        // each batch is readable in a closed file before its positions are checkpointed
+        sinker.sink_raw(mock_items(&[1, 2]), false).await.unwrap();
+        assert!(sinker.writers.is_empty());
+        sinker.sink_raw(mock_items(&[3]), false).await.unwrap();
+        assert!(sinker.writers.is_empty());
+
+        let entries = FileManifest::load(root_dir).unwrap();
+        let files: Vec<(String, usize)> = entries
+            .iter()
+            .map(|entry| (entry.file.clone(), entry.row_count))
+            .collect();
+        assert_eq!(
+            files,
+            vec![
+                ("db_1/tb_1/part-0.parquet".to_string(), 2),
+                ("db_1/tb_1/part-1.parquet".to_string(), 1)
+            ]
+        );
+        for entry in entries {
+            let file = File::open(format!("{}/{}", root_dir, entry.file)).unwrap();
+            let reader = SerializedFileReader::new(file).unwrap();
+            assert_eq!(
+                reader.metadata().file_metadata().num_rows() as usize,
+                entry.row_count
+            );
+        }
+        std::fs::remove_dir_all(root_dir).unwrap();
+    }
+}
@@ -0,0 +1,724 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+use anyhow::{bail, Context};
+use dt_common::{
+    config::config_enums::{FileCompressionEnum, FileFormatEnum},
+    error::Error,
+    meta::{col_value::ColValue, position::Position},
+};
+use flate2::write::GzEncoder;
+use parquet::{
+    basic::{Compression, GzipLevel, LogicalType, Repetition, Type as PhysicalType, ZstdLevel},
+    data_type::{
+        BoolType, ByteArray, ByteArrayType, DataType, DoubleType, FloatType, Int32Type, Int64Type,
+    },
+    file::{
+        properties::WriterProperties,
+        writer::{SerializedColumnWriter, SerializedFileWriter},
+    },
+    schema::types::{Type, TypePtr},
+};
+use serde_json::{Map, Value};
+
+use super::{
+    file_manifest::FileManifestEntry,
+    file_schema::{FileCol, FileColKind},
+};
+
+// the operation of each row: insert / update / delete
+pub const OP_COL: &str = "_op";
+const PARQUET_ROW_GROUP_ROWS: usize = 10000;
+
+pub struct FileRow {
+    pub op: String,
+    pub col_values: HashMap<String, ColValue>,
+}
+
+/// Writes rows of a table to a single file, closed by finish when the file rotates.
+/// Rows of each batch are completed by flush (csv / jsonl) or finish (parquet)
+/// before their positions are checkpointed.
+pub struct FileWriter {
+    // relative to the root directory, like: db_1/tb_1/part-0.csv
+    pub relative_path: String,
+    pub schema: String,
+    pub tb: String,
+    pub cols: Vec<FileCol>,
+    // updates changing these cols are written as delete + insert, empty for mongo
+    pub id_cols: Vec<String>,
+    pub format: FileFormatEnum,
+    pub compression: FileCompressionEnum,
+    pub row_count: usize,
+    // rows written before the last flush
+    flushed_row_count: usize,
+    pub start_position: Position,
+    pub end_position: Position,
+    pub open_time: Instant,
+    bytes: Arc<AtomicU64>,
+    // taken by flush to end the compressed frame and by finish
+    encoder: Option<FileEncoder>,
+}
+
+enum FileEncoder {
+    Text(TextEncoder),
+    Parquet {
+        writer: SerializedFileWriter<CountingWriter>,
+        // rows are buffered and written as row groups of up to PARQUET_ROW_GROUP_ROWS
+        rows: Vec<FileRow>,
+        rows_size: u64,
+    },
+}
+
+enum TextEncoder {
+    Plain(CountingWriter),
+    Gzip(GzEncoder<CountingWriter>),
+    Zstd(zstd::Encoder<'static, CountingWriter>),
+}
+
+/// Counts bytes written to the file for rotation by size.
+struct CountingWriter {
+    inner: BufWriter<File>,
+    bytes: Arc<AtomicU64>,
+}
+
+impl Write for CountingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.bytes.fetch_add(n as u64, Ordering::Relaxed);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl TextEncoder {
+    fn new(writer: CountingWriter, compression: &FileCompressionEnum) -> anyhow::Result<Self> {
+        let encoder = match compression {
+            FileCompressionEnum::None => Self::Plain(writer),
+            FileCompressionEnum::Gzip => {
+                Self::Gzip(GzEncoder::new(writer, flate2::Compression::default()))
+            }
+            FileCompressionEnum::Zstd => Self::Zstd(zstd::Encoder::new(writer, 0)?),
+            FileCompressionEnum::Snappy => {
+                bail! {Error::ConfigError(
+                    "snappy compression is only supported by parquet files".into()
+                )}
+            }
+        };
+        Ok(encoder)
+    }
+
+    fn writer(&mut self) -> &mut dyn Write {
+        match self {
+            Self::Plain(w) => w,
+            Self::Gzip(w) => w,
+            Self::Zstd(w) => w,
+        }
+    }
+
+    fn finish(self) -> anyhow::Result<CountingWriter> {
+        let writer = match self {
+            Self::Plain(w) => w,
+            Self::Gzip(w) => w.finish()?,
+            Self::Zstd(w) => w.finish()?,
+        };
+        Ok(writer)
+    }
+
+    /// Ends the gzip member / zstd frame and starts a new one for the following rows.
+    /// Concatenated members / frames are a valid file, so written rows are readable
+    /// without the trailer written by finish.
+    fn end_frame(self, compression: &FileCompressionEnum) -> anyhow::Result<Self> {
+        let mut writer = self.finish()?;
+        writer.flush()?;
+        Self::new(writer, compression)
+    }
+}
+
+impl FileWriter {
+    pub fn new(
+        root_dir: &str,
+        relative_path: String,
+        schema: &str,
+        tb: &str,
+        cols: Vec<FileCol>,
+        format: &FileFormatEnum,
+        compression: &FileCompressionEnum,
+    ) -> anyhow::Result<Self> {
+        let path = Path::new(root_dir).join(&relative_path);
+        let file = File::create(&path)
+            .with_context(|| format!("failed to create file: {}", path.display()))?;
+        let bytes = Arc::new(AtomicU64::new(0));
+        let writer = CountingWriter {
+            inner: BufWriter::new(file),
+            bytes: bytes.clone(),
+        };
+
+        let encoder = match format {
+            FileFormatEnum::Parquet => {
+                let properties = WriterProperties::builder()
+                    .set_compression(Self::get_parquet_compression(compression))
+                    .build();
+                FileEncoder::Parquet {
+                    writer: SerializedFileWriter::new(
+                        writer,
+                        Self::build_parquet_schema(&cols)?,
+                        Arc::new(properties),
+                    )?,
+                    rows: Vec::new(),
+                    rows_size: 0,
+                }
+            }
+
+            FileFormatEnum::Csv => {
+                let mut encoder = TextEncoder::new(writer, compression)?;
+                let mut header = vec![OP_COL.to_string()];
+                header.extend(cols.iter().map(|col| Self::escape_csv(&col.name)));
+                writeln!(encoder.writer(), "{}", header.join(","))?;
+                FileEncoder::Text(encoder)
+            }
+
+            FileFormatEnum::Jsonl => FileEncoder::Text(TextEncoder::new(writer, compression)?),
+        };
+
+        Ok(Self {
+            relative_path,
+            schema: schema.to_string(),
+            tb: tb.to_string(),
+            cols,
+            id_cols: Vec::new(),
+            format: format.clone(),
+            compression: compression.clone(),
+            row_count: 0,
+            flushed_row_count: 0,
+            start_position: Position::None,
+            end_position: Position::None,
+            open_time: Instant::now(),
+            bytes,
+            encoder: Some(encoder),
+        })
+    }
+
+    /// part-0.csv, part-0.jsonl.gz, part-0.parquet (compressed by pages)
+    pub fn get_extension(format: &FileFormatEnum, compression: &FileCompressionEnum) -> String {
+        let ext: &'static str = format.into();
+        match (format, compression) {
+            (FileFormatEnum::Parquet, _) => ext.to_string(),
+            (_, FileCompressionEnum::Gzip) => format!("{}.gz", ext),
+            (_, FileCompressionEnum::Zstd) => format!("{}.zst", ext),
+            _ => ext.to_string(),
+        }
+    }
+
+    pub fn write(
+        &mut self,
+        row: FileRow,
+        position: Position,
+        data_size: usize,
+    ) -> anyhow::Result<()> {
+        match self.encoder.as_mut() {
+            Some(FileEncoder::Text(encoder)) => {
+                let line = match self.format {
+                    FileFormatEnum::Csv => Self::to_csv_line(&self.cols, &row),
+                    _ => Self::to_json_line(&self.cols, &row),
+                };
+                encoder.writer().write_all(line.as_bytes())?;
+            }
+
+            Some(FileEncoder::Parquet {
+                writer,
+                rows,
+                rows_size,
+            }) => {
+                rows.push(row);
+                *rows_size += data_size as u64;
+                if rows.len() >= PARQUET_ROW_GROUP_ROWS {
+                    Self::write_row_group(writer, &self.cols, rows)?;
+                    *rows_size = 0;
+                }
+            }
+
+            // fails since a previous flush failed
+            None => {
+                self.take_encoder()?;
+            }
+        }
+
+        if self.row_count == 0 {
+            self.start_position = position.clone();
+        }
+        self.end_position = position;
+        self.row_count += 1;
+        Ok(())
+    }
+
+    /// Size of the file, including parquet rows not written yet.
+    pub fn get_bytes(&self) -> u64 {
+        let pending = match &self.encoder {
+            Some(FileEncoder::Parquet { rows_size, .. }) => *rows_size,
+            _ => 0,
+        };
+        self.bytes.load(Ordering::Relaxed) + pending
+    }
+
+    /// Called after each batch of csv / jsonl files: compressed files end the current
+    /// member / frame, then the file is flushed. Parquet files get their footer only by finish,
+    /// so they are closed after each batch instead.
+    pub fn flush(&mut self) -> anyhow::Result<()> {
+        if self.row_count == self.flushed_row_count {
+            return Ok(());
+        }
+
+        let encoder = match self.take_encoder()? {
+            FileEncoder::Text(encoder) => FileEncoder::Text(encoder.end_frame(&self.compression)?),
+            encoder => encoder,
+        };
+        self.encoder = Some(encoder);
+        self.flushed_row_count = self.row_count;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> anyhow::Result<FileManifestEntry> {
+        let writer = match self.take_encoder()? {
+            FileEncoder::Text(encoder) => encoder.finish()?,
+            FileEncoder::Parquet {
+                mut writer,
+                mut rows,
+                ..
+            } => {
+                if !rows.is_empty() {
+                    Self::write_row_group(&mut writer, &self.cols, &mut rows)?;
+                }
+                writer.into_inner()?
+            }
+        };
+
+        let file = writer.inner.into_inner()?;
+        file.sync_all()?;
+
+        Ok(FileManifestEntry {
+            file: self.relative_path,
+            schema: self.schema,
+            tb: self.tb,
+            row_count: self.row_count,
+            file_bytes: self.bytes.load(Ordering::Relaxed),
+            start_position: self.start_position,
+            end_position: self.end_position,
+        })
+    }
+
+    // None only if a previous flush failed
+    fn take_encoder(&mut self) -> anyhow::Result<FileEncoder> {
+        match self.encoder.take() {
+            Some(encoder) => Ok(encoder),
+            None => bail! {Error::SinkerError(format!(
+                "file: {} is broken by a failed flush",
+                self.relative_path
+            ))},
+        }
+    }
+
+    fn to_csv_line(cols: &[FileCol], row: &FileRow) -> String {
+        let mut line = Self::escape_csv(&row.op);
+        for col in cols {
+            line.push(',');
+            // NULL is an empty field, an empty string is quoted
+            if let Some(text) = row.col_values.get(&col.name).and_then(FileCol::to_text) {
+                line.push_str(&Self::escape_csv(&text));
+            }
+        }
+        line.push('\n');
+        line
+    }
+
+    fn to_json_line(cols: &[FileCol], row: &FileRow) -> String {
+        let mut map = Map::new();
+        map.insert(OP_COL.into(), Value::String(row.op.clone()));
+        for col in cols {
+            let value = match row.col_values.get(&col.name) {
+                Some(col_value) => FileCol::to_json(col_value),
+                None => Value::Null,
+            };
+            map.insert(col.name.clone(), value);
+        }
+        format!("{}\n", Value::Object(map))
+    }
+
+    fn escape_csv(value: &str) -> String {
+        if value.is_empty() || value.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    }
+
+    fn get_parquet_compression(compression: &FileCompressionEnum) -> Compression {
+        match compression {
+            FileCompressionEnum::None => Compression::UNCOMPRESSED,
+            FileCompressionEnum::Gzip => Compression::GZIP(GzipLevel::default()),
+            FileCompressionEnum::Zstd => Compression::ZSTD(ZstdLevel::default()),
+            FileCompressionEnum::Snappy => Compression::SNAPPY,
+        }
+    }
+
+    fn build_parquet_schema(cols: &[FileCol]) -> anyhow::Result<TypePtr> {
+        let mut fields = vec![Arc::new(
+            Type::primitive_type_builder(OP_COL, PhysicalType::BYTE_ARRAY)
+                .with_repetition(Repetition::REQUIRED)
+                .with_logical_type(Some(LogicalType::String))
+                .build()?,
+        )];
+
+        for col in cols {
+            let (physical_type, logical_type) = match col.kind {
+                FileColKind::Bool => (PhysicalType::BOOLEAN, None),
+                FileColKind::Int32 => (PhysicalType::INT32, None),
+                FileColKind::Int64 => (PhysicalType::INT64, None),
+                FileColKind::UInt64 => (
+                    PhysicalType::INT64,
+                    Some(LogicalType::Integer {
+                        bit_width: 64,
+                        is_signed: false,
+                    }),
+                ),
+                FileColKind::Float => (PhysicalType::FLOAT, None),
+                FileColKind::Double => (PhysicalType::DOUBLE, None),
+                FileColKind::String => (PhysicalType::BYTE_ARRAY, Some(LogicalType::String)),
+                FileColKind::Json => (PhysicalType::BYTE_ARRAY, Some(LogicalType::Json)),
+                FileColKind::Bytes => (PhysicalType::BYTE_ARRAY, None),
+            };
+            fields.push(Arc::new(
+                Type::primitive_type_builder(&col.name, physical_type)
+                    .with_repetition(Repetition::OPTIONAL)
+                    .with_logical_type(logical_type)
+                    .build()?,
+            ));
+        }
+
+        Ok(Arc::new(
+            Type::group_type_builder("schema")
+                .with_fields(fields)
+                .build()?,
+        ))
+    }
+
+    fn write_row_group(
+        writer: &mut SerializedFileWriter<CountingWriter>,
+        cols: &[FileCol],
+        rows: &mut Vec<FileRow>,
+    ) -> anyhow::Result<()> {
+        let mut row_group_writer = writer.next_row_group()?;
+
+        let mut col_writer = Self::next_column(row_group_writer.next_column()?)?;
+        let ops: Vec<ByteArray> = rows
+            .iter()
+            .map(|row| ByteArray::from(row.op.as_str()))
+            .collect();
+        col_writer
+            .typed::<ByteArrayType>()
+            .write_batch(&ops, None, None)?;
+        col_writer.close()?;
+
+        for col in cols {
+            let mut col_writer = Self::next_column(row_group_writer.next_column()?)?;
+            match col.kind {
+                FileColKind::Bool => {
+                    Self::write_column::<BoolType>(&mut col_writer, col, rows, |v| col.to_bool(v))?
+                }
+                FileColKind::Int32 => {
+                    Self::write_column::<Int32Type>(&mut col_writer, col, rows, |v| col.to_i32(v))?
+                }
+                FileColKind::Int64 | FileColKind::UInt64 => {
+                    Self::write_column::<Int64Type>(&mut col_writer, col, rows, |v| col.to_i64(v))?
+                }
+                FileColKind::Float => {
+                    Self::write_column::<FloatType>(&mut col_writer, col, rows, |v| {
+                        Ok(col.to_f64(v)? as f32)
+                    })?
+                }
+                FileColKind::Double => {
+                    Self::write_column::<DoubleType>(&mut col_writer, col, rows, |v| col.to_f64(v))?
+                }
+                FileColKind::String | FileColKind::Json | FileColKind::Bytes => {
+                    Self::write_column::<ByteArrayType>(&mut col_writer, col, rows, |v| {
+                        Ok(ByteArray::from(col.to_bytes(v)))
+                    })?
+                }
+            }
+            col_writer.close()?;
+        }
+
+        row_group_writer.close()?;
+        rows.clear();
+        Ok(())
+    }
+
+    fn next_column(
+        col_writer: Option<SerializedColumnWriter<'_>>,
+    ) -> anyhow::Result<SerializedColumnWriter<'_>> {
+        match col_writer {
+            Some(col_writer) => Ok(col_writer),
+            None => bail! {Error::SinkerError(
+                "parquet columns do not match the schema".into()
+            )},
+        }
+    }
+
+    fn write_column<T: DataType>(
+        col_writer: &mut SerializedColumnWriter<'_>,
+        col: &FileCol,
+        rows: &[FileRow],
+        convert: impl Fn(&ColValue) -> anyhow::Result<T::T>,
+    ) -> anyhow::Result<()> {
+        let mut values = Vec::new();
+        let mut def_levels = Vec::with_capacity(rows.len());
+        for row in rows {
+            match row.col_values.get(&col.name) {
+                Some(col_value) if !matches!(col_value, ColValue::None) => {
+                    values.push(convert(col_value)?);
+                    def_levels.push(1);
+                }
+                _ => def_levels.push(0),
+            }
+        }
+        col_writer
+            .typed::<T>()
+            .write_batch(&values, Some(&def_levels), None)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use flate2::read::{GzDecoder, MultiGzDecoder};
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use serde_json::json;
+
+    use super::*;
+
+    fn mock_dir(name: &str) -> String {
+        let dir = std::env::temp_dir().join(format!("ape_dts_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.to_str().unwrap().to_string()
+    }
+
+    fn mock_cols() -> Vec<FileCol> {
+        vec![
+            FileCol {
+                name: "id".into(),
+                kind: FileColKind::Int32,
+            },
+            FileCol {
+                name: "name".into(),
+                kind: FileColKind::String,
+            },
+            FileCol {
+                name: "amount".into(),
+                kind: FileColKind::UInt64,
+            },
+        ]
+    }
+
+    fn write_rows(writer: &mut FileWriter) {
+        let rows = vec![
+            (
+                "insert",
+                vec![
+                    ("id", ColValue::Long(1)),
+                    ("name", ColValue::String("a,\"b\"".into())),
+                    ("amount", ColValue::UnsignedLongLong(u64::MAX)),
+                ],
+            ),
+            (
+                "delete",
+                vec![
+                    ("id", ColValue::Long(2)),
+                    ("name", ColValue::String(String::new())),
+                    ("amount", ColValue::None),
+                ],
+            ),
+        ];
+        for (i, (op, col_values)) in rows.into_iter().enumerate() {
+            let row = FileRow {
+                op: op.into(),
+                col_values: col_values
+                    .into_iter()
+                    .map(|(k, v)| (k.to_string(), v))
+                    .collect(),
+            };
+            let position = Position::PgCdc {
+                lsn: format!("0/{}", i),
+                timestamp: String::new(),
+            };
+            writer.write(row, position, 10).unwrap();
+        }
+    }
+
+    fn new_writer(
+        dir: &str,
+        format: &FileFormatEnum,
+        compression: &FileCompressionEnum,
+    ) -> FileWriter {
+        let relative_path = format!("part-0.{}", FileWriter::get_extension(format, compression));
+        FileWriter::new(
+            dir,
+            relative_path,
+            "db_1",
+            "tb_1",
+            mock_cols(),
+            format,
+            compression,
+        )
+        .unwrap()
+    }
+
+    fn write_file(
+        dir: &str,
+        format: &FileFormatEnum,
+        compression: &FileCompressionEnum,
+    ) -> FileManifestEntry {
+        let mut writer = new_writer(dir, format, compression);
+        write_rows(&mut writer);
+        writer.flush().unwrap();
+        writer.finish().unwrap()
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let dir = mock_dir("csv");
+        let entry = write_file(&dir, &FileFormatEnum::Csv, &FileCompressionEnum::None);
+        assert_eq!(entry.file, "part-0.csv");
+        assert_eq!(entry.row_count, 2);
+        assert_eq!(
+            entry.start_position,
+            Position::PgCdc {
+                lsn: "0/0".into(),
+                timestamp: String::new()
+            }
+        );
+        assert_eq!(
+            entry.end_position,
+            Position::PgCdc {
+                lsn: "0/1".into(),
+                timestamp: String::new()
+            }
+        );
+
+        let content = std::fs::read_to_string(format!("{}/part-0.csv", dir)).unwrap();
+        assert_eq!(entry.file_bytes, content.len() as u64);
+        assert_eq!(
+            content,
+            "_op,id,name,amount\ninsert,1,\"a,\"\"b\"\"\",18446744073709551615\ndelete,2,\"\",\n"
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_jsonl_gzip() {
+        let dir = mock_dir("jsonl");
+        let entry = write_file(&dir, &FileFormatEnum::Jsonl, &FileCompressionEnum::Gzip);
+        assert_eq!(entry.file, "part-0.jsonl.gz");
+
+        let mut content = String::new();
+        GzDecoder::new(File::open(format!("{}/part-0.jsonl.gz", dir)).unwrap())
+            .read_to_string(&mut content)
+            .unwrap();
+        let lines: Vec<Value> = content
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                json!({"_op": "insert", "id": 1, "name": "a,\"b\"", "amount": u64::MAX}),
+                json!({"_op": "delete", "id": 2, "name": "", "amount": null}),
+            ]
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_parquet() {
+        let dir = mock_dir("parquet");
+        let entry = write_file(&dir, &FileFormatEnum::Parquet, &FileCompressionEnum::Snappy);
+        assert_eq!(entry.file, "part-0.parquet");
+
+        let file = File::open(format!("{}/part-0.parquet", dir)).unwrap();
+        assert_eq!(entry.file_bytes, file.metadata().unwrap().len());
+        let reader = SerializedFileReader::new(file).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 2);
+        let rows: Vec<Value> = reader
+            .get_row_iter(None)
+            .unwrap()
+            .map(|row| row.unwrap().to_json_value())
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                json!({"_op": "insert", "id": 1, "name": "a,\"b\"", "amount": u64::MAX}),
+                json!({"_op": "delete", "id": 2, "name": "", "amount": null}),
+            ]
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_flush_batches() {
+        let dir = mock_dir("flush_batches");
+        let read_text = |file: &str, compression: &FileCompressionEnum| {
+            let file = File::open(format!("{}/{}", dir, file)).unwrap();
+            let mut reader: Box<dyn Read> = match compression {
+                FileCompressionEnum::Gzip => Box::new(MultiGzDecoder::new(file)),
+                _ => Box::new(zstd::stream::read::Decoder::new(file).unwrap()),
+            };
+            let mut content = String::new();
+            reader.read_to_string(&mut content).unwrap();
+            content.lines().count()
+        };
+
+        // rows of each flushed batch are readable before the file is closed
+        for compression in [FileCompressionEnum::Gzip, FileCompressionEnum::Zstd] {
+            let mut writer = new_writer(&dir, &FileFormatEnum::Jsonl, &compression);
+            let file = writer.relative_path.clone();
+            write_rows(&mut writer);
+            writer.flush().unwrap();
+            assert_eq!(read_text(&file, &compression), 2);
+
+            // no rows since the last flush
+            writer.flush().unwrap();
+            write_rows(&mut writer);
+            writer.flush().unwrap();
+            assert_eq!(read_text(&file, &compression), 4);
+
+            let entry = writer.finish().unwrap();
+            assert_eq!(entry.row_count, 4);
+            assert_eq!(read_text(&file, &compression), 4);
+        }
+
+        // parquet rows are written by finish
+        let mut writer = new_writer(&dir, &FileFormatEnum::Parquet, &FileCompressionEnum::None);
+        write_rows(&mut writer);
+        writer.flush().unwrap();
+        write_rows(&mut writer);
+        writer.flush().unwrap();
+        writer.finish().unwrap();
+        let file = File::open(format!("{}/part-0.parquet", dir)).unwrap();
+        let reader = SerializedFileReader::new(file).unwrap();
+        assert_eq!(reader.metadata().num_row_groups(), 1);
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 4);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
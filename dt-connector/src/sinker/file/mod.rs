@@ -0,0 +1,4 @@
+pub mod file_manifest;
+pub mod file_schema;
+pub mod file_sinker;
+pub mod file_writer;
@@ -2,12 +2,19 @@ pub mod base_checker;
 pub mod base_sinker;
 pub mod base_struct_sinker;
 pub mod clickhouse;
+pub mod doris;
+pub mod duckdb;
 pub mod dummy_sinker;
+pub mod elasticsearch;
+pub mod file;
 pub mod foxlake;
 pub mod kafka;
+#[cfg(test)]
+pub mod mock_http_server;
 pub mod mongo;
 pub mod mysql;
 pub mod pg;
 pub mod redis;
 pub mod sql_sinker;
 pub mod starrocks;
+pub mod webhook_sinker;
@@ -0,0 +1,257 @@
+use anyhow::bail;
+use dt_common::{
+    config::config_enums::DbType,
+    error::Error,
+    meta::{
+        col_value::ColValue,
+        mysql::{mysql_col_type::MysqlColType, mysql_tb_meta::MysqlTbMeta},
+        row_data::RowData,
+    },
+    utils::sql_util::SqlUtil,
+};
+use sqlx::{MySql, Pool, Row};
+
+const NULL: &[u8] = br"\N";
+
+pub struct MysqlLoadDataUtil {}
+
+impl MysqlLoadDataUtil {
+    /// LOAD DATA LOCAL is rejected by the target unless the global local_infile is ON,
+    /// check it once when sinkers start instead of failing every batch.
+    pub async fn check_local_infile(conn_pool: &Pool<MySql>) -> anyhow::Result<()> {
+        let row = sqlx::query("SELECT CAST(@@GLOBAL.local_infile AS SIGNED)")
+            .fetch_one(conn_pool)
+            .await?;
+        let local_infile: i64 = row.try_get(0)?;
+        if local_infile == 0 {
+            bail! {Error::ConfigError(
+                "[sinker] bulk_load=true requires local_infile=ON in the target mysql, enable it by SET GLOBAL local_infile=ON or set bulk_load=false".into()
+            )}
+        }
+        Ok(())
+    }
+
+    /// LOAD DATA statement for the content generated by encode_rows.
+    /// Binary / bit columns are loaded into user variables and converted by the SET clause,
+    /// since LOAD DATA can not load them from the text format directly.
+    pub fn get_load_data_sql(tb_meta: &MysqlTbMeta) -> anyhow::Result<String> {
+        let mut cols = Vec::new();
+        let mut set_pairs = Vec::new();
+        for (i, col) in tb_meta.basic.cols.iter().enumerate() {
+            let escaped_col = SqlUtil::escape_by_db_type(col, &DbType::Mysql);
+            let col_type = tb_meta.get_col_type(col)?;
+            if Self::is_binary(col_type) {
+                cols.push(format!("@v{}", i));
+                set_pairs.push(format!("{}=UNHEX(@v{})", escaped_col, i));
+            } else if *col_type == MysqlColType::Bit {
+                cols.push(format!("@v{}", i));
+                set_pairs.push(format!("{}=CAST(@v{} AS UNSIGNED)", escaped_col, i));
+            } else {
+                cols.push(escaped_col);
+            }
+        }
+
+        let mut sql = format!(
+            r"LOAD DATA LOCAL INFILE 'ape_dts' REPLACE INTO TABLE {}.{} CHARACTER SET utf8mb4 FIELDS TERMINATED BY '\t' ESCAPED BY '\\' LINES TERMINATED BY '\n' ({})",
+            SqlUtil::escape_by_db_type(&tb_meta.basic.schema, &DbType::Mysql),
+            SqlUtil::escape_by_db_type(&tb_meta.basic.tb, &DbType::Mysql),
+            cols.join(",")
+        );
+        if !set_pairs.is_empty() {
+            sql = format!("{} SET {}", sql, set_pairs.join(","));
+        }
+        Ok(sql)
+    }
+
+    /// Encode rows as tab separated lines, escaped by backslash, NULL as \N.
+    pub fn encode_rows(tb_meta: &MysqlTbMeta, data: &[RowData]) -> anyhow::Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        for row_data in data.iter() {
+            let after = row_data.after.as_ref().unwrap();
+            for (i, col) in tb_meta.basic.cols.iter().enumerate() {
+                if i > 0 {
+                    buf.push(b'\t');
+                }
+                let col_type = tb_meta.get_col_type(col)?;
+                match after.get(col) {
+                    Some(col_value) => Self::encode_col_value(col, col_type, col_value, &mut buf)?,
+                    None => buf.extend_from_slice(NULL),
+                }
+            }
+            buf.push(b'\n');
+        }
+        Ok(buf)
+    }
+
+    fn encode_col_value(
+        col: &str,
+        col_type: &MysqlColType,
+        col_value: &ColValue,
+        buf: &mut Vec<u8>,
+    ) -> anyhow::Result<()> {
+        if Self::is_binary(col_type) {
+            let hex_str = match col_value {
+                ColValue::None => {
+                    buf.extend_from_slice(NULL);
+                    return Ok(());
+                }
+                ColValue::Blob(v) | ColValue::RawString(v) | ColValue::Json(v) => hex::encode(v),
+                _ => hex::encode(col_value.to_option_string().unwrap_or_default()),
+            };
+            buf.extend_from_slice(hex_str.as_bytes());
+            return Ok(());
+        }
+
+        match col_value {
+            ColValue::None => buf.extend_from_slice(NULL),
+            ColValue::Bool(v) => buf.push(if *v { b'1' } else { b'0' }),
+            ColValue::Blob(v) | ColValue::Json(v) => Self::escape(v, buf),
+            ColValue::RawString(v) => {
+                if std::str::from_utf8(v).is_err() {
+                    bail! {Error::SinkerError(format!(
+                        "load data does not support non-utf8 string, col: {}",
+                        col
+                    ))}
+                }
+                Self::escape(v, buf)
+            }
+            ColValue::Float(_) | ColValue::Double(_) if Self::is_nan_or_infinite(col_value) => {
+                bail! {Error::SinkerError(format!(
+                    "load data does not support value: {}, col: {}",
+                    col_value, col
+                ))}
+            }
+            // numeric set / enum values can only be inserted as integers
+            ColValue::Set(_) | ColValue::Enum(_) => {
+                bail! {Error::SinkerError(format!(
+                    "load data does not support numeric set / enum value: {}, col: {}",
+                    col_value, col
+                ))}
+            }
+            _ => {
+                let value = col_value.to_option_string().unwrap_or_default();
+                Self::escape(value.as_bytes(), buf)
+            }
+        }
+        Ok(())
+    }
+
+    fn escape(value: &[u8], buf: &mut Vec<u8>) {
+        for b in value {
+            match b {
+                b'\\' => buf.extend_from_slice(br"\\"),
+                b'\t' => buf.extend_from_slice(br"\t"),
+                b'\n' => buf.extend_from_slice(br"\n"),
+                b'\r' => buf.extend_from_slice(br"\r"),
+                b'\0' => buf.extend_from_slice(br"\0"),
+                _ => buf.push(*b),
+            }
+        }
+    }
+
+    fn is_nan_or_infinite(col_value: &ColValue) -> bool {
+        match col_value {
+            ColValue::Float(v) => !v.is_finite(),
+            ColValue::Double(v) => !v.is_finite(),
+            _ => false,
+        }
+    }
+
+    fn is_binary(col_type: &MysqlColType) -> bool {
+        matches!(
+            col_type,
+            MysqlColType::Binary { .. }
+                | MysqlColType::VarBinary { .. }
+                | MysqlColType::TinyBlob
+                | MysqlColType::MediumBlob
+                | MysqlColType::Blob
+                | MysqlColType::LongBlob
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dt_common::meta::{rdb_tb_meta::RdbTbMeta, row_type::RowType};
+
+    use super::*;
+
+    fn mock_tb_meta() -> MysqlTbMeta {
+        let cols = vec!["id", "name", "data", "flag"];
+        let col_types = vec![
+            MysqlColType::Int { unsigned: false },
+            MysqlColType::Varchar {
+                length: 255,
+                charset: "utf8mb4".into(),
+            },
+            MysqlColType::Blob,
+            MysqlColType::Bit,
+        ];
+        let basic = RdbTbMeta {
+            schema: "db_1".into(),
+            tb: "tb_1".into(),
+            cols: cols.iter().map(|c| c.to_string()).collect(),
+            ..Default::default()
+        };
+        let col_type_map = cols.iter().map(|c| c.to_string()).zip(col_types).collect();
+        MysqlTbMeta {
+            basic,
+            col_type_map,
+        }
+    }
+
+    fn mock_row_data(values: Vec<ColValue>) -> RowData {
+        let cols = ["id", "name", "data", "flag"];
+        let after: HashMap<String, ColValue> =
+            cols.iter().map(|c| c.to_string()).zip(values).collect();
+        RowData::new(
+            "db_1".into(),
+            "tb_1".into(),
+            RowType::Insert,
+            None,
+            Some(after),
+        )
+    }
+
+    #[test]
+    fn test_get_load_data_sql() {
+        let sql = MysqlLoadDataUtil::get_load_data_sql(&mock_tb_meta()).unwrap();
+        assert_eq!(
+            sql,
+            r"LOAD DATA LOCAL INFILE 'ape_dts' REPLACE INTO TABLE `db_1`.`tb_1` CHARACTER SET utf8mb4 FIELDS TERMINATED BY '\t' ESCAPED BY '\\' LINES TERMINATED BY '\n' (`id`,`name`,@v2,@v3) SET `data`=UNHEX(@v2),`flag`=CAST(@v3 AS UNSIGNED)"
+        );
+    }
+
+    #[test]
+    fn test_encode_rows() {
+        let data = vec![
+            mock_row_data(vec![
+                ColValue::Long(1),
+                ColValue::String("a\tb\nc\\d\re\0f".into()),
+                ColValue::Blob(vec![0, 1, 255]),
+                ColValue::Bit(5),
+            ]),
+            mock_row_data(vec![
+                ColValue::Long(2),
+                ColValue::None,
+                ColValue::None,
+                ColValue::None,
+            ]),
+        ];
+        let buf = MysqlLoadDataUtil::encode_rows(&mock_tb_meta(), &data).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "1\ta\\tb\\nc\\\\d\\re\\0f\t0001ff\t5\n2\t\\N\t\\N\t\\N\n"
+        );
+
+        let data = vec![mock_row_data(vec![
+            ColValue::Long(1),
+            ColValue::Double(f64::NAN),
+            ColValue::None,
+            ColValue::None,
+        ])];
+        assert!(MysqlLoadDataUtil::encode_rows(&mock_tb_meta(), &data).is_err());
+    }
+}
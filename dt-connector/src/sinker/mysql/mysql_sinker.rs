@@ -9,16 +9,24 @@ use crate::{
     rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Sinker,
 };
 
-use anyhow::Context;
+use super::mysql_load_data_util::MysqlLoadDataUtil;
+
+use anyhow::{bail, Context};
+use bytes::Bytes;
 use dt_common::{
+    error::Error,
     log_error, log_info,
     meta::ddl_meta::{ddl_data::DdlData, ddl_type::DdlType},
     monitor::monitor::Monitor,
 };
 
 use dt_common::meta::{
-    mysql::mysql_meta_manager::MysqlMetaManager, row_data::RowData, row_type::RowType,
+    mysql::{mysql_meta_manager::MysqlMetaManager, mysql_tb_meta::MysqlTbMeta},
+    row_data::RowData,
+    row_type::RowType,
 };
+use futures::StreamExt;
+use mysql_async::prelude::Queryable;
 
 use sqlx::{
     mysql::{MySqlConnectOptions, MySqlPoolOptions},
@@ -36,6 +44,8 @@ pub struct MysqlSinker {
     pub batch_size: usize,
     pub monitor: Arc<Mutex<Monitor>>,
     pub data_marker: Option<Arc<RwLock<DataMarker>>>,
+    // set for snapshot tasks with bulk_load enabled
+    pub load_data_pool: Option<mysql_async::Pool>,
 }
 
 #[async_trait]
@@ -50,7 +60,11 @@ impl Sinker for MysqlSinker {
         } else {
             match data[0].row_type {
                 RowType::Insert => {
-                    call_batch_fn!(self, data, Self::batch_insert);
+                    if self.load_data_pool.is_some() && self.data_marker.is_none() {
+                        call_batch_fn!(self, data, Self::batch_load_data);
+                    } else {
+                        call_batch_fn!(self, data, Self::batch_insert);
+                    }
                 }
                 RowType::Delete => {
                     call_batch_fn!(self, data, Self::batch_delete);
@@ -92,6 +106,9 @@ impl Sinker for MysqlSinker {
 
     async fn close(&mut self) -> anyhow::Result<()> {
         self.meta_manager.close().await?;
+        if let Some(load_data_pool) = self.load_data_pool.take() {
+            load_data_pool.disconnect().await?;
+        }
         return close_conn_pool!(self);
     }
 
@@ -211,6 +228,64 @@ impl MysqlSinker {
         BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, data_size, start_time)
     }
 
+    async fn batch_load_data(
+        &mut self,
+        data: &mut [RowData],
+        start_index: usize,
+        batch_size: usize,
+    ) -> anyhow::Result<()> {
+        let start_time = Instant::now();
+
+        let tb_meta = self
+            .meta_manager
+            .get_tb_meta_by_row_data(&data[0])
+            .await?
+            .to_owned();
+        let sub_data = &data[start_index..start_index + batch_size];
+        let data_size = sub_data.iter().map(|row_data| row_data.data_size).sum();
+
+        if let Err(error) = self.load_data(&tb_meta, sub_data).await {
+            log_error!(
+                "load data failed, will insert by batch, schema: {}, tb: {}, error: {}",
+                tb_meta.basic.schema,
+                tb_meta.basic.tb,
+                error.to_string()
+            );
+            return self.batch_insert(data, start_index, batch_size).await;
+        }
+
+        BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, data_size, start_time)
+    }
+
+    async fn load_data(&self, tb_meta: &MysqlTbMeta, data: &[RowData]) -> anyhow::Result<()> {
+        let sql = MysqlLoadDataUtil::get_load_data_sql(tb_meta)?;
+        let content = Bytes::from(MysqlLoadDataUtil::encode_rows(tb_meta, data)?);
+
+        let mut conn = self.load_data_pool.as_ref().unwrap().get_conn().await?;
+        conn.set_infile_handler(async move {
+            Ok(futures::stream::iter([Ok::<_, std::io::Error>(content)]).boxed())
+        });
+
+        // with LOCAL, data-interpretation errors become warnings and the rows are still loaded
+        // with adjusted values, roll them back before the batch is written again by INSERT,
+        // or rows of tables without keys are duplicated
+        conn.query_drop("START TRANSACTION").await?;
+        if let Err(error) = conn.query_drop(sql).await {
+            conn.query_drop("ROLLBACK").await?;
+            return Err(error.into());
+        }
+        let warnings = conn.get_warnings();
+        if warnings > 0 {
+            conn.query_drop("ROLLBACK").await?;
+            bail! {Error::SinkerError(format!(
+                "load data got {} warnings",
+                warnings
+            ))}
+        }
+        conn.query_drop("COMMIT").await?;
+        Ok(())
+    }
+
     fn get_data_marker_sql(&self) -> Option<String> {
         if let Some(data_marker) = &self.data_marker {
             let data_marker = data_marker.read().unwrap();
@@ -0,0 +1,452 @@
+use anyhow::bail;
+use byteorder::{BigEndian, WriteBytesExt};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
+use dt_common::{
+    config::config_enums::DbType,
+    error::Error,
+    meta::{
+        col_value::ColValue,
+        pg::{pg_col_type::PgColType, pg_tb_meta::PgTbMeta, pg_value_type::PgValueType},
+        row_data::RowData,
+    },
+    utils::sql_util::SqlUtil,
+};
+
+const NULL: &[u8] = br"\N";
+const BINARY_SIGNATURE: &[u8] = b"PGCOPY\n\xff\r\n\0";
+const NUMERIC_POS: u16 = 0x0000;
+const NUMERIC_NEG: u16 = 0x4000;
+const NUMERIC_NAN: u16 = 0xC000;
+const NUMERIC_PINF: u16 = 0xD000;
+const NUMERIC_NINF: u16 = 0xF000;
+
+pub struct PgCopyUtil {}
+
+impl PgCopyUtil {
+    pub fn get_copy_sql(tb_meta: &PgTbMeta, binary: bool) -> String {
+        let sql = format!(
+            "COPY {}.{}({}) FROM STDIN",
+            SqlUtil::escape_by_db_type(&tb_meta.basic.schema, &DbType::Pg),
+            SqlUtil::escape_by_db_type(&tb_meta.basic.tb, &DbType::Pg),
+            SqlUtil::escape_cols(&tb_meta.basic.cols, &DbType::Pg).join(",")
+        );
+        if binary {
+            format!("{} (FORMAT binary)", sql)
+        } else {
+            sql
+        }
+    }
+
+    /// In binary format, every column must be sent in the exact binary representation of
+    /// its type, tables with any column type not listed here can only be copied in text.
+    pub fn support_binary(tb_meta: &PgTbMeta) -> anyhow::Result<bool> {
+        for col in tb_meta.basic.cols.iter() {
+            let col_type = tb_meta.get_col_type(col)?;
+            let supported = match col_type.value_type {
+                PgValueType::Boolean
+                | PgValueType::Int16
+                | PgValueType::Int32
+                | PgValueType::Int64
+                | PgValueType::Float32
+                | PgValueType::Float64
+                | PgValueType::Numeric
+                | PgValueType::Bytes
+                | PgValueType::UUID
+                | PgValueType::Date
+                | PgValueType::Time
+                | PgValueType::Timestamp
+                | PgValueType::TimestampTZ => true,
+                PgValueType::String => {
+                    matches!(col_type.alias.as_str(), "text" | "varchar" | "bpchar")
+                }
+                PgValueType::JSON => matches!(col_type.alias.as_str(), "json" | "jsonb"),
+                _ => false,
+            };
+            if !supported {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Encode rows in COPY text format: tab separated lines, escaped by backslash, NULL as \N.
+    pub fn encode_text_rows(tb_meta: &PgTbMeta, data: &[RowData]) -> anyhow::Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        for row_data in data.iter() {
+            let after = row_data.after.as_ref().unwrap();
+            for (i, col) in tb_meta.basic.cols.iter().enumerate() {
+                if i > 0 {
+                    buf.push(b'\t');
+                }
+                let col_type = tb_meta.get_col_type(col)?;
+                match after.get(col) {
+                    Some(col_value) => Self::encode_text_value(col_type, col_value, &mut buf)?,
+                    None => buf.extend_from_slice(NULL),
+                }
+            }
+            buf.push(b'\n');
+        }
+        Ok(buf)
+    }
+
+    /// Encode rows in COPY binary format, the table should be checked by support_binary first.
+    pub fn encode_binary_rows(tb_meta: &PgTbMeta, data: &[RowData]) -> anyhow::Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        // header: signature, flags, header extension length
+        buf.extend_from_slice(BINARY_SIGNATURE);
+        buf.write_i32::<BigEndian>(0)?;
+        buf.write_i32::<BigEndian>(0)?;
+
+        let mut value_buf = Vec::new();
+        for row_data in data.iter() {
+            let after = row_data.after.as_ref().unwrap();
+            buf.write_i16::<BigEndian>(tb_meta.basic.cols.len() as i16)?;
+            for col in tb_meta.basic.cols.iter() {
+                let col_type = tb_meta.get_col_type(col)?;
+                match after.get(col) {
+                    Some(col_value) if *col_value != ColValue::None => {
+                        value_buf.clear();
+                        Self::encode_binary_value(col_type, col_value, &mut value_buf)?;
+                        buf.write_i32::<BigEndian>(value_buf.len() as i32)?;
+                        buf.extend_from_slice(&value_buf);
+                    }
+                    _ => buf.write_i32::<BigEndian>(-1)?,
+                }
+            }
+        }
+
+        // trailer
+        buf.write_i16::<BigEndian>(-1)?;
+        Ok(buf)
+    }
+
+    fn encode_text_value(
+        col_type: &PgColType,
+        col_value: &ColValue,
+        buf: &mut Vec<u8>,
+    ) -> anyhow::Result<()> {
+        match col_value {
+            ColValue::None => buf.extend_from_slice(NULL),
+            ColValue::Bool(v) => buf.push(if *v { b't' } else { b'f' }),
+            ColValue::Float(v) => Self::escape(Self::float_to_string(*v as f64).as_bytes(), buf),
+            ColValue::Double(v) => Self::escape(Self::float_to_string(*v).as_bytes(), buf),
+            ColValue::Blob(v) if col_type.value_type == PgValueType::Bytes => {
+                Self::escape(format!(r"\x{}", hex::encode(v)).as_bytes(), buf)
+            }
+            ColValue::Blob(v) | ColValue::RawString(v) | ColValue::Json(v) => {
+                Self::escape(Self::to_utf8(col_type, v)?.as_bytes(), buf)
+            }
+            // numeric bit / set / enum values have no text representation in pg
+            ColValue::Bit(_) | ColValue::Set(_) | ColValue::Enum(_) => {
+                bail! {Error::SinkerError(format!(
+                    "copy does not support value: {}, col type: {}",
+                    col_value, col_type.alias
+                ))}
+            }
+            _ => {
+                let value = col_value.to_option_string().unwrap_or_default();
+                Self::escape(value.as_bytes(), buf)
+            }
+        }
+        Ok(())
+    }
+
+    fn encode_binary_value(
+        col_type: &PgColType,
+        col_value: &ColValue,
+        buf: &mut Vec<u8>,
+    ) -> anyhow::Result<()> {
+        match col_type.value_type {
+            PgValueType::Boolean => {
+                let value = match col_value {
+                    ColValue::Bool(v) => *v,
+                    _ => match Self::to_str(col_type, col_value)?.to_lowercase().as_str() {
+                        "t" | "true" | "y" | "yes" | "on" | "1" => true,
+                        "f" | "false" | "n" | "no" | "off" | "0" => false,
+                        _ => return Self::unsupported_value(col_type, col_value),
+                    },
+                };
+                buf.push(value as u8);
+            }
+
+            PgValueType::Int16 => {
+                let value: i16 = Self::to_str(col_type, col_value)?.parse()?;
+                buf.write_i16::<BigEndian>(value)?;
+            }
+
+            PgValueType::Int32 => {
+                let value: i32 = Self::to_str(col_type, col_value)?.parse()?;
+                buf.write_i32::<BigEndian>(value)?;
+            }
+
+            PgValueType::Int64 => {
+                let value: i64 = Self::to_str(col_type, col_value)?.parse()?;
+                buf.write_i64::<BigEndian>(value)?;
+            }
+
+            PgValueType::Float32 => {
+                let value: f32 = match col_value {
+                    ColValue::Float(v) => *v,
+                    _ => Self::to_str(col_type, col_value)?.parse()?,
+                };
+                buf.write_f32::<BigEndian>(value)?;
+            }
+
+            PgValueType::Float64 => {
+                let value: f64 = match col_value {
+                    ColValue::Float(v) => *v as f64,
+                    ColValue::Double(v) => *v,
+                    _ => Self::to_str(col_type, col_value)?.parse()?,
+                };
+                buf.write_f64::<BigEndian>(value)?;
+            }
+
+            PgValueType::Numeric => {
+                Self::encode_numeric(&Self::to_str(col_type, col_value)?, buf)?;
+            }
+
+            PgValueType::Bytes => match col_value {
+                ColValue::Blob(v) | ColValue::RawString(v) | ColValue::Json(v) => {
+                    buf.extend_from_slice(v)
+                }
+                ColValue::String(v) if v.starts_with(r"\x") => {
+                    buf.extend_from_slice(&hex::decode(v.trim_start_matches(r"\x"))?)
+                }
+                _ => return Self::unsupported_value(col_type, col_value),
+            },
+
+            PgValueType::UUID => {
+                let value = hex::decode(Self::to_str(col_type, col_value)?.replace('-', ""))?;
+                if value.len() != 16 {
+                    return Self::unsupported_value(col_type, col_value);
+                }
+                buf.extend_from_slice(&value);
+            }
+
+            PgValueType::Date => {
+                let value = match Self::to_str(col_type, col_value)?.as_str() {
+                    "infinity" => i32::MAX,
+                    "-infinity" => i32::MIN,
+                    v => {
+                        let date = NaiveDate::parse_from_str(v, "%Y-%m-%d")?;
+                        (date - Self::pg_epoch().date()).num_days() as i32
+                    }
+                };
+                buf.write_i32::<BigEndian>(value)?;
+            }
+
+            PgValueType::Time => {
+                let time =
+                    NaiveTime::parse_from_str(&Self::to_str(col_type, col_value)?, "%H:%M:%S%.f")?;
+                let midnight = NaiveTime::from_hms_opt(0, 0, 0).unwrap();
+                let value = (time - midnight).num_microseconds().unwrap();
+                buf.write_i64::<BigEndian>(value)?;
+            }
+
+            PgValueType::Timestamp | PgValueType::TimestampTZ => {
+                let value = match Self::to_str(col_type, col_value)?.as_str() {
+                    "infinity" => i64::MAX,
+                    "-infinity" => i64::MIN,
+                    v => {
+                        let datetime = if col_type.value_type == PgValueType::TimestampTZ {
+                            // without time zone, the value can only be interpreted by the server
+                            DateTime::parse_from_str(v, "%Y-%m-%d %H:%M:%S%.f%#z")?.naive_utc()
+                        } else {
+                            NaiveDateTime::parse_from_str(v, "%Y-%m-%d %H:%M:%S%.f")?
+                        };
+                        match (datetime - Self::pg_epoch()).num_microseconds() {
+                            Some(v) => v,
+                            None => return Self::unsupported_value(col_type, col_value),
+                        }
+                    }
+                };
+                buf.write_i64::<BigEndian>(value)?;
+            }
+
+            PgValueType::JSON => {
+                if col_type.alias == "jsonb" {
+                    // jsonb version
+                    buf.push(1);
+                }
+                match col_value {
+                    ColValue::Json(v) | ColValue::Blob(v) | ColValue::RawString(v) => {
+                        buf.extend_from_slice(v)
+                    }
+                    _ => buf.extend_from_slice(Self::to_str(col_type, col_value)?.as_bytes()),
+                }
+            }
+
+            // text, varchar, bpchar
+            _ => match col_value {
+                ColValue::Blob(v) | ColValue::RawString(v) | ColValue::Json(v) => {
+                    buf.extend_from_slice(Self::to_utf8(col_type, v)?.as_bytes())
+                }
+                _ => buf.extend_from_slice(Self::to_str(col_type, col_value)?.as_bytes()),
+            },
+        }
+        Ok(())
+    }
+
+    /// numeric in binary: ndigits, weight, sign, dscale, followed by base-10000 digits
+    fn encode_numeric(value: &str, buf: &mut Vec<u8>) -> anyhow::Result<()> {
+        let value = value.trim();
+        let special_sign = match value {
+            "NaN" => Some(NUMERIC_NAN),
+            "Infinity" => Some(NUMERIC_PINF),
+            "-Infinity" => Some(NUMERIC_NINF),
+            _ => None,
+        };
+        if let Some(sign) = special_sign {
+            buf.write_i16::<BigEndian>(0)?;
+            buf.write_i16::<BigEndian>(0)?;
+            buf.write_u16::<BigEndian>(sign)?;
+            buf.write_u16::<BigEndian>(0)?;
+            return Ok(());
+        }
+
+        let (sign, unsigned) = if let Some(v) = value.strip_prefix('-') {
+            (NUMERIC_NEG, v)
+        } else {
+            (NUMERIC_POS, value.trim_start_matches('+'))
+        };
+        let (int_part, frac_part) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        if (int_part.is_empty() && frac_part.is_empty())
+            || !int_part
+                .bytes()
+                .chain(frac_part.bytes())
+                .all(|b| b.is_ascii_digit())
+        {
+            bail! {Error::SinkerError(format!("invalid numeric value: {}", value))}
+        }
+
+        // pad both parts to groups of 4 decimal digits around the decimal point
+        let int_part = int_part.trim_start_matches('0');
+        let int_pad = (4 - int_part.len() % 4) % 4;
+        let frac_pad = (4 - frac_part.len() % 4) % 4;
+        let padded = format!(
+            "{}{}{}{}",
+            "0".repeat(int_pad),
+            int_part,
+            frac_part,
+            "0".repeat(frac_pad)
+        );
+        let mut digits: Vec<i16> = padded
+            .as_bytes()
+            .chunks(4)
+            .map(|chunk| std::str::from_utf8(chunk).unwrap().parse().unwrap())
+            .collect();
+        let mut weight = ((int_part.len() + int_pad) / 4) as i16 - 1;
+
+        // strip leading and trailing zero digits
+        let leading_zeros = digits.iter().take_while(|d| **d == 0).count();
+        digits.drain(..leading_zeros);
+        weight -= leading_zeros as i16;
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        if digits.is_empty() {
+            weight = 0;
+        }
+
+        buf.write_i16::<BigEndian>(digits.len() as i16)?;
+        buf.write_i16::<BigEndian>(weight)?;
+        buf.write_u16::<BigEndian>(if digits.is_empty() { NUMERIC_POS } else { sign })?;
+        buf.write_u16::<BigEndian>(frac_part.len() as u16)?;
+        for digit in digits {
+            buf.write_i16::<BigEndian>(digit)?;
+        }
+        Ok(())
+    }
+
+    fn escape(value: &[u8], buf: &mut Vec<u8>) {
+        for b in value {
+            match b {
+                b'\\' => buf.extend_from_slice(br"\\"),
+                b'\t' => buf.extend_from_slice(br"\t"),
+                b'\n' => buf.extend_from_slice(br"\n"),
+                b'\r' => buf.extend_from_slice(br"\r"),
+                _ => buf.push(*b),
+            }
+        }
+    }
+
+    fn float_to_string(v: f64) -> String {
+        if v.is_nan() {
+            "NaN".into()
+        } else if v == f64::INFINITY {
+            "Infinity".into()
+        } else if v == f64::NEG_INFINITY {
+            "-Infinity".into()
+        } else {
+            v.to_string()
+        }
+    }
+
+    fn to_str(col_type: &PgColType, col_value: &ColValue) -> anyhow::Result<String> {
+        match col_value {
+            ColValue::Blob(v) | ColValue::RawString(v) | ColValue::Json(v) => {
+                Self::to_utf8(col_type, v).map(|v| v.to_string())
+            }
+            _ => Ok(col_value.to_option_string().unwrap_or_default()),
+        }
+    }
+
+    fn to_utf8<'a>(col_type: &PgColType, v: &'a [u8]) -> anyhow::Result<&'a str> {
+        match std::str::from_utf8(v) {
+            Ok(v) => Ok(v),
+            Err(_) => bail! {Error::SinkerError(format!(
+                "copy does not support non-utf8 value for col type: {}",
+                col_type.alias
+            ))},
+        }
+    }
+
+    fn unsupported_value(col_type: &PgColType, col_value: &ColValue) -> anyhow::Result<()> {
+        bail! {Error::SinkerError(format!(
+            "copy does not support value: {}, col type: {}",
+            col_value, col_type.alias
+        ))}
+    }
+
+    fn pg_epoch() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2000, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_numeric(value: &str) -> Vec<i16> {
+        let mut buf = Vec::new();
+        PgCopyUtil::encode_numeric(value, &mut buf).unwrap();
+        buf.chunks(2)
+            .map(|c| i16::from_be_bytes([c[0], c[1]]))
+            .collect()
+    }
+
+    #[test]
+    fn test_encode_numeric() {
+        // ndigits, weight, sign, dscale, digits
+        assert_eq!(encode_numeric("0"), vec![0, 0, 0, 0]);
+        assert_eq!(encode_numeric("0.00"), vec![0, 0, 0, 2]);
+        assert_eq!(encode_numeric("12345.6"), vec![3, 1, 0, 1, 1, 2345, 6000]);
+        assert_eq!(encode_numeric("-0.0001"), vec![1, -1, 0x4000, 4, 1]);
+        assert_eq!(encode_numeric("0.00001"), vec![1, -2, 0, 5, 1000]);
+        assert_eq!(encode_numeric("100000000"), vec![1, 2, 0, 0, 1]);
+        assert_eq!(encode_numeric("NaN"), vec![0, 0, 0xC000_u16 as i16, 0]);
+
+        let mut buf = Vec::new();
+        assert!(PgCopyUtil::encode_numeric("1e10", &mut buf).is_err());
+    }
+
+    #[test]
+    fn test_escape() {
+        let mut buf = Vec::new();
+        PgCopyUtil::escape(b"a\tb\nc\\d\re", &mut buf);
+        assert_eq!(buf, br"a\tb\nc\\d\re".to_vec());
+    }
+}
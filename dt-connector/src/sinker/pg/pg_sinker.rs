@@ -11,16 +11,23 @@ use crate::{
 
 use anyhow::Context;
 use dt_common::{
+    config::config_enums::PgCopyFormatEnum,
     log_error, log_info,
     meta::ddl_meta::{ddl_data::DdlData, ddl_type::DdlType},
     monitor::monitor::Monitor,
 };
 use sqlx::{
-    postgres::{PgConnectOptions, PgPoolOptions},
+    postgres::{PgConnectOptions, PgPoolCopyExt, PgPoolOptions},
     Executor, Pool, Postgres,
 };
 
-use dt_common::meta::{pg::pg_meta_manager::PgMetaManager, row_data::RowData, row_type::RowType};
+use dt_common::meta::{
+    pg::{pg_meta_manager::PgMetaManager, pg_tb_meta::PgTbMeta},
+    row_data::RowData,
+    row_type::RowType,
+};
+
+use super::pg_copy_util::PgCopyUtil;
 
 use async_trait::async_trait;
 
@@ -33,6 +40,9 @@ pub struct PgSinker {
     pub batch_size: usize,
     pub monitor: Arc<Mutex<Monitor>>,
     pub data_marker: Option<Arc<RwLock<DataMarker>>>,
+    // set for snapshot tasks with bulk_load enabled
+    pub bulk_load: bool,
+    pub copy_format: PgCopyFormatEnum,
 }
 
 #[async_trait]
@@ -47,7 +57,11 @@ impl Sinker for PgSinker {
         } else {
             match data[0].row_type {
                 RowType::Insert => {
-                    call_batch_fn!(self, data, Self::batch_insert);
+                    if self.bulk_load && self.data_marker.is_none() {
+                        call_batch_fn!(self, data, Self::batch_copy);
+                    } else {
+                        call_batch_fn!(self, data, Self::batch_insert);
+                    }
                 }
                 RowType::Delete => {
                     call_batch_fn!(self, data, Self::batch_delete);
@@ -206,6 +220,56 @@ impl PgSinker {
         BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, data_size, start_time)
     }
 
+    async fn batch_copy(
+        &mut self,
+        data: &mut [RowData],
+        start_index: usize,
+        batch_size: usize,
+    ) -> anyhow::Result<()> {
+        let start_time = Instant::now();
+
+        let tb_meta = self
+            .meta_manager
+            .get_tb_meta_by_row_data(&data[0])
+            .await?
+            .to_owned();
+        let sub_data = &data[start_index..start_index + batch_size];
+        let data_size = sub_data.iter().map(|row_data| row_data.data_size).sum();
+
+        if let Err(error) = self.copy(&tb_meta, sub_data).await {
+            log_error!(
+                "copy failed, will insert by batch, schema: {}, tb: {}, error: {}",
+                tb_meta.basic.schema,
+                tb_meta.basic.tb,
+                error.to_string()
+            );
+            return self.batch_insert(data, start_index, batch_size).await;
+        }
+
+        BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, data_size, start_time)
+    }
+
+    async fn copy(&self, tb_meta: &PgTbMeta, data: &[RowData]) -> anyhow::Result<()> {
+        // tables with types not supported by binary encoding are copied in text
+        let binary =
+            self.copy_format == PgCopyFormatEnum::Binary && PgCopyUtil::support_binary(tb_meta)?;
+        let content = if binary {
+            PgCopyUtil::encode_binary_rows(tb_meta, data)?
+        } else {
+            PgCopyUtil::encode_text_rows(tb_meta, data)?
+        };
+
+        let sql = PgCopyUtil::get_copy_sql(tb_meta, binary);
+        let mut copy_in = self.conn_pool.copy_in_raw(&sql).await?;
+        let sent = copy_in.send(content).await.map(|_| ());
+        if let Err(error) = sent {
+            copy_in.abort(error.to_string()).await?;
+            return Err(error.into());
+        }
+        copy_in.finish().await?;
+        Ok(())
+    }
+
     fn get_data_marker_sql(&self) -> Option<String> {
         if let Some(data_marker) = &self.data_marker {
             let data_marker = data_marker.read().unwrap();
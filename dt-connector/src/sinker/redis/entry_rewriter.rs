@@ -1,9 +1,9 @@
-use anyhow::bail;
-use dt_common::error::Error;
+use dt_common::log_warn;
 use dt_common::meta::redis::{
     redis_entry::RedisEntry,
     redis_object::{
-        HashObject, ListObject, ModuleObject, RedisCmd, SetObject, StringObject, ZsetObject,
+        HashObject, ListObject, RedisCmd, RedisObject, SetObject, StreamObject, StringObject,
+        ZsetObject,
     },
 };
 
@@ -294,10 +294,21 @@ impl EntryRewriter {
         Ok(cmds)
     }
 
-    pub fn rewrite_module(_obj: &mut ModuleObject) -> anyhow::Result<Vec<RedisCmd>> {
-        bail! {Error::RedisRdbError(
-            "module rewrite not implemented".into(),
-        )}
+    pub fn rewrite_module(entry: &mut RedisEntry, version: f32) -> anyhow::Result<Vec<RedisCmd>> {
+        if let RedisObject::Module(obj) = &mut entry.value {
+            if !obj.cmds.is_empty() {
+                return Ok(obj.cmds.drain(..).collect());
+            }
+            // values not decoded by ModuleParser: unknown modules, legacy encodings,
+            // timeseries with compressed chunks or compaction rules
+            log_warn!(
+                "module rewrite not supported, fall back to restore, module_name: {}, enc_ver: {}, key: {}",
+                obj.module_name,
+                obj.enc_ver,
+                entry.key
+            );
+        }
+        Ok(vec![Self::rewrite_as_restore(entry, version)?])
     }
 
     pub fn rewrite_set(obj: &mut SetObject) -> anyhow::Result<Vec<RedisCmd>> {
@@ -312,6 +323,13 @@ impl EntryRewriter {
         Ok(cmds)
     }
 
+    pub fn rewrite_stream(obj: &mut StreamObject) -> anyhow::Result<Vec<RedisCmd>> {
+        // StreamParser decodes entries, consumer groups and PEL into native cmds while parsing:
+        // XADD for entries, XSETID for last id, XGROUP CREATE for consumer groups
+        // and XCLAIM for pending entries
+        Ok(obj.cmds.drain(..).collect())
+    }
+
     pub fn rewrite_string(obj: &mut StringObject) -> anyhow::Result<Vec<RedisCmd>> {
         let mut cmd = RedisCmd::new();
         cmd.add_str_arg("set");
@@ -376,3 +394,41 @@ impl EntryRewriter {
         crc
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use dt_common::meta::redis::redis_object::{ModuleObject, RedisString};
+
+    use super::*;
+
+    fn mock_module_entry(cmds: Vec<RedisCmd>) -> RedisEntry {
+        let mut obj = ModuleObject::new();
+        obj.key = RedisString::from("k".to_string());
+        obj.module_name = "TSDB-TYPE".into();
+        obj.cmds = cmds;
+
+        let mut entry = RedisEntry::new();
+        entry.is_base = true;
+        entry.key = obj.key.clone();
+        entry.value = RedisObject::Module(obj);
+        entry.value_type_byte = 7;
+        entry.raw_bytes = vec![1, 2, 3];
+        entry
+    }
+
+    #[test]
+    fn test_rewrite_module() {
+        let cmd = RedisCmd::from_str_args(&["TS.CREATE", "k"]);
+        let mut entry = mock_module_entry(vec![cmd]);
+        let cmds = EntryRewriter::rewrite_module(&mut entry, 7.0).unwrap();
+        assert_eq!(cmds.len(), 1);
+        assert_eq!(cmds[0].to_string(), "TS.CREATE k");
+
+        // not decoded, fall back to restore
+        let mut entry = mock_module_entry(Vec::new());
+        let cmds = EntryRewriter::rewrite_module(&mut entry, 7.0).unwrap();
+        assert_eq!(cmds.len(), 1);
+        assert_eq!(cmds[0].get_str_arg(0), "restore");
+        assert_eq!(cmds[0].get_str_arg(4), "replace");
+    }
+}
@@ -1,3 +1,6 @@
 pub mod entry_rewriter;
+pub mod redis_event_publisher;
+pub mod redis_memory_report;
+pub mod redis_row_mapper;
 pub mod redis_sinker;
 pub mod redis_statistic_sinker;
@@ -0,0 +1,219 @@
+use std::{collections::HashMap, str::FromStr};
+
+use anyhow::bail;
+use dt_common::{
+    error::Error,
+    meta::{
+        col_value::ColValue, position::Position, redis::redis_object::RedisCmd, row_data::RowData,
+    },
+};
+use mongodb::bson::Bson;
+use serde_json::{json, Map, Value};
+
+use crate::rdb_router::RdbRouter;
+
+const EVENT_FIELD: &str = "event";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RedisEventMode {
+    // XADD to streams
+    Stream,
+    // PUBLISH to channels
+    Pubsub,
+}
+
+impl FromStr for RedisEventMode {
+    type Err = anyhow::Error;
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        match str {
+            "stream" => Ok(Self::Stream),
+            "pubsub" => Ok(Self::Pubsub),
+            _ => bail! {Error::ConfigError(format!(
+                "unsupported event_mode: {}, supported: stream, pubsub",
+                str
+            ))},
+        }
+    }
+}
+
+/// Publishes rdb / mongo changes to redis streams or pub/sub channels as JSON events,
+/// the stream / channel of a table is taken from [router] topic_map.
+#[derive(Clone)]
+pub struct RedisEventPublisher {
+    pub mode: RedisEventMode,
+    pub router: RdbRouter,
+    // approximate max length of streams, 0 for no trimming
+    pub stream_max_len: usize,
+}
+
+impl RedisEventPublisher {
+    pub fn get_topic(&self, row_data: &RowData) -> &str {
+        self.router.get_topic(&row_data.schema, &row_data.tb)
+    }
+
+    pub fn to_redis_cmd(&self, row_data: &mut RowData, position: &Position) -> RedisCmd {
+        row_data.convert_raw_string();
+        let event = Self::get_event(row_data, position).to_string();
+        let topic = self.get_topic(row_data);
+
+        let mut cmd = RedisCmd::new();
+        match self.mode {
+            RedisEventMode::Stream => {
+                // XADD stream MAXLEN ~ 10000 * event {...}
+                cmd.add_str_arg("XADD");
+                cmd.add_str_arg(topic);
+                if self.stream_max_len > 0 {
+                    cmd.add_str_arg("MAXLEN");
+                    cmd.add_str_arg("~");
+                    cmd.add_str_arg(&self.stream_max_len.to_string());
+                }
+                cmd.add_str_arg("*");
+                cmd.add_str_arg(EVENT_FIELD);
+                cmd.add_str_arg(&event);
+            }
+
+            RedisEventMode::Pubsub => {
+                cmd.add_str_arg("PUBLISH");
+                cmd.add_str_arg(topic);
+                cmd.add_str_arg(&event);
+            }
+        }
+        cmd
+    }
+
+    fn get_event(row_data: &RowData, position: &Position) -> Value {
+        json!({
+            "schema": row_data.schema,
+            "tb": row_data.tb,
+            "operation": row_data.row_type.to_string(),
+            "before": Self::to_json(&row_data.before),
+            "after": Self::to_json(&row_data.after),
+            "position": position,
+        })
+    }
+
+    fn to_json(col_values: &Option<HashMap<String, ColValue>>) -> Value {
+        let col_values = match col_values {
+            Some(v) => v,
+            None => return Value::Null,
+        };
+
+        let mut map = Map::new();
+        for (col, col_value) in col_values {
+            let value = match col_value {
+                // MongoDoc is serialized as null by ColValue
+                ColValue::MongoDoc(v) => Bson::Document(v.clone()).into_relaxed_extjson(),
+                _ => serde_json::to_value(col_value).unwrap_or(Value::Null),
+            };
+            map.insert(col.to_owned(), value);
+        }
+        Value::Object(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dt_common::{
+        config::{config_enums::DbType, router_config::RouterConfig},
+        meta::row_type::RowType,
+    };
+    use mongodb::bson::{doc, oid::ObjectId};
+
+    use super::*;
+
+    fn mock_publisher(mode: RedisEventMode, stream_max_len: usize) -> RedisEventPublisher {
+        let config = RouterConfig::Rdb {
+            schema_map: String::new(),
+            tb_map: String::new(),
+            col_map: String::new(),
+            topic_map: "*.*:events,db_1.tb_2:tb_2_events".into(),
+            key_prefix_map: String::new(),
+            endpoint_map: String::new(),
+        };
+        RedisEventPublisher {
+            mode,
+            router: RdbRouter::from_config(&config, &DbType::Mysql).unwrap(),
+            stream_max_len,
+        }
+    }
+
+    fn mock_row_data(tb: &str) -> RowData {
+        let before = HashMap::from([("id".to_string(), ColValue::Long(1))]);
+        let after = HashMap::from([("id".to_string(), ColValue::Long(2))]);
+        RowData::new(
+            "db_1".into(),
+            tb.into(),
+            RowType::Update,
+            Some(before),
+            Some(after),
+        )
+    }
+
+    #[test]
+    fn test_to_redis_cmd() {
+        let position = Position::PgCdc {
+            lsn: "0/16B3748".into(),
+            timestamp: "2024-01-01 00:00:00.000".into(),
+        };
+
+        let publisher = mock_publisher(RedisEventMode::Stream, 1000);
+        let cmd = publisher.to_redis_cmd(&mut mock_row_data("tb_1"), &position);
+        let args = cmd.args_to_string();
+        assert_eq!(
+            args[..7],
+            ["XADD", "events", "MAXLEN", "~", "1000", "*", "event"]
+        );
+        let event: Value = serde_json::from_str(&args[7]).unwrap();
+        assert_eq!(
+            event,
+            json!({
+                "schema": "db_1",
+                "tb": "tb_1",
+                "operation": "update",
+                "before": {"id": 1},
+                "after": {"id": 2},
+                "position": {"type": "PgCdc", "lsn": "0/16B3748", "timestamp": "2024-01-01 00:00:00.000"},
+            })
+        );
+
+        let publisher = mock_publisher(RedisEventMode::Stream, 0);
+        let cmd = publisher.to_redis_cmd(&mut mock_row_data("tb_2"), &position);
+        assert_eq!(
+            cmd.args_to_string()[..4],
+            ["XADD", "tb_2_events", "*", "event"]
+        );
+
+        let publisher = mock_publisher(RedisEventMode::Pubsub, 1000);
+        let cmd = publisher.to_redis_cmd(&mut mock_row_data("tb_2"), &position);
+        assert_eq!(cmd.args_to_string()[..2], ["PUBLISH", "tb_2_events"]);
+        assert_eq!(cmd.args.len(), 3);
+
+        // mongo docs are written as relaxed extended json
+        let oid = ObjectId::parse_str("65a1b2c3d4e5f60718293a4b").unwrap();
+        let after = HashMap::from([
+            ("_id".to_string(), ColValue::String(oid.to_hex())),
+            (
+                "doc".to_string(),
+                ColValue::MongoDoc(doc! {"_id": oid, "name": "a", "count": 1_i64}),
+            ),
+        ]);
+        let mut row_data = RowData::new(
+            "db_1".into(),
+            "tb_1".into(),
+            RowType::Insert,
+            None,
+            Some(after),
+        );
+        let publisher = mock_publisher(RedisEventMode::Pubsub, 0);
+        let cmd = publisher.to_redis_cmd(&mut row_data, &position);
+        let event: Value = serde_json::from_str(&cmd.args_to_string()[2]).unwrap();
+        assert_eq!(event["before"], Value::Null);
+        assert_eq!(
+            event["after"],
+            json!({
+                "_id": "65a1b2c3d4e5f60718293a4b",
+                "doc": {"_id": {"$oid": "65a1b2c3d4e5f60718293a4b"}, "name": "a", "count": 1},
+            })
+        );
+    }
+}
@@ -0,0 +1,417 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use dt_common::{
+    log_info,
+    meta::redis::{redis_entry::RedisEntry, redis_object::RedisObject},
+};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::extractor::redis::rdb::entry_parser;
+
+const REPORT_JSON_FILE: &str = "memory_report.json";
+const REPORT_CSV_FILE: &str = "memory_report.csv";
+const CSV_HEADER: &str = "database,type,key,size_in_bytes,encoding,num_elements,expire_ms";
+const NO_TTL: &str = "no_ttl";
+const HOUR_MS: i64 = 3600 * 1000;
+const DAY_MS: i64 = 24 * HOUR_MS;
+
+/// Aggregates memory usage of rdb entries, similar to the memory report of redis-rdb-tools.
+/// The size of a key is its rdb serialized size: the key plus the raw value payload,
+/// or the malloc size of the parsed value if the payload is not kept.
+pub struct RedisMemoryReport {
+    pub report_dir: String,
+    pub top_n: usize,
+    pub key_prefix_delimiter: String,
+    pub key_prefix_depth: usize,
+
+    total: MemoryStat,
+    by_db: HashMap<i64, MemoryStat>,
+    by_type: HashMap<String, MemoryStat>,
+    by_encoding: HashMap<String, MemoryStat>,
+    by_ttl: HashMap<String, MemoryStat>,
+    by_prefix: HashMap<String, MemoryStat>,
+    by_pattern: HashMap<String, MemoryStat>,
+    top_keys: HashMap<String, BinaryHeap<Reverse<KeyInfo>>>,
+
+    csv_writer: Option<BufWriter<File>>,
+    finished: bool,
+}
+
+#[derive(Serialize, Default, Clone)]
+struct MemoryStat {
+    key_count: u64,
+    memory_bytes: u64,
+}
+
+#[derive(Serialize)]
+struct NamedMemoryStat {
+    name: String,
+    key_count: u64,
+    memory_bytes: u64,
+}
+
+// field order matters since Ord is derived, keys are compared by memory_bytes first
+#[derive(Serialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
+struct KeyInfo {
+    memory_bytes: usize,
+    db_id: i64,
+    key: String,
+    key_type: String,
+    encoding: String,
+    num_elements: usize,
+    expire_ms: i64,
+}
+
+impl RedisMemoryReport {
+    pub fn new(
+        report_dir: &str,
+        top_n: usize,
+        key_prefix_delimiter: &str,
+        key_prefix_depth: usize,
+    ) -> Self {
+        Self {
+            report_dir: report_dir.into(),
+            top_n,
+            key_prefix_delimiter: key_prefix_delimiter.into(),
+            key_prefix_depth,
+            total: MemoryStat::default(),
+            by_db: HashMap::new(),
+            by_type: HashMap::new(),
+            by_encoding: HashMap::new(),
+            by_ttl: HashMap::new(),
+            by_prefix: HashMap::new(),
+            by_pattern: HashMap::new(),
+            top_keys: HashMap::new(),
+            csv_writer: None,
+            finished: false,
+        }
+    }
+
+    pub fn add_entry(&mut self, entry: &RedisEntry) -> anyhow::Result<()> {
+        // only rdb entries are counted, commands from cdc carry no memory info
+        if !entry.is_base {
+            return Ok(());
+        }
+
+        let key = entry.key.to_string();
+        let info = KeyInfo {
+            memory_bytes: Self::get_size_in_bytes(entry),
+            db_id: entry.db_id,
+            key_type: entry.get_type(),
+            encoding: entry_parser::get_encoding(entry.value_type_byte).to_string(),
+            num_elements: Self::get_num_elements(&entry.value),
+            expire_ms: entry.expire_ms,
+            key,
+        };
+
+        self.write_csv_row(&info)?;
+
+        let size = info.memory_bytes as u64;
+        Self::add_stat(&mut self.total, size);
+        Self::add_stat(self.by_db.entry(info.db_id).or_default(), size);
+        Self::add_stat(self.by_type.entry(info.key_type.clone()).or_default(), size);
+        Self::add_stat(
+            self.by_encoding.entry(info.encoding.clone()).or_default(),
+            size,
+        );
+        Self::add_stat(
+            self.by_ttl
+                .entry(Self::get_ttl_bucket(info.expire_ms).into())
+                .or_default(),
+            size,
+        );
+        for prefix in self.get_key_prefixes(&info.key) {
+            Self::add_stat(self.by_prefix.entry(prefix).or_default(), size);
+        }
+        let pattern = self.get_key_pattern(&info.key);
+        Self::add_stat(self.by_pattern.entry(pattern).or_default(), size);
+
+        if self.top_n > 0 {
+            let heap = self.top_keys.entry(info.key_type.clone()).or_default();
+            if heap.len() < self.top_n {
+                heap.push(Reverse(info));
+            } else if heap.peek().is_some_and(|min| min.0 < info) {
+                heap.pop();
+                heap.push(Reverse(info));
+            }
+        }
+        Ok(())
+    }
+
+    fn get_size_in_bytes(entry: &RedisEntry) -> usize {
+        if entry.is_raw() {
+            entry.key.bytes.len() + entry.raw_bytes.len()
+        } else {
+            entry.get_data_malloc_size()
+        }
+    }
+
+    pub fn finish(&mut self) -> anyhow::Result<()> {
+        // the report is shared by all parallel sinkers, only the first one closing writes it
+        if self.finished {
+            return Ok(());
+        }
+        self.finished = true;
+
+        // no key found, still output an empty csv with header
+        if self.csv_writer.is_none() {
+            self.write_csv_header()?;
+        }
+        if let Some(writer) = self.csv_writer.as_mut() {
+            writer.flush()?;
+        }
+
+        let report = json!({
+            "total": self.total,
+            "by_db": Self::sort_stats(
+                self.by_db.iter().map(|(k, v)| (k.to_string(), v.clone())).collect(),
+                0,
+            ),
+            "by_type": Self::sort_stats(self.by_type.clone(), 0),
+            "by_encoding": Self::sort_stats(self.by_encoding.clone(), 0),
+            "by_ttl": Self::sort_stats(self.by_ttl.clone(), 0),
+            "by_prefix": Self::sort_stats(self.by_prefix.clone(), self.top_n),
+            "by_pattern": Self::sort_stats(self.by_pattern.clone(), self.top_n),
+            "top_keys": self.get_top_keys(),
+        });
+
+        let json_file = Path::new(&self.report_dir).join(REPORT_JSON_FILE);
+        fs::write(&json_file, serde_json::to_string_pretty(&report)?)?;
+        log_info!("redis memory report written to: {}", json_file.display());
+        Ok(())
+    }
+
+    fn write_csv_row(&mut self, info: &KeyInfo) -> anyhow::Result<()> {
+        if self.csv_writer.is_none() {
+            self.write_csv_header()?;
+        }
+
+        let row = format!(
+            "{},{},{},{},{},{},{}\n",
+            info.db_id,
+            info.key_type,
+            Self::escape_csv(&info.key),
+            info.memory_bytes,
+            info.encoding,
+            info.num_elements,
+            info.expire_ms
+        );
+        self.csv_writer
+            .as_mut()
+            .unwrap()
+            .write_all(row.as_bytes())?;
+        Ok(())
+    }
+
+    fn write_csv_header(&mut self) -> anyhow::Result<()> {
+        fs::create_dir_all(&self.report_dir)?;
+        let file = File::create(Path::new(&self.report_dir).join(REPORT_CSV_FILE))?;
+        let mut writer = BufWriter::new(file);
+        writer.write_all(format!("{}\n", CSV_HEADER).as_bytes())?;
+        self.csv_writer = Some(writer);
+        Ok(())
+    }
+
+    fn get_top_keys(&self) -> HashMap<String, Vec<KeyInfo>> {
+        let mut top_keys = HashMap::new();
+        for (key_type, heap) in self.top_keys.iter() {
+            let mut keys: Vec<KeyInfo> = heap.iter().map(|i| i.0.clone()).collect();
+            keys.sort_by(|a, b| b.cmp(a));
+            top_keys.insert(key_type.clone(), keys);
+        }
+        top_keys
+    }
+
+    fn add_stat(stat: &mut MemoryStat, size: u64) {
+        stat.key_count += 1;
+        stat.memory_bytes += size;
+    }
+
+    /// sort by memory desc, limit 0 means no limit
+    fn sort_stats(stats: HashMap<String, MemoryStat>, limit: usize) -> Vec<NamedMemoryStat> {
+        let mut sorted: Vec<NamedMemoryStat> = stats
+            .into_iter()
+            .map(|(name, stat)| NamedMemoryStat {
+                name,
+                key_count: stat.key_count,
+                memory_bytes: stat.memory_bytes,
+            })
+            .collect();
+        sorted.sort_by(|a, b| {
+            b.memory_bytes
+                .cmp(&a.memory_bytes)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        if limit > 0 {
+            sorted.truncate(limit);
+        }
+        sorted
+    }
+
+    fn get_num_elements(value: &RedisObject) -> usize {
+        match value {
+            RedisObject::String(_) | RedisObject::Module(_) => 1,
+            RedisObject::List(v) => v.elements.len(),
+            RedisObject::Set(v) => v.elements.len(),
+            RedisObject::Zset(v) => v.elements.len(),
+            RedisObject::Hash(v) => v.value.len(),
+            RedisObject::Stream(v) => v.cmds.len(),
+            RedisObject::Unknown => 0,
+        }
+    }
+
+    fn get_ttl_bucket(expire_ms: i64) -> &'static str {
+        match expire_ms {
+            ms if ms <= 0 => NO_TTL,
+            ms if ms < HOUR_MS => "0-1h",
+            ms if ms < DAY_MS => "1h-1d",
+            ms if ms < 7 * DAY_MS => "1d-7d",
+            _ => "7d+",
+        }
+    }
+
+    /// key: "user:1001:profile", depth: 2 => ["user:", "user:1001:"]
+    fn get_key_prefixes(&self, key: &str) -> Vec<String> {
+        let mut prefixes = Vec::new();
+        if self.key_prefix_delimiter.is_empty() {
+            return prefixes;
+        }
+
+        let mut end = 0;
+        for _ in 0..self.key_prefix_depth {
+            match key[end..].find(&self.key_prefix_delimiter) {
+                Some(i) => {
+                    end += i + self.key_prefix_delimiter.len();
+                    // the whole key is not a prefix
+                    if end >= key.len() {
+                        break;
+                    }
+                    prefixes.push(key[..end].to_string());
+                }
+                None => break,
+            }
+        }
+        prefixes
+    }
+
+    /// key: "user:1001:profile" => "user:*:profile",
+    /// segments of numbers, hex strings or uuids are replaced by *
+    fn get_key_pattern(&self, key: &str) -> String {
+        if self.key_prefix_delimiter.is_empty() {
+            return key.to_string();
+        }
+
+        key.split(self.key_prefix_delimiter.as_str())
+            .map(|segment| {
+                if Self::is_variable_segment(segment) {
+                    "*"
+                } else {
+                    segment
+                }
+            })
+            .collect::<Vec<&str>>()
+            .join(&self.key_prefix_delimiter)
+    }
+
+    fn is_variable_segment(segment: &str) -> bool {
+        if segment.is_empty() {
+            return false;
+        }
+
+        if segment.chars().all(|c| c.is_ascii_digit()) {
+            return true;
+        }
+
+        segment.len() >= 8
+            && segment.chars().any(|c| c.is_ascii_digit())
+            && segment.chars().all(|c| c.is_ascii_hexdigit() || c == '-')
+    }
+
+    fn escape_csv(value: &str) -> String {
+        if value.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dt_common::meta::redis::redis_object::{RedisString, StringObject};
+
+    use super::*;
+
+    #[test]
+    fn test_key_prefix_and_pattern() {
+        let report = RedisMemoryReport::new("", 10, ":", 2);
+        assert_eq!(
+            report.get_key_prefixes("user:1001:profile"),
+            vec!["user:", "user:1001:"]
+        );
+        assert_eq!(report.get_key_prefixes("user:"), Vec::<String>::new());
+        assert_eq!(report.get_key_prefixes("user"), Vec::<String>::new());
+
+        assert_eq!(
+            report.get_key_pattern("user:1001:profile"),
+            "user:*:profile"
+        );
+        assert_eq!(
+            report.get_key_pattern("session:550e8400-e29b-41d4-a716-446655440000"),
+            "session:*"
+        );
+        assert_eq!(report.get_key_pattern("cache:abc:def"), "cache:abc:def");
+
+        assert_eq!(RedisMemoryReport::get_ttl_bucket(0), NO_TTL);
+        assert_eq!(RedisMemoryReport::get_ttl_bucket(1000), "0-1h");
+        assert_eq!(RedisMemoryReport::get_ttl_bucket(2 * DAY_MS), "1d-7d");
+
+        assert_eq!(RedisMemoryReport::escape_csv("a,\"b\""), "\"a,\"\"b\"\"\"");
+    }
+
+    #[test]
+    fn test_memory_report() {
+        let report_dir =
+            std::env::temp_dir().join(format!("ape_dts_memory_report_test_{}", std::process::id()));
+        let mut report = RedisMemoryReport::new(report_dir.to_str().unwrap(), 2, ":", 1);
+
+        // size_in_bytes = key + raw value payload
+        for (key, raw_size) in [("a:1", 7), ("a:2", 27), ("b:1", 17)] {
+            let mut entry = RedisEntry::new();
+            entry.is_base = true;
+            entry.key = RedisString::from(key.to_string());
+            entry.value = RedisObject::String(StringObject::new());
+            entry.raw_bytes = vec![0; raw_size];
+            // ignored since the raw payload is kept
+            entry.data_size = 1000;
+            report.add_entry(&entry).unwrap();
+        }
+        report.finish().unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(report_dir.join(REPORT_JSON_FILE)).unwrap())
+                .unwrap();
+        assert_eq!(json["total"]["key_count"], 3);
+        assert_eq!(json["total"]["memory_bytes"], 60);
+        assert_eq!(json["by_prefix"][0]["name"], "a:");
+        assert_eq!(json["by_prefix"][0]["memory_bytes"], 40);
+        assert_eq!(json["by_pattern"][0]["name"], "a:*");
+        let top_keys = json["top_keys"]["string"].as_array().unwrap();
+        assert_eq!(top_keys.len(), 2);
+        assert_eq!(top_keys[0]["key"], "a:2");
+        assert_eq!(top_keys[1]["key"], "b:1");
+
+        let csv = fs::read_to_string(report_dir.join(REPORT_CSV_FILE)).unwrap();
+        assert_eq!(csv.lines().count(), 4);
+        assert_eq!(csv.lines().next().unwrap(), CSV_HEADER);
+
+        fs::remove_dir_all(report_dir).unwrap();
+    }
+}
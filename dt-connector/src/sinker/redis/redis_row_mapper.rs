@@ -0,0 +1,650 @@
+use std::collections::HashMap;
+
+use anyhow::bail;
+use dt_common::{
+    error::Error,
+    log_warn,
+    meta::{
+        col_value::ColValue, redis::redis_object::RedisCmd, row_data::RowData, row_type::RowType,
+    },
+    utils::time_util::TimeUtil,
+};
+use serde::Deserialize;
+
+const JSON_PREFIX: &str = "json:";
+const WILDCARD: &str = "*";
+
+type RowValues = HashMap<String, ColValue>;
+
+/// Maps rdb rows to redis keys by per table templates, configured by [sinker] row_mappings, e.g.
+/// row_mappings=json:[{"db":"test_db","tb":"orders","key":"order:{id}","value_type":"json",
+///   "ttl":"3600","indexes":[{"type":"set","key":"idx:order:user:{user_id}"}]}]
+#[derive(Clone, Default)]
+pub struct RedisRowMapper {
+    mappings: HashMap<(String, String), RowMapping>,
+}
+
+#[derive(Clone)]
+struct RowMapping {
+    key: Template,
+    value_type: ValueType,
+    value_cols: Vec<String>,
+    value: Option<Template>,
+    ttl: Option<Template>,
+    expire_at: Option<Template>,
+    indexes: Vec<IndexMapping>,
+}
+
+#[derive(Clone)]
+struct IndexMapping {
+    index_type: IndexType,
+    key: Template,
+    member: Option<Template>,
+    score: Option<Template>,
+}
+
+#[derive(Clone, PartialEq)]
+enum ValueType {
+    Hash,
+    Json,
+    String,
+}
+
+#[derive(Clone, PartialEq)]
+enum IndexType {
+    Set,
+    Zset,
+}
+
+#[derive(Deserialize)]
+struct RowMappingConfig {
+    db: String,
+    tb: String,
+    key: String,
+    #[serde(default)]
+    value_type: String,
+    #[serde(default)]
+    value_cols: Vec<String>,
+    #[serde(default)]
+    value: String,
+    #[serde(default)]
+    ttl: String,
+    #[serde(default)]
+    expire_at: String,
+    #[serde(default)]
+    indexes: Vec<IndexMappingConfig>,
+}
+
+#[derive(Deserialize)]
+struct IndexMappingConfig {
+    #[serde(rename = "type")]
+    index_type: String,
+    key: String,
+    #[serde(default)]
+    member: String,
+    #[serde(default)]
+    score: String,
+}
+
+/// "user:{id}:{name}" => [Literal("user:"), Col("id"), Literal(":"), Col("name")],
+/// use "{{" and "}}" for literal braces
+#[derive(Clone, Debug, PartialEq)]
+enum Segment {
+    Literal(String),
+    Col(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Template {
+    segments: Vec<Segment>,
+}
+
+impl Template {
+    fn parse(template: &str) -> anyhow::Result<Self> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut col = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => col.push(c),
+                            None => bail! {Error::ConfigError(format!(
+                                "unclosed brace in redis row mapping template: {}",
+                                template
+                            ))},
+                        }
+                    }
+                    if col.is_empty() {
+                        bail! {Error::ConfigError(format!(
+                            "empty column in redis row mapping template: {}",
+                            template
+                        ))}
+                    }
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(literal.drain(..).collect()));
+                    }
+                    segments.push(Segment::Col(col));
+                }
+                '}' => bail! {Error::ConfigError(format!(
+                    "unmatched brace in redis row mapping template: {}",
+                    template
+                ))},
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+        Ok(Self { segments })
+    }
+
+    fn parse_optional(template: &str) -> anyhow::Result<Option<Self>> {
+        if template.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Self::parse(template)?))
+    }
+
+    /// return None if any referenced column is missing or NULL
+    fn render(&self, values: &RowValues) -> Option<String> {
+        let mut result = String::new();
+        for segment in self.segments.iter() {
+            match segment {
+                Segment::Literal(v) => result.push_str(v),
+                Segment::Col(col) => result.push_str(&values.get(col)?.to_option_string()?),
+            }
+        }
+        Some(result)
+    }
+}
+
+impl RedisRowMapper {
+    pub fn from_config(config_str: &str) -> anyhow::Result<Self> {
+        let mut mappings = HashMap::new();
+        if config_str.trim().is_empty() {
+            return Ok(Self { mappings });
+        }
+
+        let configs: Vec<RowMappingConfig> =
+            serde_json::from_str(config_str.trim().trim_start_matches(JSON_PREFIX))?;
+        for config in configs {
+            let value_type = match config.value_type.to_lowercase().as_str() {
+                "" | "hash" => ValueType::Hash,
+                "json" => ValueType::Json,
+                "string" => ValueType::String,
+                other => bail! {Error::ConfigError(format!(
+                    "unsupported value_type in redis row mapping: {}",
+                    other
+                ))},
+            };
+            if value_type == ValueType::String && config.value.is_empty() {
+                bail! {Error::ConfigError(format!(
+                    "value is required for value_type string in redis row mapping, db: {}, tb: {}",
+                    config.db, config.tb
+                ))}
+            }
+
+            let mut indexes = Vec::new();
+            for index in config.indexes {
+                let index_type = match index.index_type.to_lowercase().as_str() {
+                    "set" => IndexType::Set,
+                    "zset" => IndexType::Zset,
+                    other => bail! {Error::ConfigError(format!(
+                        "unsupported index type in redis row mapping: {}",
+                        other
+                    ))},
+                };
+                indexes.push(IndexMapping {
+                    index_type,
+                    key: Template::parse(&index.key)?,
+                    member: Template::parse_optional(&index.member)?,
+                    score: Template::parse_optional(&index.score)?,
+                });
+            }
+
+            let mapping = RowMapping {
+                key: Template::parse(&config.key)?,
+                value_type,
+                value_cols: config.value_cols,
+                value: Template::parse_optional(&config.value)?,
+                ttl: Template::parse_optional(&config.ttl)?,
+                expire_at: Template::parse_optional(&config.expire_at)?,
+                indexes,
+            };
+            mappings.insert((config.db, config.tb), mapping);
+        }
+        Ok(Self { mappings })
+    }
+
+    /// return None if no mapping configured for the table
+    pub fn to_redis_cmds(&self, row_data: &RowData) -> Option<Vec<RedisCmd>> {
+        let mapping = self.get_mapping(&row_data.schema, &row_data.tb)?;
+        let mut cmds = Vec::new();
+        match row_data.row_type {
+            RowType::Insert => {
+                mapping.add_write_cmds(row_data.after.as_ref().unwrap(), None, &mut cmds);
+            }
+            RowType::Update => {
+                mapping.add_write_cmds(
+                    row_data.after.as_ref().unwrap(),
+                    row_data.before.as_ref(),
+                    &mut cmds,
+                );
+            }
+            RowType::Delete => {
+                mapping.add_delete_cmds(row_data.before.as_ref().unwrap(), &mut cmds);
+            }
+        }
+
+        if cmds.is_empty() {
+            log_warn!(
+                "failed to map row to redis key, some key columns may be NULL, row: {}",
+                row_data
+            );
+        }
+        Some(cmds)
+    }
+
+    fn get_mapping(&self, schema: &str, tb: &str) -> Option<&RowMapping> {
+        self.mappings
+            .get(&(schema.into(), tb.into()))
+            .or_else(|| self.mappings.get(&(schema.into(), WILDCARD.into())))
+            .or_else(|| self.mappings.get(&(WILDCARD.into(), WILDCARD.into())))
+    }
+}
+
+impl RowMapping {
+    fn add_write_cmds(
+        &self,
+        after: &RowValues,
+        before: Option<&RowValues>,
+        cmds: &mut Vec<RedisCmd>,
+    ) {
+        let key = match self.key.render(after) {
+            Some(key) => key,
+            None => return,
+        };
+
+        // the key columns were updated, remove the old key and its index entries
+        if let Some(before) = before {
+            if let Some(old_key) = self.key.render(before) {
+                if old_key != key {
+                    cmds.push(RedisCmd::from_str_args(&["DEL", &old_key]));
+                }
+                for index in self.indexes.iter() {
+                    let old_entry = index.render(before, &old_key);
+                    if old_entry.is_some() && old_entry != index.render(after, &key) {
+                        let (index_key, member, _) = old_entry.unwrap();
+                        cmds.push(index.remove_cmd(&index_key, &member));
+                    }
+                }
+            }
+        }
+
+        match self.value_type {
+            ValueType::Hash => {
+                let value_cols = self.get_value_cols(after);
+                // HSET without fields is rejected by redis, and an empty hash can not exist
+                if !value_cols.is_empty() {
+                    let mut cmd = RedisCmd::new();
+                    cmd.add_str_arg("HSET");
+                    cmd.add_str_arg(&key);
+                    for (col, col_value) in value_cols {
+                        cmd.add_str_arg(col);
+                        cmd.add_str_arg(&col_value.to_option_string().unwrap_or_default());
+                    }
+                    cmds.push(cmd);
+                }
+            }
+
+            ValueType::Json => {
+                let json: serde_json::Map<String, serde_json::Value> = self
+                    .get_value_cols(after)
+                    .into_iter()
+                    .map(|(col, col_value)| (col.clone(), Self::to_json_value(col_value)))
+                    .collect();
+                let value = serde_json::Value::Object(json).to_string();
+                cmds.push(RedisCmd::from_str_args(&["SET", &key, &value]));
+            }
+
+            ValueType::String => {
+                let value = self
+                    .value
+                    .as_ref()
+                    .unwrap()
+                    .render(after)
+                    .unwrap_or_default();
+                cmds.push(RedisCmd::from_str_args(&["SET", &key, &value]));
+            }
+        }
+
+        if let Some(cmd) = self.get_expire_cmd(&key, after) {
+            cmds.push(cmd);
+        }
+
+        for index in self.indexes.iter() {
+            if let Some((index_key, member, score)) = index.render(after, &key) {
+                cmds.push(index.add_cmd(&index_key, &member, &score));
+            }
+        }
+    }
+
+    fn add_delete_cmds(&self, before: &RowValues, cmds: &mut Vec<RedisCmd>) {
+        let key = match self.key.render(before) {
+            Some(key) => key,
+            None => return,
+        };
+        cmds.push(RedisCmd::from_str_args(&["DEL", &key]));
+        for index in self.indexes.iter() {
+            if let Some((index_key, member, _)) = index.render(before, &key) {
+                cmds.push(index.remove_cmd(&index_key, &member));
+            }
+        }
+    }
+
+    fn get_value_cols<'a>(&'a self, values: &'a RowValues) -> Vec<(&'a String, &'a ColValue)> {
+        let mut cols: Vec<(&String, &ColValue)> = if self.value_cols.is_empty() {
+            values.iter().collect()
+        } else {
+            self.value_cols
+                .iter()
+                .filter_map(|col| values.get_key_value(col))
+                .collect()
+        };
+        if self.value_cols.is_empty() {
+            cols.sort_by(|a, b| a.0.cmp(b.0));
+        }
+        cols
+    }
+
+    fn get_expire_cmd(&self, key: &str, values: &RowValues) -> Option<RedisCmd> {
+        if let Some(ttl) = &self.ttl {
+            let ttl = ttl.render(values)?;
+            match ttl.trim().parse::<i64>() {
+                Ok(secs) if secs > 0 => {
+                    return Some(RedisCmd::from_str_args(&["EXPIRE", key, &secs.to_string()]))
+                }
+                Ok(_) => return None,
+                Err(_) => {
+                    log_warn!("invalid ttl: {} for redis key: {}", ttl, key);
+                    return None;
+                }
+            }
+        }
+
+        if let Some(expire_at) = &self.expire_at {
+            let expire_at = expire_at.render(values)?;
+            match Self::parse_unix_secs(&expire_at) {
+                Some(secs) => {
+                    return Some(RedisCmd::from_str_args(&[
+                        "EXPIREAT",
+                        key,
+                        &secs.to_string(),
+                    ]))
+                }
+                None => log_warn!("invalid expire_at: {} for redis key: {}", expire_at, key),
+            }
+        }
+        None
+    }
+
+    fn to_json_value(col_value: &ColValue) -> serde_json::Value {
+        match col_value {
+            // binary values are serialized as arrays by default, keep them as strings
+            ColValue::Blob(_) | ColValue::RawString(_) | ColValue::Json(_) => {
+                col_value.to_option_string().into()
+            }
+            // embed json columns as objects instead of escaped strings
+            ColValue::Json2(v) => serde_json::from_str(v).unwrap_or_else(|_| v.clone().into()),
+            _ => serde_json::to_value(col_value).unwrap_or(serde_json::Value::Null),
+        }
+    }
+
+    /// unix seconds or a datetime string like "2024-01-01 00:00:00" in UTC
+    fn parse_unix_secs(value: &str) -> Option<i64> {
+        let value = value.trim();
+        if let Ok(secs) = value.parse::<i64>() {
+            return Some(secs);
+        }
+        TimeUtil::datetime_from_utc_str(value)
+            .ok()
+            .map(|dt| dt.timestamp())
+    }
+}
+
+impl IndexMapping {
+    /// return (index_key, member, score), the member defaults to the mapped key
+    fn render(&self, values: &RowValues, key: &str) -> Option<(String, String, String)> {
+        let index_key = self.key.render(values)?;
+        let member = match &self.member {
+            Some(member) => member.render(values)?,
+            None => key.to_string(),
+        };
+        let score = match &self.score {
+            Some(score) => {
+                let score = score.render(values)?;
+                // invalid scores are rejected by redis, skip the index entry
+                score.trim().parse::<f64>().ok()?;
+                score
+            }
+            None => "0".to_string(),
+        };
+        Some((index_key, member, score))
+    }
+
+    fn add_cmd(&self, index_key: &str, member: &str, score: &str) -> RedisCmd {
+        match self.index_type {
+            IndexType::Set => RedisCmd::from_str_args(&["SADD", index_key, member]),
+            IndexType::Zset => RedisCmd::from_str_args(&["ZADD", index_key, score.trim(), member]),
+        }
+    }
+
+    fn remove_cmd(&self, index_key: &str, member: &str) -> RedisCmd {
+        match self.index_type {
+            IndexType::Set => RedisCmd::from_str_args(&["SREM", index_key, member]),
+            IndexType::Zset => RedisCmd::from_str_args(&["ZREM", index_key, member]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_row(
+        row_type: RowType,
+        before: Option<Vec<(&str, ColValue)>>,
+        after: Option<Vec<(&str, ColValue)>>,
+    ) -> RowData {
+        let to_map = |cols: Vec<(&str, ColValue)>| {
+            cols.into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect::<RowValues>()
+        };
+        RowData::new(
+            "test_db".into(),
+            "orders".into(),
+            row_type,
+            before.map(to_map),
+            after.map(to_map),
+        )
+    }
+
+    fn cmds_to_strings(cmds: Vec<RedisCmd>) -> Vec<String> {
+        cmds.iter().map(|cmd| cmd.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_template() {
+        let template = Template::parse("order:{{{id}}}:{user_id}").unwrap();
+        assert_eq!(
+            template.segments,
+            vec![
+                Segment::Literal("order:{".into()),
+                Segment::Col("id".into()),
+                Segment::Literal("}:".into()),
+                Segment::Col("user_id".into()),
+            ]
+        );
+        assert!(Template::parse("order:{id").is_err());
+        assert!(Template::parse("order:{}").is_err());
+        assert!(Template::parse("order:id}").is_err());
+
+        let mut values = RowValues::new();
+        values.insert("id".into(), ColValue::Long(1));
+        values.insert("user_id".into(), ColValue::None);
+        assert_eq!(template.render(&values), None);
+        values.insert("user_id".into(), ColValue::String("u1".into()));
+        assert_eq!(template.render(&values), Some("order:{1}:u1".into()));
+    }
+
+    #[test]
+    fn test_row_mapping() {
+        let config = r#"json:[{"db":"test_db","tb":"orders","key":"order:{id}","value_type":"json","value_cols":["amount","id"],"ttl":"{ttl}",
+            "indexes":[{"type":"set","key":"idx:order:user:{user_id}"},{"type":"zset","key":"idx:order:amount","score":"{amount}"}]}]"#;
+        let mapper = RedisRowMapper::from_config(config).unwrap();
+
+        let insert = build_row(
+            RowType::Insert,
+            None,
+            Some(vec![
+                ("id", ColValue::Long(1)),
+                ("user_id", ColValue::String("u1".into())),
+                ("amount", ColValue::Double(9.5)),
+                ("ttl", ColValue::Long(60)),
+            ]),
+        );
+        assert_eq!(
+            cmds_to_strings(mapper.to_redis_cmds(&insert).unwrap()),
+            vec![
+                r#"SET order:1 {"amount":9.5,"id":1}"#,
+                "EXPIRE order:1 60",
+                "SADD idx:order:user:u1 order:1",
+                "ZADD idx:order:amount 9.5 order:1",
+            ]
+        );
+
+        let update = build_row(
+            RowType::Update,
+            Some(vec![
+                ("id", ColValue::Long(1)),
+                ("user_id", ColValue::String("u1".into())),
+                ("amount", ColValue::Double(9.5)),
+                ("ttl", ColValue::Long(60)),
+            ]),
+            Some(vec![
+                ("id", ColValue::Long(2)),
+                ("user_id", ColValue::String("u1".into())),
+                ("amount", ColValue::Double(9.5)),
+                ("ttl", ColValue::None),
+            ]),
+        );
+        assert_eq!(
+            cmds_to_strings(mapper.to_redis_cmds(&update).unwrap()),
+            vec![
+                "DEL order:1",
+                "SREM idx:order:user:u1 order:1",
+                "ZREM idx:order:amount order:1",
+                r#"SET order:2 {"amount":9.5,"id":2}"#,
+                "SADD idx:order:user:u1 order:2",
+                "ZADD idx:order:amount 9.5 order:2",
+            ]
+        );
+
+        let delete = build_row(
+            RowType::Delete,
+            Some(vec![
+                ("id", ColValue::Long(2)),
+                ("user_id", ColValue::String("u1".into())),
+                ("amount", ColValue::Double(9.5)),
+            ]),
+            None,
+        );
+        assert_eq!(
+            cmds_to_strings(mapper.to_redis_cmds(&delete).unwrap()),
+            vec![
+                "DEL order:2",
+                "SREM idx:order:user:u1 order:2",
+                "ZREM idx:order:amount order:2",
+            ]
+        );
+
+        let mut other_tb = delete.clone();
+        other_tb.tb = "other".into();
+        assert!(mapper.to_redis_cmds(&other_tb).is_none());
+    }
+
+    #[test]
+    fn test_string_value_and_expire_at() {
+        let config = r#"json:[{"db":"test_db","tb":"*","key":"name:{id}","value_type":"string","value":"{first}.{last}","expire_at":"{expire_at}"}]"#;
+        let mapper = RedisRowMapper::from_config(config).unwrap();
+        let insert = build_row(
+            RowType::Insert,
+            None,
+            Some(vec![
+                ("id", ColValue::Long(1)),
+                ("first", ColValue::String("a".into())),
+                ("last", ColValue::String("b".into())),
+                (
+                    "expire_at",
+                    ColValue::DateTime("2024-01-01 00:00:00".into()),
+                ),
+            ]),
+        );
+        assert_eq!(
+            cmds_to_strings(mapper.to_redis_cmds(&insert).unwrap()),
+            vec!["SET name:1 a.b", "EXPIREAT name:1 1704067200"]
+        );
+
+        let config =
+            r#"json:[{"db":"test_db","tb":"orders","key":"k:{id}","value_type":"string"}]"#;
+        assert!(RedisRowMapper::from_config(config).is_err());
+    }
+
+    #[test]
+    fn test_hash_without_value_cols() {
+        let config = r#"json:[{"db":"test_db","tb":"orders","key":"order:{id}","value_cols":["amount"],
+            "indexes":[{"type":"set","key":"idx:order:user:{user_id}"}]}]"#;
+        let mapper = RedisRowMapper::from_config(config).unwrap();
+
+        let insert = build_row(
+            RowType::Insert,
+            None,
+            Some(vec![
+                ("id", ColValue::Long(1)),
+                ("user_id", ColValue::String("u1".into())),
+                ("amount", ColValue::Long(10)),
+            ]),
+        );
+        assert_eq!(
+            cmds_to_strings(mapper.to_redis_cmds(&insert).unwrap()),
+            vec!["HSET order:1 amount 10", "SADD idx:order:user:u1 order:1"]
+        );
+
+        // no HSET without fields
+        let insert = build_row(
+            RowType::Insert,
+            None,
+            Some(vec![
+                ("id", ColValue::Long(1)),
+                ("user_id", ColValue::String("u1".into())),
+            ]),
+        );
+        assert_eq!(
+            cmds_to_strings(mapper.to_redis_cmds(&insert).unwrap()),
+            vec!["SADD idx:order:user:u1 order:1"]
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::sync::RwLock;
@@ -10,6 +11,7 @@ use dt_common::meta::dt_data::DtData;
 use dt_common::meta::dt_data::DtItem;
 use dt_common::meta::rdb_meta_manager::RdbMetaManager;
 use dt_common::meta::redis::command::cmd_encoder::CmdEncoder;
+use dt_common::meta::redis::command::key_parser::KeyParser;
 use dt_common::meta::redis::redis_object::RedisCmd;
 use dt_common::meta::redis::redis_object::RedisObject;
 use dt_common::meta::redis::redis_write_method::RedisWriteMethod;
@@ -25,6 +27,8 @@ use crate::sinker::base_sinker::BaseSinker;
 use crate::Sinker;
 
 use super::entry_rewriter::EntryRewriter;
+use super::redis_event_publisher::RedisEventPublisher;
+use super::redis_row_mapper::RedisRowMapper;
 
 pub struct RedisSinker {
     pub id: String,
@@ -34,8 +38,14 @@ pub struct RedisSinker {
     pub version: f32,
     pub method: RedisWriteMethod,
     pub meta_manager: Option<RdbMetaManager>,
+    pub row_mapper: RedisRowMapper,
     pub monitor: Arc<Mutex<Monitor>>,
     pub data_marker: Option<Arc<RwLock<DataMarker>>>,
+    // if set, rdb / mongo changes are published as events instead of written as keys
+    pub event_publisher: Option<RedisEventPublisher>,
+    // redis cluster, slots owned by the node of this sinker, rows are sent to all nodes
+    // and each node only writes the keys in its slots
+    pub cluster_slots: Option<HashSet<u16>>,
 }
 
 #[async_trait]
@@ -85,7 +95,7 @@ impl RedisSinker {
         let mut cmds = Vec::new();
         for dt_item in data.iter_mut().skip(start_index).take(batch_size) {
             data_size += dt_item.dt_data.get_data_size();
-            cmds.extend_from_slice(&self.rewrite_entry(&mut dt_item.dt_data)?);
+            cmds.extend(self.item_to_redis_cmds(dt_item)?);
         }
 
         self.batch_sink(&cmds).await?;
@@ -99,13 +109,26 @@ impl RedisSinker {
 
         for dt_item in data.iter_mut() {
             data_size += dt_item.dt_data.get_data_size();
-            let cmds = self.rewrite_entry(&mut dt_item.dt_data)?;
+            let cmds = self.item_to_redis_cmds(dt_item)?;
             self.serial_sink(cmds).await?;
         }
 
         BaseSinker::update_serial_monitor(&mut self.monitor, data.len(), data_size, start_time)
     }
 
+    fn item_to_redis_cmds(&mut self, dt_item: &mut DtItem) -> anyhow::Result<Vec<RedisCmd>> {
+        // dmls are received with positions in event mode
+        if let DtData::Dml { row_data } = &mut dt_item.dt_data {
+            if let Some(event_publisher) = &self.event_publisher {
+                return Ok(vec![
+                    event_publisher.to_redis_cmd(row_data, &dt_item.position)
+                ]);
+            }
+            return Ok(Vec::new());
+        }
+        self.rewrite_entry(&mut dt_item.dt_data)
+    }
+
     fn rewrite_entry(&mut self, dt_data: &mut DtData) -> anyhow::Result<Vec<RedisCmd>> {
         let mut cmds = Vec::new();
         if let DtData::Redis { entry } = dt_data {
@@ -134,10 +157,9 @@ impl RedisSinker {
                         RedisObject::Set(ref mut obj) => EntryRewriter::rewrite_set(obj),
                         RedisObject::Hash(ref mut obj) => EntryRewriter::rewrite_hash(obj),
                         RedisObject::Zset(ref mut obj) => EntryRewriter::rewrite_zset(obj),
-                        RedisObject::Stream(ref mut obj) => Ok(obj.cmds.drain(..).collect()),
+                        RedisObject::Stream(ref mut obj) => EntryRewriter::rewrite_stream(obj),
                         RedisObject::Module(_) => {
-                            let cmd = EntryRewriter::rewrite_as_restore(entry, self.version)?;
-                            Ok(vec![cmd])
+                            EntryRewriter::rewrite_module(entry, self.version)
                         }
                         _ => bail! {Error::SinkerError("rewrite not implemented".into())},
                     }?;
@@ -166,9 +188,7 @@ impl RedisSinker {
         let mut cmds = Vec::new();
         for row_data in data.iter_mut().skip(start_index).take(batch_size) {
             data_size += row_data.data_size;
-            if let Some(cmd) = self.dml_to_redis_cmd(row_data).await? {
-                cmds.push(cmd);
-            }
+            cmds.extend(self.dml_to_redis_cmds(row_data).await?);
         }
         self.batch_sink(&cmds).await?;
 
@@ -181,17 +201,34 @@ impl RedisSinker {
 
         for row_data in data.iter_mut() {
             data_size += row_data.data_size;
-            if let Some(cmd) = self.dml_to_redis_cmd(row_data).await? {
-                self.serial_sink(vec![cmd]).await?
+            let cmds = self.dml_to_redis_cmds(row_data).await?;
+            if !cmds.is_empty() {
+                self.serial_sink(cmds).await?
             }
         }
 
         BaseSinker::update_serial_monitor(&mut self.monitor, data.len(), data_size, start_time)
     }
 
-    async fn dml_to_redis_cmd(&mut self, row_data: &RowData) -> anyhow::Result<Option<RedisCmd>> {
+    async fn dml_to_redis_cmds(&mut self, row_data: &RowData) -> anyhow::Result<Vec<RedisCmd>> {
+        let mut cmds = self.row_to_redis_cmds(row_data).await?;
+        // all cmds mapped from rows take the key as the first arg
+        if let Some(cluster_slots) = &self.cluster_slots {
+            cmds.retain(|cmd| {
+                cmd.args.len() > 1 && cluster_slots.contains(&KeyParser::calc_slot(&cmd.args[1]))
+            });
+        }
+        Ok(cmds)
+    }
+
+    async fn row_to_redis_cmds(&mut self, row_data: &RowData) -> anyhow::Result<Vec<RedisCmd>> {
+        // tables with row_mappings configured
+        if let Some(cmds) = self.row_mapper.to_redis_cmds(row_data) {
+            return Ok(cmds);
+        }
+
         if self.meta_manager.is_none() {
-            return Ok(None);
+            return Ok(Vec::new());
         }
 
         let tb_meta = self
@@ -203,7 +240,7 @@ impl RedisSinker {
 
         // no single primary / unique key exists, do not sink to redis
         if tb_meta.order_col.is_none() {
-            return Ok(None);
+            return Ok(Vec::new());
         }
 
         let key = if let Some(col) = &tb_meta.order_col {
@@ -219,10 +256,10 @@ impl RedisSinker {
             if let Some(v) = v.to_option_string() {
                 format!("{}.{}.{}", row_data.schema, row_data.tb, v)
             } else {
-                return Ok(None);
+                return Ok(Vec::new());
             }
         } else {
-            return Ok(None);
+            return Ok(Vec::new());
         };
 
         let mut cmd = RedisCmd::new();
@@ -244,7 +281,7 @@ impl RedisSinker {
                 cmd.add_str_arg(&key);
             }
         }
-        Ok(Some(cmd))
+        Ok(vec![cmd])
     }
 }
 
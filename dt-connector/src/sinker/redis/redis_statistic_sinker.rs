@@ -12,11 +12,14 @@ use serde_json::json;
 
 use crate::Sinker;
 
+use super::redis_memory_report::RedisMemoryReport;
+
 pub struct RedisStatisticSinker {
     pub statistic_type: RedisStatisticType,
     pub monitor: Arc<Mutex<Monitor>>,
     pub data_size_threshold: usize,
     pub freq_threshold: i64,
+    pub memory_report: Option<Arc<Mutex<RedisMemoryReport>>>,
 }
 
 #[derive(Serialize)]
@@ -64,9 +67,22 @@ impl Sinker for RedisStatisticSinker {
                         };
                         log_statistic!("{}", json!(info).to_string());
                     }
+
+                    RedisStatisticType::MemoryReport => {
+                        if let Some(report) = &self.memory_report {
+                            report.lock().unwrap().add_entry(entry)?;
+                        }
+                    }
                 }
             }
         }
         Ok(())
     }
+
+    async fn close(&mut self) -> anyhow::Result<()> {
+        if let Some(report) = &self.memory_report {
+            report.lock().unwrap().finish()?;
+        }
+        Ok(())
+    }
 }
@@ -33,6 +33,7 @@ const TIMESTAMP_COL_NAME: &str = "_ape_dts_timestamp";
 pub struct StarRocksSinker {
     pub batch_size: usize,
     pub http_client: Client,
+    pub scheme: String,
     pub host: String,
     pub port: String,
     pub username: String,
@@ -136,8 +137,8 @@ impl StarRocksSinker {
         let body = json!(load_data).to_string();
         // do stream load
         let url = format!(
-            "http://{}:{}/api/{}/{}/_stream_load",
-            self.host, self.port, db, tb
+            "{}://{}:{}/api/{}/{}/_stream_load",
+            self.scheme, self.host, self.port, db, tb
         );
         let request = self.build_request(&url, op, &body)?;
         let response = self.http_client.execute(request).await?;
@@ -146,7 +147,7 @@ impl StarRocksSinker {
         Ok(data_size)
     }
 
-    fn convert_row_data(row_data: &mut RowData, tb_meta: &MysqlTbMeta) -> anyhow::Result<()> {
+    pub fn convert_row_data(row_data: &mut RowData, tb_meta: &MysqlTbMeta) -> anyhow::Result<()> {
         if let Some(before) = &mut row_data.before {
             Self::convert_col_values(before, tb_meta)?;
         }
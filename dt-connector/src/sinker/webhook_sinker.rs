@@ -0,0 +1,419 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::bail;
+use async_trait::async_trait;
+use chrono::Utc;
+use dt_common::{
+    config::config_enums::WebhookFormatEnum,
+    error::Error,
+    log_warn,
+    meta::{avro::avro_converter::AvroConverter, ddl_meta::ddl_data::DdlData, row_data::RowData},
+    monitor::monitor::Monitor,
+};
+use openssl::{hash::MessageDigest, pkey::PKey, sign::Signer};
+use reqwest::{header, Client, Method, Response, StatusCode};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::{call_batch_fn, rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Sinker};
+
+const REQUEST_ID_HEADER: &str = "X-Ape-Dts-Request-Id";
+const TIMESTAMP_HEADER: &str = "X-Ape-Dts-Timestamp";
+const SIGNATURE_HEADER: &str = "X-Ape-Dts-Signature";
+const MAX_RETRY_INTERVAL_MS: u64 = 60_000;
+
+#[derive(Clone)]
+pub struct WebhookSinker {
+    pub http_client: Client,
+    // default endpoint for tables not in endpoint_map of router
+    pub url: String,
+    pub batch_size: usize,
+    pub format: WebhookFormatEnum,
+    pub avro_converter: AvroConverter,
+    pub router: RdbRouter,
+    pub hmac_secret: String,
+    pub max_retries: u32,
+    pub retry_interval_ms: u64,
+    pub monitor: Arc<Mutex<Monitor>>,
+}
+
+#[async_trait]
+impl Sinker for WebhookSinker {
+    async fn sink_dml(&mut self, mut data: Vec<RowData>, _batch: bool) -> anyhow::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        call_batch_fn!(self, data, Self::batch_sink);
+        Ok(())
+    }
+
+    async fn sink_ddl(&mut self, data: Vec<DdlData>, _batch: bool) -> anyhow::Result<()> {
+        let mut items = Vec::new();
+        for ddl_data in data {
+            let (schema, tb) = ddl_data.get_schema_tb();
+            let endpoint = self.get_endpoint(&schema, &tb);
+            let item = match self.format {
+                WebhookFormatEnum::Json => json!({
+                    "schema": schema,
+                    "tb": tb,
+                    "operation": "ddl",
+                    "ddl_type": ddl_data.ddl_type.to_string(),
+                    "query": ddl_data.to_sql(),
+                }),
+                WebhookFormatEnum::Avro => {
+                    json!(self.avro_converter.ddl_data_to_avro_value(ddl_data).await?)
+                }
+            };
+            items.push((endpoint, item));
+        }
+        self.send_items(items).await
+    }
+
+    async fn refresh_meta(&mut self, data: Vec<DdlData>) -> anyhow::Result<()> {
+        self.avro_converter.refresh_meta(&data);
+        Ok(())
+    }
+}
+
+impl WebhookSinker {
+    async fn batch_sink(
+        &mut self,
+        data: &mut [RowData],
+        start_index: usize,
+        batch_size: usize,
+    ) -> anyhow::Result<()> {
+        let start_time = Instant::now();
+        let mut data_size = 0;
+
+        let mut items = Vec::new();
+        for row_data in data.iter_mut().skip(start_index).take(batch_size) {
+            data_size += row_data.data_size;
+
+            row_data.convert_raw_string();
+            let endpoint = self.get_endpoint(&row_data.schema, &row_data.tb);
+            let item = match self.format {
+                WebhookFormatEnum::Json => json!({
+                    "schema": row_data.schema,
+                    "tb": row_data.tb,
+                    "operation": row_data.row_type.to_string(),
+                    "before": row_data.before,
+                    "after": row_data.after,
+                }),
+                WebhookFormatEnum::Avro => json!(
+                    self.avro_converter
+                        .row_data_to_avro_value(row_data.clone())
+                        .await?
+                ),
+            };
+            items.push((endpoint, item));
+        }
+        self.send_items(items).await?;
+
+        BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, data_size, start_time)
+    }
+
+    fn get_endpoint(&self, schema: &str, tb: &str) -> String {
+        self.router
+            .get_endpoint(schema, tb)
+            .unwrap_or(&self.url)
+            .to_string()
+    }
+
+    /// Items are grouped by endpoints, items of the same endpoint are sent in one request and keep their order.
+    /// Returns only after all requests succeeded, so positions are committed after 2xx responses.
+    async fn send_items(&self, items: Vec<(String, Value)>) -> anyhow::Result<()> {
+        let mut endpoints = Vec::new();
+        let mut endpoint_items: HashMap<String, Vec<Value>> = HashMap::new();
+        for (endpoint, item) in items {
+            if !endpoint_items.contains_key(&endpoint) {
+                endpoints.push(endpoint.clone());
+            }
+            endpoint_items.entry(endpoint).or_default().push(item);
+        }
+
+        for endpoint in endpoints {
+            let items = endpoint_items.remove(&endpoint).unwrap();
+            let body = json!({ "data": items }).to_string();
+            self.send_with_retry(&endpoint, body).await?;
+        }
+        Ok(())
+    }
+
+    /// Retry with exponential backoff on 5xx, 429 and connection errors, other responses fail immediately.
+    async fn send_with_retry(&self, endpoint: &str, body: String) -> anyhow::Result<()> {
+        // the same request id for all retries, receivers may use it to deduplicate
+        let request_id = Uuid::new_v4().to_string();
+        let mut retry_interval_ms = self.retry_interval_ms;
+        let mut retries = 0;
+        loop {
+            let request = self.build_request(endpoint, &request_id, &body)?;
+            let retry_after = match self.http_client.execute(request).await {
+                Ok(response) => {
+                    let status_code = response.status();
+                    if status_code.is_success() {
+                        return Ok(());
+                    }
+
+                    let retry_after = Self::get_retry_after(&response);
+                    let response_text = response.text().await.unwrap_or_default();
+                    let retryable = status_code.is_server_error()
+                        || status_code == StatusCode::TOO_MANY_REQUESTS;
+                    if !retryable || retries >= self.max_retries {
+                        bail! {Error::HttpError(format!(
+                            "webhook request failed, endpoint: {}, status_code: {}, response_text: {:?}",
+                            endpoint, status_code, response_text
+                        ))}
+                    }
+                    log_warn!(
+                        "webhook request failed, will retry, endpoint: {}, status_code: {}, retries: {}",
+                        endpoint,
+                        status_code,
+                        retries
+                    );
+                    retry_after
+                }
+
+                Err(error) => {
+                    if retries >= self.max_retries {
+                        bail! {Error::HttpError(format!(
+                            "webhook request failed, endpoint: {}, error: {}",
+                            endpoint, error
+                        ))}
+                    }
+                    log_warn!(
+                        "webhook request failed, will retry, endpoint: {}, error: {}, retries: {}",
+                        endpoint,
+                        error,
+                        retries
+                    );
+                    None
+                }
+            };
+
+            let wait = retry_after.unwrap_or(Duration::from_millis(retry_interval_ms));
+            tokio::time::sleep(wait).await;
+            retries += 1;
+            retry_interval_ms = (retry_interval_ms * 2).min(MAX_RETRY_INTERVAL_MS);
+        }
+    }
+
+    fn build_request(
+        &self,
+        endpoint: &str,
+        request_id: &str,
+        body: &str,
+    ) -> anyhow::Result<reqwest::Request> {
+        let mut request = self
+            .http_client
+            .request(Method::POST, endpoint)
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(REQUEST_ID_HEADER, request_id);
+
+        if !self.hmac_secret.is_empty() {
+            // signed for every attempt, receivers may reject requests with stale timestamps
+            let timestamp = Utc::now().timestamp().to_string();
+            let signature = Self::sign(&self.hmac_secret, &timestamp, body)?;
+            request = request
+                .header(TIMESTAMP_HEADER, &timestamp)
+                .header(SIGNATURE_HEADER, format!("sha256={}", signature));
+        }
+        Ok(request.body(body.to_string()).build()?)
+    }
+
+    /// Hex of HMAC-SHA256 over "{timestamp}.{body}".
+    fn sign(secret: &str, timestamp: &str, body: &str) -> anyhow::Result<String> {
+        let key = PKey::hmac(secret.as_bytes())?;
+        let mut signer = Signer::new(MessageDigest::sha256(), &key)?;
+        signer.update(timestamp.as_bytes())?;
+        signer.update(b".")?;
+        signer.update(body.as_bytes())?;
+        Ok(hex::encode(signer.sign_to_vec()?))
+    }
+
+    /// Retry-After in seconds, the http-date form is not supported.
+    fn get_retry_after(response: &Response) -> Option<Duration> {
+        response
+            .headers()
+            .get(header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(Self::parse_retry_after)
+    }
+
+    /// Capped by MAX_RETRY_INTERVAL_MS, a huge value from the receiver should not block the task.
+    fn parse_retry_after(value: &str) -> Option<Duration> {
+        value
+            .trim()
+            .parse::<u64>()
+            .ok()
+            .map(|secs| Duration::from_secs(secs).min(Duration::from_millis(MAX_RETRY_INTERVAL_MS)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dt_common::{
+        config::{config_enums::DbType, router_config::RouterConfig},
+        meta::{col_value::ColValue, row_type::RowType},
+    };
+
+    use crate::sinker::mock_http_server::MockHttpServer;
+
+    use super::*;
+
+    fn mock_sinker(url: &str, endpoint_map: &str) -> WebhookSinker {
+        let config = RouterConfig::Rdb {
+            schema_map: String::new(),
+            tb_map: String::new(),
+            col_map: String::new(),
+            topic_map: String::new(),
+            key_prefix_map: String::new(),
+            endpoint_map: endpoint_map.into(),
+        };
+        WebhookSinker {
+            http_client: Client::new(),
+            url: url.into(),
+            batch_size: 2,
+            format: WebhookFormatEnum::Json,
+            avro_converter: AvroConverter::new(None, false),
+            router: RdbRouter::from_config(&config, &DbType::Mysql).unwrap(),
+            hmac_secret: "secret".into(),
+            max_retries: 2,
+            retry_interval_ms: 10,
+            monitor: Arc::new(Mutex::new(Monitor::new("sinker", 1, 1, 1))),
+        }
+    }
+
+    fn mock_row_data(tb: &str, id: i32) -> RowData {
+        let after = HashMap::from([("id".to_string(), ColValue::Long(id))]);
+        RowData::new("db_1".into(), tb.into(), RowType::Insert, None, Some(after))
+    }
+
+    fn get_body(request: &str) -> Value {
+        let (_, body) = request.split_once("\r\n\r\n").unwrap();
+        serde_json::from_str(body).unwrap()
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(
+            WebhookSinker::parse_retry_after(" 3 "),
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(
+            WebhookSinker::parse_retry_after("86400"),
+            Some(Duration::from_millis(MAX_RETRY_INTERVAL_MS))
+        );
+        assert_eq!(
+            WebhookSinker::parse_retry_after(&u64::MAX.to_string()),
+            Some(Duration::from_millis(MAX_RETRY_INTERVAL_MS))
+        );
+        assert_eq!(
+            WebhookSinker::parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_sign() {
+        // echo -n "1700000000.{}" | openssl dgst -sha256 -hmac secret
+        assert_eq!(
+            WebhookSinker::sign("secret", "1700000000", "{}").unwrap(),
+            "b8569b78799ff9e3cbff0fc2d63a33a2b57f3282abd07c37ae5e8e7d79a5f163"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sink_dml() {
+        let ok = MockHttpServer::response("200 OK", &[], "{}");
+        let unavailable = MockHttpServer::response("503 Service Unavailable", &[], "{}");
+        let too_many =
+            MockHttpServer::response("429 Too Many Requests", &[("Retry-After", "0")], "{}");
+        let responses = vec![unavailable, too_many, ok.clone(), ok.clone(), ok];
+        let (url, handle) = MockHttpServer::start(responses).await;
+
+        // tb_2 is routed to another path of the same server
+        let endpoint_map = format!(
+            r#"json:[{{"db":"db_1","tb":"tb_2","endpoint":"{}/tb_2"}}]"#,
+            url
+        );
+        let mut sinker = mock_sinker(&format!("{}/default", url), &endpoint_map);
+        let data = vec![
+            mock_row_data("tb_1", 1),
+            mock_row_data("tb_2", 2),
+            mock_row_data("tb_1", 3),
+        ];
+        sinker.sink_dml(data, true).await.unwrap();
+
+        let requests = handle.await.unwrap();
+        // batch 1: tb_1 (retried twice), tb_2; batch 2: tb_1
+        assert_eq!(requests.len(), 5);
+        assert!(requests[0].starts_with("POST /default HTTP/1.1"));
+        assert!(requests[2].starts_with("POST /default HTTP/1.1"));
+        assert_eq!(
+            get_body(&requests[2]),
+            json!({"data": [{"schema": "db_1", "tb": "tb_1", "operation": "insert", "before": null, "after": {"id": 1}}]})
+        );
+        assert!(requests[3].starts_with("POST /tb_2 HTTP/1.1"));
+        assert_eq!(get_body(&requests[3])["data"][0]["after"], json!({"id": 2}));
+        assert!(requests[4].starts_with("POST /default HTTP/1.1"));
+        assert_eq!(get_body(&requests[4])["data"][0]["after"], json!({"id": 3}));
+
+        // retries share the request id, every attempt is signed
+        let get_header = |request: &str, name: &str| -> String {
+            request
+                .lines()
+                .find_map(|l| {
+                    let (k, v) = l.split_once(':')?;
+                    if k.eq_ignore_ascii_case(name) {
+                        Some(v.trim().to_string())
+                    } else {
+                        None
+                    }
+                })
+                .unwrap()
+        };
+        let request_id = get_header(&requests[0], REQUEST_ID_HEADER);
+        assert_eq!(get_header(&requests[2], REQUEST_ID_HEADER), request_id);
+        assert_ne!(get_header(&requests[3], REQUEST_ID_HEADER), request_id);
+        for request in requests.iter() {
+            let timestamp = get_header(request, TIMESTAMP_HEADER);
+            let (_, body) = request.split_once("\r\n\r\n").unwrap();
+            let signature = WebhookSinker::sign("secret", &timestamp, body).unwrap();
+            assert_eq!(
+                get_header(request, SIGNATURE_HEADER),
+                format!("sha256={}", signature)
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sink_dml_failed() {
+        // 4xx is not retried
+        let bad_request = MockHttpServer::response("400 Bad Request", &[], "{}");
+        let (url, handle) = MockHttpServer::start(vec![bad_request]).await;
+        let mut sinker = mock_sinker(&url, "");
+        let err = sinker
+            .sink_dml(vec![mock_row_data("tb_1", 1)], true)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("400"));
+        assert_eq!(handle.await.unwrap().len(), 1);
+
+        // retries exhausted
+        let unavailable = MockHttpServer::response("503 Service Unavailable", &[], "{}");
+        let (url, handle) = MockHttpServer::start(vec![unavailable; 3]).await;
+        let mut sinker = mock_sinker(&url, "");
+        assert!(sinker
+            .sink_dml(vec![mock_row_data("tb_1", 1)], true)
+            .await
+            .is_err());
+        assert_eq!(handle.await.unwrap().len(), 3);
+    }
+}
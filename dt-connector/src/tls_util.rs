@@ -0,0 +1,138 @@
+use dt_common::config::{
+    config_enums::SaslMechanism,
+    tls_config::{SaslConfig, TlsConfig},
+};
+use kafka::client::SecurityConfig;
+use openssl::ssl::{SslConnector, SslConnectorBuilder, SslFiletype, SslMethod, SslVerifyMode};
+use postgres_openssl::MakeTlsConnector;
+use rdkafka::ClientConfig;
+use reqwest::redirect::Policy;
+use std::time::Duration;
+
+pub struct TlsUtil {}
+
+impl TlsUtil {
+    pub fn build_ssl_connector(tls: &TlsConfig) -> anyhow::Result<SslConnectorBuilder> {
+        let mut builder = SslConnector::builder(SslMethod::tls())?;
+        if !tls.ca_file.is_empty() {
+            builder.set_ca_file(&tls.ca_file)?;
+        }
+        if !tls.cert_file.is_empty() {
+            builder.set_certificate_chain_file(&tls.cert_file)?;
+        }
+        if !tls.key_file.is_empty() {
+            builder.set_private_key_file(&tls.key_file, SslFiletype::PEM)?;
+        }
+        if !tls.verify_cert() {
+            builder.set_verify(SslVerifyMode::NONE);
+        }
+        Ok(builder)
+    }
+
+    /// For the pg replication connection, which is built by tokio_postgres instead of sqlx.
+    pub fn build_pg_tls_connector(tls: &TlsConfig) -> anyhow::Result<MakeTlsConnector> {
+        let mut connector = MakeTlsConnector::new(Self::build_ssl_connector(tls)?.build());
+        if !tls.verify_hostname() {
+            connector.set_callback(|config, _| {
+                config.set_verify_hostname(false);
+                Ok(())
+            });
+        }
+        Ok(connector)
+    }
+
+    /// For the redis replication connection, which is built on async_std::net::TcpStream.
+    pub fn build_async_tls_connector(
+        tls: &TlsConfig,
+    ) -> anyhow::Result<async_native_tls::TlsConnector> {
+        let mut connector = async_native_tls::TlsConnector::new()
+            .danger_accept_invalid_certs(!tls.verify_cert())
+            .danger_accept_invalid_hostnames(!tls.verify_hostname());
+        if let Some(ca) = tls.read_ca()? {
+            connector =
+                connector.add_root_certificate(async_native_tls::Certificate::from_pem(&ca)?);
+        }
+        if let (Some(cert), Some(key)) = (tls.read_cert()?, tls.read_key()?) {
+            connector = connector.identity(async_native_tls::Identity::from_pkcs8(&cert, &key)?);
+        }
+        Ok(connector)
+    }
+
+    pub fn build_kafka_security_config(tls: &TlsConfig) -> anyhow::Result<SecurityConfig> {
+        let connector = Self::build_ssl_connector(tls)?.build();
+        Ok(SecurityConfig::new(connector).with_hostname_verification(tls.verify_hostname()))
+    }
+
+    pub fn set_rdkafka_security(config: &mut ClientConfig, tls: &TlsConfig, sasl: &SaslConfig) {
+        let enable_sasl = sasl.mechanism != SaslMechanism::None;
+        let protocol = match (tls.is_enabled(), enable_sasl) {
+            (false, false) => "plaintext",
+            (true, false) => "ssl",
+            (false, true) => "sasl_plaintext",
+            (true, true) => "sasl_ssl",
+        };
+        config.set("security.protocol", protocol);
+
+        if tls.is_enabled() {
+            if !tls.ca_file.is_empty() {
+                config.set("ssl.ca.location", &tls.ca_file);
+            }
+            if !tls.cert_file.is_empty() {
+                config.set("ssl.certificate.location", &tls.cert_file);
+            }
+            if !tls.key_file.is_empty() {
+                config.set("ssl.key.location", &tls.key_file);
+            }
+            config.set(
+                "enable.ssl.certificate.verification",
+                tls.verify_cert().to_string(),
+            );
+            let algorithm = if tls.verify_hostname() {
+                "https"
+            } else {
+                "none"
+            };
+            config.set("ssl.endpoint.identification.algorithm", algorithm);
+        }
+
+        if enable_sasl {
+            config.set("sasl.mechanism", sasl.mechanism.to_string());
+            config.set("sasl.username", &sasl.username);
+            config.set("sasl.password", &sasl.password);
+        }
+    }
+
+    /// For http based sinkers: starrocks stream load and clickhouse.
+    pub fn build_http_client(tls: &TlsConfig) -> anyhow::Result<reqwest::Client> {
+        Ok(Self::http_client_builder(tls)?.build()?)
+    }
+
+    /// For http based sinkers which should not wait on a stuck endpoint forever: webhook.
+    pub fn build_http_client_with_timeout(
+        tls: &TlsConfig,
+        timeout_secs: u64,
+    ) -> anyhow::Result<reqwest::Client> {
+        Ok(Self::http_client_builder(tls)?
+            .timeout(Duration::from_secs(timeout_secs))
+            .build()?)
+    }
+
+    fn http_client_builder(tls: &TlsConfig) -> anyhow::Result<reqwest::ClientBuilder> {
+        let custom = Policy::custom(|attempt| attempt.follow());
+        let mut builder = reqwest::Client::builder()
+            .http1_title_case_headers()
+            .redirect(custom);
+        if tls.is_enabled() {
+            if let Some(ca) = tls.read_ca()? {
+                builder = builder.add_root_certificate(reqwest::Certificate::from_pem(&ca)?);
+            }
+            if let (Some(cert), Some(key)) = (tls.read_cert()?, tls.read_key()?) {
+                builder = builder.identity(reqwest::Identity::from_pkcs8_pem(&cert, &key)?);
+            }
+            builder = builder
+                .danger_accept_invalid_certs(!tls.verify_cert())
+                .danger_accept_invalid_hostnames(!tls.verify_hostname());
+        }
+        Ok(builder)
+    }
+}
@@ -0,0 +1,113 @@
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{BufRead, BufReader},
+    path::PathBuf,
+};
+
+use anyhow::Context;
+use dt_connector::check_log::{check_log::CheckLog, log_type::LogType};
+
+use crate::table_printer::TablePrinter;
+
+#[derive(Default)]
+pub struct CheckReport {
+    tbs: BTreeMap<(String, String), TbReport>,
+}
+
+#[derive(Default)]
+struct TbReport {
+    miss_count: u64,
+    diff_count: u64,
+    diff_cols: BTreeMap<String, u64>,
+}
+
+impl CheckReport {
+    pub fn from_dir(check_log_dir: &str) -> anyhow::Result<Self> {
+        let mut me = Self::default();
+        for file in Self::list_check_logs(check_log_dir)? {
+            let reader = BufReader::new(
+                File::open(&file)
+                    .with_context(|| format!("failed to open: [{}]", file.display()))?,
+            );
+            for (i, line) in reader.lines().enumerate() {
+                let line = line?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let check_log: CheckLog = serde_json::from_str(&line).with_context(|| {
+                    format!("invalid check log in [{}], line: {}", file.display(), i + 1)
+                })?;
+                me.add(check_log);
+            }
+        }
+        Ok(me)
+    }
+
+    pub fn is_consistent(&self) -> bool {
+        self.tbs.is_empty()
+    }
+
+    pub fn print(&self) {
+        let mut rows = Vec::new();
+        let (mut miss_count, mut diff_count) = (0, 0);
+        for ((schema, tb), report) in self.tbs.iter() {
+            miss_count += report.miss_count;
+            diff_count += report.diff_count;
+            let diff_cols: Vec<String> = report
+                .diff_cols
+                .iter()
+                .map(|(col, count)| format!("{}({})", col, count))
+                .collect();
+            rows.push(vec![
+                schema.clone(),
+                tb.clone(),
+                report.miss_count.to_string(),
+                report.diff_count.to_string(),
+                diff_cols.join(", "),
+            ]);
+        }
+
+        TablePrinter::print(&["schema", "tb", "miss", "diff", "diff_cols"], &rows);
+        println!(
+            "\ninconsistent tables: {}, miss rows: {}, diff rows: {}",
+            self.tbs.len(),
+            miss_count,
+            diff_count
+        );
+    }
+
+    fn add(&mut self, check_log: CheckLog) {
+        let key = (check_log.schema, check_log.tb);
+        match check_log.log_type {
+            LogType::Miss => self.tbs.entry(key).or_default().miss_count += 1,
+            LogType::Diff => {
+                let report = self.tbs.entry(key).or_default();
+                report.diff_count += 1;
+                for col in check_log.diff_col_values.into_keys() {
+                    *report.diff_cols.entry(col).or_default() += 1;
+                }
+            }
+            LogType::Unknown => {}
+        }
+    }
+
+    fn list_check_logs(check_log_dir: &str) -> anyhow::Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        let entries = fs::read_dir(check_log_dir)
+            .with_context(|| format!("failed to list files in dir: [{}]", check_log_dir))?;
+        for entry in entries {
+            let path = entry?.path();
+            let name = path
+                .file_name()
+                .and_then(|i| i.to_str())
+                .unwrap_or_default();
+            // miss.log, diff.log and their rolled files, extra.log is not in json
+            if path.is_file() && (name.starts_with("miss") || name.starts_with("diff")) {
+                files.push(path);
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+}
@@ -0,0 +1,184 @@
+use std::path::Path;
+
+use clap::{error::ErrorKind, Args, Parser, Subcommand};
+use dt_precheck::config::task_config::PrecheckTaskConfig;
+
+#[derive(Parser)]
+#[command(
+    name = "ape-dts",
+    about = "ape-dts: data transfer between databases",
+    version
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+impl Cli {
+    /// Parses subcommands, falls back to the legacy form: ape-dts task_config.ini, only if
+    /// the single argument is not a subcommand but an existing file. The legacy form runs
+    /// prechecks if the config has a [precheck] section, otherwise runs the task.
+    pub fn parse_args(args: Vec<String>) -> Self {
+        match Self::try_parse_from(&args) {
+            Ok(cli) => cli,
+            Err(err)
+                if err.kind() == ErrorKind::InvalidSubcommand
+                    && args.len() == 2
+                    && Path::new(&args[1]).is_file() =>
+            {
+                let config = args[1].clone();
+                let command = if PrecheckTaskConfig::has_precheck_section(&config) {
+                    Command::Precheck { config }
+                } else {
+                    Command::Run { config }
+                };
+                Self { command }
+            }
+            Err(err) => err.exit(),
+        }
+    }
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Run a task
+    Run {
+        /// Path of the task config, in ini / yaml / toml
+        config: String,
+    },
+
+    /// Run prechecks against source and sink
+    Precheck {
+        /// Path of the task config, in ini / yaml / toml
+        config: String,
+    },
+
+    /// Parse the task config and test connectivity without moving data
+    Validate {
+        /// Path of the task config, in ini / yaml / toml
+        config: String,
+    },
+
+    /// Inspect or generate task positions
+    Position {
+        #[command(subcommand)]
+        command: PositionCommand,
+    },
+
+    /// Summarize miss / diff logs generated by a check task
+    CheckReport {
+        /// The check_log_dir of the check task
+        check_log_dir: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PositionCommand {
+    /// Decode position.log / finished.log into a table
+    Show {
+        /// A task log dir containing position.log / finished.log, or a single position file
+        path: String,
+
+        /// Show every line instead of the latest position of each table / type
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Write a resume config to start a cdc task from the given position
+    Set {
+        /// Path of the resume config, refer to [resumer] resume_config_file
+        #[arg(long, short)]
+        output: String,
+
+        #[command(subcommand)]
+        position: SetPosition,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SetPosition {
+    /// MySQL binlog file + position, or gtid set
+    Mysql(MysqlPositionArgs),
+
+    /// Postgres lsn
+    Pg {
+        /// E.g. 0/5D65CB0
+        #[arg(long)]
+        lsn: String,
+    },
+
+    /// Mongo change stream resume token, or oplog operation time
+    Mongo(MongoPositionArgs),
+}
+
+#[derive(Args)]
+pub struct MysqlPositionArgs {
+    /// E.g. mysql-bin.000004
+    #[arg(long, requires = "binlog_position")]
+    pub binlog_file: Option<String>,
+
+    #[arg(long, requires = "binlog_file")]
+    pub binlog_position: Option<u32>,
+
+    /// E.g. 9663a096-8adc-11ef-b617-0242ac110002:1-3112
+    #[arg(long, required_unless_present = "binlog_file")]
+    pub gtid_set: Option<String>,
+}
+
+#[derive(Args)]
+pub struct MongoPositionArgs {
+    /// E.g. {"_data":"8267..."}
+    #[arg(long, required_unless_present = "operation_time")]
+    pub resume_token: Option<String>,
+
+    /// Seconds since epoch
+    #[arg(long)]
+    pub operation_time: Option<u32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::*;
+
+    fn parse(args: &[&str]) -> Command {
+        Cli::parse_args(args.iter().map(|i| i.to_string()).collect()).command
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let dir = env::temp_dir();
+        let task_config = dir.join("ape_dts_test_cli_task.ini");
+        let precheck_config = dir.join("ape_dts_test_cli_precheck.ini");
+        fs::write(&task_config, "[extractor]\ndb_type=mysql\n").unwrap();
+        fs::write(
+            &precheck_config,
+            "[extractor]\ndb_type=mysql\n[precheck]\ndo_struct_init=true\ndo_cdc=false\n",
+        )
+        .unwrap();
+        let task_config = task_config.to_str().unwrap();
+        let precheck_config = precheck_config.to_str().unwrap();
+
+        assert!(matches!(
+            parse(&["ape-dts", "run", precheck_config]),
+            Command::Run { config } if config == precheck_config
+        ));
+        assert!(matches!(
+            parse(&["ape-dts", "validate", task_config]),
+            Command::Validate { config } if config == task_config
+        ));
+        // legacy form
+        assert!(matches!(
+            parse(&["ape-dts", task_config]),
+            Command::Run { config } if config == task_config
+        ));
+        assert!(matches!(
+            parse(&["ape-dts", precheck_config]),
+            Command::Precheck { config } if config == precheck_config
+        ));
+
+        fs::remove_file(task_config).unwrap();
+        fs::remove_file(precheck_config).unwrap();
+    }
+}
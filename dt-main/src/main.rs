@@ -1,21 +1,179 @@
-use std::env;
+mod check_report;
+mod cli;
+mod position_tool;
+mod table_printer;
 
-use dt_precheck::{config::task_config::PrecheckTaskConfig, do_precheck};
-use dt_task::task_runner::TaskRunner;
+use std::{env, process::ExitCode};
+
+use cli::{Cli, Command, PositionCommand, SetPosition};
+use dt_common::{
+    config::{config_enums::ExtractType, task_config::TaskConfig},
+    meta::position::Position,
+    rdb_filter::RdbFilter,
+};
+use dt_connector::rdb_router::RdbRouter;
+use dt_precheck::{
+    builder::prechecker_builder::PrecheckerBuilder,
+    config::{precheck_config::PrecheckConfig, task_config::PrecheckTaskConfig},
+};
+use dt_task::{plugin_registry::PluginRegistry, task_runner::TaskRunner};
+
+use crate::{check_report::CheckReport, position_tool::PositionTool};
+
+// exit codes, clap also exits with 2 for invalid arguments
+const EXIT_OK: u8 = 0;
+const EXIT_FAILED: u8 = 1;
+const EXIT_INVALID_CONFIG: u8 = 2;
+const EXIT_CHECK_NOT_PASSED: u8 = 3;
+const EXIT_DATA_INCONSISTENT: u8 = 4;
 
 #[tokio::main]
-async fn main() {
-    let args: Vec<String> = env::args().collect();
-    if args.len() < 2 {
-        panic!("no task_config provided in args");
+async fn main() -> ExitCode {
+    let cli = Cli::parse_args(env::args().collect());
+    let code = match cli.command {
+        Command::Run { config } => run(&config).await,
+        Command::Precheck { config } => precheck(&config).await,
+        Command::Validate { config } => validate(&config).await,
+        Command::Position { command } => position(command),
+        Command::CheckReport { check_log_dir } => check_report(&check_log_dir),
+    };
+    ExitCode::from(code)
+}
+
+async fn run(config: &str) -> u8 {
+    let runner = match TaskRunner::new(config) {
+        Ok(runner) => runner,
+        Err(err) => return fail(EXIT_INVALID_CONFIG, err),
+    };
+    match runner.start_task(true).await {
+        Ok(_) => EXIT_OK,
+        Err(err) => fail(EXIT_FAILED, err),
     }
+}
 
-    let task_config = args[1].clone();
+async fn precheck(config: &str) -> u8 {
+    let task_config = match TaskConfig::new(config) {
+        Ok(task_config) => task_config,
+        Err(err) => return fail(EXIT_INVALID_CONFIG, err),
+    };
+    // [precheck] is optional when running the precheck subcommand
+    let precheck_config = if PrecheckTaskConfig::has_precheck_section(config) {
+        match PrecheckTaskConfig::new(config) {
+            Ok(precheck_task_config) => precheck_task_config.precheck,
+            Err(err) => return fail(EXIT_INVALID_CONFIG, err),
+        }
+    } else {
+        PrecheckConfig {
+            do_struct_init: matches!(
+                task_config.extractor_basic.extract_type,
+                ExtractType::Struct
+            ),
+            do_cdc: matches!(
+                task_config.extractor_basic.extract_type,
+                ExtractType::Cdc | ExtractType::SnapshotAndCdc
+            ),
+        }
+    };
 
-    if PrecheckTaskConfig::new(&task_config).is_ok() {
-        do_precheck(&task_config).await;
+    let builder = PrecheckerBuilder::build(precheck_config, task_config);
+    match builder.verify_check_result().await {
+        Ok(_) => {
+            println!("precheck passed.");
+            EXIT_OK
+        }
+        Err(err) => {
+            println!("precheck not passed.");
+            fail(EXIT_CHECK_NOT_PASSED, err)
+        }
+    }
+}
+
+async fn validate(config: &str) -> u8 {
+    let task_config = match parse_config(config) {
+        Ok(task_config) => task_config,
+        Err(err) => return fail(EXIT_INVALID_CONFIG, err),
+    };
+    println!("config is valid.");
+
+    let builder = PrecheckerBuilder::build(PrecheckConfig::default(), task_config);
+    match builder.check_connection().await {
+        Ok(true) => {
+            println!("validate passed.");
+            EXIT_OK
+        }
+        Ok(false) => {
+            println!("validate not passed.");
+            EXIT_CHECK_NOT_PASSED
+        }
+        Err(err) => fail(EXIT_CHECK_NOT_PASSED, err),
+    }
+}
+
+fn parse_config(config: &str) -> anyhow::Result<TaskConfig> {
+    let task_config = TaskConfig::new(config)?;
+    // dt-main registers no plugins, db_types / parallel_types must be built-in
+    PluginRegistry::new().validate(&task_config)?;
+    let db_type = &task_config.extractor_basic.db_type;
+    RdbFilter::from_config(&task_config.filter, db_type)?;
+    RdbRouter::from_config(&task_config.router, db_type)?;
+    Ok(task_config)
+}
+
+fn position(command: PositionCommand) -> u8 {
+    let result = match command {
+        PositionCommand::Show { path, all } => PositionTool::show(&path, all),
+        PositionCommand::Set { output, position } => match build_position(position) {
+            Ok(position) => PositionTool::set(&output, position),
+            Err(err) => return fail(EXIT_INVALID_CONFIG, err),
+        },
+    };
+    match result {
+        Ok(_) => EXIT_OK,
+        Err(err) => fail(EXIT_FAILED, err),
+    }
+}
+
+fn build_position(position: SetPosition) -> anyhow::Result<Position> {
+    let position = match position {
+        SetPosition::Mysql(args) => Position::MysqlCdc {
+            server_id: String::new(),
+            binlog_filename: args.binlog_file.unwrap_or_default(),
+            next_event_position: args.binlog_position.unwrap_or_default(),
+            gtid_set: args.gtid_set.unwrap_or_default(),
+            timestamp: String::new(),
+        },
+
+        SetPosition::Pg { lsn } => {
+            PositionTool::check_pg_lsn(&lsn)?;
+            Position::PgCdc {
+                lsn,
+                timestamp: String::new(),
+            }
+        }
+
+        SetPosition::Mongo(args) => Position::MongoCdc {
+            resume_token: args.resume_token.unwrap_or_default(),
+            operation_time: args.operation_time.unwrap_or_default(),
+            timestamp: String::new(),
+        },
+    };
+    Ok(position)
+}
+
+fn check_report(check_log_dir: &str) -> u8 {
+    let report = match CheckReport::from_dir(check_log_dir) {
+        Ok(report) => report,
+        Err(err) => return fail(EXIT_FAILED, err),
+    };
+    report.print();
+    if report.is_consistent() {
+        EXIT_OK
     } else {
-        let runner = TaskRunner::new(&task_config).unwrap();
-        runner.start_task(true).await.unwrap()
+        EXIT_DATA_INCONSISTENT
     }
 }
+
+fn fail(code: u8, err: anyhow::Error) -> u8 {
+    eprintln!("error: {:?}", err);
+    code
+}
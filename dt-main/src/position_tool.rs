@@ -0,0 +1,195 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::{bail, Context};
+use dt_common::{error::Error, meta::position::Position};
+use serde_json::Value;
+
+use crate::table_printer::TablePrinter;
+
+const POSITION_LOG: &str = "position.log";
+const FINISHED_LOG: &str = "finished.log";
+
+pub struct PositionTool {}
+
+impl PositionTool {
+    pub fn show(path: &str, all: bool) -> anyhow::Result<()> {
+        let files: Vec<PathBuf> = if Path::new(path).is_dir() {
+            [POSITION_LOG, FINISHED_LOG]
+                .iter()
+                .map(|i| Path::new(path).join(i))
+                .filter(|i| i.is_file())
+                .collect()
+        } else {
+            vec![PathBuf::from(path)]
+        };
+        if files.is_empty() {
+            bail! {Error::ConfigError(format!(
+                "no {} or {} found in: [{}]",
+                POSITION_LOG, FINISHED_LOG, path
+            ))}
+        }
+
+        for file in files {
+            let rows = Self::read_rows(&file, all)?;
+            println!("{}:", file.display());
+            TablePrinter::print(&["time", "tag", "type", "position"], &rows);
+            println!();
+        }
+        Ok(())
+    }
+
+    pub fn set(output: &str, position: Position) -> anyhow::Result<()> {
+        // positions without the current_position tag are loaded as checkpoint_position by CdcResumer
+        fs::write(output, format!("{}\n", position))
+            .with_context(|| format!("failed to write resume config: [{}]", output))?;
+        println!("resume config written to: {}", output);
+        println!("{}", position);
+        println!(
+            "to resume from it, set in task config:\n[resumer]\nresume_config_file={}",
+            output
+        );
+        Ok(())
+    }
+
+    pub fn check_pg_lsn(lsn: &str) -> anyhow::Result<()> {
+        let valid = match lsn.split_once('/') {
+            Some((high, low)) => {
+                u32::from_str_radix(high, 16).is_ok() && u32::from_str_radix(low, 16).is_ok()
+            }
+            None => false,
+        };
+        if !valid {
+            bail! {Error::ConfigError(format!(
+                "invalid pg lsn: {}, expected format like: 0/5D65CB0",
+                lsn
+            ))}
+        }
+        Ok(())
+    }
+
+    fn read_rows(file: &Path, all: bool) -> anyhow::Result<Vec<Vec<String>>> {
+        let reader = BufReader::new(
+            File::open(file).with_context(|| format!("failed to open: [{}]", file.display()))?,
+        );
+
+        let mut rows = Vec::new();
+        // if not all, keep the latest row of each key, in the order of first appearance
+        let mut row_indexes = HashMap::new();
+        for line in reader.lines() {
+            let line = line?;
+            let (time, tag, position) = match Self::parse_line(&line) {
+                Some(parsed) => parsed,
+                None => continue,
+            };
+
+            let (position_type, detail) = Self::describe(&position);
+            let key = format!(
+                "{}|{}|{}",
+                tag,
+                position_type,
+                Self::position_key(&position)
+            );
+            let row = vec![time, tag, position_type, detail];
+            match row_indexes.get(&key) {
+                Some(i) if !all => rows[*i] = row,
+                _ => {
+                    row_indexes.insert(key, rows.len());
+                    rows.push(row);
+                }
+            }
+        }
+        Ok(rows)
+    }
+
+    fn parse_line(line: &str) -> Option<(String, String, Position)> {
+        // 2024-03-29 07:02:24.463776 | current_position | {"type":"RdbSnapshot",...}
+        // 2024-04-01 03:25:18.701725 | {"type":"RdbSnapshotFinished",...}
+        let left = line.find('{')?;
+        let right = line.rfind('}')?;
+        if right < left {
+            return None;
+        }
+        let position = Position::from_str(&line[left..=right]).ok()?;
+
+        let (mut time, mut tag) = (String::new(), String::new());
+        for part in line[..left].split('|').map(|i| i.trim()) {
+            if part.ends_with("_position") {
+                tag = part.to_string();
+            } else if !part.is_empty() {
+                time = part.to_string();
+            }
+        }
+        Some((time, tag, position))
+    }
+
+    fn position_key(position: &Position) -> String {
+        match position {
+            Position::RdbSnapshot { schema, tb, .. }
+            | Position::RdbSnapshotFinished { schema, tb, .. }
+            | Position::FoxlakeS3 { schema, tb, .. } => format!("{}.{}", schema, tb),
+            Position::Kafka {
+                topic, partition, ..
+            } => format!("{}:{}", topic, partition),
+            _ => String::new(),
+        }
+    }
+
+    fn describe(position: &Position) -> (String, String) {
+        let mut position_type = String::new();
+        let mut fields = Vec::new();
+        if let Value::Object(map) = serde_json::json!(position) {
+            for (key, value) in map {
+                let value = match value {
+                    Value::String(v) => v,
+                    v => v.to_string(),
+                };
+                if key == "type" {
+                    position_type = value;
+                } else if !value.is_empty() {
+                    fields.push(format!("{}={}", key, value));
+                }
+            }
+        }
+        (position_type, fields.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        let line = r#"2024-10-18 05:21:45.207788 | checkpoint_position | {"type":"MysqlCdc","server_id":"","binlog_filename":"mysql-bin.000004","next_event_position":44315,"gtid_set":"","timestamp":"2024-10-18 05:21:44.000"}"#;
+        let (time, tag, position) = PositionTool::parse_line(line).unwrap();
+        assert_eq!(time, "2024-10-18 05:21:45.207788");
+        assert_eq!(tag, "checkpoint_position");
+        let (position_type, detail) = PositionTool::describe(&position);
+        assert_eq!(position_type, "MysqlCdc");
+        assert_eq!(
+            detail,
+            "binlog_filename=mysql-bin.000004, next_event_position=44315, timestamp=2024-10-18 05:21:44.000"
+        );
+
+        let line =
+            r#"{"type":"RdbSnapshotFinished","db_type":"mysql","schema":"test_db","tb":"a"}"#;
+        let (time, tag, position) = PositionTool::parse_line(line).unwrap();
+        assert!(time.is_empty() && tag.is_empty());
+        assert_eq!(PositionTool::position_key(&position), "test_db.a");
+
+        assert!(PositionTool::parse_line("task finished").is_none());
+    }
+
+    #[test]
+    fn test_check_pg_lsn() {
+        assert!(PositionTool::check_pg_lsn("0/5D65CB0").is_ok());
+        assert!(PositionTool::check_pg_lsn("5D65CB0").is_err());
+        assert!(PositionTool::check_pg_lsn("0/xyz").is_err());
+    }
+}
@@ -0,0 +1,30 @@
+pub struct TablePrinter {}
+
+impl TablePrinter {
+    pub fn print(header: &[&str], rows: &[Vec<String>]) {
+        let mut widths: Vec<usize> = header.iter().map(|i| i.chars().count()).collect();
+        for row in rows {
+            for (i, cell) in row.iter().enumerate() {
+                widths[i] = widths[i].max(cell.chars().count());
+            }
+        }
+
+        let header: Vec<String> = header.iter().map(|i| i.to_string()).collect();
+        println!("{}", Self::format_row(&header, &widths));
+        let separator: Vec<String> = widths.iter().map(|i| "-".repeat(*i)).collect();
+        println!("{}", Self::format_row(&separator, &widths));
+        for row in rows {
+            println!("{}", Self::format_row(row, &widths));
+        }
+    }
+
+    fn format_row(row: &[String], widths: &[usize]) -> String {
+        row.iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<String>>()
+            .join(" | ")
+            .trim_end()
+            .to_string()
+    }
+}
@@ -5,15 +5,16 @@ use dt_common::monitor::counter::Counter;
 use dt_common::monitor::counter_type::CounterType;
 use dt_common::{error::Error, monitor::monitor::Monitor};
 use dt_connector::Sinker;
-use ratelimit::Ratelimiter;
 use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
 
+use crate::rps_limiter::RpsLimiter;
+
 #[derive(Default)]
 pub struct BaseParallelizer {
     pub poped_data: VecDeque<DtItem>,
     pub monitor: Arc<Mutex<Monitor>>,
-    pub rps_limiter: Option<Ratelimiter>,
+    pub rps_limiter: RpsLimiter,
 }
 
 impl BaseParallelizer {
@@ -64,14 +65,11 @@ impl BaseParallelizer {
         record_size_counter: &mut Counter,
     ) -> anyhow::Result<DtItem> {
         // rps limit
-        if let Some(rps_limiter) = &self.rps_limiter {
-            // refer: https://docs.rs/ratelimit/0.7.1/ratelimit
-            if let Err(_sleep) = rps_limiter.try_wait() {
-                bail! {Error::PipelineError(format!(
-                    "reach rps limit: {}",
-                    rps_limiter.max_tokens(),
-                ))};
-            }
+        if let Err(_sleep) = self.rps_limiter.try_wait() {
+            bail! {Error::PipelineError(format!(
+                "reach rps limit: {}",
+                self.rps_limiter.max_rps(),
+            ))};
         }
 
         match buffer.pop() {
@@ -7,9 +7,11 @@ pub mod partition_parallelizer;
 pub mod rdb_merger;
 pub mod rdb_partitioner;
 pub mod redis_parallelizer;
+pub mod rps_limiter;
 pub mod serial_parallelizer;
 pub mod snapshot_parallelizer;
 pub mod table_parallelizer;
+pub mod transaction_parallelizer;
 
 use std::sync::Arc;
 
@@ -25,6 +27,12 @@ use merge_parallelizer::TbMergedData;
 pub trait Parallelizer {
     fn get_name(&self) -> String;
 
+    /// If true, dmls are passed to sink_raw along with DtData::Commit, so the
+    /// boundaries of source transactions are kept.
+    fn keep_transactions(&self) -> bool {
+        false
+    }
+
     async fn drain(&mut self, _buffer: &DtQueue) -> anyhow::Result<Vec<DtItem>> {
         Ok(Vec::new())
     }
@@ -6,9 +6,10 @@ use dt_common::meta::{
     dt_data::{DtData, DtItem},
     dt_queue::DtQueue,
     redis::command::key_parser::KeyParser,
+    row_data::RowData,
 };
 use dt_common::{error::Error, log_warn};
-use dt_connector::Sinker;
+use dt_connector::{rdb_router::RdbRouter, Sinker};
 
 use crate::Parallelizer;
 
@@ -21,6 +22,8 @@ pub struct RedisParallelizer {
     pub slot_node_map: HashMap<u16, &'static str>,
     pub key_parser: KeyParser,
     pub node_sinker_index_map: HashMap<String, usize>,
+    // redis event_mode, dmls are sent to the node of their streams / channels
+    pub event_router: Option<RdbRouter>,
 }
 
 #[async_trait]
@@ -33,6 +36,27 @@ impl Parallelizer for RedisParallelizer {
         self.base_parallelizer.drain(buffer).await
     }
 
+    async fn sink_dml(
+        &mut self,
+        data: Vec<RowData>,
+        sinkers: &[Arc<async_mutex::Mutex<Box<dyn Sinker + Send>>>],
+    ) -> anyhow::Result<()> {
+        if self.slot_node_map.is_empty() {
+            return self
+                .base_parallelizer
+                .sink_dml(vec![data], sinkers, 1, false)
+                .await;
+        }
+
+        // for redis cluster, keys mapped from a row (the row key and its index keys)
+        // may hash to slots of different nodes, so rows are sent to all nodes
+        // and each node only writes the keys in its own slots
+        let sub_datas = vec![data; sinkers.len()];
+        self.base_parallelizer
+            .sink_dml(sub_datas, sinkers, sinkers.len(), false)
+            .await
+    }
+
     async fn sink_raw(
         &mut self,
         data: Vec<DtItem>,
@@ -75,8 +99,13 @@ impl Parallelizer for RedisParallelizer {
                     log_warn!("entry has no key, cmd: {}", entry.cmd.to_string());
                 }
                 slots
+            } else if let (DtData::Dml { row_data }, Some(event_router)) =
+                (&dt_item.dt_data, &self.event_router)
+            {
+                let topic = event_router.get_topic(&row_data.schema, &row_data.tb);
+                vec![KeyParser::calc_slot(topic.as_bytes())]
             } else {
-                // never happen
+                // begin / commit
                 vec![]
             };
 
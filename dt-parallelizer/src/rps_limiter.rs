@@ -0,0 +1,78 @@
+use std::{
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use ratelimit::Ratelimiter;
+
+/// A Ratelimiter shared by the parallelizers of a task, max_rps can be changed while running.
+#[derive(Clone, Default)]
+pub struct RpsLimiter {
+    // (max_rps, limiter), no limiter if max_rps is 0
+    inner: Arc<RwLock<(u64, Option<Ratelimiter>)>>,
+}
+
+impl RpsLimiter {
+    pub fn new(max_rps: u64) -> anyhow::Result<Self> {
+        let limiter = Self::default();
+        limiter.set_max_rps(max_rps)?;
+        Ok(limiter)
+    }
+
+    /// 0 means no limit
+    pub fn set_max_rps(&self, max_rps: u64) -> anyhow::Result<()> {
+        let limiter = if max_rps > 0 {
+            Some(
+                Ratelimiter::builder(max_rps, Duration::from_secs(1))
+                    .max_tokens(max_rps)
+                    .initial_available(max_rps)
+                    .build()?,
+            )
+        } else {
+            None
+        };
+        *self.inner.write().unwrap() = (max_rps, limiter);
+        Ok(())
+    }
+
+    pub fn max_rps(&self) -> u64 {
+        self.inner.read().unwrap().0
+    }
+
+    /// refer: https://docs.rs/ratelimit/0.7.1/ratelimit
+    pub fn try_wait(&self) -> Result<(), Duration> {
+        match &self.inner.read().unwrap().1 {
+            Some(limiter) => limiter.try_wait(),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_max_rps() {
+        // no limit
+        let limiter = RpsLimiter::new(0).unwrap();
+        for _ in 0..1000 {
+            assert!(limiter.try_wait().is_ok());
+        }
+
+        // a burst of max_rps is allowed, then callers need to wait
+        limiter.set_max_rps(10).unwrap();
+        assert_eq!(limiter.max_rps(), 10);
+        for _ in 0..10 {
+            assert!(limiter.try_wait().is_ok());
+        }
+        let wait = limiter.try_wait().unwrap_err();
+        assert!(wait <= Duration::from_secs(1));
+
+        // clones share the same limiter
+        let cloned = limiter.clone();
+        cloned.set_max_rps(0).unwrap();
+        assert_eq!(limiter.max_rps(), 0);
+        assert!(limiter.try_wait().is_ok());
+    }
+}
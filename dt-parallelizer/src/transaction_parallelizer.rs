@@ -0,0 +1,438 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use dt_common::meta::{
+    col_value::ColValue,
+    dt_data::{DtData, DtItem},
+    dt_queue::DtQueue,
+    rdb_meta_manager::RdbMetaManager,
+    rdb_tb_meta::RdbTbMeta,
+    row_data::RowData,
+};
+use dt_common::monitor::counter::Counter;
+use dt_connector::Sinker;
+
+use crate::Parallelizer;
+
+use super::base_parallelizer::BaseParallelizer;
+
+/// Applies cdc data by source transactions, each source transaction is committed atomically
+/// in the target, together with other whole transactions sinked by the same sinker.
+///
+/// Transactions are scheduled by a writeset dependency graph: a transaction depends on the
+/// previous transactions which wrote any of the same keys, transactions without dependencies
+/// between them are sinked in parallel.
+pub struct TransactionParallelizer {
+    pub base_parallelizer: BaseParallelizer,
+    pub meta_manager: RdbMetaManager,
+    pub parallel_size: usize,
+}
+
+#[async_trait]
+impl Parallelizer for TransactionParallelizer {
+    fn get_name(&self) -> String {
+        "TransactionParallelizer".to_string()
+    }
+
+    fn keep_transactions(&self) -> bool {
+        true
+    }
+
+    async fn close(&mut self) -> anyhow::Result<()> {
+        self.meta_manager.close().await
+    }
+
+    async fn drain(&mut self, buffer: &DtQueue) -> anyhow::Result<Vec<DtItem>> {
+        // whole transactions
+        let mut data = Vec::new();
+        // the transaction not committed yet
+        let mut txn_data = Vec::new();
+        let mut record_size_counter = Counter::new(0, 0);
+
+        loop {
+            // poped_data holds the uncommitted transaction of the last drain
+            let item = if let Some(item) = self.base_parallelizer.poped_data.pop_front() {
+                item
+            } else if let Ok(item) = self
+                .base_parallelizer
+                .pop(buffer, &mut record_size_counter)
+                .await
+            {
+                item
+            } else {
+                break;
+            };
+
+            match &item.dt_data {
+                DtData::Ddl { .. } => {
+                    // ddls and dmls should be drained seperately
+                    if data.is_empty() && txn_data.is_empty() {
+                        data.push(item);
+                        break;
+                    }
+
+                    self.base_parallelizer.poped_data.push_front(item);
+                    if data.is_empty() {
+                        // dmls in the same transaction with the ddl (pg), sink them first
+                        data.append(&mut txn_data);
+                    }
+                    break;
+                }
+
+                DtData::Commit { .. } => {
+                    txn_data.push(item);
+                    data.append(&mut txn_data);
+                }
+
+                _ => txn_data.push(item),
+            }
+        }
+
+        for item in txn_data.into_iter().rev() {
+            self.base_parallelizer.poped_data.push_front(item);
+        }
+
+        self.base_parallelizer
+            .update_monitor(&record_size_counter)
+            .await;
+        Ok(data)
+    }
+
+    async fn sink_raw(
+        &mut self,
+        data: Vec<DtItem>,
+        sinkers: &[Arc<async_mutex::Mutex<Box<dyn Sinker + Send>>>],
+    ) -> anyhow::Result<()> {
+        let txns = Self::split_txns(data);
+        let mut writesets = Vec::new();
+        for txn in txns.iter() {
+            writesets.push(self.get_writeset(txn).await?);
+        }
+        let levels = Self::get_levels(&writesets);
+
+        for sub_datas in Self::get_level_sub_datas(txns, &levels, self.parallel_size) {
+            self.base_parallelizer
+                .sink_dml(sub_datas, sinkers, self.parallel_size, false)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+impl TransactionParallelizer {
+    fn split_txns(data: Vec<DtItem>) -> Vec<Vec<RowData>> {
+        let mut txns = Vec::new();
+        let mut txn = Vec::new();
+        for item in data {
+            match item.dt_data {
+                DtData::Dml { row_data } => txn.push(row_data),
+                DtData::Commit { .. } if !txn.is_empty() => txns.push(std::mem::take(&mut txn)),
+                _ => {}
+            }
+        }
+        if !txn.is_empty() {
+            txns.push(txn);
+        }
+        txns
+    }
+
+    /// Sub datas of sinkers for each level, transactions in the same level have no dependencies
+    /// between them, each sinker commits its sub data in one target transaction.
+    fn get_level_sub_datas(
+        txns: Vec<Vec<RowData>>,
+        levels: &[usize],
+        parallel_size: usize,
+    ) -> Vec<Vec<Vec<RowData>>> {
+        let mut level_txns: Vec<Vec<Vec<RowData>>> = Vec::new();
+        for (txn, level) in txns.into_iter().zip(levels.iter().cloned()) {
+            if level_txns.len() <= level {
+                level_txns.resize_with(level + 1, Vec::new);
+            }
+            level_txns[level].push(txn);
+        }
+
+        let mut level_sub_datas = Vec::new();
+        for txns in level_txns {
+            let mut sub_datas = Vec::new();
+            for (i, txn) in txns.into_iter().enumerate() {
+                let index = i % parallel_size;
+                if sub_datas.len() <= index {
+                    sub_datas.push(Vec::new());
+                }
+                sub_datas[index].extend(txn);
+            }
+            level_sub_datas.push(sub_datas);
+        }
+        level_sub_datas
+    }
+
+    async fn get_writeset(&mut self, txn: &[RowData]) -> anyhow::Result<HashSet<u64>> {
+        let mut writeset = HashSet::new();
+        for row_data in txn {
+            let tb_meta = self
+                .meta_manager
+                .get_tb_meta(&row_data.schema, &row_data.tb)
+                .await?;
+            writeset.extend(Self::get_row_writeset(tb_meta, row_data));
+        }
+        Ok(writeset)
+    }
+
+    /// Keys written by the row: values of primary / unique keys before and after the change.
+    /// Tables without keys, or related by foreign keys, are written as a whole.
+    fn get_row_writeset(tb_meta: &RdbTbMeta, row_data: &RowData) -> Vec<u64> {
+        let mut writeset = Vec::new();
+        if tb_meta.key_map.is_empty() {
+            writeset.push(Self::hash_key(&tb_meta.schema, &tb_meta.tb, &[], &[]));
+        }
+
+        if !tb_meta.ref_by_foreign_keys.is_empty() {
+            writeset.push(Self::hash_key(&tb_meta.schema, &tb_meta.tb, &[], &[]));
+        }
+        for foreign_key in tb_meta.foreign_keys.iter() {
+            writeset.push(Self::hash_key(
+                &foreign_key.ref_schema,
+                &foreign_key.ref_tb,
+                &[],
+                &[],
+            ));
+        }
+
+        for col_values in [&row_data.before, &row_data.after].into_iter().flatten() {
+            for key_cols in tb_meta.key_map.values() {
+                let key_values: Vec<Option<&ColValue>> =
+                    key_cols.iter().map(|col| col_values.get(col)).collect();
+                writeset.push(Self::hash_key(
+                    &tb_meta.schema,
+                    &tb_meta.tb,
+                    key_cols,
+                    &key_values,
+                ));
+            }
+        }
+        writeset
+    }
+
+    fn hash_key(schema: &str, tb: &str, cols: &[String], values: &[Option<&ColValue>]) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        schema.hash(&mut hasher);
+        tb.hash(&mut hasher);
+        cols.hash(&mut hasher);
+        for value in values {
+            value.and_then(|v| v.to_option_string()).hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// A transaction is put in the level next to the highest level of the previous
+    /// transactions it depends on.
+    fn get_levels(writesets: &[HashSet<u64>]) -> Vec<usize> {
+        let mut levels = Vec::new();
+        let mut key_levels: HashMap<u64, usize> = HashMap::new();
+        for writeset in writesets {
+            let level = writeset
+                .iter()
+                .filter_map(|key| key_levels.get(key))
+                .map(|level| level + 1)
+                .max()
+                .unwrap_or(0);
+            for key in writeset {
+                key_levels.insert(*key, level);
+            }
+            levels.push(level);
+        }
+        levels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use dt_common::{
+        meta::{ddl_meta::ddl_data::DdlData, position::Position, row_type::RowType},
+        monitor::monitor::Monitor,
+    };
+
+    use super::*;
+
+    fn mock_parallelizer() -> TransactionParallelizer {
+        TransactionParallelizer {
+            base_parallelizer: BaseParallelizer {
+                monitor: Arc::new(Mutex::new(Monitor::new("test", 10, 100, 10))),
+                ..Default::default()
+            },
+            meta_manager: RdbMetaManager {
+                mysql_meta_manager: None,
+                pg_meta_manager: None,
+            },
+            parallel_size: 2,
+        }
+    }
+
+    fn mock_row_data(id: i32) -> RowData {
+        RowData::new(
+            "test_db".into(),
+            "tb_1".into(),
+            RowType::Insert,
+            None,
+            Some(HashMap::from([("id".to_string(), ColValue::Long(id))])),
+        )
+    }
+
+    fn mock_item(dt_data: DtData) -> DtItem {
+        DtItem {
+            dt_data,
+            position: Position::None,
+            data_origin_node: String::new(),
+        }
+    }
+
+    fn dml(id: i32) -> DtItem {
+        mock_item(DtData::Dml {
+            row_data: mock_row_data(id),
+        })
+    }
+
+    fn commit() -> DtItem {
+        mock_item(DtData::Commit { xid: String::new() })
+    }
+
+    fn ddl() -> DtItem {
+        mock_item(DtData::Ddl {
+            ddl_data: DdlData::default(),
+        })
+    }
+
+    fn get_id(row_data: &RowData) -> i32 {
+        match row_data.after.as_ref().unwrap().get("id") {
+            Some(ColValue::Long(v)) => *v,
+            _ => panic!("id not found"),
+        }
+    }
+
+    fn to_strs(items: &[DtItem]) -> Vec<String> {
+        items
+            .iter()
+            .map(|item| match &item.dt_data {
+                DtData::Dml { row_data } => format!("dml_{}", get_id(row_data)),
+                DtData::Commit { .. } => "commit".to_string(),
+                DtData::Ddl { .. } => "ddl".to_string(),
+                _ => "other".to_string(),
+            })
+            .collect()
+    }
+
+    async fn push(buffer: &DtQueue, items: Vec<DtItem>) {
+        for item in items {
+            buffer.push(item).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_drain_keeps_uncommitted_txn() {
+        let mut parallelizer = mock_parallelizer();
+        let buffer = DtQueue::new(100, 0);
+
+        push(&buffer, vec![dml(1), dml(2), commit(), dml(3)]).await;
+        let data = parallelizer.drain(&buffer).await.unwrap();
+        assert_eq!(to_strs(&data), vec!["dml_1", "dml_2", "commit"]);
+        // the uncommitted transaction waits for its commit
+        assert_eq!(
+            to_strs(&Vec::from(
+                parallelizer.base_parallelizer.poped_data.clone()
+            )),
+            vec!["dml_3"]
+        );
+        assert!(parallelizer.drain(&buffer).await.unwrap().is_empty());
+
+        push(&buffer, vec![dml(4), commit()]).await;
+        let data = parallelizer.drain(&buffer).await.unwrap();
+        assert_eq!(to_strs(&data), vec!["dml_3", "dml_4", "commit"]);
+        assert!(parallelizer.base_parallelizer.poped_data.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_drain_split_by_ddl() {
+        let mut parallelizer = mock_parallelizer();
+        let buffer = DtQueue::new(100, 0);
+
+        push(
+            &buffer,
+            vec![dml(1), commit(), dml(2), ddl(), commit(), dml(3), commit()],
+        )
+        .await;
+        let mut drained = Vec::new();
+        for _ in 0..5 {
+            drained.push(to_strs(&parallelizer.drain(&buffer).await.unwrap()));
+        }
+        assert_eq!(
+            drained,
+            vec![
+                vec!["dml_1", "commit"],
+                // dmls in the same transaction with the ddl
+                vec!["dml_2"],
+                vec!["ddl"],
+                vec!["commit", "dml_3", "commit"],
+                vec![],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sink_by_whole_txns() {
+        let data = vec![
+            dml(1),
+            dml(2),
+            commit(),
+            dml(3),
+            commit(),
+            dml(4),
+            dml(5),
+            commit(),
+            // commits without dmls are skipped
+            commit(),
+            dml(6),
+        ];
+        let txns = TransactionParallelizer::split_txns(data);
+        let ids: Vec<Vec<i32>> = txns
+            .iter()
+            .map(|txn| txn.iter().map(get_id).collect())
+            .collect();
+        assert_eq!(ids, vec![vec![1, 2], vec![3], vec![4, 5], vec![6]]);
+
+        // the last transaction depends on the first one
+        let level_sub_datas = TransactionParallelizer::get_level_sub_datas(txns, &[0, 0, 0, 1], 2);
+        let ids: Vec<Vec<Vec<i32>>> = level_sub_datas
+            .iter()
+            .map(|sub_datas| {
+                sub_datas
+                    .iter()
+                    .map(|sub_data| sub_data.iter().map(get_id).collect())
+                    .collect()
+            })
+            .collect();
+        // each transaction is sinked by one sinker as a whole
+        assert_eq!(ids, vec![vec![vec![1, 2, 4, 5], vec![3]], vec![vec![6]]]);
+    }
+
+    #[test]
+    fn test_get_levels() {
+        let writesets = vec![
+            HashSet::from([1, 2]),
+            HashSet::from([3]),
+            HashSet::from([2, 4]),
+            HashSet::from([5]),
+            HashSet::from([4, 3]),
+            HashSet::from([1]),
+        ];
+        assert_eq!(
+            TransactionParallelizer::get_levels(&writesets),
+            vec![0, 0, 1, 0, 2, 1]
+        );
+    }
+}
@@ -26,7 +26,7 @@ use dt_common::{
 use dt_connector::{data_marker::DataMarker, Sinker};
 use dt_parallelizer::Parallelizer;
 
-use crate::{lua_processor::LuaProcessor, Pipeline};
+use crate::{lua_processor::LuaProcessor, pipeline_control::PipelineControl, Pipeline};
 
 pub struct BasePipeline {
     pub buffer: Arc<DtQueue>,
@@ -40,6 +40,7 @@ pub struct BasePipeline {
     pub monitor: Arc<Mutex<Monitor>>,
     pub data_marker: Option<Arc<RwLock<DataMarker>>>,
     pub lua_processor: Option<LuaProcessor>,
+    pub control: Arc<PipelineControl>,
 }
 
 enum SinkMethod {
@@ -47,6 +48,7 @@ enum SinkMethod {
     Ddl,
     Dml,
     Struct,
+    Transaction,
 }
 
 #[async_trait]
@@ -72,6 +74,19 @@ impl Pipeline for BasePipeline {
         let mut last_commit_position = Position::None;
 
         while !self.shut_down.load(Ordering::Acquire) || !self.buffer.is_empty() {
+            let checkpoint_requested = self.control.take_checkpoint_request();
+            if self.control.is_paused() && !self.shut_down.load(Ordering::Acquire) {
+                if checkpoint_requested {
+                    last_checkpoint_time = self.record_checkpoint(
+                        None,
+                        &last_received_position,
+                        &last_commit_position,
+                    );
+                }
+                TimeUtil::sleep_millis(100).await;
+                continue;
+            }
+
             // to avoid too many sub counters, only add counter when buffer is not empty
             if !self.buffer.is_empty() {
                 self.monitor
@@ -83,6 +98,7 @@ impl Pipeline for BasePipeline {
             // some sinkers (foxlake) need to accumulate data to a big batch and sink
             let data = if last_sink_time.elapsed().as_secs() < self.batch_sink_interval_secs
                 && !self.buffer.is_full()
+                && !checkpoint_requested
             {
                 Vec::new()
             } else {
@@ -103,6 +119,7 @@ impl Pipeline for BasePipeline {
                 SinkMethod::Dml => self.sink_dml(data).await?,
                 SinkMethod::Raw => self.sink_raw(data).await?,
                 SinkMethod::Struct => self.sink_struct(data).await?,
+                SinkMethod::Transaction => self.sink_transaction(data).await?,
             };
 
             if let Some(position) = &last_received {
@@ -114,7 +131,11 @@ impl Pipeline for BasePipeline {
             }
 
             last_checkpoint_time = self.record_checkpoint(
-                Some(last_checkpoint_time),
+                if checkpoint_requested {
+                    None
+                } else {
+                    Some(last_checkpoint_time)
+                },
                 &last_received_position,
                 &last_commit_position,
             );
@@ -178,6 +199,55 @@ impl BasePipeline {
         Ok((count, last_received_position, last_commit_position))
     }
 
+    async fn sink_transaction(
+        &mut self,
+        mut all_data: Vec<DtItem>,
+    ) -> anyhow::Result<(usize, Option<Position>, Option<Position>)> {
+        let (last_received_position, last_commit_position) = Self::fetch_raw(&all_data);
+        let mut data = Vec::new();
+        let mut txn_data = Vec::new();
+        let mut count = 0;
+        for item in all_data.drain(..) {
+            if let DtData::Dml { row_data } = item.dt_data {
+                count += 1;
+                txn_data.push(row_data);
+            } else {
+                // execute lua processor for each transaction
+                self.push_transaction(&mut data, &mut txn_data)?;
+                data.push(item);
+            }
+        }
+        self.push_transaction(&mut data, &mut txn_data)?;
+
+        if count > 0 {
+            self.parallelizer.sink_raw(data, &self.sinkers).await?
+        }
+        Ok((count, last_received_position, last_commit_position))
+    }
+
+    fn push_transaction(
+        &self,
+        data: &mut Vec<DtItem>,
+        txn_data: &mut Vec<RowData>,
+    ) -> anyhow::Result<()> {
+        if txn_data.is_empty() {
+            return Ok(());
+        }
+
+        let mut txn_data = std::mem::take(txn_data);
+        if let Some(lua_processor) = &self.lua_processor {
+            txn_data = lua_processor.process(txn_data)?;
+        }
+        for row_data in txn_data {
+            data.push(DtItem {
+                dt_data: DtData::Dml { row_data },
+                position: Position::None,
+                data_origin_node: String::new(),
+            });
+        }
+        Ok(())
+    }
+
     async fn sink_ddl(
         &mut self,
         all_data: Vec<DtItem>,
@@ -285,7 +355,13 @@ impl BasePipeline {
                 DtData::Dml { .. } => match self.sinker_config {
                     SinkerConfig::FoxlakePush { .. }
                     | SinkerConfig::FoxlakeMerge { .. }
-                    | SinkerConfig::Foxlake { .. } => return SinkMethod::Raw,
+                    | SinkerConfig::Foxlake { .. }
+                    | SinkerConfig::File { .. } => return SinkMethod::Raw,
+                    // events are published with positions
+                    SinkerConfig::Redis { ref event_mode, .. } if !event_mode.is_empty() => {
+                        return SinkMethod::Raw
+                    }
+                    _ if self.parallelizer.keep_transactions() => return SinkMethod::Transaction,
                     _ => return SinkMethod::Dml,
                 },
                 DtData::Redis { .. } | DtData::Foxlake { .. } => return SinkMethod::Raw,
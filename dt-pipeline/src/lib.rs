@@ -1,6 +1,7 @@
 pub mod base_pipeline;
 pub mod http_server_pipeline;
 pub mod lua_processor;
+pub mod pipeline_control;
 
 use async_trait::async_trait;
 
@@ -0,0 +1,56 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Runtime switches of BasePipeline, shared with the admin server.
+#[derive(Default)]
+pub struct PipelineControl {
+    paused: AtomicBool,
+    checkpoint_requested: AtomicBool,
+}
+
+impl PipelineControl {
+    /// Stop draining the buffer, extractors are blocked once the buffer is full.
+    /// Ignored after the task is shut down, so remaining data can still be sinked.
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::Release);
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::Release);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Acquire)
+    }
+
+    /// Sink buffered data and record the checkpoint in the next loop,
+    /// regardless of batch_sink_interval_secs and checkpoint_interval_secs.
+    pub fn request_checkpoint(&self) {
+        self.checkpoint_requested.store(true, Ordering::Release);
+    }
+
+    pub fn take_checkpoint_request(&self) -> bool {
+        self.checkpoint_requested.swap(false, Ordering::AcqRel)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pipeline_control() {
+        let control = PipelineControl::default();
+        assert!(!control.is_paused());
+        control.pause();
+        control.pause();
+        assert!(control.is_paused());
+        control.resume();
+        assert!(!control.is_paused());
+
+        // a checkpoint request is taken only once
+        assert!(!control.take_checkpoint_request());
+        control.request_checkpoint();
+        assert!(control.take_checkpoint_request());
+        assert!(!control.take_checkpoint_request());
+    }
+}
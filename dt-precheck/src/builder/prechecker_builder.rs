@@ -12,7 +12,7 @@ use crate::{
         mongo::mongo_fetcher::MongoFetcher, mysql::mysql_fetcher::MysqlFetcher,
         postgresql::pg_fetcher::PgFetcher, redis::redis_fetcher::RedisFetcher,
     },
-    meta::check_result::CheckResult,
+    meta::{check_item::CheckItem, check_result::CheckResult},
     prechecker::{
         mongo_prechecker::MongoPrechecker, mysql_prechecker::MySqlPrechecker,
         pg_prechecker::PostgresqlPrechecker, redis_prechecker::RedisPrechecker, traits::Prechecker,
@@ -38,15 +38,17 @@ impl PrecheckerBuilder {
     }
 
     pub fn build_checker(&self, is_source: bool) -> Option<Box<dyn Prechecker + Send>> {
-        let (db_type, url) = if is_source {
+        let (db_type, url, tls) = if is_source {
             (
                 self.task_config.extractor_basic.db_type.clone(),
                 self.task_config.extractor_basic.url.clone(),
+                self.task_config.extractor_basic.tls.clone(),
             )
         } else {
             (
                 self.task_config.sinker_basic.db_type.clone(),
                 self.task_config.sinker_basic.url.clone(),
+                self.task_config.sinker_basic.tls.clone(),
             )
         };
 
@@ -89,6 +91,7 @@ impl PrecheckerBuilder {
                 fetcher: RedisFetcher {
                     conn: None,
                     url,
+                    tls,
                     is_source,
                     filter,
                 },
@@ -152,6 +155,58 @@ impl PrecheckerBuilder {
         Ok(check_results)
     }
 
+    /// Only check whether source and sink can be connected, used by: dt-main validate.
+    /// Returns false if any connection failed, db types without a prechecker are skipped.
+    pub async fn check_connection(&self) -> anyhow::Result<bool> {
+        let mut passed = true;
+        for is_source in [true, false] {
+            let (db_type, url) = if is_source {
+                (
+                    &self.task_config.extractor_basic.db_type,
+                    &self.task_config.extractor_basic.url,
+                )
+            } else {
+                (
+                    &self.task_config.sinker_basic.db_type,
+                    &self.task_config.sinker_basic.url,
+                )
+            };
+            let source_or_sink = if is_source { "source" } else { "sink" };
+            if url.is_empty() {
+                println!(
+                    "[*]{} url is empty, skip checking the connection",
+                    source_or_sink
+                );
+                continue;
+            }
+
+            let mut checker = match self.build_checker(is_source) {
+                Some(checker) => checker,
+                None => {
+                    println!(
+                        "[*]connection check for {} db_type: {} is not supported, skipped",
+                        source_or_sink, db_type
+                    );
+                    continue;
+                }
+            };
+
+            println!("[*]begin to check the {} connection", source_or_sink);
+            let result = match checker.build_connection().await {
+                Ok(result) => result,
+                Err(err) => CheckResult::build_with_err(
+                    CheckItem::CheckDatabaseConnection,
+                    is_source,
+                    db_type.clone(),
+                    Some(err),
+                ),
+            };
+            result.log();
+            passed &= result.is_validate;
+        }
+        Ok(passed)
+    }
+
     pub async fn verify_check_result(&self) -> anyhow::Result<()> {
         let check_results = self.check().await;
         match check_results {
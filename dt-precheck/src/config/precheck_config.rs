@@ -1,4 +1,4 @@
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct PrecheckConfig {
     pub do_struct_init: bool,
     pub do_cdc: bool,
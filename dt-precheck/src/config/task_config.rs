@@ -1,5 +1,4 @@
 use anyhow::bail;
-use configparser::ini::Ini;
 use dt_common::{config::ini_loader::IniLoader, error::Error};
 
 use super::precheck_config::PrecheckConfig;
@@ -12,27 +11,29 @@ pub struct PrecheckTaskConfig {
 
 impl PrecheckTaskConfig {
     pub fn new(task_config_file: &str) -> anyhow::Result<Self> {
-        let ini = IniLoader::new(task_config_file).ini;
-        let precheck_config = Self::load_precheck_config(&ini)?;
+        let loader = IniLoader::new(task_config_file)?;
+        let precheck_config = Self::load_precheck_config(&loader)?;
         Ok(Self {
             precheck: precheck_config,
         })
     }
 
-    fn load_precheck_config(ini: &Ini) -> anyhow::Result<PrecheckConfig> {
-        let (do_struct_opt, do_cdc_opt): (Option<String>, Option<String>) = (
-            ini.get(PRECHECK, "do_struct_init"),
-            ini.get(PRECHECK, "do_cdc"),
-        );
-        if let (Some(do_struct), Some(do_cdc)) = (do_struct_opt, do_cdc_opt) {
-            Ok(PrecheckConfig {
-                do_struct_init: do_struct.parse().unwrap(),
-                do_cdc: do_cdc.parse().unwrap(),
-            })
-        } else {
+    /// Whether the config has a [precheck] section, regardless of whether it is valid
+    pub fn has_precheck_section(task_config_file: &str) -> bool {
+        IniLoader::new(task_config_file)
+            .map(|loader| loader.ini.get_map_ref().contains_key(PRECHECK))
+            .unwrap_or(false)
+    }
+
+    fn load_precheck_config(loader: &IniLoader) -> anyhow::Result<PrecheckConfig> {
+        if !loader.ini.get_map_ref().contains_key(PRECHECK) {
             bail! {Error::ConfigError(
                 "config is not valid for precheck.".into(),
             )}
         }
+        Ok(PrecheckConfig {
+            do_struct_init: loader.get_optional(PRECHECK, "do_struct_init")?,
+            do_cdc: loader.get_optional(PRECHECK, "do_cdc")?,
+        })
     }
 }
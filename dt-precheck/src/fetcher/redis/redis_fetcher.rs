@@ -1,10 +1,13 @@
 use async_trait::async_trait;
-use dt_common::{rdb_filter::RdbFilter, utils::redis_util::RedisUtil};
+use dt_common::{
+    config::tls_config::TlsConfig, rdb_filter::RdbFilter, utils::redis_util::RedisUtil,
+};
 
 use crate::fetcher::traits::Fetcher;
 
 pub struct RedisFetcher {
     pub url: String,
+    pub tls: TlsConfig,
     pub conn: Option<redis::Connection>,
     pub is_source: bool,
     pub filter: RdbFilter,
@@ -13,7 +16,7 @@ pub struct RedisFetcher {
 #[async_trait]
 impl Fetcher for RedisFetcher {
     async fn build_connection(&mut self) -> anyhow::Result<()> {
-        self.conn = Some(RedisUtil::create_redis_conn(&self.url).await?);
+        self.conn = Some(RedisUtil::create_redis_conn(&self.url, &self.tls).await?);
         Ok(())
     }
 
@@ -10,16 +10,16 @@ pub mod fetcher;
 pub mod meta;
 pub mod prechecker;
 
-pub async fn do_precheck(config: &str) {
-    let task_config = TaskConfig::new(config).unwrap();
-    let precheck_config = PrecheckTaskConfig::new(config).unwrap();
+pub async fn do_precheck(config: &str) -> anyhow::Result<()> {
+    let task_config = TaskConfig::new(config)?;
+    let precheck_config = PrecheckTaskConfig::new(config)?;
 
     let checker_connector = PrecheckerBuilder::build(precheck_config.precheck, task_config);
-    let result = checker_connector.verify_check_result().await;
-    if let Err(e) = result {
+    if let Err(e) = checker_connector.verify_check_result().await {
         println!("precheck not passed.");
-        panic!("precheck meet error: {}", e);
+        return Err(e);
     }
 
     println!("precheck passed.");
+    Ok(())
 }
@@ -3,7 +3,7 @@ use std::sync::{atomic::AtomicBool, Arc, Mutex};
 use async_trait::async_trait;
 use dt_common::{
     config::{config_enums::DbType, extractor_config::ExtractorConfig, task_config::TaskConfig},
-    meta::dt_queue::DtQueue,
+    meta::{dt_queue::DtQueue, redis::command::key_parser::KeyParser},
     monitor::monitor::Monitor,
     rdb_filter::RdbFilter,
     time_filter::TimeFilter,
@@ -73,7 +73,7 @@ impl Prechecker for RedisPrechecker {
             // should never happen since we've already checked the extractor type before into this function
             _ => 0,
         };
-        let mut conn = RedisClient::new(&self.fetcher.url).await?;
+        let mut conn = RedisClient::new(&self.fetcher.url, &self.fetcher.tls).await?;
         let buffer = Arc::new(DtQueue::new(1, 0));
 
         let filter = RdbFilter::from_config(&self.task_config.filter, &DbType::Redis)?;
@@ -87,6 +87,7 @@ impl Prechecker for RedisPrechecker {
             time_filter: TimeFilter::default(),
         };
 
+        let key_parser = KeyParser::new();
         let mut psyncer = RedisPsyncExtractor {
             conn: &mut conn,
             repl_id: String::new(),
@@ -95,6 +96,7 @@ impl Prechecker for RedisPrechecker {
             repl_port,
             filter,
             base_extractor: &mut base_extractor,
+            key_parser: &key_parser,
         };
 
         if let Err(error) = psyncer.start_psync().await {
@@ -0,0 +1,422 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    net::ToSocketAddrs,
+    os::unix::fs::FileTypeExt,
+    path::Path,
+    sync::{mpsc, Arc, Mutex, RwLock},
+    thread,
+};
+
+use actix_web::{
+    body::MessageBody,
+    dev::{ServerHandle, ServiceRequest, ServiceResponse},
+    error::ErrorUnauthorized,
+    http::header,
+    middleware::{from_fn, Next},
+    rt::System,
+    web, App, HttpResponse, HttpServer, Responder,
+};
+use anyhow::{bail, Context};
+use dt_common::{
+    config::admin_config::AdminConfig,
+    error::Error,
+    log_error, log_info,
+    meta::{dt_queue::DtQueue, position::Position, syncer::Syncer},
+    monitor::monitor::Monitor,
+};
+use dt_connector::extractor::cdc_backfill::BackfillRequests;
+use dt_parallelizer::rps_limiter::RpsLimiter;
+use dt_pipeline::pipeline_control::PipelineControl;
+use serde::{Deserialize, Serialize};
+
+use crate::task_handle::{TaskState, TaskStatus};
+
+const UNIX_PREFIX: &str = "unix:";
+const BEARER_PREFIX: &str = "Bearer ";
+
+/// Token required by all endpoints, empty for no auth
+struct AdminToken(String);
+
+/// Handles of the running sub task, replaced when the next sub task (table) starts.
+pub(crate) struct SubTaskHandles {
+    pub buffer: Arc<DtQueue>,
+    pub syncer: Arc<Mutex<Syncer>>,
+    pub monitors: Vec<Arc<Mutex<Monitor>>>,
+}
+
+/// State shared between a running task and the admin server.
+pub(crate) struct AdminState {
+    pub task_state: Arc<TaskState>,
+    pub control: Arc<PipelineControl>,
+    pub rps_limiter: RpsLimiter,
+    pub backfill_requests: BackfillRequests,
+    sub_task: RwLock<Option<SubTaskHandles>>,
+}
+
+#[derive(Serialize)]
+struct StatusResp {
+    task_status: TaskStatus,
+    paused: bool,
+    max_rps: u64,
+    current_position: Position,
+    checkpoint_position: Position,
+    buffer_len: usize,
+    buffer_bytes: i64,
+    /// monitor name -> counter -> aggregate -> value
+    monitors: BTreeMap<String, BTreeMap<String, BTreeMap<String, usize>>>,
+}
+
+#[derive(Deserialize)]
+struct MaxRpsReq {
+    max_rps: u64,
+}
+
+#[derive(Deserialize)]
+struct BackfillReq {
+    tbs: String,
+}
+
+impl AdminState {
+    pub fn new(task_state: Arc<TaskState>) -> Self {
+        Self {
+            task_state,
+            control: Arc::new(PipelineControl::default()),
+            rps_limiter: RpsLimiter::default(),
+            backfill_requests: BackfillRequests::default(),
+            sub_task: RwLock::new(None),
+        }
+    }
+
+    pub fn set_sub_task(&self, sub_task: SubTaskHandles) {
+        *self.sub_task.write().unwrap() = Some(sub_task);
+    }
+
+    fn status(&self) -> StatusResp {
+        let mut resp = StatusResp {
+            task_status: self.task_state.status(),
+            paused: self.control.is_paused(),
+            max_rps: self.rps_limiter.max_rps(),
+            current_position: Position::None,
+            checkpoint_position: Position::None,
+            buffer_len: 0,
+            buffer_bytes: 0,
+            monitors: BTreeMap::new(),
+        };
+
+        if let Some(sub_task) = self.sub_task.read().unwrap().as_ref() {
+            {
+                let syncer = sub_task.syncer.lock().unwrap();
+                resp.current_position = syncer.received_position.clone();
+                resp.checkpoint_position = syncer.committed_position.clone();
+            }
+            resp.buffer_len = sub_task.buffer.len();
+            resp.buffer_bytes = sub_task.buffer.bytes();
+            for monitor in sub_task.monitors.iter() {
+                let mut monitor = monitor.lock().unwrap();
+                let snapshot = monitor.snapshot();
+                resp.monitors.insert(monitor.name.clone(), snapshot);
+            }
+        }
+        resp
+    }
+}
+
+/// A small http server to control a running task, separate from HttpServerPipeline.
+/// If [admin] token is set, all endpoints require "Authorization: Bearer <token>".
+///   GET  /status      task status, positions, buffer and monitors
+///   POST /pause       stop sinking, extracting is blocked once the buffer is full
+///   POST /resume
+///   POST /max_rps     {"max_rps": 1000}, 0 means no limit
+///   POST /checkpoint  sink buffered data and record the checkpoint immediately
+///   POST /backfill    {"tbs": "db_1.tb_1,db_2.tb_2"}, add tbs to a running mysql / pg cdc task
+pub(crate) struct AdminServer {
+    handle: ServerHandle,
+    unix_socket: Option<String>,
+}
+
+impl AdminServer {
+    /// bind: host:port, or unix:/path/to/socket for local-only access
+    pub(crate) fn start(config: &AdminConfig, state: Arc<AdminState>) -> anyhow::Result<Self> {
+        let unix_socket = config.bind.strip_prefix(UNIX_PREFIX).map(|i| i.to_string());
+        match &unix_socket {
+            Some(path) => Self::remove_stale_socket(path)?,
+            None => Self::check_tcp_bind(&config.bind, &config.token)?,
+        }
+
+        // the server runs in its own actix system, independent of the task runtime
+        let (sender, receiver) = mpsc::channel();
+        let (bind, token) = (config.bind.clone(), config.token.clone());
+        thread::spawn(move || {
+            System::new().block_on(async move {
+                match Self::build_server(&bind, token, state) {
+                    Ok(server) => {
+                        let _ = sender.send(Ok(server.handle()));
+                        if let Err(err) = server.await {
+                            log_error!("admin server exits with error: {}", err);
+                        }
+                    }
+                    Err(err) => {
+                        let _ = sender.send(Err(err));
+                    }
+                }
+            })
+        });
+
+        let handle = match receiver.recv() {
+            Ok(result) => result?,
+            Err(_) => bail! {Error::Unexpected("admin server thread exited".into())},
+        };
+        log_info!("admin server listening on: {}", config.bind);
+        Ok(Self {
+            handle,
+            unix_socket,
+        })
+    }
+
+    pub async fn stop(&self) {
+        self.handle.stop(true).await;
+        if let Some(path) = &self.unix_socket {
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    /// Remove the socket left by a previous run, any other file at the path is kept
+    fn remove_stale_socket(path: &str) -> anyhow::Result<()> {
+        let metadata = match fs::symlink_metadata(path) {
+            Ok(metadata) => metadata,
+            Err(_) => return Ok(()),
+        };
+        if !metadata.file_type().is_socket() {
+            bail! {Error::ConfigError(format!(
+                "[admin] bind: [{}] already exists and is not a unix socket",
+                path
+            ))}
+        }
+        fs::remove_file(path).with_context(|| format!("failed to remove unix socket: [{}]", path))
+    }
+
+    /// Without a token, the tcp server can only be reached from the local host
+    fn check_tcp_bind(bind: &str, token: &str) -> anyhow::Result<()> {
+        if !token.is_empty() {
+            return Ok(());
+        }
+        let addrs: Vec<_> = bind
+            .to_socket_addrs()
+            .with_context(|| format!("invalid [admin] bind: [{}]", bind))?
+            .collect();
+        if addrs.is_empty() || addrs.iter().any(|i| !i.ip().is_loopback()) {
+            bail! {Error::ConfigError(format!(
+                "[admin] bind: [{}] is not a loopback address, [admin] token is required",
+                bind
+            ))}
+        }
+        Ok(())
+    }
+
+    fn build_server(
+        bind: &str,
+        token: String,
+        state: Arc<AdminState>,
+    ) -> anyhow::Result<actix_web::dev::Server> {
+        let token = web::Data::new(AdminToken(token));
+        let server = HttpServer::new(move || {
+            App::new()
+                .wrap(from_fn(authorize))
+                .app_data(token.clone())
+                .app_data(web::Data::new(state.clone()))
+                .service(web::resource("/status").route(web::get().to(status)))
+                .service(web::resource("/pause").route(web::post().to(pause)))
+                .service(web::resource("/resume").route(web::post().to(resume)))
+                .service(web::resource("/max_rps").route(web::post().to(max_rps)))
+                .service(web::resource("/checkpoint").route(web::post().to(checkpoint)))
+                .service(web::resource("/backfill").route(web::post().to(backfill)))
+        })
+        .workers(1);
+
+        let server = match bind.strip_prefix(UNIX_PREFIX) {
+            Some(path) => server.bind_uds(path),
+            None => server.bind(bind),
+        }
+        .with_context(|| format!("admin server failed to bind: [{}]", bind))?;
+        Ok(server.run())
+    }
+}
+
+async fn authorize(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let token = req
+        .app_data::<web::Data<AdminToken>>()
+        .map(|i| i.0.as_str())
+        .unwrap_or_default();
+    if !token.is_empty() {
+        let authorized = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|i| i.to_str().ok())
+            .and_then(|i| i.strip_prefix(BEARER_PREFIX))
+            .is_some_and(|i| i == token);
+        if !authorized {
+            return Err(ErrorUnauthorized("invalid or missing admin token"));
+        }
+    }
+    next.call(req).await
+}
+
+async fn status(state: web::Data<Arc<AdminState>>) -> impl Responder {
+    HttpResponse::Ok().json(state.status())
+}
+
+async fn pause(state: web::Data<Arc<AdminState>>) -> impl Responder {
+    state.control.pause();
+    log_info!("pipeline paused by admin server");
+    HttpResponse::Ok().json(state.status())
+}
+
+async fn resume(state: web::Data<Arc<AdminState>>) -> impl Responder {
+    state.control.resume();
+    log_info!("pipeline resumed by admin server");
+    HttpResponse::Ok().json(state.status())
+}
+
+async fn max_rps(data: web::Json<MaxRpsReq>, state: web::Data<Arc<AdminState>>) -> impl Responder {
+    if let Err(err) = state.rps_limiter.set_max_rps(data.max_rps) {
+        return HttpResponse::BadRequest().body(err.to_string());
+    }
+    log_info!("max_rps set to {} by admin server", data.max_rps);
+    HttpResponse::Ok().json(state.status())
+}
+
+async fn checkpoint(state: web::Data<Arc<AdminState>>) -> impl Responder {
+    state.control.request_checkpoint();
+    HttpResponse::Ok().json(state.status())
+}
+
+async fn backfill(
+    data: web::Json<BackfillReq>,
+    state: web::Data<Arc<AdminState>>,
+) -> impl Responder {
+    if let Err(err) = state.backfill_requests.push(&data.tbs) {
+        return HttpResponse::BadRequest().body(err.to_string());
+    }
+    log_info!("backfill requested by admin server, tbs: {}", data.tbs);
+    HttpResponse::Ok().json(state.status())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env,
+        io::{Read, Write},
+        os::unix::net::{UnixListener, UnixStream},
+    };
+
+    use super::*;
+
+    fn admin_config(bind: &str, token: &str) -> AdminConfig {
+        AdminConfig {
+            bind: bind.into(),
+            token: token.into(),
+        }
+    }
+
+    /// Sends a request by http/1.1 over the unix socket, returns the status code and body
+    fn request(socket: &str, method: &str, path: &str, token: &str, body: &str) -> (u16, String) {
+        let mut stream = UnixStream::connect(socket).unwrap();
+        let auth = if token.is_empty() {
+            String::new()
+        } else {
+            format!("Authorization: Bearer {}\r\n", token)
+        };
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: localhost\r\n{}Content-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            method,
+            path,
+            auth,
+            body.len(),
+            body
+        )
+        .unwrap();
+
+        let mut resp = String::new();
+        stream.read_to_string(&mut resp).unwrap();
+        // HTTP/1.1 200 OK
+        let status = resp[9..12].parse().unwrap();
+        let body = resp
+            .split_once("\r\n\r\n")
+            .map(|(_, body)| body.to_string())
+            .unwrap_or_default();
+        (status, body)
+    }
+
+    #[tokio::test]
+    async fn test_admin_commands() {
+        let socket = env::temp_dir().join("ape_dts_test_admin_commands.sock");
+        let socket = socket.to_str().unwrap();
+        let bind = format!("{}{}", UNIX_PREFIX, socket);
+        let state = Arc::new(AdminState::new(Arc::new(TaskState::new(None))));
+        let server = AdminServer::start(&admin_config(&bind, "abc"), state.clone()).unwrap();
+
+        let (code, _) = request(socket, "GET", "/status", "", "");
+        assert_eq!(code, 401);
+        let (code, _) = request(socket, "GET", "/status", "abd", "");
+        assert_eq!(code, 401);
+        let (code, body) = request(socket, "GET", "/status", "abc", "");
+        assert_eq!(code, 200);
+        assert!(body.contains(r#""paused":false"#));
+
+        let (code, body) = request(socket, "POST", "/pause", "abc", "");
+        assert_eq!(code, 200);
+        assert!(body.contains(r#""paused":true"#));
+        assert!(state.control.is_paused());
+
+        let (code, body) = request(socket, "POST", "/resume", "abc", "");
+        assert_eq!(code, 200);
+        assert!(body.contains(r#""paused":false"#));
+        assert!(!state.control.is_paused());
+
+        let (code, body) = request(socket, "POST", "/max_rps", "abc", r#"{"max_rps": 100}"#);
+        assert_eq!(code, 200);
+        assert!(body.contains(r#""max_rps":100"#));
+        assert_eq!(state.rps_limiter.max_rps(), 100);
+        let (code, _) = request(socket, "POST", "/max_rps", "", r#"{"max_rps": 0}"#);
+        assert_eq!(code, 401);
+        assert_eq!(state.rps_limiter.max_rps(), 100);
+
+        let (code, _) = request(socket, "POST", "/checkpoint", "abc", "");
+        assert_eq!(code, 200);
+        assert!(state.control.take_checkpoint_request());
+
+        server.stop().await;
+        assert!(!Path::new(socket).exists());
+    }
+
+    #[tokio::test]
+    async fn test_admin_bind() {
+        let state = Arc::new(AdminState::new(Arc::new(TaskState::new(None))));
+
+        // a file which is not a socket is kept
+        let file = env::temp_dir().join("ape_dts_test_admin_bind.sock");
+        fs::write(&file, "abc").unwrap();
+        let bind = format!("{}{}", UNIX_PREFIX, file.display());
+        assert!(AdminServer::start(&admin_config(&bind, ""), state.clone()).is_err());
+        assert_eq!(fs::read_to_string(&file).unwrap(), "abc");
+        fs::remove_file(&file).unwrap();
+
+        // the socket left by a previous run is replaced
+        drop(UnixListener::bind(&file).unwrap());
+        assert!(file.exists());
+        let server = AdminServer::start(&admin_config(&bind, ""), state.clone()).unwrap();
+        server.stop().await;
+
+        // tcp without token is only allowed on loopback addresses
+        assert!(AdminServer::start(&admin_config("0.0.0.0:0", ""), state.clone()).is_err());
+        let server = AdminServer::start(&admin_config("127.0.0.1:0", ""), state.clone()).unwrap();
+        server.stop().await;
+        let server = AdminServer::start(&admin_config("0.0.0.0:0", "abc"), state.clone()).unwrap();
+        server.stop().await;
+    }
+}
@@ -16,8 +16,10 @@ use dt_common::{
 };
 use dt_common::{
     meta::{
-        avro::avro_converter::AvroConverter, mongo::mongo_cdc_source::MongoCdcSource,
-        pg::pg_meta_manager::PgMetaManager, redis::redis_statistic_type::RedisStatisticType,
+        avro::avro_converter::AvroConverter,
+        mongo::mongo_cdc_source::MongoCdcSource,
+        pg::pg_meta_manager::PgMetaManager,
+        redis::{command::key_parser::KeyParser, redis_statistic_type::RedisStatisticType},
         syncer::Syncer,
     },
     utils::redis_util::RedisUtil,
@@ -26,7 +28,9 @@ use dt_connector::{
     data_marker::DataMarker,
     extractor::{
         base_extractor::BaseExtractor,
+        cdc_backfill::{BackfillRequests, CdcBackfill},
         extractor_monitor::ExtractorMonitor,
+        file::file_extractor::FileExtractor,
         foxlake::foxlake_s3_extractor::FoxlakeS3Extractor,
         kafka::kafka_extractor::KafkaExtractor,
         mongo::{
@@ -43,6 +47,7 @@ use dt_connector::{
             pg_snapshot_extractor::PgSnapshotExtractor, pg_struct_extractor::PgStructExtractor,
         },
         redis::{
+            redis_aof_file_extractor::RedisAofFileExtractor,
             redis_cdc_extractor::RedisCdcExtractor, redis_client::RedisClient,
             redis_reshard_extractor::RedisReshardExtractor,
             redis_scan_extractor::RedisScanExtractor,
@@ -50,11 +55,14 @@ use dt_connector::{
             redis_snapshot_file_extractor::RedisSnapshotFileExtractor,
         },
         resumer::{cdc_resumer::CdcResumer, snapshot_resumer::SnapshotResumer},
+        snapshot_conn_pool::{ConsistentSnapshot, ExtractorConnPool},
     },
     rdb_router::RdbRouter,
     Extractor,
 };
 
+use crate::plugin_registry::{ExtractorContext, PluginRegistry};
+
 use super::task_util::TaskUtil;
 
 pub struct ExtractorUtil {}
@@ -71,6 +79,9 @@ impl ExtractorUtil {
         router: RdbRouter,
         snapshot_resumer: SnapshotResumer,
         cdc_resumer: CdcResumer,
+        backfill_requests: BackfillRequests,
+        consistent_snapshot: Option<ConsistentSnapshot>,
+        plugins: &PluginRegistry,
     ) -> anyhow::Result<Box<dyn Extractor + Send>> {
         let mut base_extractor = BaseExtractor {
             buffer,
@@ -83,6 +94,7 @@ impl ExtractorUtil {
 
         let enable_sqlx_log = TaskUtil::check_enable_sqlx_log(&config.runtime.log_level);
         let filter = RdbFilter::from_config(&config.filter, &config.extractor_basic.db_type)?;
+        let tls = &config.extractor_basic.tls;
 
         let extractor: Box<dyn Extractor + Send> = match extractor_config.to_owned() {
             ExtractorConfig::MysqlSnapshot {
@@ -92,12 +104,20 @@ impl ExtractorUtil {
                 sample_interval,
                 parallel_size,
                 batch_size,
+                ..
             } => {
-                // max_connections: 1 for extracting data from table, 1 for db-meta-manager
-                let max_connections = cmp::max(2, parallel_size as u32 + 1);
-                let conn_pool =
-                    TaskUtil::create_mysql_conn_pool(&url, max_connections, enable_sqlx_log)
-                        .await?;
+                let conn_pool = if let Some(ConsistentSnapshot::MySQL(conn_pool)) =
+                    consistent_snapshot
+                {
+                    ExtractorConnPool::Snapshot(conn_pool)
+                } else {
+                    // max_connections: 1 for extracting data from table, 1 for db-meta-manager
+                    let max_connections = cmp::max(2, parallel_size as u32 + 1);
+                    ExtractorConnPool::Pool(
+                        TaskUtil::create_mysql_conn_pool(&url, max_connections, enable_sqlx_log)
+                            .await?,
+                    )
+                };
                 let meta_manager = TaskUtil::create_mysql_meta_manager(
                     &url,
                     &config.runtime.log_level,
@@ -106,7 +126,7 @@ impl ExtractorUtil {
                 )
                 .await?;
                 let extractor = MysqlSnapshotExtractor {
-                    conn_pool: conn_pool.clone(),
+                    conn_pool,
                     meta_manager,
                     resumer: snapshot_resumer,
                     db,
@@ -155,6 +175,8 @@ impl ExtractorUtil {
                 heartbeat_tb,
                 start_time_utc,
                 end_time_utc,
+                signal_tb,
+                batch_size,
             } => {
                 let conn_pool = TaskUtil::create_mysql_conn_pool(&url, 2, enable_sqlx_log).await?;
                 let meta_manager = TaskUtil::create_mysql_meta_manager(
@@ -165,11 +187,13 @@ impl ExtractorUtil {
                 )
                 .await?;
                 base_extractor.time_filter = TimeFilter::new(&start_time_utc, &end_time_utc)?;
+                let backfill = CdcBackfill::new(&signal_tb, batch_size, &DbType::Mysql)?;
                 let extractor = MysqlCdcExtractor {
                     meta_manager,
                     filter,
                     conn_pool,
                     url,
+                    tls: tls.clone(),
                     binlog_filename,
                     binlog_position,
                     server_id,
@@ -180,6 +204,8 @@ impl ExtractorUtil {
                     resumer: cdc_resumer,
                     gtid_enabled,
                     gtid_set,
+                    backfill,
+                    backfill_requests,
                 };
                 Box::new(extractor)
             }
@@ -190,15 +216,33 @@ impl ExtractorUtil {
                 tb,
                 sample_interval,
                 batch_size,
+                extract_by_copy,
+                ..
             } => {
-                let conn_pool = TaskUtil::create_pg_conn_pool(&url, 2, enable_sqlx_log).await?;
-                let meta_manager = PgMetaManager::new(conn_pool.clone()).await?;
+                let (conn_pool, meta_manager) =
+                    if let Some(ConsistentSnapshot::PostgreSQL(conn_pool)) = consistent_snapshot {
+                        // the snapshot connections are for data only, metas are read by another pool
+                        let meta_conn_pool =
+                            TaskUtil::create_pg_conn_pool(&url, 1, enable_sqlx_log).await?;
+                        (
+                            ExtractorConnPool::Snapshot(conn_pool),
+                            PgMetaManager::new(meta_conn_pool).await?,
+                        )
+                    } else {
+                        let conn_pool =
+                            TaskUtil::create_pg_conn_pool(&url, 2, enable_sqlx_log).await?;
+                        (
+                            ExtractorConnPool::Pool(conn_pool.clone()),
+                            PgMetaManager::new(conn_pool).await?,
+                        )
+                    };
                 let extractor = PgSnapshotExtractor {
                     conn_pool,
                     meta_manager,
                     resumer: snapshot_resumer,
                     batch_size,
                     sample_interval,
+                    extract_by_copy,
                     schema,
                     tb,
                     base_extractor,
@@ -237,14 +281,18 @@ impl ExtractorUtil {
                 ddl_meta_tb,
                 start_time_utc,
                 end_time_utc,
+                signal_tb,
+                batch_size,
             } => {
                 let conn_pool = TaskUtil::create_pg_conn_pool(&url, 2, enable_sqlx_log).await?;
                 let meta_manager = PgMetaManager::new(conn_pool.clone()).await?;
                 base_extractor.time_filter = TimeFilter::new(&start_time_utc, &end_time_utc)?;
+                let backfill = CdcBackfill::new(&signal_tb, batch_size, &DbType::Pg)?;
                 let extractor = PgCdcExtractor {
                     meta_manager,
                     filter,
                     url,
+                    tls: tls.clone(),
                     conn_pool,
                     slot_name,
                     pub_name,
@@ -257,6 +305,8 @@ impl ExtractorUtil {
                     ddl_meta_tb,
                     resumer: cdc_resumer,
                     base_extractor,
+                    backfill,
+                    backfill_requests,
                 };
                 Box::new(extractor)
             }
@@ -345,12 +395,13 @@ impl ExtractorUtil {
             }
 
             ExtractorConfig::RedisSnapshot { url, repl_port } => {
-                let conn = RedisClient::new(&url).await?;
+                let conn = RedisClient::new(&url, tls).await?;
                 let extractor = RedisSnapshotExtractor {
                     conn,
                     repl_port,
                     filter,
                     base_extractor,
+                    key_parser: KeyParser::new(),
                 };
                 Box::new(extractor)
             }
@@ -360,6 +411,17 @@ impl ExtractorUtil {
                     file_path,
                     filter,
                     base_extractor,
+                    key_parser: KeyParser::new(),
+                };
+                Box::new(extractor)
+            }
+
+            ExtractorConfig::RedisAofFile { file_path } => {
+                let extractor = RedisAofFileExtractor {
+                    file_path,
+                    filter,
+                    base_extractor,
+                    key_parser: KeyParser::new(),
                 };
                 Box::new(extractor)
             }
@@ -369,7 +431,7 @@ impl ExtractorUtil {
                 scan_count,
                 statistic_type,
             } => {
-                let conn = RedisUtil::create_redis_conn(&url).await?;
+                let conn = RedisUtil::create_redis_conn(&url, tls).await?;
                 let statistic_type = RedisStatisticType::from_str(&statistic_type)?;
                 let extractor = RedisScanExtractor {
                     conn,
@@ -391,7 +453,7 @@ impl ExtractorUtil {
                 heartbeat_interval_secs,
                 heartbeat_key,
             } => {
-                let conn = RedisClient::new(&url).await?;
+                let conn = RedisClient::new(&url, tls).await?;
                 let extractor = RedisCdcExtractor {
                     conn,
                     repl_id,
@@ -405,6 +467,7 @@ impl ExtractorUtil {
                     filter,
                     resumer: cdc_resumer,
                     base_extractor,
+                    key_parser: KeyParser::new(),
                 };
                 Box::new(extractor)
             }
@@ -413,6 +476,7 @@ impl ExtractorUtil {
                 let extractor = RedisReshardExtractor {
                     base_extractor,
                     url,
+                    tls: tls.clone(),
                 };
                 Box::new(extractor)
             }
@@ -424,6 +488,7 @@ impl ExtractorUtil {
                 partition,
                 offset,
                 ack_interval_secs,
+                sasl,
             } => {
                 let meta_manager = TaskUtil::create_rdb_meta_manager(config).await?;
                 let avro_converter = AvroConverter::new(meta_manager, false);
@@ -434,6 +499,8 @@ impl ExtractorUtil {
                     partition,
                     offset,
                     ack_interval_secs,
+                    tls: tls.clone(),
+                    sasl,
                     avro_converter,
                     syncer,
                     resumer: cdc_resumer,
@@ -461,6 +528,41 @@ impl ExtractorUtil {
                 };
                 Box::new(extractor)
             }
+
+            ExtractorConfig::File {
+                url,
+                db,
+                tb,
+                batch_size,
+            } => {
+                // values are converted by the column types of target tables
+                let meta_manager = TaskUtil::create_rdb_meta_manager(config).await?;
+                let extractor = FileExtractor {
+                    url,
+                    db,
+                    tb,
+                    batch_size,
+                    base_extractor,
+                    filter,
+                    resumer: cdc_resumer,
+                    meta_manager,
+                    sinker_db_type: config.sinker_basic.db_type.clone(),
+                };
+                Box::new(extractor)
+            }
+
+            ExtractorConfig::Plugin { name, configs } => {
+                let context = ExtractorContext {
+                    name,
+                    configs,
+                    task_config: config.clone(),
+                    base_extractor,
+                    syncer,
+                    snapshot_resumer,
+                    cdc_resumer,
+                };
+                plugins.create_extractor(context).await?
+            }
         };
         Ok(extractor)
     }
@@ -1,7 +1,10 @@
 #![allow(clippy::too_many_arguments)]
 
+pub mod admin_server;
 pub mod extractor_util;
 pub mod parallelizer_util;
+pub mod plugin_registry;
 pub mod sinker_util;
+pub mod task_handle;
 pub mod task_runner;
 pub mod task_util;
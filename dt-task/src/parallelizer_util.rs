@@ -3,24 +3,29 @@ use std::{
     sync::{Arc, Mutex},
 };
 
+use anyhow::bail;
 use dt_common::{
     config::{
         config_enums::{DbType, ParallelType},
         sinker_config::SinkerConfig,
         task_config::TaskConfig,
     },
+    error::Error,
     monitor::monitor::Monitor,
 };
 use dt_common::{meta::redis::command::key_parser::KeyParser, utils::redis_util::RedisUtil};
+use dt_connector::rdb_router::RdbRouter;
 use dt_parallelizer::{
     base_parallelizer::BaseParallelizer, check_parallelizer::CheckParallelizer,
     foxlake_parallelizer::FoxlakeParallelizer, merge_parallelizer::MergeParallelizer,
     mongo_merger::MongoMerger, partition_parallelizer::PartitionParallelizer,
     rdb_merger::RdbMerger, rdb_partitioner::RdbPartitioner, redis_parallelizer::RedisParallelizer,
-    serial_parallelizer::SerialParallelizer, snapshot_parallelizer::SnapshotParallelizer,
-    table_parallelizer::TableParallelizer, Merger, Parallelizer,
+    rps_limiter::RpsLimiter, serial_parallelizer::SerialParallelizer,
+    snapshot_parallelizer::SnapshotParallelizer, table_parallelizer::TableParallelizer,
+    transaction_parallelizer::TransactionParallelizer, Merger, Parallelizer,
 };
-use ratelimit::Ratelimiter;
+
+use crate::plugin_registry::{ParallelizerContext, PluginRegistry};
 
 use super::task_util::TaskUtil;
 
@@ -30,7 +35,8 @@ impl ParallelizerUtil {
     pub async fn create_parallelizer(
         config: &TaskConfig,
         monitor: Arc<Mutex<Monitor>>,
-        rps_limiter: Option<Ratelimiter>,
+        rps_limiter: RpsLimiter,
+        plugins: &PluginRegistry,
     ) -> anyhow::Result<Box<dyn Parallelizer + Send>> {
         let parallel_size = config.parallelizer.parallel_size;
         let parallel_type = &config.parallelizer.parallel_type;
@@ -79,6 +85,25 @@ impl ParallelizerUtil {
                 })
             }
 
+            ParallelType::RdbTransaction => {
+                // source transactions are committed atomically by serial sink of rdb sinkers
+                if !matches!(
+                    config.sinker,
+                    SinkerConfig::Mysql { .. } | SinkerConfig::Pg { .. }
+                ) {
+                    bail! {Error::ConfigError(
+                        "parallel_type=rdb_transaction is only supported by mysql / pg sinkers"
+                            .into()
+                    )}
+                }
+                let meta_manager = TaskUtil::create_rdb_meta_manager(config).await?.unwrap();
+                Box::new(TransactionParallelizer {
+                    base_parallelizer,
+                    meta_manager,
+                    parallel_size,
+                })
+            }
+
             ParallelType::Serial => Box::new(SerialParallelizer { base_parallelizer }),
 
             ParallelType::Table => Box::new(TableParallelizer {
@@ -99,12 +124,28 @@ impl ParallelizerUtil {
 
             ParallelType::Redis => {
                 let mut slot_node_map = HashMap::new();
-                if let SinkerConfig::Redis { is_cluster, .. } = config.sinker {
-                    let mut conn = RedisUtil::create_redis_conn(&config.sinker_basic.url).await?;
+                let mut event_router = None;
+                if let SinkerConfig::Redis {
+                    is_cluster,
+                    ref event_mode,
+                    ..
+                } = config.sinker
+                {
+                    let mut conn = RedisUtil::create_redis_conn(
+                        &config.sinker_basic.url,
+                        &config.sinker_basic.tls,
+                    )
+                    .await?;
                     if is_cluster {
                         let nodes = RedisUtil::get_cluster_master_nodes(&mut conn)?;
                         slot_node_map = RedisUtil::get_slot_address_map(&nodes);
                     }
+                    if !event_mode.is_empty() {
+                        event_router = Some(RdbRouter::from_config(
+                            &config.router,
+                            &config.extractor_basic.db_type,
+                        )?);
+                    }
                 }
                 Box::new(RedisParallelizer {
                     base_parallelizer,
@@ -112,6 +153,7 @@ impl ParallelizerUtil {
                     slot_node_map,
                     key_parser: KeyParser::new(),
                     node_sinker_index_map: HashMap::new(),
+                    event_router,
                 })
             }
 
@@ -125,6 +167,17 @@ impl ParallelizerUtil {
                     base_parallelizer: snapshot_parallelizer,
                 })
             }
+
+            ParallelType::Plugin(name) => {
+                let context = ParallelizerContext {
+                    name: name.clone(),
+                    configs: config.parallelizer.configs.clone(),
+                    task_config: config.clone(),
+                    base_parallelizer,
+                    parallel_size,
+                };
+                plugins.create_parallelizer(context).await?
+            }
         };
         Ok(parallelizer)
     }
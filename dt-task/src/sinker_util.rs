@@ -1,14 +1,19 @@
 use std::{
+    collections::HashMap,
     str::FromStr,
     sync::{Arc, Mutex, RwLock},
 };
 
-use anyhow::Context;
+use anyhow::{bail, Context};
 use dt_common::{
     config::{
-        config_enums::DbType, extractor_config::ExtractorConfig, sinker_config::SinkerConfig,
+        config_enums::{DbType, FileCompressionEnum, FileFormatEnum, ParallelType, SaslMechanism},
+        extractor_config::ExtractorConfig,
+        sinker_config::SinkerConfig,
         task_config::TaskConfig,
+        tls_config::TlsConfig,
     },
+    error::Error,
     monitor::monitor::Monitor,
     rdb_filter::RdbFilter,
     utils::url_util::UrlUtil,
@@ -30,33 +35,55 @@ use dt_connector::{
         clickhouse::{
             clickhouse_sinker::ClickhouseSinker, clickhouse_struct_sinker::ClickhouseStructSinker,
         },
+        doris::{
+            doris_ddl_converter::DorisDdlConverter, doris_sinker::DorisSinker,
+            doris_struct_sinker::DorisStructSinker,
+        },
+        duckdb::{duckdb_sinker::DuckDbSinker, duckdb_struct_sinker::DuckDbStructSinker},
         dummy_sinker::DummySinker,
+        elasticsearch::{
+            elasticsearch_client::ElasticsearchClient, elasticsearch_sinker::ElasticsearchSinker,
+            elasticsearch_struct_sinker::ElasticsearchStructSinker,
+        },
+        file::{file_manifest::FileManifest, file_sinker::FileSinker},
         foxlake::{
             foxlake_merger::FoxlakeMerger, foxlake_pusher::FoxlakePusher,
             foxlake_sinker::FoxlakeSinker, foxlake_struct_sinker::FoxlakeStructSinker,
             orc_sequencer::OrcSequencer,
         },
-        kafka::kafka_sinker::KafkaSinker,
+        kafka::{kafka_sinker::KafkaSinker, rdkafka_sinker::RdkafkaSinker},
         mongo::{mongo_checker::MongoChecker, mongo_sinker::MongoSinker},
         mysql::{
-            mysql_checker::MysqlChecker, mysql_sinker::MysqlSinker,
-            mysql_struct_sinker::MysqlStructSinker,
+            mysql_checker::MysqlChecker, mysql_load_data_util::MysqlLoadDataUtil,
+            mysql_sinker::MysqlSinker, mysql_struct_sinker::MysqlStructSinker,
         },
         pg::{pg_checker::PgChecker, pg_sinker::PgSinker, pg_struct_sinker::PgStructSinker},
-        redis::{redis_sinker::RedisSinker, redis_statistic_sinker::RedisStatisticSinker},
+        redis::{
+            redis_event_publisher::{RedisEventMode, RedisEventPublisher},
+            redis_memory_report::RedisMemoryReport,
+            redis_row_mapper::RedisRowMapper,
+            redis_sinker::RedisSinker,
+            redis_statistic_sinker::RedisStatisticSinker,
+        },
         sql_sinker::SqlSinker,
         starrocks::{
             starrocks_sinker::StarRocksSinker, starrocks_struct_sinker::StarrocksStructSinker,
         },
+        webhook_sinker::WebhookSinker,
     },
+    tls_util::TlsUtil,
     Sinker,
 };
 use kafka::producer::{Producer, RequiredAcks};
-use reqwest::{redirect::Policy, Url};
+use rdkafka::{producer::FutureProducer, ClientConfig};
+use reqwest::Url;
 use rusoto_s3::S3Client;
 use sqlx::types::chrono::Utc;
 
-use crate::extractor_util::ExtractorUtil;
+use crate::{
+    extractor_util::ExtractorUtil,
+    plugin_registry::{PluginRegistry, SinkerContext},
+};
 
 use super::task_util::TaskUtil;
 
@@ -70,10 +97,12 @@ impl SinkerUtil {
         extractor_config: &ExtractorConfig,
         monitor: Arc<Mutex<Monitor>>,
         data_marker: Option<Arc<RwLock<DataMarker>>>,
+        plugins: &PluginRegistry,
     ) -> anyhow::Result<Sinkers> {
         let log_level = &task_config.runtime.log_level;
         let enable_sqlx_log = TaskUtil::check_enable_sqlx_log(log_level);
         let parallel_size = task_config.parallelizer.parallel_size as u32;
+        let tls = &task_config.sinker_basic.tls;
 
         let mut sub_sinkers: Sinkers = Vec::new();
         match task_config.sinker.clone() {
@@ -84,12 +113,22 @@ impl SinkerUtil {
                 }
             }
 
-            SinkerConfig::Mysql { url, batch_size } => {
+            SinkerConfig::Mysql {
+                url,
+                batch_size,
+                bulk_load,
+            } => {
                 let router = RdbRouter::from_config(&task_config.router, &DbType::Mysql)?;
                 let conn_pool =
                     TaskUtil::create_mysql_conn_pool(&url, parallel_size * 2, enable_sqlx_log)
                         .await?;
                 let meta_manager = MysqlMetaManager::new(conn_pool.clone()).await?;
+                let load_data_pool = if bulk_load && Self::is_snapshot(extractor_config) {
+                    MysqlLoadDataUtil::check_local_infile(&conn_pool).await?;
+                    Some(TaskUtil::create_mysql_load_data_pool(&url, parallel_size).await?)
+                } else {
+                    None
+                };
                 // to avoid contention for monitor write lock between sinker threads,
                 // create a monitor for each sinker instead of sharing a single monitor between sinkers,
                 // sometimes a sinker may cost several millis to get write lock for a global monitor.
@@ -102,6 +141,7 @@ impl SinkerUtil {
                         batch_size,
                         monitor: monitor.clone(),
                         data_marker: data_marker.clone(),
+                        load_data_pool: load_data_pool.clone(),
                     };
                     sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
                 }
@@ -137,7 +177,12 @@ impl SinkerUtil {
                 }
             }
 
-            SinkerConfig::Pg { url, batch_size } => {
+            SinkerConfig::Pg {
+                url,
+                batch_size,
+                bulk_load,
+                copy_format,
+            } => {
                 let router = RdbRouter::from_config(&task_config.router, &DbType::Pg)?;
                 let conn_pool =
                     TaskUtil::create_pg_conn_pool(&url, parallel_size * 2, enable_sqlx_log).await?;
@@ -152,6 +197,8 @@ impl SinkerUtil {
                         batch_size,
                         monitor: monitor.clone(),
                         data_marker: data_marker.clone(),
+                        bulk_load: bulk_load && Self::is_snapshot(extractor_config),
+                        copy_format: copy_format.clone(),
                     };
                     sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
                 }
@@ -230,6 +277,7 @@ impl SinkerUtil {
                 ack_timeout_secs,
                 required_acks,
                 with_field_defs,
+                sasl,
             } => {
                 let router = RdbRouter::from_config(
                     &task_config.router,
@@ -248,14 +296,41 @@ impl SinkerUtil {
                 };
 
                 for _ in 0..parallel_size {
-                    // TODO, authentication, https://github.com/kafka-rust/kafka-rust/blob/master/examples/example-ssl.rs
-                    let producer = Producer::from_hosts(brokers.clone())
-                        .with_ack_timeout(std::time::Duration::from_secs(ack_timeout_secs))
-                        .with_required_acks(acks)
-                        .create()
-                        .with_context(|| {
+                    // kafka-rust does not support sasl, use RdkafkaSinker instead
+                    if sasl.mechanism != SaslMechanism::None {
+                        let mut config = ClientConfig::new();
+                        config.set("bootstrap.servers", &url);
+                        let rdkafka_acks = match required_acks.as_str() {
+                            "all" => "all",
+                            "none" => "0",
+                            _ => "1",
+                        };
+                        config.set("acks", rdkafka_acks);
+                        TlsUtil::set_rdkafka_security(&mut config, tls, &sasl);
+                        let producer: FutureProducer = config.create().with_context(|| {
                             format!("failed to create kafka producer, url: [{}]", url)
                         })?;
+                        let sinker = RdkafkaSinker {
+                            batch_size,
+                            router: router.clone(),
+                            producer,
+                            avro_converter: avro_converter.clone(),
+                            monitor: monitor.clone(),
+                            queue_timeout_secs: ack_timeout_secs,
+                        };
+                        sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
+                        continue;
+                    }
+
+                    let mut builder = Producer::from_hosts(brokers.clone())
+                        .with_ack_timeout(std::time::Duration::from_secs(ack_timeout_secs))
+                        .with_required_acks(acks);
+                    if tls.is_enabled() {
+                        builder = builder.with_security(TlsUtil::build_kafka_security_config(tls)?);
+                    }
+                    let producer = builder.create().with_context(|| {
+                        format!("failed to create kafka producer, url: [{}]", url)
+                    })?;
                     // the sending performance of RdkafkaSinker is much worse than KafkaSinker
                     let sinker = KafkaSinker {
                         batch_size,
@@ -308,14 +383,52 @@ impl SinkerUtil {
                 batch_size,
                 method,
                 is_cluster,
+                row_mappings,
+                event_mode,
+                stream_max_len,
             } => {
                 // redis sinker may need meta data from RDB extractor
                 let meta_manager = ExtractorUtil::get_extractor_meta_manager(task_config).await?;
-                let mut conn = RedisUtil::create_redis_conn(&url).await?;
+                let row_mapper = RedisRowMapper::from_config(&row_mappings)?;
+                let mut conn = RedisUtil::create_redis_conn(&url, tls).await?;
                 let version = RedisUtil::get_redis_version(&mut conn)?;
                 let method = RedisWriteMethod::from_str(&method)?;
+                let event_publisher = if event_mode.is_empty() {
+                    None
+                } else {
+                    let router = RdbRouter::from_config(
+                        &task_config.router,
+                        &task_config.extractor_basic.db_type,
+                    )?;
+                    // events of the same key must be published in order
+                    if !matches!(
+                        task_config.parallelizer.parallel_type,
+                        ParallelType::Serial | ParallelType::Redis
+                    ) {
+                        bail! {Error::ConfigError(
+                            "redis event_mode only supports parallel_type: serial, redis".into()
+                        )}
+                    }
+                    // streams / channels are taken from topic_map, which needs a default
+                    if !router.topic_map.contains_key(&("*".into(), "*".into())) {
+                        bail! {Error::ConfigError(
+                            "topic_map with *.* is required by redis event_mode".into()
+                        )}
+                    }
+                    Some(RedisEventPublisher {
+                        mode: RedisEventMode::from_str(&event_mode)?,
+                        router,
+                        stream_max_len,
+                    })
+                };
 
                 if is_cluster {
+                    // keys are routed to the nodes owning their slots by RedisParallelizer
+                    if !matches!(task_config.parallelizer.parallel_type, ParallelType::Redis) {
+                        bail! {Error::ConfigError(
+                            "redis cluster sinker only supports parallel_type: redis".into()
+                        )}
+                    }
                     let url_info = Url::parse(&url)?;
                     let username = url_info.username();
                     let password = url_info.password().unwrap_or("").to_string();
@@ -327,7 +440,7 @@ impl SinkerUtil {
                         }
 
                         let new_url = format!("redis://{}:{}@{}", username, password, node.address);
-                        let conn = RedisUtil::create_redis_conn(&new_url).await?;
+                        let conn = RedisUtil::create_redis_conn(&new_url, tls).await?;
                         let sinker = RedisSinker {
                             id: node.address.clone(),
                             conn,
@@ -336,14 +449,17 @@ impl SinkerUtil {
                             version,
                             method: method.clone(),
                             meta_manager: meta_manager.clone(),
+                            row_mapper: row_mapper.clone(),
                             monitor: monitor.clone(),
                             data_marker: data_marker.clone(),
+                            event_publisher: event_publisher.clone(),
+                            cluster_slots: Some(node.slots.iter().cloned().collect()),
                         };
                         sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
                     }
                 } else {
                     for _ in 0..parallel_size {
-                        let conn = RedisUtil::create_redis_conn(&url).await?;
+                        let conn = RedisUtil::create_redis_conn(&url, tls).await?;
                         let sinker = RedisSinker {
                             id: url.to_string(),
                             conn,
@@ -352,8 +468,11 @@ impl SinkerUtil {
                             version,
                             method: method.clone(),
                             meta_manager: meta_manager.clone(),
+                            row_mapper: row_mapper.clone(),
                             monitor: monitor.clone(),
                             data_marker: data_marker.clone(),
+                            event_publisher: event_publisher.clone(),
+                            cluster_slots: None,
                         };
                         sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
                     }
@@ -364,15 +483,36 @@ impl SinkerUtil {
                 statistic_type,
                 data_size_threshold,
                 freq_threshold,
-                ..
+                statistic_log_dir,
+                top_n,
+                key_prefix_delimiter,
+                key_prefix_depth,
             } => {
                 let statistic_type = RedisStatisticType::from_str(&statistic_type)?;
+                // the memory report is shared by all sinkers and written once when closed
+                let memory_report = match statistic_type {
+                    RedisStatisticType::MemoryReport => {
+                        let report_dir = if statistic_log_dir.is_empty() {
+                            format!("{}/statistic", task_config.runtime.log_dir)
+                        } else {
+                            statistic_log_dir
+                        };
+                        Some(Arc::new(Mutex::new(RedisMemoryReport::new(
+                            &report_dir,
+                            top_n,
+                            &key_prefix_delimiter,
+                            key_prefix_depth,
+                        ))))
+                    }
+                    _ => None,
+                };
                 for _ in 0..parallel_size {
                     let sinker = RedisStatisticSinker {
                         statistic_type: statistic_type.clone(),
                         data_size_threshold,
                         freq_threshold,
                         monitor: monitor.clone(),
+                        memory_report: memory_report.clone(),
                     };
                     sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
                 }
@@ -386,15 +526,12 @@ impl SinkerUtil {
             } => {
                 for _ in 0..parallel_size {
                     let url_info = UrlUtil::parse(&stream_load_url)?;
+                    let scheme = Self::get_http_scheme(&url_info, tls);
                     let host = url_info.host_str().unwrap().to_string();
                     let port = format!("{}", url_info.port().unwrap());
                     let username = url_info.username().to_string();
                     let password = url_info.password().unwrap_or("").to_string();
-                    let custom = Policy::custom(|attempt| attempt.follow());
-                    let http_client = reqwest::Client::builder()
-                        .http1_title_case_headers()
-                        .redirect(custom)
-                        .build()?;
+                    let http_client = TlsUtil::build_http_client(tls)?;
                     let conn_pool =
                         TaskUtil::create_mysql_conn_pool(&url, parallel_size * 2, enable_sqlx_log)
                             .await?;
@@ -405,6 +542,7 @@ impl SinkerUtil {
                     .await?;
                     let sinker = StarRocksSinker {
                         http_client,
+                        scheme,
                         host,
                         port,
                         username,
@@ -440,20 +578,88 @@ impl SinkerUtil {
                 sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
             }
 
-            SinkerConfig::ClickHouse { url, batch_size } => {
+            SinkerConfig::Doris {
+                url,
+                batch_size,
+                stream_load_url,
+                hard_delete,
+                group_commit,
+            } => {
+                let filter = RdbFilter::from_config(
+                    &task_config.filter,
+                    &task_config.extractor_basic.db_type,
+                )?;
+                DorisDdlConverter::check_do_ddls(
+                    &task_config.extractor_basic.db_type,
+                    &filter.do_ddls,
+                )?;
+
+                for _ in 0..parallel_size {
+                    let url_info = UrlUtil::parse(&stream_load_url)?;
+                    let scheme = Self::get_http_scheme(&url_info, tls);
+                    let host = url_info.host_str().unwrap().to_string();
+                    let port = format!("{}", url_info.port().unwrap());
+                    let username = url_info.username().to_string();
+                    let password = url_info.password().unwrap_or("").to_string();
+                    let http_client = TlsUtil::build_http_client(tls)?;
+                    let conn_pool =
+                        TaskUtil::create_mysql_conn_pool(&url, parallel_size * 2, enable_sqlx_log)
+                            .await?;
+                    let meta_manager =
+                        MysqlMetaManager::new_mysql_compatible(conn_pool.clone(), DbType::Doris)
+                            .await?;
+                    let sinker = DorisSinker {
+                        http_client,
+                        scheme,
+                        host,
+                        port,
+                        username,
+                        password,
+                        batch_size,
+                        conn_pool,
+                        meta_manager,
+                        monitor: monitor.clone(),
+                        sync_timestamp: Utc::now().timestamp_millis(),
+                        hard_delete,
+                        group_commit: group_commit.clone(),
+                    };
+                    sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
+                }
+            }
+
+            SinkerConfig::DorisStruct {
+                url,
+                conflict_policy,
+            } => {
+                let conn_pool = TaskUtil::create_mysql_conn_pool(&url, 2, enable_sqlx_log).await?;
+                let extractor_meta_manager = ExtractorUtil::get_extractor_meta_manager(task_config)
+                    .await?
+                    .unwrap();
+                let sinker = DorisStructSinker {
+                    conn_pool,
+                    conflict_policy,
+                    extractor_meta_manager,
+                    backend_count: 0,
+                };
+                sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
+            }
+
+            SinkerConfig::ClickHouse {
+                url,
+                batch_size,
+                async_insert,
+            } => {
                 for _ in 0..parallel_size {
                     let url_info = UrlUtil::parse(&url)?;
+                    let scheme = Self::get_http_scheme(&url_info, tls);
                     let host = url_info.host_str().unwrap().to_string();
                     let port = format!("{}", url_info.port().unwrap());
                     let username = url_info.username().to_string();
                     let password = url_info.password().unwrap_or("").to_string();
-                    let custom = Policy::custom(|attempt| attempt.follow());
-                    let http_client = reqwest::Client::builder()
-                        .http1_title_case_headers()
-                        .redirect(custom)
-                        .build()?;
+                    let http_client = TlsUtil::build_http_client(tls)?;
                     let sinker = ClickhouseSinker {
                         http_client,
+                        scheme,
                         host,
                         port,
                         username,
@@ -461,6 +667,8 @@ impl SinkerUtil {
                         batch_size,
                         monitor: monitor.clone(),
                         sync_timestamp: Utc::now().timestamp_millis(),
+                        async_insert,
+                        tb_metas: HashMap::new(),
                     };
                     sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
                 }
@@ -472,10 +680,12 @@ impl SinkerUtil {
                 engine,
             } => {
                 let url_info = UrlUtil::parse(&url)?;
+                let scheme = Self::get_http_scheme(&url_info, tls);
                 let host = url_info.host_str().unwrap().to_string();
                 let port = format!("{}", url_info.port().unwrap());
+                // the clickhouse client verifies https servers with the system root certificates
                 let client = clickhouse::Client::default()
-                    .with_url(format!("http://{}:{}", host, port))
+                    .with_url(format!("{}://{}:{}", scheme, host, port))
                     .with_user(url_info.username())
                     .with_password(url_info.password().unwrap_or(""));
                 let filter = RdbFilter::from_config(&task_config.filter, &DbType::Mysql)?;
@@ -494,6 +704,184 @@ impl SinkerUtil {
                 sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
             }
 
+            SinkerConfig::DuckDb { url, batch_size } => {
+                // sinkers share the database instance by cloned connections
+                let conn = duckdb::Connection::open(&url)
+                    .with_context(|| format!("failed to open duckdb file: {}", url))?;
+                for _ in 0..parallel_size {
+                    let sinker = DuckDbSinker {
+                        conn: conn.try_clone()?,
+                        batch_size,
+                        monitor: monitor.clone(),
+                        tb_metas: HashMap::new(),
+                    };
+                    sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
+                }
+            }
+
+            SinkerConfig::DuckDbStruct {
+                url,
+                conflict_policy,
+            } => {
+                let conn = duckdb::Connection::open(&url)
+                    .with_context(|| format!("failed to open duckdb file: {}", url))?;
+                let extractor_meta_manager = ExtractorUtil::get_extractor_meta_manager(task_config)
+                    .await?
+                    .unwrap();
+                let sinker = DuckDbStructSinker {
+                    conn,
+                    conflict_policy,
+                    extractor_meta_manager,
+                };
+                sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
+            }
+
+            SinkerConfig::Elasticsearch {
+                url,
+                batch_size,
+                partial_update,
+            } => {
+                let client = Self::create_elasticsearch_client(&url, tls)?;
+                let reverse_router = RdbRouter::from_config(
+                    &task_config.router,
+                    &task_config.extractor_basic.db_type,
+                )?
+                .reverse();
+                for _ in 0..parallel_size {
+                    // None for mongo, documents are identified by _id
+                    let meta_manager =
+                        ExtractorUtil::get_extractor_meta_manager(task_config).await?;
+                    let sinker = ElasticsearchSinker {
+                        client: client.clone(),
+                        batch_size,
+                        partial_update,
+                        meta_manager,
+                        reverse_router: reverse_router.clone(),
+                        monitor: monitor.clone(),
+                    };
+                    sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
+                }
+            }
+
+            SinkerConfig::ElasticsearchStruct {
+                url,
+                conflict_policy,
+            } => {
+                let client = Self::create_elasticsearch_client(&url, tls)?;
+                let router = RdbRouter::from_config(
+                    &task_config.router,
+                    &task_config.extractor_basic.db_type,
+                )?;
+                let reverse_router = router.reverse();
+                let extractor_meta_manager = ExtractorUtil::get_extractor_meta_manager(task_config)
+                    .await?
+                    .unwrap();
+                let sinker = ElasticsearchStructSinker {
+                    client,
+                    conflict_policy,
+                    router,
+                    reverse_router,
+                    extractor_meta_manager,
+                };
+                sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
+            }
+
+            SinkerConfig::Webhook {
+                url,
+                batch_size,
+                format,
+                with_field_defs,
+                hmac_secret,
+                max_retries,
+                retry_interval_ms,
+                timeout_secs,
+            } => {
+                let router = RdbRouter::from_config(
+                    &task_config.router,
+                    &task_config.extractor_basic.db_type,
+                )?;
+                // avro encoding may need meta data from RDB extractor
+                let meta_manager = ExtractorUtil::get_extractor_meta_manager(task_config).await?;
+                let avro_converter = AvroConverter::new(meta_manager, with_field_defs);
+                let http_client = TlsUtil::build_http_client_with_timeout(tls, timeout_secs)?;
+                for _ in 0..parallel_size {
+                    let sinker = WebhookSinker {
+                        http_client: http_client.clone(),
+                        url: url.clone(),
+                        batch_size,
+                        format: format.clone(),
+                        avro_converter: avro_converter.clone(),
+                        router: router.clone(),
+                        hmac_secret: hmac_secret.clone(),
+                        max_retries,
+                        retry_interval_ms,
+                        monitor: monitor.clone(),
+                    };
+                    sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
+                }
+            }
+
+            SinkerConfig::File {
+                url,
+                format,
+                compression,
+                max_file_bytes,
+                rotate_interval_secs,
+            } => {
+                // rows are received by sink_raw, which is not supported by other parallelizers
+                match task_config.parallelizer.parallel_type {
+                    ParallelType::Serial | ParallelType::Snapshot | ParallelType::Table => {}
+                    _ => bail! {Error::ConfigError(
+                        "file sinker only supports parallel_type: serial, snapshot, table".into()
+                    )},
+                }
+                if compression == FileCompressionEnum::Snappy && format != FileFormatEnum::Parquet {
+                    bail! {Error::ConfigError(
+                        "snappy compression is only supported by parquet files".into()
+                    )}
+                }
+
+                let router = RdbRouter::from_config(
+                    &task_config.router,
+                    &task_config.extractor_basic.db_type,
+                )?;
+                let reverse_router = router.reverse();
+                // parts and manifest are shared by sinkers, which may write the same table
+                let manifest = Arc::new(Mutex::new(FileManifest::new(&url)));
+                for _ in 0..parallel_size {
+                    let meta_manager =
+                        ExtractorUtil::get_extractor_meta_manager(task_config).await?;
+                    let sinker = FileSinker {
+                        root_dir: url.clone(),
+                        format: format.clone(),
+                        compression: compression.clone(),
+                        max_file_bytes,
+                        rotate_interval_secs,
+                        meta_manager,
+                        router: router.clone(),
+                        reverse_router: reverse_router.clone(),
+                        manifest: manifest.clone(),
+                        writers: HashMap::new(),
+                        monitor: monitor.clone(),
+                    };
+                    sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
+                }
+            }
+
+            SinkerConfig::Plugin { name, configs } => {
+                let context = SinkerContext {
+                    name,
+                    configs,
+                    task_config: task_config.clone(),
+                    parallel_size: parallel_size as usize,
+                    monitor: monitor.clone(),
+                    data_marker: data_marker.clone(),
+                };
+                for sinker in plugins.create_sinkers(context).await? {
+                    sub_sinkers.push(Arc::new(async_mutex::Mutex::new(sinker)));
+                }
+            }
+
             SinkerConfig::Sql { reverse } => {
                 let router = RdbRouter::from_config(
                     &task_config.router,
@@ -665,4 +1053,37 @@ impl SinkerUtil {
         };
         Ok(sub_sinkers)
     }
+
+    fn get_http_scheme(url_info: &Url, tls: &TlsConfig) -> String {
+        if tls.is_enabled() {
+            "https".into()
+        } else {
+            url_info.scheme().into()
+        }
+    }
+
+    fn create_elasticsearch_client(
+        url: &str,
+        tls: &TlsConfig,
+    ) -> anyhow::Result<ElasticsearchClient> {
+        let url_info = UrlUtil::parse(url)?;
+        let scheme = Self::get_http_scheme(&url_info, tls);
+        let host = url_info.host_str().unwrap().to_string();
+        let port = url_info.port_or_known_default().unwrap();
+        let (username, password) = UrlUtil::get_credentials(&url_info)?;
+        Ok(ElasticsearchClient {
+            http_client: TlsUtil::build_http_client(tls)?,
+            url: format!("{}://{}:{}", scheme, host, port),
+            username,
+            password,
+        })
+    }
+
+    /// Bulk load only applies to snapshot data, which are all inserts of new rows.
+    fn is_snapshot(extractor_config: &ExtractorConfig) -> bool {
+        matches!(
+            extractor_config,
+            ExtractorConfig::MysqlSnapshot { .. } | ExtractorConfig::PgSnapshot { .. }
+        )
+    }
 }
@@ -0,0 +1,344 @@
+use std::sync::{
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    Arc, RwLock,
+};
+
+use dt_common::meta::position::Position;
+use serde::Serialize;
+use tokio::task::JoinHandle;
+
+pub type EventCallback = Arc<dyn Fn(TaskEvent) + Send + Sync>;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub enum TaskStatus {
+    Created,
+    Running,
+    Stopping,
+    Stopped,
+    Finished,
+    Failed(String),
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct TaskProgress {
+    /// the schema / tb being migrated by a snapshot task
+    pub schema: String,
+    pub tb: String,
+    pub finished_tb_count: u64,
+    pub sinked_count: u64,
+    pub received_position: Position,
+    pub committed_position: Position,
+}
+
+#[derive(Clone, Debug)]
+pub enum TaskEvent {
+    Started,
+    TbStarted { schema: String, tb: String },
+    TbFinished { schema: String, tb: String },
+    Progress(TaskProgress),
+    Stopped,
+    Finished,
+    Failed(String),
+}
+
+/// State shared between a running task and its TaskHandle.
+pub(crate) struct TaskState {
+    status: RwLock<TaskStatus>,
+    progress: RwLock<TaskProgress>,
+    stop_requested: AtomicBool,
+    // sinked count of finished sub tasks, each sub task has its own monitors
+    finished_sinked_count: AtomicU64,
+    event_callback: Option<EventCallback>,
+}
+
+impl TaskState {
+    pub fn new(event_callback: Option<EventCallback>) -> Self {
+        Self {
+            status: RwLock::new(TaskStatus::Created),
+            progress: RwLock::new(TaskProgress::default()),
+            stop_requested: AtomicBool::new(false),
+            finished_sinked_count: AtomicU64::new(0),
+            event_callback,
+        }
+    }
+
+    pub fn emit(&self, event: TaskEvent) {
+        if let Some(callback) = &self.event_callback {
+            callback(event);
+        }
+    }
+
+    pub fn status(&self) -> TaskStatus {
+        self.status.read().unwrap().clone()
+    }
+
+    pub fn set_status(&self, status: TaskStatus) {
+        *self.status.write().unwrap() = status;
+    }
+
+    pub fn progress(&self) -> TaskProgress {
+        self.progress.read().unwrap().clone()
+    }
+
+    pub fn request_stop(&self) {
+        self.stop_requested.store(true, Ordering::Release);
+        let mut status = self.status.write().unwrap();
+        if matches!(*status, TaskStatus::Created | TaskStatus::Running) {
+            *status = TaskStatus::Stopping;
+        }
+    }
+
+    pub fn is_stop_requested(&self) -> bool {
+        self.stop_requested.load(Ordering::Acquire)
+    }
+
+    pub fn start_tb(&self, schema: &str, tb: &str) {
+        {
+            let mut progress = self.progress.write().unwrap();
+            progress.schema = schema.to_string();
+            progress.tb = tb.to_string();
+        }
+        self.emit(TaskEvent::TbStarted {
+            schema: schema.to_string(),
+            tb: tb.to_string(),
+        });
+    }
+
+    pub fn finish_tb(&self, schema: &str, tb: &str) {
+        {
+            let mut progress = self.progress.write().unwrap();
+            progress.finished_tb_count += 1;
+            progress.schema.clear();
+            progress.tb.clear();
+        }
+        self.emit(TaskEvent::TbFinished {
+            schema: schema.to_string(),
+            tb: tb.to_string(),
+        });
+    }
+
+    /// Called periodically by each sub task with its own counters,
+    /// if sub_task_finished, the sinked count is accumulated for following sub tasks.
+    pub fn update_progress(
+        &self,
+        sinked_count: u64,
+        received_position: Position,
+        committed_position: Position,
+        sub_task_finished: bool,
+    ) {
+        let progress = {
+            let mut progress = self.progress.write().unwrap();
+            progress.sinked_count =
+                self.finished_sinked_count.load(Ordering::Acquire) + sinked_count;
+            progress.received_position = received_position;
+            progress.committed_position = committed_position;
+            progress.clone()
+        };
+        if sub_task_finished {
+            self.finished_sinked_count
+                .fetch_add(sinked_count, Ordering::AcqRel);
+        }
+        self.emit(TaskEvent::Progress(progress));
+    }
+}
+
+/// Returned by TaskRunner::spawn, to control and observe a task running in background.
+pub struct TaskHandle {
+    pub(crate) state: Arc<TaskState>,
+    pub(crate) join_handle: JoinHandle<anyhow::Result<()>>,
+}
+
+impl TaskHandle {
+    /// Ask the task to stop: extracting stops immediately and the extractor is closed, data
+    /// already extracted are sinked and the position is checkpointed before the task exits.
+    /// Use wait() to wait for it.
+    pub fn stop(&self) {
+        self.state.request_stop();
+    }
+
+    pub fn status(&self) -> TaskStatus {
+        self.state.status()
+    }
+
+    pub fn progress(&self) -> TaskProgress {
+        self.state.progress()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.join_handle.is_finished()
+    }
+
+    pub async fn wait(self) -> anyhow::Result<()> {
+        self.join_handle.await?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+    use async_trait::async_trait;
+    use dt_common::{
+        config::task_config_builder::TaskConfigBuilder,
+        meta::{col_value::ColValue, row_data::RowData, row_type::RowType},
+        utils::time_util::TimeUtil,
+    };
+    use dt_connector::{
+        extractor::base_extractor::BaseExtractor, sinker::dummy_sinker::DummySinker, Extractor,
+        Sinker,
+    };
+
+    use super::*;
+    use crate::{
+        plugin_registry::{
+            ExtractorContext, ExtractorFactory, PluginRegistry, SinkerContext, SinkerFactory,
+        },
+        task_runner::TaskRunner,
+    };
+
+    /// Pushes row_count rows, or rows endlessly if row_count is None.
+    struct TestExtractor {
+        base_extractor: BaseExtractor,
+        row_count: Option<u64>,
+        closed: Arc<AtomicBool>,
+    }
+
+    #[async_trait]
+    impl Extractor for TestExtractor {
+        async fn extract(&mut self) -> anyhow::Result<()> {
+            let mut i = 0;
+            while self.row_count.map_or(true, |count| i < count) {
+                let after = HashMap::from([("id".to_string(), ColValue::LongLong(i as i64))]);
+                let row_data = RowData::new(
+                    "db_1".into(),
+                    "tb_1".into(),
+                    RowType::Insert,
+                    None,
+                    Some(after),
+                );
+                self.base_extractor
+                    .push_row(row_data, Position::None)
+                    .await?;
+                i += 1;
+                TimeUtil::sleep_millis(1).await;
+            }
+            self.base_extractor.wait_task_finish().await
+        }
+
+        async fn close(&mut self) -> anyhow::Result<()> {
+            self.closed.store(true, Ordering::Release);
+            Ok(())
+        }
+    }
+
+    struct TestExtractorFactory {
+        row_count: Option<u64>,
+        closed: Arc<AtomicBool>,
+    }
+
+    #[async_trait]
+    impl ExtractorFactory for TestExtractorFactory {
+        async fn create(
+            &self,
+            context: ExtractorContext,
+        ) -> anyhow::Result<Box<dyn Extractor + Send>> {
+            Ok(Box::new(TestExtractor {
+                base_extractor: context.base_extractor,
+                row_count: self.row_count,
+                closed: self.closed.clone(),
+            }))
+        }
+    }
+
+    struct DummySinkerFactory {}
+
+    #[async_trait]
+    impl SinkerFactory for DummySinkerFactory {
+        async fn create(
+            &self,
+            context: SinkerContext,
+        ) -> anyhow::Result<Vec<Box<dyn Sinker + Send>>> {
+            let mut sinkers: Vec<Box<dyn Sinker + Send>> = Vec::new();
+            for _ in 0..context.parallel_size {
+                sinkers.push(Box::new(DummySinker {}));
+            }
+            Ok(sinkers)
+        }
+    }
+
+    fn spawn_task(
+        row_count: Option<u64>,
+        closed: Arc<AtomicBool>,
+        events: Arc<Mutex<Vec<TaskEvent>>>,
+    ) -> TaskHandle {
+        let mut plugins = PluginRegistry::new();
+        plugins
+            .register_extractor("test_source", TestExtractorFactory { row_count, closed })
+            .unwrap();
+        plugins
+            .register_sinker("test_sink", DummySinkerFactory {})
+            .unwrap();
+
+        let config = TaskConfigBuilder::new()
+            .extractor("db_type", "test_source")
+            .extractor("extract_type", "cdc")
+            .sinker("db_type", "test_sink")
+            .pipeline("checkpoint_interval_secs", 1)
+            .build()
+            .unwrap();
+        TaskRunner::from_config(config)
+            .on_event(move |event| events.lock().unwrap().push(event))
+            .with_plugins(plugins)
+            .spawn()
+    }
+
+    #[tokio::test]
+    async fn test_task_finished() {
+        let closed = Arc::new(AtomicBool::new(false));
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let handle = spawn_task(Some(100), closed.clone(), events.clone());
+        let state = handle.state.clone();
+
+        handle.wait().await.unwrap();
+        assert_eq!(state.status(), TaskStatus::Finished);
+        assert_eq!(state.progress().sinked_count, 100);
+        assert!(closed.load(Ordering::Acquire));
+
+        let events = events.lock().unwrap();
+        assert!(matches!(events.first(), Some(TaskEvent::Started)));
+        assert!(matches!(events.last(), Some(TaskEvent::Finished)));
+        assert!(events
+            .iter()
+            .any(|i| matches!(i, TaskEvent::Progress(progress) if progress.sinked_count == 100)));
+    }
+
+    #[tokio::test]
+    async fn test_task_stopped() {
+        let closed = Arc::new(AtomicBool::new(false));
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let handle = spawn_task(None, closed.clone(), events.clone());
+
+        // wait for the first progress
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while handle.progress().sinked_count == 0 {
+                TimeUtil::sleep_millis(100).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(handle.status(), TaskStatus::Running);
+        assert!(!handle.is_finished());
+
+        handle.stop();
+        assert_eq!(handle.status(), TaskStatus::Stopping);
+        let state = handle.state.clone();
+        handle.wait().await.unwrap();
+        assert_eq!(state.status(), TaskStatus::Stopped);
+        // closed instead of being aborted
+        assert!(closed.load(Ordering::Acquire));
+
+        let events = events.lock().unwrap();
+        assert!(matches!(events.first(), Some(TaskEvent::Started)));
+        assert!(matches!(events.last(), Some(TaskEvent::Stopped)));
+    }
+}
@@ -22,7 +22,7 @@ use dt_common::{
     error::Error,
     log_finished, log_info,
     meta::{avro::avro_converter::AvroConverter, dt_queue::DtQueue},
-    monitor::monitor::Monitor,
+    monitor::{counter_type::CounterType, monitor::Monitor},
     rdb_filter::RdbFilter,
     utils::{sql_util::SqlUtil, time_util::TimeUtil},
 };
@@ -32,7 +32,15 @@ use dt_common::{
 };
 use dt_connector::{
     data_marker::DataMarker,
-    extractor::resumer::{cdc_resumer::CdcResumer, snapshot_resumer::SnapshotResumer},
+    extractor::{
+        mysql::binlog_util::BinlogUtil,
+        pg::pg_cdc_client::{PgCdcClient, PgExportedSnapshot},
+        resumer::{
+            cdc_resumer::CdcResumer, snapshot_resumer::SnapshotResumer, HANDOFF_POSITION_LOG_FLAG,
+            SNAPSHOT_POSITION_LOG_FLAG,
+        },
+        snapshot_conn_pool::ConsistentSnapshot,
+    },
     rdb_router::RdbRouter,
     Sinker,
 };
@@ -42,10 +50,14 @@ use dt_pipeline::{
 };
 
 use log4rs::config::RawConfig;
-use ratelimit::Ratelimiter;
-use tokio::try_join;
+use tokio::{sync::watch, task::JoinError};
 
-use crate::task_util::TaskUtil;
+use crate::{
+    admin_server::{AdminServer, AdminState, SubTaskHandles},
+    plugin_registry::PluginRegistry,
+    task_handle::{TaskEvent, TaskHandle, TaskState, TaskStatus},
+    task_util::TaskUtil,
+};
 
 use super::{
     extractor_util::ExtractorUtil, parallelizer_util::ParallelizerUtil, sinker_util::SinkerUtil,
@@ -53,6 +65,9 @@ use super::{
 
 pub struct TaskRunner {
     config: TaskConfig,
+    state: Arc<TaskState>,
+    plugins: Arc<PluginRegistry>,
+    admin: Arc<AdminState>,
 }
 
 const CHECK_LOG_DIR_PLACEHODLER: &str = "CHECK_LOG_DIR_PLACEHODLER";
@@ -66,9 +81,42 @@ impl TaskRunner {
     pub fn new(task_config_file: &str) -> anyhow::Result<Self> {
         let config = TaskConfig::new(task_config_file)
             .with_context(|| format!("invalid configs in [{}]", task_config_file))?;
-        Ok(Self { config })
+        Ok(Self::from_config(config))
+    }
+
+    /// For embedding, the config can be built by TaskConfigBuilder.
+    pub fn from_config(config: TaskConfig) -> Self {
+        let state = Arc::new(TaskState::new(None));
+        Self {
+            config,
+            admin: Arc::new(AdminState::new(state.clone())),
+            state,
+            plugins: Arc::new(PluginRegistry::new()),
+        }
+    }
+
+    /// Receive task events, to forward them to a channel:
+    ///   runner.on_event(move |event| { let _ = sender.send(event); })
+    pub fn on_event(self, callback: impl Fn(TaskEvent) + Send + Sync + 'static) -> Self {
+        let state = Arc::new(TaskState::new(Some(Arc::new(callback))));
+        Self {
+            admin: Arc::new(AdminState::new(state.clone())),
+            state,
+            ..self
+        }
+    }
+
+    /// Extractors / sinkers / parallelizers that are not built-in are created by the
+    /// factories registered in plugins.
+    pub fn with_plugins(self, plugins: PluginRegistry) -> Self {
+        Self {
+            plugins: Arc::new(plugins),
+            ..self
+        }
     }
 
+    /// Run the task as the whole process, used by dt-main. It initializes log4rs if enabled,
+    /// sets RUST_BACKTRACE and installs a panic hook. Use spawn() to embed ape-dts instead.
     pub async fn start_task(&self, enable_log4rs: bool) -> anyhow::Result<()> {
         if enable_log4rs {
             self.init_log4rs()?;
@@ -79,20 +127,93 @@ impl TaskRunner {
             log_error!("panic: {}", panic_info);
         }));
 
+        self.run().await
+    }
+
+    /// Run the task in background without touching process-wide settings (logger,
+    /// environment variables, panic hook), logs go to whatever `log` implementation
+    /// the caller installed.
+    pub fn spawn(self) -> TaskHandle {
+        let state = self.state.clone();
+        let join_handle = tokio::spawn(async move { self.run().await });
+        TaskHandle { state, join_handle }
+    }
+
+    async fn run(&self) -> anyhow::Result<()> {
+        self.state.set_status(TaskStatus::Running);
+        self.state.emit(TaskEvent::Started);
+
+        let result = self.run_internal().await;
+        match &result {
+            Ok(_) if self.state.is_stop_requested() => {
+                log_info!("task stopped");
+                self.state.set_status(TaskStatus::Stopped);
+                self.state.emit(TaskEvent::Stopped);
+            }
+            Ok(_) => {
+                self.state.set_status(TaskStatus::Finished);
+                self.state.emit(TaskEvent::Finished);
+            }
+            Err(err) => {
+                log_error!("task failed: {:?}", err);
+                self.state.set_status(TaskStatus::Failed(err.to_string()));
+                self.state.emit(TaskEvent::Failed(err.to_string()));
+            }
+        }
+        result
+    }
+
+    async fn run_internal(&self) -> anyhow::Result<()> {
+        log_info!("task config: {}", self.config.to_redacted_string()?);
+        self.plugins.validate(&self.config)?;
+
+        self.admin
+            .rps_limiter
+            .set_max_rps(self.config.pipeline.max_rps)?;
+        let admin_server = match &self.config.admin {
+            Some(admin_config) => Some(AdminServer::start(admin_config, self.admin.clone())?),
+            None => None,
+        };
+
+        let result = self.run_tasks().await;
+        if let Some(admin_server) = admin_server {
+            admin_server.stop().await;
+        }
+        result
+    }
+
+    async fn run_tasks(&self) -> anyhow::Result<()> {
         let db_type = &self.config.extractor_basic.db_type;
         let router = RdbRouter::from_config(&self.config.router, db_type)?;
         let snapshot_resumer = SnapshotResumer::from_config(&self.config.resumer)?;
         let cdc_resumer = CdcResumer::from_config(&self.config.resumer)?;
 
         match &self.config.extractor {
-            ExtractorConfig::MysqlStruct { url, .. }
-            | ExtractorConfig::PgStruct { url, .. }
-            | ExtractorConfig::MysqlSnapshot { url, .. }
-            | ExtractorConfig::PgSnapshot { url, .. }
-            | ExtractorConfig::MongoSnapshot { url, .. }
-            | ExtractorConfig::FoxlakeS3 { url, .. } => {
-                self.start_multi_task(url, &router, &snapshot_resumer, &cdc_resumer)
-                    .await?
+            ExtractorConfig::MysqlStruct { .. }
+            | ExtractorConfig::PgStruct { .. }
+            | ExtractorConfig::MysqlSnapshot { .. }
+            | ExtractorConfig::PgSnapshot { .. }
+            | ExtractorConfig::MongoSnapshot { .. }
+            | ExtractorConfig::FoxlakeS3 { .. } => {
+                self.start_multi_task(
+                    &self.config.extractor,
+                    &router,
+                    &snapshot_resumer,
+                    &cdc_resumer,
+                    None,
+                )
+                .await?
+            }
+
+            ExtractorConfig::SnapshotAndCdc { snapshot, cdc } => {
+                self.start_snapshot_and_cdc_task(
+                    snapshot,
+                    cdc,
+                    &router,
+                    &snapshot_resumer,
+                    &cdc_resumer,
+                )
+                .await?
             }
 
             _ => {
@@ -101,34 +222,248 @@ impl TaskRunner {
                     &router,
                     &snapshot_resumer,
                     &cdc_resumer,
+                    None,
                 )
                 .await?
             }
         };
 
-        log_finished!("task finished");
+        if !self.state.is_stop_requested() {
+            log_finished!("task finished");
+        }
         Ok(())
     }
 
+    async fn start_snapshot_and_cdc_task(
+        &self,
+        snapshot_config: &ExtractorConfig,
+        cdc_config: &ExtractorConfig,
+        router: &RdbRouter,
+        snapshot_resumer: &SnapshotResumer,
+        cdc_resumer: &CdcResumer,
+    ) -> anyhow::Result<()> {
+        // the cdc phase has started before, resume it by the checkpoint_position
+        if matches!(
+            cdc_resumer.checkpoint_position,
+            Position::MysqlCdc { .. } | Position::PgCdc { .. }
+        ) {
+            log_info!("cdc already started, skip snapshot");
+            return self
+                .start_single_task(cdc_config, router, snapshot_resumer, cdc_resumer, None)
+                .await;
+        }
+
+        // record the cdc position before the snapshot starts, changes made during
+        // the snapshot will be replayed by cdc, sinkers write them idempotently.
+        // for pg, the snapshot reads data exactly at the handoff position
+        let (handoff_position, exported_snapshot) =
+            if cdc_resumer.handoff_position == Position::None {
+                let (position, exported_snapshot) = self.get_handoff_position(cdc_config).await?;
+                log_finished!("{} {}", HANDOFF_POSITION_LOG_FLAG, position);
+                (position, exported_snapshot)
+            } else {
+                // the exported snapshot is gone, the rest of the snapshot reads the latest data
+                log_info!(
+                    "resume snapshot, handoff_position: {}",
+                    cdc_resumer.handoff_position
+                );
+                (cdc_resumer.handoff_position.clone(), None)
+            };
+
+        let mut snapshot_config = snapshot_config.clone();
+        if let ExtractorConfig::PgSnapshot {
+            consistent_snapshot,
+            ..
+        } = &mut snapshot_config
+        {
+            // the pool shared by tables is needed to import the exported snapshot
+            *consistent_snapshot = true;
+        }
+        self.start_multi_task(
+            &snapshot_config,
+            router,
+            snapshot_resumer,
+            cdc_resumer,
+            exported_snapshot,
+        )
+        .await?;
+        if self.state.is_stop_requested() {
+            return Ok(());
+        }
+
+        log_info!("snapshot finished, start cdc from: {}", handoff_position);
+        let mut cdc_config = cdc_config.clone();
+        match (&mut cdc_config, handoff_position) {
+            (
+                ExtractorConfig::MysqlCdc {
+                    binlog_filename,
+                    binlog_position,
+                    gtid_enabled,
+                    gtid_set,
+                    ..
+                },
+                Position::MysqlCdc {
+                    binlog_filename: handoff_binlog_filename,
+                    next_event_position,
+                    gtid_set: handoff_gtid_set,
+                    ..
+                },
+            ) => {
+                *binlog_filename = handoff_binlog_filename;
+                *binlog_position = next_event_position;
+                if *gtid_enabled {
+                    *gtid_set = handoff_gtid_set;
+                }
+            }
+
+            (
+                ExtractorConfig::PgCdc {
+                    start_lsn,
+                    recreate_slot_if_exists,
+                    ..
+                },
+                Position::PgCdc { lsn, .. },
+            ) => {
+                *start_lsn = lsn;
+                // the slot created before the snapshot holds the changes to replay
+                *recreate_slot_if_exists = false;
+            }
+
+            (_, handoff_position) => {
+                bail! {Error::ConfigError(format!(
+                    "invalid handoff_position: {} for extract_type=snapshot_and_cdc",
+                    handoff_position
+                ))}
+            }
+        }
+
+        self.start_single_task(&cdc_config, router, snapshot_resumer, cdc_resumer, None)
+            .await
+    }
+
+    async fn get_handoff_position(
+        &self,
+        cdc_config: &ExtractorConfig,
+    ) -> anyhow::Result<(Position, Option<PgExportedSnapshot>)> {
+        let res = match cdc_config {
+            ExtractorConfig::MysqlCdc { url, server_id, .. } => {
+                let enable_sqlx_log =
+                    TaskUtil::check_enable_sqlx_log(&self.config.runtime.log_level);
+                let conn_pool = TaskUtil::create_mysql_conn_pool(url, 1, enable_sqlx_log).await?;
+                let mut conn = conn_pool.acquire().await?;
+                let (binlog_filename, next_event_position, gtid_set) =
+                    BinlogUtil::get_binlog_status(&mut conn).await?;
+                drop(conn);
+                conn_pool.close().await;
+                let position = Position::MysqlCdc {
+                    server_id: server_id.to_string(),
+                    binlog_filename,
+                    next_event_position,
+                    gtid_set,
+                    timestamp: String::new(),
+                };
+                (position, None)
+            }
+
+            ExtractorConfig::PgCdc {
+                url,
+                slot_name,
+                pub_name,
+                start_lsn,
+                recreate_slot_if_exists,
+                ..
+            } => {
+                let mut client = PgCdcClient {
+                    url: url.clone(),
+                    tls: self.config.extractor_basic.tls.clone(),
+                    slot_name: slot_name.clone(),
+                    pub_name: pub_name.clone(),
+                    start_lsn: start_lsn.clone(),
+                    recreate_slot_if_exists: *recreate_slot_if_exists,
+                };
+                let exported_snapshot = client.create_slot_with_snapshot().await?;
+                let position = Position::PgCdc {
+                    lsn: exported_snapshot.start_lsn.clone(),
+                    timestamp: String::new(),
+                };
+                (position, Some(exported_snapshot))
+            }
+
+            _ => {
+                bail! {Error::ConfigError(
+                    "extract_type=snapshot_and_cdc is only supported by mysql and pg".into()
+                )}
+            }
+        };
+        Ok(res)
+    }
+
     async fn start_multi_task(
         &self,
-        url: &str,
+        extractor_config: &ExtractorConfig,
+        router: &RdbRouter,
+        snapshot_resumer: &SnapshotResumer,
+        cdc_resumer: &CdcResumer,
+        exported_snapshot: Option<PgExportedSnapshot>,
+    ) -> anyhow::Result<()> {
+        // shared by tasks of all tables so they read under the same view
+        let consistent_snapshot = self
+            .create_consistent_snapshot(extractor_config, exported_snapshot)
+            .await?;
+
+        let result = self
+            .start_tasks_by_schemas(
+                extractor_config,
+                router,
+                snapshot_resumer,
+                cdc_resumer,
+                &consistent_snapshot,
+            )
+            .await;
+        // close the snapshot even if a task failed, or its transaction is held until the process exits
+        if let Some(consistent_snapshot) = consistent_snapshot {
+            let close_result = consistent_snapshot.close().await;
+            result?;
+            return close_result;
+        }
+        result
+    }
+
+    async fn start_tasks_by_schemas(
+        &self,
+        extractor_config: &ExtractorConfig,
         router: &RdbRouter,
         snapshot_resumer: &SnapshotResumer,
         cdc_resumer: &CdcResumer,
+        consistent_snapshot: &Option<ConsistentSnapshot>,
     ) -> anyhow::Result<()> {
+        let url = match extractor_config {
+            ExtractorConfig::MysqlStruct { url, .. }
+            | ExtractorConfig::PgStruct { url, .. }
+            | ExtractorConfig::MysqlSnapshot { url, .. }
+            | ExtractorConfig::PgSnapshot { url, .. }
+            | ExtractorConfig::MongoSnapshot { url, .. }
+            | ExtractorConfig::FoxlakeS3 { url, .. } => url,
+            _ => {
+                bail! {Error::ConfigError("unsupported extractor config".into())};
+            }
+        };
         let db_type = &self.config.extractor_basic.db_type;
         let mut filter = RdbFilter::from_config(&self.config.filter, db_type)?;
 
         let schemas = TaskUtil::list_schemas(url, db_type).await?;
         for schema in schemas.iter() {
+            if self.state.is_stop_requested() {
+                break;
+            }
+
             if filter.filter_schema(schema) {
                 log_info!("schema: {} filtered", schema);
                 continue;
             }
 
             // start a task for each schema
-            let schema_extractor_config = match &self.config.extractor {
+            let schema_extractor_config = match extractor_config {
                 ExtractorConfig::MysqlStruct { url, .. } => Some(ExtractorConfig::MysqlStruct {
                     url: url.clone(),
                     db: schema.clone(),
@@ -143,14 +478,24 @@ impl TaskRunner {
             };
 
             if let Some(extractor_config) = schema_extractor_config {
-                self.start_single_task(&extractor_config, router, snapshot_resumer, cdc_resumer)
-                    .await?;
+                self.start_single_task(
+                    &extractor_config,
+                    router,
+                    snapshot_resumer,
+                    cdc_resumer,
+                    None,
+                )
+                .await?;
                 continue;
             }
 
             // start a task for each tb
             let tbs = TaskUtil::list_tbs(url, schema, db_type).await?;
             for tb in tbs.iter() {
+                if self.state.is_stop_requested() {
+                    break;
+                }
+
                 if snapshot_resumer.check_finished(schema, tb) {
                     log_info!("schema: {}, tb: {} already finished", schema, tb);
                     continue;
@@ -160,12 +505,14 @@ impl TaskRunner {
                     continue;
                 }
 
-                let tb_extractor_config = match &self.config.extractor {
+                let tb_extractor_config = match extractor_config {
                     ExtractorConfig::MysqlSnapshot {
                         url,
                         sample_interval,
                         parallel_size,
                         batch_size,
+                        consistent_snapshot,
+                        flush_tables_with_read_lock,
                         ..
                     } => ExtractorConfig::MysqlSnapshot {
                         url: url.clone(),
@@ -174,12 +521,16 @@ impl TaskRunner {
                         sample_interval: *sample_interval,
                         parallel_size: *parallel_size,
                         batch_size: *batch_size,
+                        consistent_snapshot: *consistent_snapshot,
+                        flush_tables_with_read_lock: *flush_tables_with_read_lock,
                     },
 
                     ExtractorConfig::PgSnapshot {
                         url,
                         sample_interval,
                         batch_size,
+                        consistent_snapshot,
+                        extract_by_copy,
                         ..
                     } => ExtractorConfig::PgSnapshot {
                         url: url.clone(),
@@ -187,6 +538,8 @@ impl TaskRunner {
                         tb: tb.clone(),
                         sample_interval: *sample_interval,
                         batch_size: *batch_size,
+                        consistent_snapshot: *consistent_snapshot,
+                        extract_by_copy: *extract_by_copy,
                     },
 
                     ExtractorConfig::MongoSnapshot { url, app_name, .. } => {
@@ -216,19 +569,77 @@ impl TaskRunner {
                     }
                 };
 
-                self.start_single_task(&tb_extractor_config, router, snapshot_resumer, cdc_resumer)
-                    .await?;
+                self.start_single_task(
+                    &tb_extractor_config,
+                    router,
+                    snapshot_resumer,
+                    cdc_resumer,
+                    consistent_snapshot.clone(),
+                )
+                .await?;
             }
         }
         Ok(())
     }
 
+    /// exported_snapshot: imported by all connections of pg instead of exporting a new one,
+    /// it is dropped once imported.
+    async fn create_consistent_snapshot(
+        &self,
+        extractor_config: &ExtractorConfig,
+        exported_snapshot: Option<PgExportedSnapshot>,
+    ) -> anyhow::Result<Option<ConsistentSnapshot>> {
+        let enable_sqlx_log = TaskUtil::check_enable_sqlx_log(&self.config.runtime.log_level);
+        let consistent_snapshot = match extractor_config {
+            ExtractorConfig::MysqlSnapshot {
+                url,
+                parallel_size,
+                consistent_snapshot: true,
+                flush_tables_with_read_lock,
+                ..
+            } => {
+                let (conn_pool, position) = TaskUtil::create_mysql_consistent_snapshot_pool(
+                    url,
+                    *parallel_size as u32,
+                    *flush_tables_with_read_lock,
+                    enable_sqlx_log,
+                )
+                .await?;
+                if let Some(position) = position {
+                    log_finished!("{} {}", SNAPSHOT_POSITION_LOG_FLAG, position);
+                }
+                Some(ConsistentSnapshot::MySQL(conn_pool))
+            }
+
+            ExtractorConfig::PgSnapshot {
+                url,
+                consistent_snapshot: true,
+                ..
+            } => {
+                // tables are extracted one by one, each by a single query
+                let snapshot_name = exported_snapshot.as_ref().map(|i| i.snapshot_name.as_str());
+                let conn_pool = TaskUtil::create_pg_consistent_snapshot_pool(
+                    url,
+                    1,
+                    snapshot_name,
+                    enable_sqlx_log,
+                )
+                .await?;
+                Some(ConsistentSnapshot::PostgreSQL(conn_pool))
+            }
+
+            _ => None,
+        };
+        Ok(consistent_snapshot)
+    }
+
     async fn start_single_task(
         &self,
         extractor_config: &ExtractorConfig,
         router: &RdbRouter,
         snapshot_resumer: &SnapshotResumer,
         cdc_resumer: &CdcResumer,
+        consistent_snapshot: Option<ConsistentSnapshot>,
     ) -> anyhow::Result<()> {
         let max_bytes = self.config.pipeline.buffer_memory_mb * 1024 * 1024;
         let buffer = Arc::new(DtQueue::new(
@@ -278,6 +689,9 @@ impl TaskRunner {
             router.clone(),
             snapshot_resumer.clone(),
             cdc_resumer.clone(),
+            self.admin.backfill_requests.clone(),
+            consistent_snapshot,
+            &self.plugins,
         )
         .await?;
 
@@ -293,6 +707,7 @@ impl TaskRunner {
             extractor_config,
             sinker_monitor.clone(),
             rw_sinker_data_marker.clone(),
+            &self.plugins,
         )
         .await?;
 
@@ -304,11 +719,21 @@ impl TaskRunner {
             monitor_count_window,
         )));
 
+        self.admin.set_sub_task(SubTaskHandles {
+            buffer: buffer.clone(),
+            syncer: syncer.clone(),
+            monitors: vec![
+                extractor_monitor.clone(),
+                pipeline_monitor.clone(),
+                sinker_monitor.clone(),
+            ],
+        });
+
         let mut pipeline = self
             .create_pipeline(
                 buffer,
                 shut_down.clone(),
-                syncer,
+                syncer.clone(),
                 sinkers,
                 pipeline_monitor.clone(),
                 rw_sinker_data_marker.clone(),
@@ -316,55 +741,101 @@ impl TaskRunner {
             .await?;
 
         // do pre operations before task starts
-        self.pre_single_task(sinker_data_marker).await?;
+        self.pre_single_task(extractor_config, sinker_data_marker)
+            .await?;
 
-        // start threads
-        let f1 = tokio::spawn(async move {
-            extractor.extract().await.unwrap();
-            extractor.close().await.unwrap();
+        let (schema, tb) = match extractor_config {
+            ExtractorConfig::MysqlSnapshot { db, tb, .. }
+            | ExtractorConfig::MongoSnapshot { db, tb, .. } => (db.to_owned(), tb.to_owned()),
+            ExtractorConfig::PgSnapshot { schema, tb, .. }
+            | ExtractorConfig::FoxlakeS3 { schema, tb, .. } => (schema.to_owned(), tb.to_owned()),
+            _ => (String::new(), String::new()),
+        };
+        if !tb.is_empty() {
+            self.state.start_tb(&schema, &tb);
+        }
+
+        // start threads, f1 returns whether extracting was stopped before it finished
+        let (stop_extract_tx, mut stop_extract_rx) = watch::channel(false);
+        let mut f1 = tokio::spawn(async move {
+            let stopped = tokio::select! {
+                result = extractor.extract() => {
+                    result?;
+                    false
+                }
+                _ = stop_extract_rx.changed() => true,
+            };
+            // the extractor is closed whether it finished or was stopped,
+            // so connections and replication streams are released
+            extractor.close().await?;
+            anyhow::Ok(stopped)
         });
 
         let f2 = tokio::spawn(async move {
-            pipeline.start().await.unwrap();
-            pipeline.stop().await.unwrap();
+            pipeline.start().await?;
+            pipeline.stop().await
         });
 
         let interval_secs = self.config.pipeline.checkpoint_interval_secs;
+        // monitors do the last flush after the pipeline exits, so the final progress
+        // includes the data sinked after the extractor finished
+        let monitor_shut_down = Arc::new(AtomicBool::new(false));
+        let (f3_shut_down, state) = (monitor_shut_down.clone(), self.state.clone());
         let f3 = tokio::spawn(async move {
             Self::flush_monitors(
                 interval_secs,
-                shut_down,
+                f3_shut_down,
                 extractor_monitor,
                 pipeline_monitor,
                 sinker_monitor,
+                syncer,
+                state,
             )
             .await
         });
-        try_join!(f1, f2, f3).unwrap();
 
-        // finished log
-        let (schema, tb) = match extractor_config {
-            ExtractorConfig::MysqlSnapshot { db, tb, .. }
-            | ExtractorConfig::MongoSnapshot { db, tb, .. } => (db.to_owned(), tb.to_owned()),
-            ExtractorConfig::PgSnapshot { schema, tb, .. }
-            | ExtractorConfig::FoxlakeS3 { schema, tb, .. } => (schema.to_owned(), tb.to_owned()),
-            _ => (String::new(), String::new()),
+        // stop the extractor if the task is stopped or the pipeline exits unexpectedly
+        let extractor_result = loop {
+            if let Ok(result) = tokio::time::timeout(Duration::from_millis(100), &mut f1).await {
+                break Self::flatten_join(result);
+            }
+            let pipeline_exited = f2.is_finished() && !shut_down.load(Ordering::Acquire);
+            if self.state.is_stop_requested() || pipeline_exited {
+                let _ = stop_extract_tx.send(true);
+                break Self::flatten_join(f1.await);
+            }
         };
-        if !tb.is_empty() {
+
+        // normally set by the extractor after all extracted data sinked, set it anyway
+        // so the pipeline sinks the remaining data in buffer and exits
+        shut_down.store(true, Ordering::Release);
+        let pipeline_result = Self::flatten_join(f2.await);
+        monitor_shut_down.store(true, Ordering::Release);
+        f3.await?;
+        let stopped = extractor_result?;
+        pipeline_result?;
+
+        // finished log
+        if !tb.is_empty() && !stopped {
             log_finished!(
                 "{}",
                 Position::RdbSnapshotFinished {
                     db_type: self.config.extractor_basic.db_type.to_string(),
-                    schema,
-                    tb,
+                    schema: schema.clone(),
+                    tb: tb.clone(),
                 }
                 .to_string()
             );
+            self.state.finish_tb(&schema, &tb);
         }
 
         Ok(())
     }
 
+    fn flatten_join<T>(result: Result<anyhow::Result<T>, JoinError>) -> anyhow::Result<T> {
+        result?
+    }
+
     async fn create_pipeline(
         &self,
         buffer: Arc<DtQueue>,
@@ -376,17 +847,6 @@ impl TaskRunner {
     ) -> anyhow::Result<Box<dyn Pipeline + Send>> {
         match self.config.pipeline.pipeline_type {
             PipelineType::Basic => {
-                let rps_limiter = if self.config.pipeline.max_rps > 0 {
-                    Some(
-                        Ratelimiter::builder(self.config.pipeline.max_rps, Duration::from_secs(1))
-                            .max_tokens(self.config.pipeline.max_rps)
-                            .initial_available(self.config.pipeline.max_rps)
-                            .build()?,
-                    )
-                } else {
-                    None
-                };
-
                 let lua_processor =
                     self.config
                         .processor
@@ -398,7 +858,8 @@ impl TaskRunner {
                 let parallelizer = ParallelizerUtil::create_parallelizer(
                     &self.config,
                     monitor.clone(),
-                    rps_limiter,
+                    self.admin.rps_limiter.clone(),
+                    &self.plugins,
                 )
                 .await?;
 
@@ -414,6 +875,7 @@ impl TaskRunner {
                     monitor,
                     data_marker,
                     lua_processor,
+                    control: self.admin.control.clone(),
                 };
                 Ok(Box::new(pipeline))
             }
@@ -486,6 +948,8 @@ impl TaskRunner {
         extractor_monitor: Arc<Mutex<Monitor>>,
         pipeline_monitor: Arc<Mutex<Monitor>>,
         sinker_monitor: Arc<Mutex<Monitor>>,
+        syncer: Arc<Mutex<Syncer>>,
+        state: Arc<TaskState>,
     ) {
         loop {
             // do an extra flush before exit if task finished
@@ -498,15 +962,39 @@ impl TaskRunner {
             pipeline_monitor.lock().unwrap().flush();
             sinker_monitor.lock().unwrap().flush();
 
+            let sinked_count = pipeline_monitor
+                .lock()
+                .unwrap()
+                .no_window_counters
+                .get(&CounterType::SinkedCount)
+                .map_or(0, |counter| counter.value as u64);
+            let (received_position, committed_position) = {
+                let syncer = syncer.lock().unwrap();
+                (
+                    syncer.received_position.clone(),
+                    syncer.committed_position.clone(),
+                )
+            };
+            state.update_progress(
+                sinked_count,
+                received_position,
+                committed_position,
+                finished,
+            );
+
             if finished {
                 break;
             }
         }
     }
 
-    async fn pre_single_task(&self, sinker_data_marker: Option<DataMarker>) -> anyhow::Result<()> {
+    async fn pre_single_task(
+        &self,
+        extractor_config: &ExtractorConfig,
+        sinker_data_marker: Option<DataMarker>,
+    ) -> anyhow::Result<()> {
         // create heartbeat table
-        let schema_tb = match &self.config.extractor {
+        let schema_tb = match extractor_config {
             ExtractorConfig::MysqlCdc { heartbeat_tb, .. }
             | ExtractorConfig::PgCdc { heartbeat_tb, .. } => ConfigTokenParser::parse(
                 heartbeat_tb,
@@ -517,7 +1005,7 @@ impl TaskRunner {
         };
 
         if schema_tb.len() == 2 {
-            match &self.config.extractor {
+            match extractor_config {
                 ExtractorConfig::MysqlCdc { url, .. } => {
                     let db_sql = format!("CREATE DATABASE IF NOT EXISTS `{}`", schema_tb[0]);
                     let tb_sql = format!(
@@ -0,0 +1,11 @@
+DROP DATABASE IF EXISTS test_db_1;
+
+CREATE DATABASE test_db_1;
+
+CREATE TABLE test_db_1.tb_1 (id int, value varchar(255), PRIMARY KEY(id));
+
+CREATE TABLE test_db_1.tb_2 (id int, value varchar(255), PRIMARY KEY(id));
+
+INSERT INTO test_db_1.tb_1 VALUES (1, 'a'), (2, 'b'), (3, 'c'), (4, 'd');
+
+INSERT INTO test_db_1.tb_2 VALUES (1, 'a'), (2, 'b');
@@ -0,0 +1,11 @@
+INSERT INTO test_db_1.tb_1 VALUES (5, 'e'), (6, 'f');
+
+INSERT INTO test_db_1.tb_2 VALUES (3, 'c');
+
+UPDATE test_db_1.tb_1 SET value = 'aa' WHERE id = 1;
+
+UPDATE test_db_1.tb_2 SET value = 'bb' WHERE id = 2;
+
+DELETE FROM test_db_1.tb_1 WHERE id = 3;
+
+DELETE FROM test_db_1.tb_2 WHERE id = 1;
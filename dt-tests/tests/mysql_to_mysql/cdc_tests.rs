@@ -17,6 +17,13 @@ mod test {
         TestBase::run_cdc_test("mysql_to_mysql/cdc/uk_changed_test", 3000, 2000).await;
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn cdc_snapshot_and_cdc_test() {
+        TestBase::run_snapshot_and_cdc_test("mysql_to_mysql/cdc/snapshot_and_cdc_test", 5000, 3000)
+            .await;
+    }
+
     #[tokio::test]
     #[serial]
     async fn cdc_charset_test() {
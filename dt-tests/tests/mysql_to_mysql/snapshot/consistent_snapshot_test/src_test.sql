@@ -0,0 +1,5 @@
+INSERT INTO test_db_1.tb_1 VALUES (1,1),(2,2),(3,3),(7,7),(9,9),(10,10),(11,11),(12,12),(14,14),(16,16),(17,17),(18,18),(19,19);
+
+INSERT INTO test_db_1.tb_2 VALUES ("1",1),("2",2),("3",3),("7",7),("9",9),("10",10),("11",11),("12",12),("14",14),("16",16),("17",17),("18",18),("19",19);
+
+INSERT INTO test_db_1.tb_3 VALUES (1,1),(2,2),(3,3),(7,7),(9,9),(10,10),(11,11),(12,12),(14,14),(16,16),(17,17),(18,18),(19,19);
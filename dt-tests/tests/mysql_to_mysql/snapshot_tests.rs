@@ -90,4 +90,10 @@ mod test {
     async fn snapshot_parallel_test() {
         TestBase::run_snapshot_test("mysql_to_mysql/snapshot/parallel_test").await;
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_consistent_snapshot_test() {
+        TestBase::run_snapshot_test("mysql_to_mysql/snapshot/consistent_snapshot_test").await;
+    }
 }
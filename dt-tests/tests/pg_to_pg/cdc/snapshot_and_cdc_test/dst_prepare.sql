@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS tb_1;
+CREATE TABLE tb_1 (id int, value varchar(255), PRIMARY KEY(id));
+
+DROP TABLE IF EXISTS tb_2;
+CREATE TABLE tb_2 (id int, value varchar(255), PRIMARY KEY(id));
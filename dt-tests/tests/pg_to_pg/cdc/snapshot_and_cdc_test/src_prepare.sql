@@ -0,0 +1,9 @@
+DROP TABLE IF EXISTS tb_1;
+CREATE TABLE tb_1 (id int, value varchar(255), PRIMARY KEY(id));
+
+DROP TABLE IF EXISTS tb_2;
+CREATE TABLE tb_2 (id int, value varchar(255), PRIMARY KEY(id));
+
+INSERT INTO tb_1 VALUES (1, 'a'), (2, 'b'), (3, 'c'), (4, 'd');
+
+INSERT INTO tb_2 VALUES (1, 'a'), (2, 'b');
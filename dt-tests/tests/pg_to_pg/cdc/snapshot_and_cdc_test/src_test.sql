@@ -0,0 +1,11 @@
+INSERT INTO tb_1 VALUES (5, 'e'), (6, 'f');
+
+INSERT INTO tb_2 VALUES (3, 'c');
+
+UPDATE tb_1 SET value = 'aa' WHERE id = 1;
+
+UPDATE tb_2 SET value = 'bb' WHERE id = 2;
+
+DELETE FROM tb_1 WHERE id = 3;
+
+DELETE FROM tb_2 WHERE id = 1;
@@ -23,6 +23,12 @@ mod test {
         TestBase::run_cdc_test("pg_to_pg/cdc/postgis_array_test", 3000, 9000).await;
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn cdc_snapshot_and_cdc_test() {
+        TestBase::run_snapshot_and_cdc_test("pg_to_pg/cdc/snapshot_and_cdc_test", 5000, 3000).await;
+    }
+
     #[tokio::test]
     #[serial]
     async fn cdc_charset_test() {
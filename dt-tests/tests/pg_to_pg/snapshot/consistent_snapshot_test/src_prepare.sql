@@ -0,0 +1,8 @@
+DROP SCHEMA IF EXISTS test_db_1 CASCADE;
+
+CREATE SCHEMA test_db_1;
+
+CREATE TABLE test_db_1.tb_1 ( id int, value int, PRIMARY KEY (id) );
+
+-- no primary key, extracted without batch
+CREATE TABLE test_db_1.tb_2 ( id int, value int );
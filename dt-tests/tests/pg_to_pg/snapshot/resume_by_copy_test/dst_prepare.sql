@@ -0,0 +1,13 @@
+DROP SCHEMA IF EXISTS "test_db_*.*" CASCADE;
+
+CREATE SCHEMA "test_db_*.*";
+
+CREATE TYPE "test_db_*.*"."mood" AS ENUM ('sad', 'ok', 'happy');
+
+CREATE TABLE "test_db_*.*".resume_varchar(pk varchar(255), val int, PRIMARY KEY(pk));
+
+CREATE TABLE "test_db_*.*".resume_numeric(pk numeric(20,8), val int, PRIMARY KEY(pk));
+
+CREATE TABLE "test_db_*.*".resume_timestamp(pk timestamp, val int, PRIMARY KEY(pk));
+
+CREATE TABLE "test_db_*.*".resume_enum(pk "test_db_*.*"."mood", val int, PRIMARY KEY(pk));
@@ -0,0 +1,7 @@
+INSERT INTO "test_db_*.*".resume_varchar(pk, val) VALUES ('x''\1', 1), ('x''\2', 2), ('x''\3', 3);
+
+INSERT INTO "test_db_*.*".resume_numeric(pk, val) VALUES (1.5, 1), (2.5, 2), (3.5, 3);
+
+INSERT INTO "test_db_*.*".resume_timestamp(pk, val) VALUES ('2024-01-01 00:00:01', 1), ('2024-01-01 00:00:02', 2), ('2024-01-01 00:00:03', 3);
+
+INSERT INTO "test_db_*.*".resume_enum(pk, val) VALUES ('sad', 1), ('ok', 2), ('happy', 3);
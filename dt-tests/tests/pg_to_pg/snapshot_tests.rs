@@ -75,6 +75,23 @@ mod test {
         .await;
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_resume_by_copy_test() {
+        let mut dst_expected_counts = HashMap::new();
+        dst_expected_counts.insert(r#""test_db_*.*"."resume_varchar""#, 2);
+        dst_expected_counts.insert(r#""test_db_*.*"."resume_numeric""#, 2);
+        dst_expected_counts.insert(r#""test_db_*.*"."resume_timestamp""#, 2);
+        dst_expected_counts.insert(r#""test_db_*.*"."resume_enum""#, 2);
+
+        TestBase::run_snapshot_test_and_check_dst_count(
+            "pg_to_pg/snapshot/resume_by_copy_test",
+            &DbType::Pg,
+            dst_expected_counts,
+        )
+        .await;
+    }
+
     #[tokio::test]
     #[serial]
     async fn snapshot_special_character_in_name_test() {
@@ -92,4 +109,10 @@ mod test {
     async fn snapshot_route_test() {
         TestBase::run_snapshot_test("pg_to_pg/snapshot/route_test").await;
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_consistent_snapshot_test() {
+        TestBase::run_snapshot_test("pg_to_pg/snapshot/consistent_snapshot_test").await;
+    }
 }
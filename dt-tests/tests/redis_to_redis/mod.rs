@@ -19,3 +19,4 @@ pub mod snapshot_cross_version_tests;
 pub mod snapshot_rebloom_tests;
 pub mod snapshot_redisearch_tests;
 pub mod snapshot_rejson_tests;
+pub mod snapshot_timeseries_tests;
@@ -0,0 +1,12 @@
+-- expected data, the same cmds as appendonly.aof
+SELECT 0
+SET k_1 v_1
+HSET h_1 f_1 v_1 f_2 v_2
+RPUSH l_1 a b c
+SADD s_1 a b
+ZADD z_1 1 a 2 b
+SET k_2 v_2
+PEXPIREAT k_2 4102444800000
+
+SELECT 1
+SET k_3 v_3
@@ -0,0 +1,26 @@
+-- uncompressed, rewritten by TS.CREATE + TS.MADD
+TS.CREATE 1-1 ENCODING UNCOMPRESSED
+TS.MADD 1-1 1000 1.5 1-1 2000 2 1-1 3000 -3.25
+
+-- uncompressed with retention, chunk size, duplicate policy and labels
+TS.CREATE 2-1 RETENTION 0 ENCODING UNCOMPRESSED CHUNK_SIZE 128 DUPLICATE_POLICY LAST LABELS sensor_id 2 area_id 32
+TS.ADD 2-1 1000 1
+TS.ADD 2-1 1000 2
+TS.ADD 2-1 2000 3
+
+-- uncompressed with multiple chunks
+TS.CREATE 3-1 ENCODING UNCOMPRESSED CHUNK_SIZE 64
+TS.MADD 3-1 1000 1 3-1 2000 2 3-1 3000 3 3-1 4000 4 3-1 5000 5 3-1 6000 6
+
+-- empty series
+TS.CREATE 4-1 ENCODING UNCOMPRESSED
+
+-- compressed chunks by default, fall back to restore
+TS.CREATE 5-1
+TS.MADD 5-1 1000 1 5-1 2000 2 5-1 3000 3
+
+-- compaction rules, fall back to restore
+TS.CREATE 6-1 ENCODING UNCOMPRESSED
+TS.CREATE 6-2 ENCODING UNCOMPRESSED
+TS.CREATERULE 6-1 6-2 AGGREGATION avg 1000
+TS.MADD 6-1 1000 1 6-1 1500 2 6-1 2500 3
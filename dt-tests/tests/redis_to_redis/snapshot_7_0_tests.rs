@@ -83,6 +83,12 @@ mod test {
         TestBase::run_redis_snapshot_test("redis_to_redis/snapshot/7_0/filter_db_test").await;
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_aof_file_test() {
+        TestBase::run_redis_snapshot_test("redis_to_redis/snapshot/7_0/aof_file_test").await;
+    }
+
     #[tokio::test]
     #[serial]
     async fn snapshot_statistic_big_key_rdb_test() {
@@ -8,4 +8,12 @@ mod test {
     async fn snapshot_cmds_test() {
         TestBase::run_redis_rejson_snapshot_test("redis_to_redis/snapshot/rejson/cmds_test").await;
     }
+
+    // test sinking module data by rewrite instead of restore
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_rewrite_test() {
+        TestBase::run_redis_rejson_snapshot_test("redis_to_redis/snapshot/rejson/rewrite_test")
+            .await;
+    }
 }
@@ -0,0 +1,12 @@
+#[cfg(test)]
+mod test {
+    use crate::test_runner::test_base::TestBase;
+    use serial_test::serial;
+
+    // test sinking timeseries by rewrite, compressed series fall back to restore
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_rewrite_test() {
+        TestBase::run_redis_snapshot_test("redis_to_redis/snapshot/timeseries/rewrite_test").await;
+    }
+}
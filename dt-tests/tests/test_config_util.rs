@@ -65,7 +65,7 @@ impl TestConfigUtil {
         dotenv::from_path(&env_file).unwrap();
 
         let mut update_configs = Vec::new();
-        let ini = IniLoader::new(src_task_config_file).ini;
+        let ini = IniLoader::new(src_task_config_file).unwrap().ini;
         for (section, kvs) in ini.get_map().unwrap() {
             for (k, v) in kvs.iter() {
                 if v.is_none() {
@@ -132,7 +132,8 @@ impl TestConfigUtil {
                 ));
             }
 
-            ExtractorConfig::RedisSnapshotFile { file_path } => {
+            ExtractorConfig::RedisSnapshotFile { file_path }
+            | ExtractorConfig::RedisAofFile { file_path } => {
                 let file_path = format!("{}/{}", project_root, file_path);
                 update_configs.push((EXTRACTOR.to_string(), "file_path".to_string(), file_path));
             }
@@ -197,7 +198,7 @@ impl TestConfigUtil {
         dst_task_config_file: &str,
         config: &[(String, String, String)],
     ) {
-        let mut ini = IniLoader::new(src_task_config_file).ini;
+        let mut ini = IniLoader::new(src_task_config_file).unwrap().ini;
         for (section, key, value) in config.iter() {
             ini.set(section, key, Some(value.to_string()));
         }
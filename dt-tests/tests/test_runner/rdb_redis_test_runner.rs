@@ -32,7 +32,11 @@ impl RdbRedisTestRunner {
         let mysql_conn_pool =
             Some(TaskUtil::create_mysql_conn_pool(&config.extractor_basic.url, 1, false).await?);
         let redis_conn = match config.sinker {
-            SinkerConfig::Redis { url, .. } => RedisUtil::create_redis_conn(&url).await.unwrap(),
+            SinkerConfig::Redis { url, .. } => {
+                RedisUtil::create_redis_conn(&url, &config.sinker_basic.tls)
+                    .await
+                    .unwrap()
+            }
             _ => {
                 bail! {Error::ConfigError("unsupported sinker config".into())};
             }
@@ -229,6 +229,23 @@ impl RdbTestRunner {
         self.base.wait_task_finish(&task).await
     }
 
+    /// extract_type=snapshot_and_cdc: rows in src_prepare.sql are synced by the snapshot,
+    /// rows in src_test.sql are synced by cdc started from the handoff position.
+    pub async fn run_snapshot_and_cdc_test(
+        &self,
+        start_millis: u64,
+        parse_millis: u64,
+    ) -> anyhow::Result<()> {
+        self.execute_prepare_sqls().await?;
+
+        let task = self.spawn_cdc_task(start_millis, parse_millis).await?;
+        let (src_db_tbs, dst_db_tbs) = self.get_compare_db_tbs()?;
+        assert!(self.compare_data_for_tbs(&src_db_tbs, &dst_db_tbs).await?);
+
+        self.execute_test_sqls_and_compare(parse_millis).await?;
+        self.base.wait_task_finish(&task).await
+    }
+
     pub async fn run_heartbeat_test(
         &self,
         _start_millis: u64,
@@ -1,3 +1,4 @@
+use dt_common::config::tls_config::TlsConfig;
 use dt_common::utils::redis_util::RedisUtil;
 use dt_common::{meta::redis::command::key_parser::KeyParser, utils::url_util::UrlUtil};
 use redis::Connection;
@@ -11,10 +12,10 @@ pub struct RedisClusterConnection {
 }
 
 impl RedisClusterConnection {
-    pub async fn new(url: &str, is_cluster: bool) -> anyhow::Result<Self> {
+    pub async fn new(url: &str, is_cluster: bool, tls: &TlsConfig) -> anyhow::Result<Self> {
         let mut slot_node_map = HashMap::new();
         let mut node_conn_map = HashMap::new();
-        let mut conn = RedisUtil::create_redis_conn(url).await?;
+        let mut conn = RedisUtil::create_redis_conn(url, tls).await?;
 
         if is_cluster {
             let nodes = RedisUtil::get_cluster_master_nodes(&mut conn)?;
@@ -28,7 +29,7 @@ impl RedisClusterConnection {
             for node in nodes {
                 println!("redis cluster node: {}", node.address);
                 let new_url = format!("redis://{}:{}@{}", username, password, node.address);
-                let conn = RedisUtil::create_redis_conn(&new_url).await?;
+                let conn = RedisUtil::create_redis_conn(&new_url, tls).await?;
                 node_conn_map.insert(node.address.clone(), conn);
             }
         }
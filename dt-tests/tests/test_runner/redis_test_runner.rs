@@ -13,6 +13,7 @@ use dt_common::{
 };
 
 use redis::{Connection, Value};
+use std::env;
 
 pub struct RedisTestRunner {
     pub base: BaseTestRunner,
@@ -36,7 +37,16 @@ impl RedisTestRunner {
         let config = TaskConfig::new(&base.task_config_file).unwrap();
         let src_conn = match config.extractor {
             ExtractorConfig::RedisSnapshot { url, .. } | ExtractorConfig::RedisCdc { url, .. } => {
-                RedisUtil::create_redis_conn(&url).await.unwrap()
+                RedisUtil::create_redis_conn(&url, &config.extractor_basic.tls)
+                    .await
+                    .unwrap()
+            }
+            // the source is a file, src_test.sql prepares the expected data in a redis to compare with
+            ExtractorConfig::RedisAofFile { .. } => {
+                let url = env::var("redis_extractor_url_7_0").unwrap();
+                RedisUtil::create_redis_conn(&url, &config.extractor_basic.tls)
+                    .await
+                    .unwrap()
             }
             _ => {
                 bail! {Error::ConfigError("unsupported extractor config".into())};
@@ -46,7 +56,9 @@ impl RedisTestRunner {
         let dst_conn = match config.sinker {
             SinkerConfig::Redis {
                 url, is_cluster, ..
-            } => RedisClusterConnection::new(&url, is_cluster).await.unwrap(),
+            } => RedisClusterConnection::new(&url, is_cluster, &config.sinker_basic.tls)
+                .await
+                .unwrap(),
             _ => {
                 bail! {Error::ConfigError("unsupported sinker config".into())};
             }
@@ -179,6 +191,7 @@ impl RedisTestRunner {
         let mut json_keys = Vec::new();
         let mut bf_bloom_keys = Vec::new();
         let mut cf_bloom_keys = Vec::new();
+        let mut ts_keys = Vec::new();
 
         let keys = self.redis_util.list_keys(&mut self.src_conn, "*");
         for i in keys.iter() {
@@ -199,6 +212,7 @@ impl RedisTestRunner {
                 "rejson-rl" => json_keys.push(key),
                 "mbbloom--" => bf_bloom_keys.push(key),
                 "mbbloomcf" => cf_bloom_keys.push(key),
+                "tsdb-type" => ts_keys.push(key),
                 _ => {
                     println!("unknown type: {} for key: {}", key_type, key);
                     string_keys.push(key)
@@ -215,6 +229,7 @@ impl RedisTestRunner {
         self.compare_rejson_entries(db, &json_keys);
         self.compare_bf_bloom_entries(db, &bf_bloom_keys);
         self.compare_cf_bloom_entries(db, &cf_bloom_keys);
+        self.compare_ts_entries(db, &ts_keys);
         self.check_expire(&keys);
         Ok(())
     }
@@ -326,6 +341,13 @@ impl RedisTestRunner {
         }
     }
 
+    fn compare_ts_entries(&mut self, db: &str, keys: &Vec<String>) {
+        for key in keys {
+            let cmd = format!("TS.RANGE {} - +", self.redis_util.escape_key(key));
+            self.compare_cmd_results(&cmd, db, key);
+        }
+    }
+
     fn compare_cmd_results(&mut self, cmd: &str, db: &str, key: &str) {
         let src_result = self.redis_util.execute_cmd(&mut self.src_conn, cmd);
         let dst_result = self
@@ -59,6 +59,15 @@ impl TestBase {
         runner.close().await.unwrap();
     }
 
+    pub async fn run_snapshot_and_cdc_test(test_dir: &str, start_millis: u64, parse_millis: u64) {
+        let runner = RdbTestRunner::new(test_dir).await.unwrap();
+        runner
+            .run_snapshot_and_cdc_test(start_millis, parse_millis)
+            .await
+            .unwrap();
+        runner.close().await.unwrap();
+    }
+
     pub async fn run_cdc_to_sql_test(
         test_dir: &str,
         reverse: bool,